@@ -0,0 +1,562 @@
+//! # Command-Line Interface
+//!
+//! Runs simulations without the GUI, for scripted batch renders on machines
+//! with no display. Invoking the binary with a subcommand such as
+//!
+//! ```text
+//! vizza render --sim slime_mold --preset "Default" --frames 600 --size 3840x2160 --out frames/
+//! ```
+//!
+//! renders headlessly and exits instead of opening the window. Simulations,
+//! presets and LUTs are loaded exactly as the app loads them, so anything saved
+//! from the GUI can be rendered from the command line.
+
+use clap::{Args, CommandFactory, Parser, Subcommand};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use wgpu::TextureFormat;
+
+use crate::commands::AppSettings;
+use crate::error::{AppResult, SimulationError};
+use crate::simulation::audio::{AudioAnalysis, AudioClip, AudioMappingFile, AudioReactive};
+use crate::simulation::headless::{DEFAULT_DELTA_TIME, HeadlessRenderer};
+use crate::simulation::sweep::{SweepAxis, SweepOptions, run_sweep};
+use crate::simulation::timeline::{Timeline, TimelinePlayer};
+use crate::simulations::registry;
+use crate::simulations::shared::{random, save_png};
+use crate::simulations::traits::SimulationType;
+
+/// Render Vizza simulations without opening a window
+#[derive(Debug, Parser)]
+#[command(name = "vizza", version)]
+pub struct Cli {
+    #[command(subcommand)]
+    command: CliCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum CliCommand {
+    /// Render a simulation to a still image or a numbered PNG sequence
+    Render(RenderArgs),
+    /// Render a grid of settings combinations into a labelled contact sheet
+    Sweep(SweepArgs),
+    /// List the simulations that can be rendered
+    List,
+}
+
+/// Which simulation to run and how to set it up
+#[derive(Debug, Args)]
+struct SimulationArgs {
+    /// Simulation type, as shown by `vizza list`
+    #[arg(long)]
+    sim: String,
+    /// Preset to apply before rendering
+    #[arg(long)]
+    preset: Option<String>,
+    /// LUT to apply after the preset
+    #[arg(long)]
+    lut: Option<String>,
+}
+
+#[derive(Debug, Args)]
+struct RenderArgs {
+    #[command(flatten)]
+    simulation: SimulationArgs,
+    /// Number of frames to step the simulation
+    #[arg(long, default_value_t = 1)]
+    frames: u32,
+    /// Output resolution as WIDTHxHEIGHT
+    #[arg(long, default_value = "1920x1080", value_parser = parse_size)]
+    size: (u32, u32),
+    /// Output directory for the frame sequence, or a .png file to save only
+    /// the last frame
+    #[arg(long, default_value = "render")]
+    out: PathBuf,
+    /// Save every Nth frame of the sequence
+    #[arg(long, default_value_t = 1)]
+    every: u32,
+    /// Fixed simulation time step, in seconds
+    #[arg(long, default_value_t = DEFAULT_DELTA_TIME)]
+    delta_time: f32,
+    /// Seed all randomness the simulation draws on the CPU. Renders are only
+    /// reproducible for simulations listed as deterministic, the others
+    /// still vary with GPU thread scheduling.
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Keyframe timeline TOML to play while rendering
+    #[arg(long)]
+    timeline: Option<PathBuf>,
+    /// WAV or FLAC file whose analysis drives settings, or `-` to read raw
+    /// signed 16-bit little-endian PCM from stdin
+    #[arg(long, requires = "audio_map")]
+    audio: Option<PathBuf>,
+    /// TOML file of mappings from audio features to settings
+    #[arg(long, requires = "audio")]
+    audio_map: Option<PathBuf>,
+    /// Sample rate of PCM read from stdin
+    #[arg(long, default_value_t = 44100)]
+    audio_sample_rate: u32,
+    /// Number of interleaved channels in PCM read from stdin
+    #[arg(long, default_value_t = 2)]
+    audio_channels: u16,
+}
+
+#[derive(Debug, Args)]
+struct SweepArgs {
+    #[command(flatten)]
+    simulation: SimulationArgs,
+    /// Settings field swept across the columns, as field=start:end:count or
+    /// field=a,b,c
+    #[arg(long, value_parser = SweepAxis::parse)]
+    x: SweepAxis,
+    /// Settings field swept down the rows, in the same form as --x
+    #[arg(long, value_parser = SweepAxis::parse)]
+    y: Option<SweepAxis>,
+    /// Number of frames each cell is stepped before it is captured
+    #[arg(long, default_value_t = 600)]
+    steps: u32,
+    /// Resolution of each cell as WIDTHxHEIGHT
+    #[arg(long, default_value = "256x256", value_parser = parse_size)]
+    cell_size: (u32, u32),
+    /// Fixed simulation time step, in seconds
+    #[arg(long, default_value_t = DEFAULT_DELTA_TIME)]
+    delta_time: f32,
+    /// Seed for the state every cell starts from
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// Output directory for the contact sheet and the cell presets
+    #[arg(long, default_value = "sweep")]
+    out: PathBuf,
+}
+
+impl RenderArgs {
+    /// Whether `out` names a single image rather than a sequence directory
+    fn is_still(&self) -> bool {
+        self.out
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("png"))
+    }
+
+    fn validate(&self) -> AppResult<()> {
+        if self.frames == 0 {
+            return Err(invalid("Frame count must be at least 1"));
+        }
+        if self.every == 0 {
+            return Err(invalid("Frame interval must be at least 1"));
+        }
+        if !(self.delta_time.is_finite() && self.delta_time > 0.0) {
+            return Err(invalid("Time step must be positive"));
+        }
+        Ok(())
+    }
+}
+
+fn invalid(message: &str) -> crate::error::AppError {
+    SimulationError::InvalidParameter(message.to_string()).into()
+}
+
+/// Parse a resolution such as `3840x2160`
+fn parse_size(value: &str) -> Result<(u32, u32), String> {
+    let (width, height) = value
+        .split_once(['x', 'X'])
+        .ok_or_else(|| format!("Expected WIDTHxHEIGHT, got '{}'", value))?;
+    let parse = |dimension: &str| match dimension.trim().parse::<u32>() {
+        Ok(dimension) if dimension > 0 => Ok(dimension),
+        _ => Err(format!("Invalid dimension '{}' in '{}'", dimension, value)),
+    };
+    Ok((parse(width)?, parse(height)?))
+}
+
+/// Whether the arguments ask for the command-line interface rather than the GUI
+fn is_cli_invocation(args: &[OsString]) -> bool {
+    let Some(first) = args.get(1).and_then(|arg| arg.to_str()) else {
+        return false;
+    };
+    matches!(first, "help" | "--help" | "-h" | "--version" | "-V")
+        || Cli::command().find_subcommand(first).is_some()
+}
+
+/// Run the command-line interface if the process arguments name a subcommand.
+/// Returns the exit code, or `None` when the GUI should start instead.
+pub fn run_from_env() -> Option<i32> {
+    let args = std::env::args_os().collect::<Vec<_>>();
+    if !is_cli_invocation(&args) {
+        return None;
+    }
+
+    let cli = match Cli::try_parse_from(args) {
+        Ok(cli) => cli,
+        Err(e) => {
+            let _ = e.print();
+            return Some(e.exit_code());
+        }
+    };
+
+    let result = match cli.command {
+        CliCommand::Render(args) => render(&args),
+        CliCommand::Sweep(args) => sweep(&args),
+        CliCommand::List => {
+            list();
+            Ok(())
+        }
+    };
+    match result {
+        Ok(()) => Some(0),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            Some(1)
+        }
+    }
+}
+
+/// Run `f` with simulation randomness drawn from `rng` when one is given
+fn seeded<R>(rng: Option<&mut StdRng>, f: impl FnOnce() -> R) -> R {
+    match rng {
+        Some(rng) => random::scoped(rng, f),
+        None => f(),
+    }
+}
+
+/// Create the renderer and the simulation, then apply the preset and LUT.
+/// Randomness drawn while setting up comes from `rng` when one is given.
+fn start_simulation(
+    args: &SimulationArgs,
+    width: u32,
+    height: u32,
+    mut rng: Option<&mut StdRng>,
+) -> AppResult<(HeadlessRenderer, SimulationType)> {
+    // Simulation construction is async in name only, so a single-threaded
+    // runtime keeps it on this thread where the seeded scope applies
+    let runtime = tokio::runtime::Builder::new_current_thread().build()?;
+    let renderer = runtime.block_on(HeadlessRenderer::new(
+        width,
+        height,
+        TextureFormat::Rgba8UnormSrgb,
+        AppSettings::load_from_file()?.render_settings(),
+    ))?;
+
+    let mut simulation = seeded(rng.as_deref_mut(), || {
+        runtime.block_on(renderer.create_simulation(&args.sim))
+    })?;
+    if let Some(preset) = &args.preset {
+        seeded(rng, || renderer.apply_preset(&mut simulation, preset))?;
+    }
+    if let Some(lut) = &args.lut {
+        renderer.apply_lut(&mut simulation, lut)?;
+    }
+    Ok((renderer, simulation))
+}
+
+/// Decode and analyse the render's audio track, one analysis frame per
+/// rendered frame
+fn load_audio(args: &RenderArgs) -> AppResult<Option<AudioReactive>> {
+    let (Some(path), Some(map)) = (&args.audio, &args.audio_map) else {
+        return Ok(None);
+    };
+    let clip = if path.as_os_str() == "-" {
+        AudioClip::read_pcm(
+            std::io::stdin().lock(),
+            args.audio_sample_rate,
+            args.audio_channels,
+        )?
+    } else {
+        AudioClip::load(path)?
+    };
+    let analysis = AudioAnalysis::analyze(&clip, args.delta_time)?;
+    let mappings = AudioMappingFile::load(map)?.mappings;
+    let schema = registry::settings_schema(&args.simulation.sim)?;
+    Ok(Some(AudioReactive::new(analysis, mappings, &schema)?))
+}
+
+fn render(args: &RenderArgs) -> AppResult<()> {
+    args.validate()?;
+    let (width, height) = args.size;
+    let sim = &args.simulation.sim;
+
+    let mut timeline = args
+        .timeline
+        .as_deref()
+        .map(|path| -> AppResult<TimelinePlayer> {
+            let timeline = Timeline::load(path)?;
+            if timeline.simulation_type != *sim {
+                return Err(invalid(&format!(
+                    "Timeline is for {}, not {}",
+                    timeline.simulation_type, sim
+                )));
+            }
+            Ok(TimelinePlayer::new(timeline)?)
+        })
+        .transpose()?;
+    let mut audio = load_audio(args)?;
+    // The render takes no live input, so seeding the CPU randomness is all a
+    // deterministic run would add
+    let mut rng = args.seed.map(StdRng::seed_from_u64);
+    let (renderer, mut simulation) =
+        start_simulation(&args.simulation, width, height, rng.as_mut())?;
+
+    let still = args.is_still();
+    if still {
+        if let Some(parent) = args.out.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+    } else {
+        std::fs::create_dir_all(&args.out)?;
+    }
+
+    let started_at = Instant::now();
+    let mut frames_saved = 0;
+    seeded(rng.as_mut(), || -> AppResult<()> {
+        for frame in 0..args.frames {
+            if let Some(player) = &mut timeline {
+                player.step(
+                    args.delta_time,
+                    &mut simulation,
+                    &renderer.lut_manager,
+                    &renderer.device,
+                    &renderer.queue,
+                )?;
+            }
+            if let Some(audio) = &mut audio {
+                for (setting_name, value) in audio.step(args.delta_time) {
+                    simulation.update_setting(
+                        setting_name,
+                        value,
+                        &renderer.device,
+                        &renderer.queue,
+                    )?;
+                }
+            }
+            renderer.render_frame(&mut simulation, args.delta_time)?;
+
+            let path = if still {
+                (frame + 1 == args.frames).then(|| args.out.clone())
+            } else {
+                frame.is_multiple_of(args.every).then(|| {
+                    args.out
+                        .join(format!("frame_{:06}.png", frame / args.every))
+                })
+            };
+            if let Some(path) = path {
+                save_frame(&renderer, &path)?;
+                frames_saved += 1;
+            }
+        }
+        renderer.device.poll(wgpu::Maintain::Wait);
+        Ok(())
+    })?;
+
+    println!(
+        "Rendered {} frames of {} at {}x{} in {:.1}s, saved {} to {}",
+        args.frames,
+        sim,
+        width,
+        height,
+        started_at.elapsed().as_secs_f32(),
+        frames_saved,
+        args.out.display()
+    );
+    Ok(())
+}
+
+fn sweep(args: &SweepArgs) -> AppResult<()> {
+    let (width, height) = args.cell_size;
+    let (renderer, mut simulation) = start_simulation(&args.simulation, width, height, None)?;
+
+    let options = SweepOptions {
+        x: args.x.clone(),
+        y: args.y.clone(),
+        steps: args.steps,
+        delta_time: args.delta_time,
+        seed: args.seed,
+        out_dir: args.out.clone(),
+    };
+    let summary = run_sweep(&renderer, &mut simulation, &options)?;
+
+    println!(
+        "Rendered {} sweep cells, contact sheet saved to {}",
+        summary.presets.len(),
+        summary.contact_sheet.display()
+    );
+    Ok(())
+}
+
+fn list() {
+    for info in registry::list() {
+        let capabilities = [
+            (info.supports_presets, "presets"),
+            (info.capabilities.camera, "camera"),
+            (info.capabilities.luts, "luts"),
+            (info.capabilities.cursor, "cursor"),
+            (info.capabilities.deterministic, "deterministic"),
+        ]
+        .iter()
+        .filter(|(supported, _)| *supported)
+        .map(|(_, name)| *name)
+        .collect::<Vec<_>>();
+
+        println!(
+            "{:<16}{:<20}{}",
+            info.id,
+            info.display_name,
+            capabilities.join(", ")
+        );
+    }
+}
+
+fn save_frame(renderer: &HeadlessRenderer, path: &Path) -> AppResult<()> {
+    let pixels = renderer.read_pixels()?;
+    save_png(path, renderer.target.width, renderer.target.height, &pixels)?;
+    tracing::debug!("Saved {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+        Cli::try_parse_from(std::iter::once("vizza").chain(args.iter().copied()))
+    }
+
+    fn render_args(args: &[&str]) -> RenderArgs {
+        match parse(args).unwrap().command {
+            CliCommand::Render(args) => args,
+            command => panic!("Expected render, got {:?}", command),
+        }
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("3840x2160"), Ok((3840, 2160)));
+        assert_eq!(parse_size("640X480"), Ok((640, 480)));
+        assert!(parse_size("3840").is_err());
+        assert!(parse_size("0x100").is_err());
+        assert!(parse_size("axb").is_err());
+    }
+
+    #[test]
+    fn test_parses_render_arguments() {
+        let args = render_args(&[
+            "render",
+            "--sim",
+            "slime_mold",
+            "--preset",
+            "Default",
+            "--frames",
+            "600",
+            "--size",
+            "3840x2160",
+            "--out",
+            "dir/",
+        ]);
+        assert_eq!(args.simulation.sim, "slime_mold");
+        assert_eq!(args.simulation.preset.as_deref(), Some("Default"));
+        assert_eq!(args.frames, 600);
+        assert_eq!(args.size, (3840, 2160));
+        assert!(!args.is_still());
+
+        let args = render_args(&["render", "--sim", "flow", "--out", "still.PNG"]);
+        assert!(args.is_still());
+        assert!(parse(&["render"]).is_err());
+
+        let args = render_args(&["render", "--sim", "flow", "--timeline", "piece.toml"]);
+        assert_eq!(args.timeline.as_deref(), Some(Path::new("piece.toml")));
+
+        let args = render_args(&[
+            "render",
+            "--sim",
+            "particle_life",
+            "--audio",
+            "-",
+            "--audio-map",
+            "kick.toml",
+        ]);
+        assert_eq!(args.audio.as_deref(), Some(Path::new("-")));
+        assert_eq!(args.audio_sample_rate, 44100);
+        assert!(parse(&["render", "--sim", "flow", "--audio", "track.wav"]).is_err());
+    }
+
+    #[test]
+    fn test_parses_sweep_arguments() {
+        let command = parse(&[
+            "sweep",
+            "--sim",
+            "gray_scott",
+            "--x",
+            "feed_rate=0.01:0.1:10",
+            "--y",
+            "kill_rate=0.045:0.07:10",
+            "--cell-size",
+            "128x128",
+        ])
+        .unwrap()
+        .command;
+        let CliCommand::Sweep(args) = command else {
+            panic!("Expected sweep, got {:?}", command);
+        };
+        assert_eq!(args.x.field, "feed_rate");
+        assert_eq!(args.x.values.len(), 10);
+        assert_eq!(args.y.map(|y| y.field).as_deref(), Some("kill_rate"));
+        assert_eq!(args.cell_size, (128, 128));
+
+        assert!(parse(&["sweep", "--sim", "gray_scott", "--x", "feed_rate"]).is_err());
+    }
+
+    #[test]
+    fn test_detects_cli_invocation() {
+        let args = |args: &[&str]| args.iter().map(OsString::from).collect::<Vec<_>>();
+        assert!(is_cli_invocation(&args(&[
+            "vizza", "render", "--sim", "flow"
+        ])));
+        assert!(is_cli_invocation(&args(&["vizza", "--help"])));
+        assert!(is_cli_invocation(&args(&["vizza", "list"])));
+        assert!(!is_cli_invocation(&args(&["vizza"])));
+        assert!(!is_cli_invocation(&args(&["vizza", "-psn_0_12345"])));
+    }
+
+    #[test]
+    fn test_renders_frame_sequence_and_still() {
+        let dir = std::env::temp_dir().join(format!("vizza-cli-{}", std::process::id()));
+        let sequence = render_args(&[
+            "render",
+            "--sim",
+            "gray_scott",
+            "--frames",
+            "5",
+            "--every",
+            "2",
+            "--size",
+            "64x48",
+            "--out",
+            dir.join("frames").to_str().unwrap(),
+        ]);
+        render(&sequence).unwrap();
+        for index in 0..3 {
+            assert!(dir.join(format!("frames/frame_{:06}.png", index)).exists());
+        }
+        assert!(!dir.join("frames/frame_000003.png").exists());
+
+        let still = render_args(&[
+            "render",
+            "--sim",
+            "slime_mold",
+            "--frames",
+            "3",
+            "--size",
+            "64x48",
+            "--seed",
+            "7",
+            "--out",
+            dir.join("still.png").to_str().unwrap(),
+        ]);
+        render(&still).unwrap();
+        assert!(dir.join("still.png").exists());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1,27 +1,11 @@
-use dirs::home_dir;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 use tauri::Manager;
 use toml;
-use wgpu;
+use vizza_core::settings::{RenderSettings, data_dir};
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
-pub enum TextureFiltering {
-    Linear,
-    Nearest,
-    Lanczos,
-}
-
-impl From<TextureFiltering> for wgpu::FilterMode {
-    fn from(filtering: TextureFiltering) -> Self {
-        match filtering {
-            TextureFiltering::Linear => wgpu::FilterMode::Linear,
-            TextureFiltering::Nearest => wgpu::FilterMode::Nearest,
-            TextureFiltering::Lanczos => wgpu::FilterMode::Linear, // Lanczos uses Linear as base, custom shader handles the rest
-        }
-    }
-}
+pub use vizza_core::settings::TextureFiltering;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppSettings {
@@ -43,6 +27,35 @@ pub struct AppSettings {
 
     // Camera Settings
     pub default_camera_sensitivity: f32,
+
+    // Remote Control Settings, applied on the next start
+    #[serde(default)]
+    pub osc_enabled: bool,
+    #[serde(default = "default_osc_port")]
+    pub osc_port: u16,
+    /// Interface the OSC server listens on. The loopback default keeps other
+    /// machines out; `0.0.0.0` lets anyone on the network control the app.
+    #[serde(default = "default_osc_bind_address")]
+    pub osc_bind_address: String,
+    /// `host:port` that receives OSC feedback, or empty for none
+    #[serde(default)]
+    pub osc_feedback_address: String,
+    #[serde(default)]
+    pub http_api_enabled: bool,
+    #[serde(default = "default_http_api_port")]
+    pub http_api_port: u16,
+}
+
+fn default_osc_port() -> u16 {
+    9000
+}
+
+fn default_osc_bind_address() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_http_api_port() -> u16 {
+    8765
 }
 
 impl AppSettings {
@@ -55,6 +68,13 @@ impl AppSettings {
             .map_err(|e| format!("Failed to read settings file: {}", e))?;
         toml::from_str(&content).map_err(|e| format!("Failed to parse settings file: {}", e))
     }
+
+    /// The subset of these settings the simulations use
+    pub fn render_settings(&self) -> RenderSettings {
+        RenderSettings {
+            texture_filtering: self.texture_filtering,
+        }
+    }
 }
 
 impl Default for AppSettings {
@@ -78,18 +98,20 @@ impl Default for AppSettings {
 
             // Camera Settings
             default_camera_sensitivity: 1.0,
+
+            // Remote Control Settings
+            osc_enabled: false,
+            osc_port: default_osc_port(),
+            osc_bind_address: default_osc_bind_address(),
+            osc_feedback_address: String::new(),
+            http_api_enabled: false,
+            http_api_port: default_http_api_port(),
         }
     }
 }
 
 fn get_settings_path() -> PathBuf {
-    let home_dir = home_dir().unwrap_or_else(|| PathBuf::from("."));
-    home_dir.join(env!("CARGO_PKG_NAME")).join("settings.toml")
-}
-
-pub(crate) fn get_settings_dir() -> PathBuf {
-    let home_dir = home_dir().unwrap_or_else(|| PathBuf::from("."));
-    home_dir.join(env!("CARGO_PKG_NAME"))
+    data_dir().join("settings.toml")
 }
 
 #[tauri::command]
@@ -118,7 +140,7 @@ pub async fn get_app_settings() -> Result<AppSettings, String> {
 
 #[tauri::command]
 pub async fn save_app_settings(settings: AppSettings) -> Result<String, String> {
-    let settings_dir = get_settings_dir();
+    let settings_dir = data_dir();
     let settings_path = get_settings_path();
 
     // Create settings directory if it doesn't exist
@@ -0,0 +1,70 @@
+use crate::simulation::SimulationManager;
+use crate::simulation::audio::{AudioAnalysis, AudioClip, AudioMapping};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Arc;
+use tauri::State;
+
+/// Frame length used to analyse a track when the render loop isn't running
+/// at a fixed time step
+const LIVE_FRAME_DURATION: f32 = 1.0 / 60.0;
+
+/// Progress of the playing audio track
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioStatus {
+    pub time: f64,
+    pub duration: f64,
+}
+
+/// Decode a WAV or FLAC file and drive settings of the running simulation
+/// from it through `mappings`
+#[tauri::command]
+pub async fn start_audio_reactive(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+    path: String,
+    mappings: Vec<AudioMapping>,
+) -> Result<String, String> {
+    // Decoding can take a moment, so do it before taking the lock
+    let clip = AudioClip::load(Path::new(&path))
+        .map_err(|e| format!("Failed to load audio {}: {}", path, e))?;
+
+    let mut sim_manager = manager.lock().await;
+    let frame_duration = sim_manager
+        .fixed_delta_time()
+        .unwrap_or(LIVE_FRAME_DURATION);
+
+    match AudioAnalysis::analyze(&clip, frame_duration)
+        .map_err(Into::into)
+        .and_then(|analysis| sim_manager.start_audio(analysis, mappings))
+    {
+        Ok(()) => {
+            tracing::info!("Playing audio {} ({:.1}s)", path, clip.duration());
+            Ok(format!("Playing audio {}", path))
+        }
+        Err(e) => {
+            tracing::error!("Failed to start audio-reactive playback: {}", e);
+            Err(format!("Failed to start audio-reactive playback: {}", e))
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn stop_audio_reactive(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+) -> Result<String, String> {
+    let mut sim_manager = manager.lock().await;
+    sim_manager.audio = None;
+    Ok("Audio stopped".to_string())
+}
+
+/// Progress of the playing audio track, or `None` when nothing is playing
+#[tauri::command]
+pub async fn get_audio_status(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+) -> Result<Option<AudioStatus>, String> {
+    let sim_manager = manager.lock().await;
+    Ok(sim_manager.audio.as_ref().map(|audio| AudioStatus {
+        time: audio.time(),
+        duration: audio.duration(),
+    }))
+}
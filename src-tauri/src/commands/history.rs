@@ -0,0 +1,47 @@
+use crate::simulation::SimulationManager;
+use crate::simulation::history::HistoryStatus;
+use std::sync::Arc;
+use tauri::State;
+
+/// Undo the last settings, LUT or force matrix edit. Returns false if there
+/// was nothing to undo.
+#[tauri::command]
+pub async fn undo(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+    gpu_context: State<'_, Arc<tokio::sync::Mutex<crate::GpuContext>>>,
+) -> Result<bool, String> {
+    let mut sim_manager = manager.lock().await;
+    let gpu_ctx = gpu_context.lock().await;
+
+    sim_manager
+        .undo(&gpu_ctx.device, &gpu_ctx.queue)
+        .map_err(|e| {
+            tracing::error!("Failed to undo: {}", e);
+            format!("Failed to undo: {}", e)
+        })
+}
+
+/// Redo the last undone edit. Returns false if there was nothing to redo.
+#[tauri::command]
+pub async fn redo(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+    gpu_context: State<'_, Arc<tokio::sync::Mutex<crate::GpuContext>>>,
+) -> Result<bool, String> {
+    let mut sim_manager = manager.lock().await;
+    let gpu_ctx = gpu_context.lock().await;
+
+    sim_manager
+        .redo(&gpu_ctx.device, &gpu_ctx.queue)
+        .map_err(|e| {
+            tracing::error!("Failed to redo: {}", e);
+            format!("Failed to redo: {}", e)
+        })
+}
+
+#[tauri::command]
+pub async fn get_history_status(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+) -> Result<HistoryStatus, String> {
+    let sim_manager = manager.lock().await;
+    Ok(sim_manager.history_status())
+}
@@ -1,6 +1,7 @@
 use crate::SimulationType;
 use crate::simulation::manager::SimulationManager;
 use crate::simulations::shared::lut::LutData;
+use std::path::Path;
 use std::sync::Arc;
 use tauri::State;
 
@@ -90,6 +91,78 @@ pub async fn save_custom_lut(
     }
 }
 
+/// Import a palette file (`.cube`, `.ggr`, `.gpl`, `.map`, `.css`, `.png` or `.json`)
+/// as a custom LUT, named after the file unless `name` is given
+#[tauri::command]
+pub async fn import_lut(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+    path: String,
+    name: Option<String>,
+) -> Result<String, String> {
+    let sim_manager = manager.lock().await;
+
+    match sim_manager
+        .lut_manager
+        .import(Path::new(&path), name.as_deref())
+    {
+        Ok(name) => {
+            tracing::info!("Imported '{}' as custom LUT '{}'", path, name);
+            Ok(name)
+        }
+        Err(e) => {
+            tracing::error!("Failed to import LUT from '{}': {}", path, e);
+            Err(format!("Failed to import LUT from '{}': {}", path, e))
+        }
+    }
+}
+
+/// Save a CSS `linear-gradient()` as the custom LUT `name`
+#[tauri::command]
+pub async fn import_css_gradient_lut(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+    name: String,
+    gradient: String,
+) -> Result<String, String> {
+    let sim_manager = manager.lock().await;
+
+    let lut_data = LutData::from_css_gradient(name.clone(), &gradient)
+        .map_err(|e| format!("Failed to read CSS gradient: {}", e))?;
+
+    match sim_manager.lut_manager.save_custom(&name, &lut_data) {
+        Ok(_) => {
+            tracing::info!("Custom LUT '{}' saved successfully", name);
+            Ok(name)
+        }
+        Err(e) => {
+            tracing::error!("Failed to save custom LUT '{}': {}", name, e);
+            Err(format!("Failed to save custom LUT '{}': {}", name, e))
+        }
+    }
+}
+
+/// Write a built-in or custom LUT to `path` as a PNG strip, `.cube`, `.ggr`,
+/// `.gpl`, `.map`, CSS gradient (`.css`/`.txt`) or JSON array of hex colors,
+/// chosen by the file's extension
+#[tauri::command]
+pub async fn export_lut(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+    lut_name: String,
+    path: String,
+) -> Result<String, String> {
+    let sim_manager = manager.lock().await;
+
+    match sim_manager.lut_manager.export(&lut_name, Path::new(&path)) {
+        Ok(_) => {
+            tracing::info!("LUT '{}' exported to '{}'", lut_name, path);
+            Ok(format!("LUT '{}' exported to '{}'", lut_name, path))
+        }
+        Err(e) => {
+            tracing::error!("Failed to export LUT '{}': {}", lut_name, e);
+            Err(format!("Failed to export LUT '{}': {}", lut_name, e))
+        }
+    }
+}
+
 #[tauri::command]
 pub async fn update_gradient_preview(
     manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
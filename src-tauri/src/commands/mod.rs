@@ -1,34 +1,50 @@
 pub mod app_settings;
+pub mod audio;
 pub mod camera;
 pub mod flow;
 pub mod gradient;
 pub mod gray_scott;
+pub mod history;
 pub mod interaction;
 pub mod luts;
+pub mod modulation;
 pub mod particle_life;
 pub mod pellets;
 pub mod presets;
+pub mod recording;
 pub mod rendering;
+pub mod replay;
 pub mod reset;
 pub mod settings;
 pub mod simulation;
 pub mod slime_mold;
+pub mod snapshots;
+pub mod streaming;
+pub mod timeline;
 pub mod utility;
 
 // Re-export all command functions for easy access
 pub use app_settings::*;
+pub use audio::*;
 pub use camera::*;
 pub use flow::*;
 pub use gradient::*;
 pub use gray_scott::*;
+pub use history::*;
 pub use interaction::*;
 pub use luts::*;
+pub use modulation::*;
 pub use particle_life::*;
 pub use pellets::*;
 pub use presets::*;
+pub use recording::*;
 pub use rendering::*;
+pub use replay::*;
 pub use reset::*;
 pub use settings::*;
 pub use simulation::*;
 pub use slime_mold::*;
+pub use snapshots::*;
+pub use streaming::*;
+pub use timeline::*;
 pub use utility::*;
@@ -0,0 +1,57 @@
+use crate::simulation::SimulationManager;
+use crate::simulation::modulation::Modulator;
+use std::sync::Arc;
+use tauri::State;
+
+/// Attach a modulator to a numeric setting of the running simulation
+#[tauri::command]
+pub async fn add_modulator(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+    modulator: Modulator,
+) -> Result<Vec<Modulator>, String> {
+    let mut sim_manager = manager.lock().await;
+    let setting_name = modulator.setting_name.clone();
+
+    match sim_manager.add_modulator(modulator) {
+        Ok(()) => {
+            tracing::debug!("Added modulator for {}", setting_name);
+            Ok(sim_manager.get_modulators())
+        }
+        Err(e) => {
+            tracing::error!("Failed to add modulator for {}: {}", setting_name, e);
+            Err(format!("Failed to add modulator: {}", e))
+        }
+    }
+}
+
+/// Detach the modulator at `index` in the list returned by `get_modulators`.
+/// The setting keeps its last modulated value.
+#[tauri::command]
+pub async fn remove_modulator(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+    index: usize,
+) -> Result<Vec<Modulator>, String> {
+    let mut sim_manager = manager.lock().await;
+
+    match sim_manager.remove_modulator(index) {
+        Ok(_) => Ok(sim_manager.get_modulators()),
+        Err(e) => Err(format!("Failed to remove modulator: {}", e)),
+    }
+}
+
+#[tauri::command]
+pub async fn clear_modulators(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+) -> Result<String, String> {
+    let mut sim_manager = manager.lock().await;
+    sim_manager.modulation.clear();
+    Ok("Modulators cleared".to_string())
+}
+
+#[tauri::command]
+pub async fn get_modulators(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+) -> Result<Vec<Modulator>, String> {
+    let sim_manager = manager.lock().await;
+    Ok(sim_manager.get_modulators())
+}
@@ -18,6 +18,7 @@ pub async fn scale_force_matrix(
     );
     let mut sim_manager = manager.lock().await;
     let gpu_ctx = gpu_context.lock().await;
+    let before = sim_manager.history_entry();
 
     if let Some(SimulationType::ParticleLife(simulation)) = &mut sim_manager.current_simulation {
         simulation.settings.scale_force_matrix(scale_factor);
@@ -39,6 +40,8 @@ pub async fn scale_force_matrix(
         // Recreate bind groups that use this buffer
         simulation.recreate_bind_groups_with_force_matrix(&gpu_ctx.device);
 
+        sim_manager.record_history(before, None);
+
         Ok("Force matrix scaled successfully".to_string())
     } else {
         Err("This command is only available for Particle Life simulation".to_string())
@@ -53,6 +56,7 @@ pub async fn flip_force_matrix_horizontal(
     tracing::debug!("flip_force_matrix_horizontal called");
     let mut sim_manager = manager.lock().await;
     let gpu_ctx = gpu_context.lock().await;
+    let before = sim_manager.history_entry();
 
     if let Some(SimulationType::ParticleLife(simulation)) = &mut sim_manager.current_simulation {
         simulation.settings.flip_horizontal();
@@ -74,6 +78,8 @@ pub async fn flip_force_matrix_horizontal(
         // Recreate bind groups that use this buffer
         simulation.recreate_bind_groups_with_force_matrix(&gpu_ctx.device);
 
+        sim_manager.record_history(before, None);
+
         Ok("Force matrix flipped horizontally successfully".to_string())
     } else {
         Err("This command is only available for Particle Life simulation".to_string())
@@ -88,6 +94,7 @@ pub async fn flip_force_matrix_vertical(
     tracing::debug!("flip_force_matrix_vertical called");
     let mut sim_manager = manager.lock().await;
     let gpu_ctx = gpu_context.lock().await;
+    let before = sim_manager.history_entry();
 
     if let Some(SimulationType::ParticleLife(simulation)) = &mut sim_manager.current_simulation {
         simulation.settings.flip_vertical();
@@ -109,6 +116,8 @@ pub async fn flip_force_matrix_vertical(
         // Recreate bind groups that use this buffer
         simulation.recreate_bind_groups_with_force_matrix(&gpu_ctx.device);
 
+        sim_manager.record_history(before, None);
+
         Ok("Force matrix flipped vertically successfully".to_string())
     } else {
         Err("This command is only available for Particle Life simulation".to_string())
@@ -123,6 +132,7 @@ pub async fn rotate_force_matrix_clockwise(
     tracing::debug!("rotate_force_matrix_clockwise called");
     let mut sim_manager = manager.lock().await;
     let gpu_ctx = gpu_context.lock().await;
+    let before = sim_manager.history_entry();
 
     if let Some(SimulationType::ParticleLife(simulation)) = &mut sim_manager.current_simulation {
         simulation.settings.rotate_clockwise();
@@ -144,6 +154,8 @@ pub async fn rotate_force_matrix_clockwise(
         // Recreate bind groups that use this buffer
         simulation.recreate_bind_groups_with_force_matrix(&gpu_ctx.device);
 
+        sim_manager.record_history(before, None);
+
         Ok("Force matrix rotated clockwise successfully".to_string())
     } else {
         Err("This command is only available for Particle Life simulation".to_string())
@@ -158,6 +170,7 @@ pub async fn rotate_force_matrix_counterclockwise(
     tracing::debug!("rotate_force_matrix_counterclockwise called");
     let mut sim_manager = manager.lock().await;
     let gpu_ctx = gpu_context.lock().await;
+    let before = sim_manager.history_entry();
 
     if let Some(SimulationType::ParticleLife(simulation)) = &mut sim_manager.current_simulation {
         simulation.settings.rotate_counterclockwise();
@@ -179,6 +192,8 @@ pub async fn rotate_force_matrix_counterclockwise(
         // Recreate bind groups that use this buffer
         simulation.recreate_bind_groups_with_force_matrix(&gpu_ctx.device);
 
+        sim_manager.record_history(before, None);
+
         Ok("Force matrix rotated counterclockwise successfully".to_string())
     } else {
         Err("This command is only available for Particle Life simulation".to_string())
@@ -193,6 +208,7 @@ pub async fn shift_force_matrix_left(
     tracing::debug!("shift_force_matrix_left called");
     let mut sim_manager = manager.lock().await;
     let gpu_ctx = gpu_context.lock().await;
+    let before = sim_manager.history_entry();
 
     if let Some(SimulationType::ParticleLife(simulation)) = &mut sim_manager.current_simulation {
         simulation.settings.shift_left();
@@ -214,6 +230,8 @@ pub async fn shift_force_matrix_left(
         // Recreate bind groups that use this buffer
         simulation.recreate_bind_groups_with_force_matrix(&gpu_ctx.device);
 
+        sim_manager.record_history(before, None);
+
         Ok("Force matrix shifted left successfully".to_string())
     } else {
         Err("This command is only available for Particle Life simulation".to_string())
@@ -228,6 +246,7 @@ pub async fn shift_force_matrix_right(
     tracing::debug!("shift_force_matrix_right called");
     let mut sim_manager = manager.lock().await;
     let gpu_ctx = gpu_context.lock().await;
+    let before = sim_manager.history_entry();
 
     if let Some(SimulationType::ParticleLife(simulation)) = &mut sim_manager.current_simulation {
         simulation.settings.shift_right();
@@ -249,6 +268,8 @@ pub async fn shift_force_matrix_right(
         // Recreate bind groups that use this buffer
         simulation.recreate_bind_groups_with_force_matrix(&gpu_ctx.device);
 
+        sim_manager.record_history(before, None);
+
         Ok("Force matrix shifted right successfully".to_string())
     } else {
         Err("This command is only available for Particle Life simulation".to_string())
@@ -263,6 +284,7 @@ pub async fn shift_force_matrix_up(
     tracing::debug!("shift_force_matrix_up called");
     let mut sim_manager = manager.lock().await;
     let gpu_ctx = gpu_context.lock().await;
+    let before = sim_manager.history_entry();
 
     if let Some(SimulationType::ParticleLife(simulation)) = &mut sim_manager.current_simulation {
         simulation.settings.shift_up();
@@ -284,6 +306,8 @@ pub async fn shift_force_matrix_up(
         // Recreate bind groups that use this buffer
         simulation.recreate_bind_groups_with_force_matrix(&gpu_ctx.device);
 
+        sim_manager.record_history(before, None);
+
         Ok("Force matrix shifted up successfully".to_string())
     } else {
         Err("This command is only available for Particle Life simulation".to_string())
@@ -298,6 +322,7 @@ pub async fn shift_force_matrix_down(
     tracing::debug!("shift_force_matrix_down called");
     let mut sim_manager = manager.lock().await;
     let gpu_ctx = gpu_context.lock().await;
+    let before = sim_manager.history_entry();
 
     if let Some(SimulationType::ParticleLife(simulation)) = &mut sim_manager.current_simulation {
         simulation.settings.shift_down();
@@ -319,6 +344,8 @@ pub async fn shift_force_matrix_down(
         // Recreate bind groups that use this buffer
         simulation.recreate_bind_groups_with_force_matrix(&gpu_ctx.device);
 
+        sim_manager.record_history(before, None);
+
         Ok("Force matrix shifted down successfully".to_string())
     } else {
         Err("This command is only available for Particle Life simulation".to_string())
@@ -333,6 +360,7 @@ pub async fn zero_force_matrix(
     tracing::debug!("zero_force_matrix called");
     let mut sim_manager = manager.lock().await;
     let gpu_ctx = gpu_context.lock().await;
+    let before = sim_manager.history_entry();
 
     if let Some(SimulationType::ParticleLife(simulation)) = &mut sim_manager.current_simulation {
         simulation.settings.zero_matrix();
@@ -354,6 +382,8 @@ pub async fn zero_force_matrix(
         // Recreate bind groups that use this buffer
         simulation.recreate_bind_groups_with_force_matrix(&gpu_ctx.device);
 
+        sim_manager.record_history(before, None);
+
         Ok("Force matrix zeroed successfully".to_string())
     } else {
         Err("This command is only available for Particle Life simulation".to_string())
@@ -368,6 +398,7 @@ pub async fn flip_force_matrix_sign(
     tracing::debug!("flip_force_matrix_sign called");
     let mut sim_manager = manager.lock().await;
     let gpu_ctx = gpu_context.lock().await;
+    let before = sim_manager.history_entry();
 
     if let Some(SimulationType::ParticleLife(simulation)) = &mut sim_manager.current_simulation {
         simulation.settings.flip_sign();
@@ -389,6 +420,8 @@ pub async fn flip_force_matrix_sign(
         // Recreate bind groups that use this buffer
         simulation.recreate_bind_groups_with_force_matrix(&gpu_ctx.device);
 
+        sim_manager.record_history(before, None);
+
         Ok("Force matrix sign flipped successfully".to_string())
     } else {
         Err("This command is only available for Particle Life simulation".to_string())
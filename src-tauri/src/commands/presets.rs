@@ -1,6 +1,8 @@
 use crate::simulation::SimulationManager;
+use crate::simulation::preset_bundle::{BUNDLE_EXTENSION, PresetBundle};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tauri::State;
+use tauri::{Emitter, State};
 
 #[tauri::command]
 pub async fn get_available_presets(
@@ -82,3 +84,107 @@ pub async fn delete_preset(
         }
     }
 }
+
+/// Export the current settings, LUT and camera as a self-contained preset file
+#[tauri::command]
+pub async fn export_preset_bundle(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+    preset_name: String,
+    path: String,
+) -> Result<String, String> {
+    let sim_manager = manager.lock().await;
+
+    let mut path = PathBuf::from(path);
+    if path.extension().is_none() {
+        path.set_extension(BUNDLE_EXTENSION);
+    }
+
+    match sim_manager
+        .export_preset_bundle(&preset_name)
+        .and_then(|bundle| Ok(bundle.save(&path)?))
+    {
+        Ok(()) => {
+            tracing::info!("Exported preset '{}' to {}", preset_name, path.display());
+            Ok(path.display().to_string())
+        }
+        Err(e) => {
+            tracing::error!("Failed to export preset '{}': {}", preset_name, e);
+            Err(format!("Failed to export preset '{}': {}", preset_name, e))
+        }
+    }
+}
+
+/// Import a preset file written by `export_preset_bundle` as a user preset.
+/// With `apply` set the preset, LUT and camera are also applied, switching
+/// to the bundle's simulation type first if needed.
+#[tauri::command]
+pub async fn import_preset_bundle(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+    gpu_context: State<'_, Arc<tokio::sync::Mutex<crate::GpuContext>>>,
+    app: tauri::AppHandle,
+    path: String,
+    overwrite: bool,
+    apply: bool,
+) -> Result<String, String> {
+    let bundle = PresetBundle::load(Path::new(&path)).map_err(|e| {
+        tracing::error!("Failed to read preset bundle: {}", e);
+        format!("Failed to read preset bundle: {}", e)
+    })?;
+
+    let mut sim_manager = manager.lock().await;
+    if let Err(e) = sim_manager.import_preset_bundle(&bundle, overwrite) {
+        tracing::error!("Failed to import preset '{}': {}", bundle.name, e);
+        return Err(format!("Failed to import preset '{}': {}", bundle.name, e));
+    }
+    if !apply {
+        return Ok(bundle.name);
+    }
+
+    let gpu_ctx = gpu_context.lock().await;
+    let running_type = sim_manager
+        .current_simulation
+        .as_ref()
+        .map(|simulation| simulation.type_name());
+    if running_type != Some(bundle.simulation_type.as_str()) {
+        let surface_config = gpu_ctx.surface_config.lock().await.clone();
+        if let Err(e) = sim_manager
+            .start_simulation(
+                bundle.simulation_type.clone(),
+                &gpu_ctx.device,
+                &gpu_ctx.queue,
+                &surface_config,
+                &gpu_ctx.adapter_info,
+            )
+            .await
+        {
+            tracing::error!("Failed to start simulation for preset bundle: {}", e);
+            return Err(format!(
+                "Failed to start simulation for preset bundle: {}",
+                e
+            ));
+        }
+
+        sim_manager.start_render_loop(
+            app.clone(),
+            gpu_context.inner().clone(),
+            manager.inner().clone(),
+        );
+        if let Err(e) = app.emit("simulation-initialized", ()) {
+            tracing::warn!("Failed to emit simulation-initialized event: {}", e);
+        }
+    }
+
+    match sim_manager.apply_preset_bundle(&bundle, &gpu_ctx.device, &gpu_ctx.queue) {
+        Ok(()) => {
+            tracing::info!("Applied imported preset '{}'", bundle.name);
+            Ok(bundle.name)
+        }
+        Err(e) => {
+            tracing::error!("Failed to apply imported preset '{}': {}", bundle.name, e);
+            Err(format!(
+                "Failed to apply imported preset '{}': {}",
+                bundle.name, e
+            ))
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use crate::simulation::SimulationManager;
+use crate::simulation::recorder::{RecordingOptions, RecordingProgress};
+use std::sync::Arc;
+use tauri::{Emitter, State};
+
+#[tauri::command]
+pub async fn start_recording(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+    gpu_context: State<'_, Arc<tokio::sync::Mutex<crate::GpuContext>>>,
+    app: tauri::AppHandle,
+    options: RecordingOptions,
+) -> Result<String, String> {
+    let (device, surface_config) = {
+        let gpu_ctx = gpu_context.lock().await;
+        let surface_config = gpu_ctx.surface_config.lock().await.clone();
+        (gpu_ctx.device.clone(), surface_config)
+    };
+
+    let mut sim_manager = manager.lock().await;
+    match sim_manager.start_recording(options.clone(), &device, &surface_config) {
+        Ok(()) => {
+            if let Err(e) = app.emit("recording-started", &options) {
+                tracing::warn!("Failed to emit recording-started event: {}", e);
+            }
+            Ok("Recording started".to_string())
+        }
+        Err(e) => {
+            tracing::error!("Failed to start recording: {}", e);
+            Err(format!("Failed to start recording: {}", e))
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn stop_recording(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+    app: tauri::AppHandle,
+) -> Result<String, String> {
+    let mut sim_manager = manager.lock().await;
+    match sim_manager.stop_recording() {
+        Ok(Some(summary)) => {
+            let message = format!("Recorded {} frames", summary.frames_captured);
+            if let Err(e) = app.emit("recording-stopped", &summary) {
+                tracing::warn!("Failed to emit recording-stopped event: {}", e);
+            }
+            Ok(message)
+        }
+        Ok(None) => Ok("No recording in progress".to_string()),
+        Err(e) => {
+            tracing::error!("Failed to stop recording: {}", e);
+            Err(format!("Failed to stop recording: {}", e))
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_recording_progress(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+) -> Result<Option<RecordingProgress>, String> {
+    let sim_manager = manager.lock().await;
+    Ok(sim_manager
+        .recorder
+        .as_ref()
+        .map(|recorder| recorder.progress()))
+}
@@ -1,5 +1,6 @@
 use crate::simulation::SimulationManager;
 use crate::simulations::traits::Simulation;
+use std::path::Path;
 use std::sync::Arc;
 use tauri::State;
 
@@ -110,3 +111,36 @@ pub async fn handle_window_resize(
     tracing::trace!("Window resized to {}x{}", width, height);
     Ok(())
 }
+
+#[tauri::command]
+pub async fn capture_screenshot(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+    gpu_context: State<'_, Arc<tokio::sync::Mutex<crate::GpuContext>>>,
+    path: String,
+    scale: Option<u32>,
+) -> Result<String, String> {
+    let (device, queue, surface_config) = {
+        let gpu_ctx = gpu_context.lock().await;
+        let surface_config = gpu_ctx.surface_config.lock().await.clone();
+        (
+            gpu_ctx.device.clone(),
+            gpu_ctx.queue.clone(),
+            surface_config,
+        )
+    };
+
+    let mut sim_manager = manager.lock().await;
+    match sim_manager.capture_screenshot(
+        Path::new(&path),
+        scale.unwrap_or(1),
+        &device,
+        &queue,
+        &surface_config,
+    ) {
+        Ok((width, height)) => Ok(format!("Saved {}x{} screenshot to {}", width, height, path)),
+        Err(e) => {
+            tracing::error!("Failed to capture screenshot: {}", e);
+            Err(format!("Failed to capture screenshot: {}", e))
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use crate::simulation::SimulationManager;
+use crate::simulation::replay::InputLog;
+use crate::simulations::traits::Simulation;
+use std::path::Path;
+use std::sync::Arc;
+use tauri::State;
+
+/// Restart the current simulation in deterministic mode, logging mouse input so
+/// the run can be replayed. Returns the seed the run was started with.
+#[tauri::command]
+pub async fn start_deterministic_run(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+    gpu_context: State<'_, Arc<tokio::sync::Mutex<crate::GpuContext>>>,
+    seed: Option<u64>,
+    delta_time: Option<f32>,
+) -> Result<u64, String> {
+    let mut sim_manager = manager.lock().await;
+    let gpu_ctx = gpu_context.lock().await;
+    let surface_config = gpu_ctx.surface_config.lock().await.clone();
+
+    let Some(simulation) = sim_manager.current_simulation.as_ref() else {
+        return Err("No simulation running".to_string());
+    };
+    let seed = seed.unwrap_or_else(rand::random);
+    let mut log = InputLog::new(
+        simulation.type_name(),
+        seed,
+        delta_time.unwrap_or(1.0 / 60.0),
+    );
+    log.settings = Some(simulation.get_settings());
+
+    match sim_manager.start_deterministic_run(
+        log,
+        false,
+        &gpu_ctx.device,
+        &gpu_ctx.queue,
+        &surface_config,
+        &gpu_ctx.adapter_info,
+    ) {
+        Ok(()) => Ok(seed),
+        Err(e) => {
+            tracing::error!("Failed to start deterministic run: {}", e);
+            Err(format!("Failed to start deterministic run: {}", e))
+        }
+    }
+}
+
+/// Replay an input log saved by `stop_deterministic_run`
+#[tauri::command]
+pub async fn replay_input_log(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+    gpu_context: State<'_, Arc<tokio::sync::Mutex<crate::GpuContext>>>,
+    path: String,
+) -> Result<String, String> {
+    let log = InputLog::load(Path::new(&path)).map_err(|e| {
+        tracing::error!("Failed to load input log: {}", e);
+        format!("Failed to load input log: {}", e)
+    })?;
+    let event_count = log.events.len();
+
+    let mut sim_manager = manager.lock().await;
+    let gpu_ctx = gpu_context.lock().await;
+    let surface_config = gpu_ctx.surface_config.lock().await.clone();
+
+    match sim_manager.start_deterministic_run(
+        log,
+        true,
+        &gpu_ctx.device,
+        &gpu_ctx.queue,
+        &surface_config,
+        &gpu_ctx.adapter_info,
+    ) {
+        Ok(()) => Ok(format!("Replaying {} recorded inputs", event_count)),
+        Err(e) => {
+            tracing::error!("Failed to replay input log: {}", e);
+            Err(format!("Failed to replay input log: {}", e))
+        }
+    }
+}
+
+/// End the deterministic run, saving its input log to `path` if given
+#[tauri::command]
+pub async fn stop_deterministic_run(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+    path: Option<String>,
+) -> Result<String, String> {
+    let mut sim_manager = manager.lock().await;
+    let Some(log) = sim_manager.stop_deterministic_run() else {
+        return Ok("No deterministic run in progress".to_string());
+    };
+
+    match path {
+        Some(path) => match log.save(Path::new(&path)) {
+            Ok(()) => Ok(format!(
+                "Saved {} recorded inputs to {}",
+                log.events.len(),
+                path
+            )),
+            Err(e) => {
+                tracing::error!("Failed to save input log: {}", e);
+                Err(format!("Failed to save input log: {}", e))
+            }
+        },
+        None => Ok("Deterministic run stopped".to_string()),
+    }
+}
@@ -1,5 +1,7 @@
 use crate::GpuContext;
 use crate::simulation::SimulationManager;
+use crate::simulations::registry;
+use crate::simulations::shared::SettingField;
 use std::sync::Arc;
 use tauri::State;
 
@@ -48,6 +50,31 @@ pub async fn get_current_settings(
     }
 }
 
+/// Describe the settings of a simulation, defaulting to the running one
+#[tauri::command]
+pub async fn get_settings_schema(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+    simulation_type: Option<String>,
+) -> Result<Vec<SettingField>, String> {
+    let simulation_type = match simulation_type {
+        Some(simulation_type) => simulation_type,
+        None => {
+            let sim_manager = manager.lock().await;
+            match sim_manager.simulation() {
+                Some(simulation) => simulation.descriptor().id.to_string(),
+                None => return Err("No simulation running".to_string()),
+            }
+        }
+    };
+
+    registry::settings_schema(&simulation_type).map_err(|e| {
+        format!(
+            "Failed to get settings schema for {}: {}",
+            simulation_type, e
+        )
+    })
+}
+
 #[tauri::command]
 pub async fn get_current_state(
     manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
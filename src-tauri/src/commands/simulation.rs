@@ -1,4 +1,5 @@
 use crate::simulation::SimulationManager;
+use crate::simulations::registry::{self, SimulationInfo};
 use std::sync::Arc;
 use tauri::{Emitter, State};
 
@@ -284,6 +285,13 @@ pub async fn start_pellets_simulation(
     }
 }
 
+/// Simulations that can be started with `start_simulation`, with their
+/// display names and capabilities
+#[tauri::command]
+pub async fn list_simulations() -> Result<Vec<SimulationInfo>, String> {
+    Ok(registry::list())
+}
+
 #[tauri::command]
 pub async fn start_simulation(
     manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
@@ -0,0 +1,89 @@
+use crate::simulation::SimulationManager;
+use crate::simulations::shared::SimulationSnapshot;
+use std::path::Path;
+use std::sync::Arc;
+use tauri::{Emitter, State};
+
+/// Save the full state of the current simulation to `path`
+#[tauri::command]
+pub async fn save_snapshot(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+    gpu_context: State<'_, Arc<tokio::sync::Mutex<crate::GpuContext>>>,
+    path: String,
+) -> Result<String, String> {
+    let sim_manager = manager.lock().await;
+    let gpu_ctx = gpu_context.lock().await;
+
+    match sim_manager
+        .take_snapshot(&gpu_ctx.device, &gpu_ctx.queue)
+        .and_then(|snapshot| snapshot.save(Path::new(&path)))
+    {
+        Ok(()) => {
+            tracing::info!("Saved snapshot to {}", path);
+            Ok(format!("Snapshot saved to {}", path))
+        }
+        Err(e) => {
+            tracing::error!("Failed to save snapshot: {}", e);
+            Err(format!("Failed to save snapshot: {}", e))
+        }
+    }
+}
+
+/// Load a snapshot saved by `save_snapshot`, switching to its simulation type
+/// first if a different simulation is running
+#[tauri::command]
+pub async fn load_snapshot(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+    gpu_context: State<'_, Arc<tokio::sync::Mutex<crate::GpuContext>>>,
+    app: tauri::AppHandle,
+    path: String,
+) -> Result<String, String> {
+    let snapshot = SimulationSnapshot::load(Path::new(&path)).map_err(|e| {
+        tracing::error!("Failed to load snapshot: {}", e);
+        format!("Failed to load snapshot: {}", e)
+    })?;
+
+    let mut sim_manager = manager.lock().await;
+    let gpu_ctx = gpu_context.lock().await;
+
+    let running_type = sim_manager
+        .current_simulation
+        .as_ref()
+        .map(|simulation| simulation.type_name());
+    if running_type != Some(snapshot.simulation_type.as_str()) {
+        let surface_config = gpu_ctx.surface_config.lock().await.clone();
+        if let Err(e) = sim_manager
+            .start_simulation(
+                snapshot.simulation_type.clone(),
+                &gpu_ctx.device,
+                &gpu_ctx.queue,
+                &surface_config,
+                &gpu_ctx.adapter_info,
+            )
+            .await
+        {
+            tracing::error!("Failed to start simulation for snapshot: {}", e);
+            return Err(format!("Failed to start simulation for snapshot: {}", e));
+        }
+
+        sim_manager.start_render_loop(
+            app.clone(),
+            gpu_context.inner().clone(),
+            manager.inner().clone(),
+        );
+        if let Err(e) = app.emit("simulation-initialized", ()) {
+            tracing::warn!("Failed to emit simulation-initialized event: {}", e);
+        }
+    }
+
+    match sim_manager.restore_snapshot(&snapshot, &gpu_ctx.device, &gpu_ctx.queue) {
+        Ok(()) => {
+            tracing::info!("Loaded {} snapshot from {}", snapshot.simulation_type, path);
+            Ok(format!("Snapshot loaded from {}", path))
+        }
+        Err(e) => {
+            tracing::error!("Failed to restore snapshot: {}", e);
+            Err(format!("Failed to restore snapshot: {}", e))
+        }
+    }
+}
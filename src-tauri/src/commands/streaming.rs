@@ -0,0 +1,61 @@
+use crate::simulation::SimulationManager;
+use crate::simulation::stream::{FrameStream, StreamOptions, StreamStatus};
+use std::sync::Arc;
+use tauri::State;
+
+/// Start streaming the rendered output to local MJPEG and raw RGBA clients,
+/// replacing any stream already running
+#[tauri::command]
+pub async fn start_frame_stream(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+    gpu_context: State<'_, Arc<tokio::sync::Mutex<crate::GpuContext>>>,
+    options: StreamOptions,
+) -> Result<StreamStatus, String> {
+    let (device, format) = {
+        let gpu_ctx = gpu_context.lock().await;
+        let surface_config = gpu_ctx.surface_config.lock().await;
+        // The stream scales the composited surface texture down by sampling it
+        if !surface_config
+            .usage
+            .contains(wgpu::TextureUsages::TEXTURE_BINDING)
+        {
+            return Err("Streaming needs a window surface that can be sampled".to_string());
+        }
+        (gpu_ctx.device.clone(), surface_config.format)
+    };
+
+    let mut sim_manager = manager.lock().await;
+    // Free the ports of a running stream before binding them again
+    sim_manager.frame_stream = None;
+    match FrameStream::start(options, &device, format).await {
+        Ok(stream) => {
+            let status = stream.status();
+            sim_manager.frame_stream = Some(stream);
+            Ok(status)
+        }
+        Err(e) => {
+            tracing::error!("Failed to start frame stream: {}", e);
+            Err(format!("Failed to start frame stream: {}", e))
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn stop_frame_stream(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+) -> Result<String, String> {
+    let mut sim_manager = manager.lock().await;
+    match sim_manager.frame_stream.take() {
+        Some(_) => Ok("Frame stream stopped".to_string()),
+        None => Ok("No frame stream running".to_string()),
+    }
+}
+
+/// State of the frame stream, or `None` when nothing is streaming
+#[tauri::command]
+pub async fn get_frame_stream_status(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+) -> Result<Option<StreamStatus>, String> {
+    let sim_manager = manager.lock().await;
+    Ok(sim_manager.frame_stream.as_ref().map(FrameStream::status))
+}
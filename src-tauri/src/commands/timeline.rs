@@ -0,0 +1,55 @@
+use crate::simulation::SimulationManager;
+use crate::simulation::timeline::Timeline;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Arc;
+use tauri::State;
+
+/// Progress of the playing timeline
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineStatus {
+    pub time: f32,
+    pub duration: f32,
+}
+
+/// Load a timeline TOML file and play it on the running simulation
+#[tauri::command]
+pub async fn start_timeline(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+    path: String,
+) -> Result<String, String> {
+    let mut sim_manager = manager.lock().await;
+
+    match Timeline::load(Path::new(&path)).and_then(|timeline| sim_manager.start_timeline(timeline))
+    {
+        Ok(()) => {
+            tracing::info!("Playing timeline {}", path);
+            Ok(format!("Playing timeline {}", path))
+        }
+        Err(e) => {
+            tracing::error!("Failed to start timeline: {}", e);
+            Err(format!("Failed to start timeline: {}", e))
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn stop_timeline(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+) -> Result<String, String> {
+    let mut sim_manager = manager.lock().await;
+    sim_manager.stop_timeline();
+    Ok("Timeline stopped".to_string())
+}
+
+/// Progress of the playing timeline, or `None` when no timeline is playing
+#[tauri::command]
+pub async fn get_timeline_status(
+    manager: State<'_, Arc<tokio::sync::Mutex<SimulationManager>>>,
+) -> Result<Option<TimelineStatus>, String> {
+    let sim_manager = manager.lock().await;
+    Ok(sim_manager.timeline.as_ref().map(|player| TimelineStatus {
+        time: player.time(),
+        duration: player.timeline().duration(),
+    }))
+}
@@ -0,0 +1,442 @@
+//! # HTTP Control API
+//!
+//! Exposes the Tauri commands to scripts, stream decks and dashboards on the
+//! same machine. Each command is called as
+//!
+//! ```text
+//! POST /api/<command>
+//! Content-Type: application/json
+//!
+//! {"preset_name": "Default"}
+//! ```
+//!
+//! with the command's arguments in the body, named as in Rust or in the
+//! camelCase the frontend passes to `invoke`. The body may be empty for
+//! commands without arguments, but the JSON content type is always required
+//! so that web pages cannot trigger commands with cross-origin form posts.
+//! Results come back as `{"result": ...}`, failures as `{"error": "..."}`.
+//!
+//! `GET /api/commands` lists the available commands, and `GET /api/events`
+//! upgrades to a WebSocket that streams app events such as `fps-update` as
+//! `{"event": "fps-update", "payload": 60}`.
+//!
+//! The server only listens on the loopback interface. That alone does not
+//! keep web pages out: a page on a domain that resolves to 127.0.0.1 (DNS
+//! rebinding) reaches the server as same-origin. Requests must therefore name
+//! the server as `127.0.0.1:<port>` or `localhost:<port>` in their `Host`
+//! header, and WebSocket upgrades from a browser must come from a page served
+//! by one of those origins.
+
+use std::net::SocketAddr;
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, Request, State};
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use tauri::{AppHandle, Listener, Manager};
+use tokio::sync::broadcast;
+
+use crate::commands::{self, AppSettings};
+
+/// Commands reachable over HTTP, by the name used with `invoke`
+pub const COMMANDS: &[&str] = &[
+    "list_simulations",
+    "start_simulation",
+    "pause_simulation",
+    "resume_simulation",
+    "destroy_simulation",
+    "get_simulation_status",
+    "update_simulation_setting",
+    "get_current_settings",
+    "get_settings_schema",
+    "randomize_settings",
+    "get_available_presets",
+    "get_presets_for_simulation_type",
+    "apply_preset",
+    "get_available_luts",
+    "apply_lut",
+    "toggle_lut_reversed",
+    "pan_camera",
+    "zoom_camera",
+    "reset_camera",
+    "get_camera_state",
+    "reset_trails",
+    "reset_agents",
+    "reset_simulation",
+    "undo",
+    "redo",
+];
+
+/// App events forwarded to WebSocket clients
+const FORWARDED_EVENTS: &[&str] = &[
+    "fps-update",
+    "simulation-initialized",
+    "simulation-resumed",
+    "recording-started",
+    "recording-progress",
+    "recording-stopped",
+    "recording-error",
+];
+
+/// Events buffered per WebSocket client before the slowest start missing some
+const EVENT_BUFFER: usize = 64;
+
+/// An app event as sent to WebSocket clients
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ControlEvent {
+    pub event: String,
+    pub payload: serde_json::Value,
+}
+
+#[derive(Clone)]
+struct ServerState {
+    app: AppHandle,
+    events: broadcast::Sender<ControlEvent>,
+    port: u16,
+}
+
+/// Start the HTTP server in the background if it is enabled in `settings`
+pub fn start(settings: &AppSettings, app: AppHandle) {
+    if !settings.http_api_enabled {
+        return;
+    }
+    let address = SocketAddr::from(([127, 0, 0, 1], settings.http_api_port));
+
+    let (events, _) = broadcast::channel(EVENT_BUFFER);
+    for &name in FORWARDED_EVENTS {
+        let events = events.clone();
+        app.listen_any(name, move |event| {
+            let payload = serde_json::from_str(event.payload()).unwrap_or_default();
+            // Sending only fails when no client is connected
+            let _ = events.send(ControlEvent {
+                event: name.to_string(),
+                payload,
+            });
+        });
+    }
+    let router = router(ServerState {
+        app,
+        events,
+        port: settings.http_api_port,
+    });
+
+    tauri::async_runtime::spawn(async move {
+        let listener = match tokio::net::TcpListener::bind(address).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                tracing::error!("Failed to start HTTP API on {}: {}", address, e);
+                return;
+            }
+        };
+        tracing::info!("HTTP API listening on http://{}", address);
+        if let Err(e) = axum::serve(listener, router).await {
+            tracing::error!("HTTP API stopped: {}", e);
+        }
+    });
+}
+
+fn router(state: ServerState) -> Router {
+    Router::new()
+        .route("/api/commands", get(list_commands))
+        .route("/api/events", get(stream_events))
+        .route("/api/{command}", post(invoke))
+        .layer(middleware::from_fn_with_state(state.clone(), check_host))
+        .with_state(state)
+}
+
+/// Reject requests addressed to any host but this server, which is what a
+/// DNS rebinding page sends
+async fn check_host(State(state): State<ServerState>, request: Request, next: Next) -> Response {
+    let host = request
+        .headers()
+        .get(header::HOST)
+        .and_then(|value| value.to_str().ok());
+    if !host.is_some_and(|host| is_local_host(host, state.port)) {
+        return error(
+            StatusCode::FORBIDDEN,
+            format!(
+                "Expected Host: 127.0.0.1:{} or localhost:{}",
+                state.port, state.port
+            ),
+        );
+    }
+    next.run(request).await
+}
+
+/// Whether `host` names this server, as `127.0.0.1:<port>` or `localhost:<port>`
+fn is_local_host(host: &str, port: u16) -> bool {
+    let Some((name, host_port)) = host.rsplit_once(':') else {
+        return false;
+    };
+    host_port
+        .parse::<u16>()
+        .is_ok_and(|host_port| host_port == port)
+        && (name == "127.0.0.1" || name.eq_ignore_ascii_case("localhost"))
+}
+
+/// Whether a browser `Origin` is a page served by this server
+fn is_local_origin(origin: &str, port: u16) -> bool {
+    origin
+        .strip_prefix("http://")
+        .is_some_and(|host| is_local_host(host, port))
+}
+
+async fn list_commands() -> Response {
+    axum::Json(COMMANDS).into_response()
+}
+
+async fn invoke(
+    State(state): State<ServerState>,
+    Path(command): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let is_json = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    if !is_json {
+        return error(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "Expected Content-Type: application/json".to_string(),
+        );
+    }
+    if !COMMANDS.contains(&command.as_str()) {
+        return error(
+            StatusCode::NOT_FOUND,
+            format!("Unknown command {}", command),
+        );
+    }
+    let args = match Args::parse(&body) {
+        Ok(args) => args,
+        Err(e) => return error(StatusCode::BAD_REQUEST, e),
+    };
+
+    match dispatch(&state.app, &command, &args).await {
+        Ok(result) => axum::Json(json!({ "result": result })).into_response(),
+        Err(e) => error(StatusCode::BAD_REQUEST, e),
+    }
+}
+
+fn error(status: StatusCode, message: String) -> Response {
+    (status, axum::Json(json!({ "error": message }))).into_response()
+}
+
+fn reply<T: Serialize>(result: Result<T, String>) -> Result<serde_json::Value, String> {
+    result.and_then(|value| serde_json::to_value(value).map_err(|e| e.to_string()))
+}
+
+/// Call the Tauri command named `command` with the app's managed state
+async fn dispatch(
+    app: &AppHandle,
+    command: &str,
+    args: &Args,
+) -> Result<serde_json::Value, String> {
+    match command {
+        "list_simulations" => reply(commands::list_simulations().await),
+        "start_simulation" => reply(
+            commands::start_simulation(
+                app.state(),
+                app.state(),
+                app.clone(),
+                args.get("simulation_type")?,
+            )
+            .await,
+        ),
+        "pause_simulation" => reply(commands::pause_simulation(app.state()).await),
+        "resume_simulation" => reply(commands::resume_simulation(app.state(), app.clone()).await),
+        "destroy_simulation" => reply(commands::destroy_simulation(app.state()).await),
+        "get_simulation_status" => reply(commands::get_simulation_status(app.state()).await),
+        "update_simulation_setting" => reply(
+            commands::update_simulation_setting(
+                app.state(),
+                app.state(),
+                args.get("setting_name")?,
+                args.get("value")?,
+            )
+            .await,
+        ),
+        "get_current_settings" => reply(commands::get_current_settings(app.state()).await),
+        "get_settings_schema" => {
+            reply(commands::get_settings_schema(app.state(), args.get("simulation_type")?).await)
+        }
+        "randomize_settings" => reply(commands::randomize_settings(app.state(), app.state()).await),
+        "get_available_presets" => reply(commands::get_available_presets(app.state()).await),
+        "get_presets_for_simulation_type" => reply(
+            commands::get_presets_for_simulation_type(app.state(), args.get("simulation_type")?)
+                .await,
+        ),
+        "apply_preset" => {
+            reply(commands::apply_preset(app.state(), app.state(), args.get("preset_name")?).await)
+        }
+        "get_available_luts" => reply(commands::get_available_luts(app.state()).await),
+        "apply_lut" => {
+            reply(commands::apply_lut(app.state(), app.state(), args.get("lut_name")?).await)
+        }
+        "toggle_lut_reversed" => {
+            reply(commands::toggle_lut_reversed(app.state(), app.state()).await)
+        }
+        "pan_camera" => reply(
+            commands::pan_camera(app.state(), args.get("delta_x")?, args.get("delta_y")?).await,
+        ),
+        "zoom_camera" => reply(commands::zoom_camera(app.state(), args.get("delta")?).await),
+        "reset_camera" => reply(commands::reset_camera(app.state()).await),
+        "get_camera_state" => reply(commands::get_camera_state(app.state()).await),
+        "reset_trails" => reply(commands::reset_trails(app.state(), app.state()).await),
+        "reset_agents" => reply(commands::reset_agents(app.state(), app.state()).await),
+        "reset_simulation" => reply(commands::reset_simulation(app.state(), app.state()).await),
+        "undo" => reply(commands::undo(app.state(), app.state()).await),
+        "redo" => reply(commands::redo(app.state(), app.state()).await),
+        _ => Err(format!("Unknown command {}", command)),
+    }
+}
+
+/// Arguments of a command call
+#[derive(Debug, Default)]
+struct Args(serde_json::Map<String, serde_json::Value>);
+
+impl Args {
+    /// Parse a request body, treating an empty body as no arguments
+    fn parse(body: &[u8]) -> Result<Self, String> {
+        if body.iter().all(u8::is_ascii_whitespace) {
+            return Ok(Self::default());
+        }
+        serde_json::from_slice(body)
+            .map(Self)
+            .map_err(|e| format!("Expected a JSON object of arguments: {}", e))
+    }
+
+    /// Argument `name`, also accepted in camelCase. Missing arguments are
+    /// read as null, so optional ones may be left out.
+    fn get<T: DeserializeOwned>(&self, name: &str) -> Result<T, String> {
+        let value = self
+            .0
+            .get(name)
+            .or_else(|| self.0.get(&camel_case(name)))
+            .cloned()
+            .unwrap_or_default();
+        serde_json::from_value(value).map_err(|e| format!("Invalid argument {}: {}", name, e))
+    }
+}
+
+fn camel_case(name: &str) -> String {
+    let mut parts = name.split('_');
+    let mut camel = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            camel.extend(first.to_uppercase());
+            camel.push_str(chars.as_str());
+        }
+    }
+    camel
+}
+
+/// Forward app events to a WebSocket client until it disconnects. Browsers
+/// send an `Origin` with the upgrade, which must be this server; other
+/// clients such as scripts send none.
+async fn stream_events(
+    State(state): State<ServerState>,
+    headers: HeaderMap,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    if let Some(origin) = headers.get(header::ORIGIN)
+        && !origin
+            .to_str()
+            .is_ok_and(|origin| is_local_origin(origin, state.port))
+    {
+        return error(
+            StatusCode::FORBIDDEN,
+            "WebSocket connections are only accepted from this machine".to_string(),
+        );
+    }
+    let events = state.events.subscribe();
+    upgrade.on_upgrade(move |socket| forward_events(socket, events))
+}
+
+async fn forward_events(mut socket: WebSocket, mut events: broadcast::Receiver<ControlEvent>) {
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    let Ok(text) = serde_json::to_string(&event) else {
+                        continue;
+                    };
+                    if socket.send(Message::Text(text.into())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::debug!("WebSocket client missed {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_camel_case() {
+        assert_eq!(camel_case("preset_name"), "presetName");
+        assert_eq!(camel_case("delta_x"), "deltaX");
+        assert_eq!(camel_case("value"), "value");
+    }
+
+    #[test]
+    fn test_only_local_hosts_and_origins_are_allowed() {
+        assert!(is_local_host("127.0.0.1:8765", 8765));
+        assert!(is_local_host("localhost:8765", 8765));
+        assert!(is_local_host("LocalHost:8765", 8765));
+        assert!(!is_local_host("127.0.0.1:8766", 8765));
+        assert!(!is_local_host("127.0.0.1", 8765));
+        assert!(!is_local_host("rebound.example.com:8765", 8765));
+        assert!(!is_local_host("localhost.example.com:8765", 8765));
+
+        assert!(is_local_origin("http://localhost:8765", 8765));
+        assert!(is_local_origin("http://127.0.0.1:8765", 8765));
+        assert!(!is_local_origin("http://rebound.example.com:8765", 8765));
+        assert!(!is_local_origin("https://localhost:8765", 8765));
+        assert!(!is_local_origin("null", 8765));
+    }
+
+    #[test]
+    fn test_args() {
+        let args = Args::parse(br#"{"setting_name": "feed_rate", "deltaX": 1.5}"#).unwrap();
+        assert_eq!(args.get::<String>("setting_name").unwrap(), "feed_rate");
+        assert_eq!(args.get::<f32>("delta_x").unwrap(), 1.5);
+        assert_eq!(args.get::<Option<String>>("simulation_type").unwrap(), None);
+        assert!(args.get::<String>("preset_name").is_err());
+        assert!(args.get::<f32>("setting_name").is_err());
+
+        assert!(Args::parse(b"").unwrap().0.is_empty());
+        assert!(Args::parse(b" \n").unwrap().0.is_empty());
+        assert!(Args::parse(b"[1, 2]").is_err());
+    }
+
+    #[test]
+    fn test_events_serialize_like_tauri_payloads() {
+        let event = ControlEvent {
+            event: "fps-update".to_string(),
+            payload: json!(60),
+        };
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            json!({ "event": "fps-update", "payload": 60 })
+        );
+    }
+}
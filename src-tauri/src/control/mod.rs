@@ -0,0 +1,8 @@
+//! # Remote Control
+//!
+//! Servers that let other programs drive a running instance alongside the
+//! GUI. Each is optional, enabled in `AppSettings`, and acts on the same
+//! `SimulationManager` the Tauri commands use.
+
+pub mod http;
+pub mod osc;
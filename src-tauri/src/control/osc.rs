@@ -0,0 +1,651 @@
+//! # OSC Control
+//!
+//! Listens for Open Sound Control messages over UDP so that controllers and
+//! VJ software can drive Vizza during a live set. Addresses map onto
+//! `SimulationManager` calls:
+//!
+//! | Address                        | Arguments      | Action                        |
+//! |--------------------------------|----------------|-------------------------------|
+//! | `/vizza/<simulation>/<setting>`| value          | Update a setting              |
+//! | `/vizza/preset/apply`          | name           | Apply a preset                |
+//! | `/vizza/lut/apply`             | name           | Apply a LUT                   |
+//! | `/vizza/lut/reverse`           |                | Reverse the current LUT       |
+//! | `/vizza/camera/zoom`           | delta          | Zoom the camera               |
+//! | `/vizza/camera/pan`            | delta x, y     | Pan the camera                |
+//! | `/vizza/camera/reset`          |                | Reset the camera              |
+//! | `/vizza/pause`, `/vizza/resume`|                | Pause or resume               |
+//! | `/vizza/reset`                 |                | Reset the simulation          |
+//!
+//! Setting messages only apply while that simulation is running, so one
+//! controller layout can carry pages for several simulations. Buttons send
+//! 1 when pressed and 0 when released; trigger addresses ignore the release.
+//!
+//! The server listens on the loopback interface unless another bind address
+//! is configured. OSC has no authentication, so listening on the network lets
+//! anyone who can reach the port control the app.
+//!
+//! When a feedback address is configured, the frame rate, running simulation
+//! and current preset are sent there as `/vizza/fps`, `/vizza/simulation` and
+//! `/vizza/preset` once a second.
+
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use rosc::{OscMessage, OscPacket, OscType};
+use serde_json::json;
+use tokio::net::UdpSocket;
+use tokio::sync::Mutex;
+
+use crate::GpuContext;
+use crate::commands::AppSettings;
+use crate::error::{AppResult, SimulationError, SimulationResult};
+use crate::simulation::SimulationManager;
+use crate::simulations::registry;
+use crate::simulations::shared::settings_schema::SettingKind;
+
+/// Prefix shared by every address Vizza responds to
+const ADDRESS_PREFIX: &str = "/vizza/";
+
+/// Largest datagram accepted
+const MAX_PACKET_SIZE: usize = 65_536;
+
+/// How often feedback is sent
+const FEEDBACK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// An action requested over OSC
+#[derive(Debug, Clone, PartialEq)]
+pub enum OscCommand {
+    UpdateSetting {
+        simulation_type: String,
+        setting_name: String,
+        value: serde_json::Value,
+    },
+    ApplyPreset(String),
+    ApplyLut(String),
+    ReverseLut,
+    ZoomCamera(f32),
+    PanCamera {
+        delta_x: f32,
+        delta_y: f32,
+    },
+    ResetCamera,
+    Pause,
+    Resume,
+    Reset,
+}
+
+impl OscCommand {
+    /// Interpret a message. Returns `None` for messages that should be
+    /// ignored, such as a button being released.
+    pub fn parse(message: &OscMessage) -> SimulationResult<Option<Self>> {
+        let path = message.addr.strip_prefix(ADDRESS_PREFIX).ok_or_else(|| {
+            SimulationError::InvalidParameter(format!("Unknown OSC address {}", message.addr))
+        })?;
+        let parts = path.split('/').collect::<Vec<_>>();
+
+        let trigger = |command| Ok((!is_release(&message.args)).then_some(command));
+        match parts.as_slice() {
+            ["preset", "apply"] => Ok(Some(OscCommand::ApplyPreset(string_arg(message)?))),
+            ["lut", "apply"] => Ok(Some(OscCommand::ApplyLut(string_arg(message)?))),
+            ["lut", "reverse"] => trigger(OscCommand::ReverseLut),
+            ["camera", "zoom"] => Ok(Some(OscCommand::ZoomCamera(float_arg(message, 0)?))),
+            ["camera", "pan"] => Ok(Some(OscCommand::PanCamera {
+                delta_x: float_arg(message, 0)?,
+                delta_y: float_arg(message, 1)?,
+            })),
+            ["camera", "reset"] => trigger(OscCommand::ResetCamera),
+            ["pause"] => trigger(OscCommand::Pause),
+            ["resume"] => trigger(OscCommand::Resume),
+            ["reset"] => trigger(OscCommand::Reset),
+            [simulation_type, setting_name] => {
+                registry::get(simulation_type)?;
+                let value = match message.args.as_slice() {
+                    [] => {
+                        return Err(SimulationError::InvalidParameter(format!(
+                            "{} needs a value",
+                            message.addr
+                        )));
+                    }
+                    [arg] => to_json(arg)?,
+                    args => serde_json::Value::Array(
+                        args.iter().map(to_json).collect::<SimulationResult<_>>()?,
+                    ),
+                };
+                let (setting_name, value) = coerce_setting(simulation_type, setting_name, value);
+                Ok(Some(OscCommand::UpdateSetting {
+                    simulation_type: simulation_type.to_string(),
+                    setting_name,
+                    value,
+                }))
+            }
+            _ => Err(SimulationError::InvalidParameter(format!(
+                "Unknown OSC address {}",
+                message.addr
+            ))),
+        }
+    }
+}
+
+/// Whether a trigger message is a button release, i.e. a zero or false
+fn is_release(args: &[OscType]) -> bool {
+    match args.first() {
+        Some(OscType::Float(value)) => *value == 0.0,
+        Some(OscType::Double(value)) => *value == 0.0,
+        Some(OscType::Int(value)) => *value == 0,
+        Some(OscType::Long(value)) => *value == 0,
+        Some(OscType::Bool(value)) => !value,
+        _ => false,
+    }
+}
+
+fn string_arg(message: &OscMessage) -> SimulationResult<String> {
+    match message.args.first() {
+        Some(OscType::String(value)) => Ok(value.clone()),
+        _ => Err(SimulationError::InvalidParameter(format!(
+            "{} needs a string argument",
+            message.addr
+        ))),
+    }
+}
+
+fn float_arg(message: &OscMessage, index: usize) -> SimulationResult<f32> {
+    match message.args.get(index) {
+        Some(OscType::Float(value)) => Ok(*value),
+        Some(OscType::Double(value)) => Ok(*value as f32),
+        Some(OscType::Int(value)) => Ok(*value as f32),
+        Some(OscType::Long(value)) => Ok(*value as f32),
+        _ => Err(SimulationError::InvalidParameter(format!(
+            "{} needs a number as argument {}",
+            message.addr,
+            index + 1
+        ))),
+    }
+}
+
+fn to_json(arg: &OscType) -> SimulationResult<serde_json::Value> {
+    match arg {
+        OscType::Float(value) => Ok(json!(*value as f64)),
+        OscType::Double(value) => Ok(json!(value)),
+        OscType::Int(value) => Ok(json!(value)),
+        OscType::Long(value) => Ok(json!(value)),
+        OscType::Bool(value) => Ok(json!(value)),
+        OscType::String(value) => Ok(json!(value)),
+        other => Err(SimulationError::InvalidParameter(format!(
+            "Unsupported OSC argument {:?}",
+            other
+        ))),
+    }
+}
+
+/// Resolve a setting addressed by its field name to its `update_setting`
+/// name, and convert the floats most controllers send for boolean and
+/// integer settings
+fn coerce_setting(
+    simulation_type: &str,
+    setting_name: &str,
+    value: serde_json::Value,
+) -> (String, serde_json::Value) {
+    let Ok(schema) = registry::settings_schema(simulation_type) else {
+        return (setting_name.to_string(), value);
+    };
+    let Some(field) = schema
+        .iter()
+        .find(|field| field.name == setting_name || field.setting_name == setting_name)
+    else {
+        return (setting_name.to_string(), value);
+    };
+
+    let value = match (&field.kind, value.as_f64()) {
+        (SettingKind::Bool, Some(number)) => json!(number != 0.0),
+        (SettingKind::Integer { .. }, Some(number)) => json!(number.round() as i64),
+        _ => value,
+    };
+    (field.setting_name.to_string(), value)
+}
+
+/// Status sent back to the feedback address
+#[derive(Debug, Clone, PartialEq)]
+pub struct OscFeedback {
+    pub fps: u32,
+    pub simulation: Option<String>,
+    pub preset: Option<String>,
+}
+
+impl OscFeedback {
+    fn messages(&self) -> Vec<OscMessage> {
+        let message = |name: &str, arg| OscMessage {
+            addr: format!("{}{}", ADDRESS_PREFIX, name),
+            args: vec![arg],
+        };
+        vec![
+            message("fps", OscType::Int(self.fps as i32)),
+            message(
+                "simulation",
+                OscType::String(self.simulation.clone().unwrap_or_default()),
+            ),
+            message(
+                "preset",
+                OscType::String(self.preset.clone().unwrap_or_default()),
+            ),
+        ]
+    }
+}
+
+/// UDP socket receiving OSC commands and sending feedback
+pub struct OscServer {
+    socket: UdpSocket,
+    feedback_address: Option<SocketAddr>,
+}
+
+impl OscServer {
+    pub async fn bind(
+        address: SocketAddr,
+        feedback_address: Option<SocketAddr>,
+    ) -> AppResult<Self> {
+        Ok(Self {
+            socket: UdpSocket::bind(address).await?,
+            feedback_address,
+        })
+    }
+
+    pub fn local_addr(&self) -> AppResult<SocketAddr> {
+        Ok(self.socket.local_addr()?)
+    }
+
+    /// Receive messages until the socket fails, passing each command to
+    /// `handle`. Malformed messages and failed commands are logged and
+    /// skipped.
+    pub async fn run<F, Fut>(&self, mut handle: F) -> AppResult<()>
+    where
+        F: FnMut(OscCommand) -> Fut,
+        Fut: Future<Output = AppResult<()>>,
+    {
+        let mut buffer = vec![0; MAX_PACKET_SIZE];
+        loop {
+            let (length, from) = self.socket.recv_from(&mut buffer).await?;
+            let packet = match rosc::decoder::decode_udp(&buffer[..length]) {
+                Ok((_, packet)) => packet,
+                Err(e) => {
+                    tracing::warn!("Ignoring malformed OSC packet from {}: {}", from, e);
+                    continue;
+                }
+            };
+
+            for message in flatten(packet) {
+                match OscCommand::parse(&message) {
+                    Ok(Some(command)) => {
+                        if let Err(e) = handle(command).await {
+                            tracing::warn!("OSC {} failed: {}", message.addr, e);
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => tracing::warn!("Ignoring OSC message from {}: {}", from, e),
+                }
+            }
+        }
+    }
+
+    /// Send `feedback` to the feedback address, if one is configured
+    pub async fn send_feedback(&self, feedback: &OscFeedback) -> AppResult<()> {
+        let Some(address) = self.feedback_address else {
+            return Ok(());
+        };
+        for message in feedback.messages() {
+            let packet = rosc::encoder::encode(&OscPacket::Message(message))
+                .map_err(|e| SimulationError::InvalidParameter(e.to_string()))?;
+            self.socket.send_to(&packet, address).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Messages of a packet, including those nested in bundles
+fn flatten(packet: OscPacket) -> Vec<OscMessage> {
+    match packet {
+        OscPacket::Message(message) => vec![message],
+        OscPacket::Bundle(bundle) => bundle.content.into_iter().flat_map(flatten).collect(),
+    }
+}
+
+/// Start the OSC server in the background if it is enabled in `settings`
+pub fn start(
+    settings: &AppSettings,
+    manager: Arc<Mutex<SimulationManager>>,
+    gpu_context: Arc<Mutex<GpuContext>>,
+) {
+    if !settings.osc_enabled {
+        return;
+    }
+    let bind_address = match settings.osc_bind_address.trim().parse::<IpAddr>() {
+        Ok(bind_address) => bind_address,
+        Err(e) => {
+            tracing::error!(
+                "Not starting OSC server, invalid bind address {:?}: {}",
+                settings.osc_bind_address,
+                e
+            );
+            return;
+        }
+    };
+    let address = SocketAddr::new(bind_address, settings.osc_port);
+    let feedback_address = settings.osc_feedback_address.trim().to_string();
+
+    tauri::async_runtime::spawn(async move {
+        let feedback_address = if feedback_address.is_empty() {
+            None
+        } else {
+            match tokio::net::lookup_host(&feedback_address).await {
+                Ok(mut addresses) => addresses.next(),
+                Err(e) => {
+                    tracing::warn!(
+                        "Not sending OSC feedback, could not resolve {}: {}",
+                        feedback_address,
+                        e
+                    );
+                    None
+                }
+            }
+        };
+
+        let server = match OscServer::bind(address, feedback_address).await {
+            Ok(server) => Arc::new(server),
+            Err(e) => {
+                tracing::error!("Failed to start OSC server on {}: {}", address, e);
+                return;
+            }
+        };
+        tracing::info!("OSC server listening on {}", address);
+
+        if feedback_address.is_some() {
+            let server = server.clone();
+            let manager = manager.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(FEEDBACK_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    let feedback = {
+                        let sim_manager = manager.lock().await;
+                        OscFeedback {
+                            fps: sim_manager.fps.load(Ordering::Relaxed),
+                            simulation: sim_manager
+                                .simulation()
+                                .map(|simulation| simulation.type_name().to_string()),
+                            preset: sim_manager.current_preset.clone(),
+                        }
+                    };
+                    if let Err(e) = server.send_feedback(&feedback).await {
+                        tracing::warn!("Failed to send OSC feedback: {}", e);
+                    }
+                }
+            });
+        }
+
+        let result = server
+            .run(move |command| {
+                let manager = manager.clone();
+                let gpu_context = gpu_context.clone();
+                async move { apply(command, &manager, &gpu_context).await }
+            })
+            .await;
+        if let Err(e) = result {
+            tracing::error!("OSC server stopped: {}", e);
+        }
+    });
+}
+
+/// Carry out `command` on the simulation manager
+async fn apply(
+    command: OscCommand,
+    manager: &Mutex<SimulationManager>,
+    gpu_context: &Mutex<GpuContext>,
+) -> AppResult<()> {
+    let mut sim_manager = manager.lock().await;
+    let gpu_ctx = gpu_context.lock().await;
+    let (device, queue) = (&gpu_ctx.device, &gpu_ctx.queue);
+
+    match command {
+        OscCommand::UpdateSetting {
+            simulation_type,
+            setting_name,
+            value,
+        } => {
+            let running = sim_manager
+                .simulation()
+                .ok_or(SimulationError::NotRunning)?
+                .type_name();
+            if running != simulation_type {
+                tracing::debug!(
+                    "Ignoring OSC setting for {} while {} is running",
+                    simulation_type,
+                    running
+                );
+                return Ok(());
+            }
+            sim_manager.update_setting(&setting_name, value, device, queue)
+        }
+        OscCommand::ApplyPreset(preset_name) => {
+            sim_manager.apply_preset(&preset_name, device, queue)
+        }
+        OscCommand::ApplyLut(lut_name) => sim_manager.apply_lut(&lut_name, device, queue),
+        OscCommand::ReverseLut => sim_manager.reverse_current_lut(device, queue),
+        OscCommand::ZoomCamera(delta) => {
+            sim_manager.zoom_camera(delta);
+            Ok(())
+        }
+        OscCommand::PanCamera { delta_x, delta_y } => {
+            sim_manager.pan_camera(delta_x, delta_y);
+            Ok(())
+        }
+        OscCommand::ResetCamera => {
+            sim_manager.reset_camera();
+            Ok(())
+        }
+        OscCommand::Pause => {
+            sim_manager.pause();
+            Ok(())
+        }
+        OscCommand::Resume => {
+            sim_manager.resume();
+            Ok(())
+        }
+        OscCommand::Reset => sim_manager.reset_simulation(device, queue),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(addr: &str, args: Vec<OscType>) -> OscMessage {
+        OscMessage {
+            addr: addr.to_string(),
+            args,
+        }
+    }
+
+    fn parse(addr: &str, args: Vec<OscType>) -> Option<OscCommand> {
+        OscCommand::parse(&message(addr, args)).unwrap()
+    }
+
+    #[test]
+    fn test_parses_addresses() {
+        assert_eq!(
+            parse(
+                "/vizza/slime_mold/agent_turn_rate",
+                vec![OscType::Float(0.5)]
+            ),
+            Some(OscCommand::UpdateSetting {
+                simulation_type: "slime_mold".to_string(),
+                setting_name: "agent_turn_rate".to_string(),
+                value: json!(0.5),
+            })
+        );
+        assert_eq!(
+            parse(
+                "/vizza/preset/apply",
+                vec![OscType::String("Default".into())]
+            ),
+            Some(OscCommand::ApplyPreset("Default".to_string()))
+        );
+        assert_eq!(
+            parse("/vizza/camera/zoom", vec![OscType::Double(-0.25)]),
+            Some(OscCommand::ZoomCamera(-0.25))
+        );
+        assert_eq!(
+            parse(
+                "/vizza/camera/pan",
+                vec![OscType::Int(3), OscType::Float(4.0)]
+            ),
+            Some(OscCommand::PanCamera {
+                delta_x: 3.0,
+                delta_y: 4.0
+            })
+        );
+
+        for (addr, args) in [
+            (
+                "/other/preset/apply",
+                vec![OscType::String("Default".into())],
+            ),
+            ("/vizza/preset/apply", vec![OscType::Float(1.0)]),
+            ("/vizza/not_a_simulation/speed", vec![OscType::Float(1.0)]),
+            ("/vizza/slime_mold/agent_turn_rate", vec![]),
+            ("/vizza/camera/pan", vec![OscType::Float(1.0)]),
+        ] {
+            assert!(OscCommand::parse(&message(addr, args)).is_err(), "{addr}");
+        }
+    }
+
+    #[test]
+    fn test_triggers_ignore_release() {
+        assert_eq!(parse("/vizza/reset", vec![]), Some(OscCommand::Reset));
+        assert_eq!(
+            parse("/vizza/camera/reset", vec![OscType::Float(1.0)]),
+            Some(OscCommand::ResetCamera)
+        );
+        assert_eq!(
+            parse("/vizza/camera/reset", vec![OscType::Float(0.0)]),
+            None
+        );
+        assert_eq!(parse("/vizza/pause", vec![OscType::Bool(false)]), None);
+    }
+
+    #[test]
+    fn test_controller_floats_become_settings_values() {
+        let Some(OscCommand::UpdateSetting { value, .. }) =
+            parse("/vizza/particle_life/wrap_edges", vec![OscType::Float(1.0)])
+        else {
+            panic!("Expected a setting update");
+        };
+        assert_eq!(value, json!(true));
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_over_udp() {
+        let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let server = Arc::new(
+            OscServer::bind(
+                "127.0.0.1:0".parse().unwrap(),
+                Some(client.local_addr().unwrap()),
+            )
+            .await
+            .unwrap(),
+        );
+        let server_address = server.local_addr().unwrap();
+
+        let (sender, mut received) = tokio::sync::mpsc::unbounded_channel();
+        let running = server.clone();
+        tokio::spawn(async move {
+            running
+                .run(move |command| {
+                    let sender = sender.clone();
+                    async move {
+                        sender.send(command).unwrap();
+                        Ok(())
+                    }
+                })
+                .await
+        });
+
+        // A bundle carrying a setting change and a LUT, then a stray message
+        let bundle = OscPacket::Bundle(rosc::OscBundle {
+            timetag: rosc::OscTime {
+                seconds: 0,
+                fractional: 1,
+            },
+            content: vec![
+                OscPacket::Message(message(
+                    "/vizza/slime_mold/agent_speed_max",
+                    vec![OscType::Float(120.0)],
+                )),
+                OscPacket::Message(message(
+                    "/vizza/lut/apply",
+                    vec![OscType::String("MATPLOTLIB_viridis".into())],
+                )),
+            ],
+        });
+        for packet in [
+            bundle,
+            OscPacket::Message(message("/unrelated", vec![])),
+            OscPacket::Message(message("/vizza/resume", vec![])),
+        ] {
+            let bytes = rosc::encoder::encode(&packet).unwrap();
+            client.send_to(&bytes, server_address).await.unwrap();
+        }
+
+        let mut commands = Vec::new();
+        for _ in 0..3 {
+            let command = tokio::time::timeout(Duration::from_secs(5), received.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            commands.push(command);
+        }
+        assert_eq!(
+            commands,
+            vec![
+                OscCommand::UpdateSetting {
+                    simulation_type: "slime_mold".to_string(),
+                    setting_name: "agent_speed_max".to_string(),
+                    value: json!(120.0),
+                },
+                OscCommand::ApplyLut("MATPLOTLIB_viridis".to_string()),
+                OscCommand::Resume,
+            ]
+        );
+
+        server
+            .send_feedback(&OscFeedback {
+                fps: 60,
+                simulation: Some("slime_mold".to_string()),
+                preset: None,
+            })
+            .await
+            .unwrap();
+        let mut buffer = vec![0; MAX_PACKET_SIZE];
+        let mut feedback = Vec::new();
+        for _ in 0..3 {
+            let (length, _) =
+                tokio::time::timeout(Duration::from_secs(5), client.recv_from(&mut buffer))
+                    .await
+                    .unwrap()
+                    .unwrap();
+            let (_, packet) = rosc::decoder::decode_udp(&buffer[..length]).unwrap();
+            feedback.extend(flatten(packet));
+        }
+        assert_eq!(
+            feedback,
+            vec![
+                message("/vizza/fps", vec![OscType::Int(60)]),
+                message(
+                    "/vizza/simulation",
+                    vec![OscType::String("slime_mold".into())]
+                ),
+                message("/vizza/preset", vec![OscType::String(String::new())]),
+            ]
+        );
+    }
+}
@@ -9,10 +9,12 @@ use std::sync::Arc;
 use tauri::{Manager, WebviewWindow};
 use wgpu::{Backends, Device, Instance, Queue, Surface, SurfaceConfiguration};
 
+mod cli;
 mod commands;
-mod error;
+mod control;
 mod simulation;
-mod simulations;
+
+use vizza_core::{error, simulations};
 
 use simulation::SimulationManager;
 
@@ -92,7 +94,9 @@ impl GpuContext {
             .unwrap_or(surface_caps.formats[0]);
 
         let surface_config = SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            // Frame streams sample the surface texture where the platform allows it
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | (surface_caps.usages & wgpu::TextureUsages::TEXTURE_BINDING),
             format: surface_format,
             width: window_size.width,
             height: window_size.height,
@@ -120,7 +124,7 @@ impl GpuContext {
             &surface_config,
             &adapter_info,
             &lut_manager,
-            app_settings,
+            &app_settings.render_settings(),
         )
         .await
         .map_err(|e| AppError::Gpu(GpuError::DeviceCreationFailed(e.to_string())))?;
@@ -160,6 +164,11 @@ fn main() {
     // Initialize tracing
     tracing_subscriber::fmt::init();
 
+    // Subcommands such as `vizza render` run headlessly instead of opening the app
+    if let Some(exit_code) = cli::run_from_env() {
+        std::process::exit(exit_code);
+    }
+
     // Load app settings from file
     let app_settings =
         Arc::new(AppSettings::load_from_file().expect("Failed to load app settings"));
@@ -180,12 +189,22 @@ fn main() {
                     .unwrap()
             });
 
-            app.manage(Arc::new(tokio::sync::Mutex::new(gpu_context)));
+            let gpu_context = Arc::new(tokio::sync::Mutex::new(gpu_context));
+            app.manage(gpu_context.clone());
+
+            // Remote control servers run alongside the GUI when enabled
+            let manager = app
+                .state::<Arc<tokio::sync::Mutex<SimulationManager>>>()
+                .inner()
+                .clone();
+            control::osc::start(&app_settings_clone, manager, gpu_context);
+            control::http::start(&app_settings_clone, app.handle().clone());
 
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             // Simulation commands
+            commands::list_simulations,
             commands::start_simulation,
             commands::start_slime_mold_simulation,
             commands::start_gray_scott_simulation,
@@ -225,17 +244,55 @@ fn main() {
             commands::render_frame,
             commands::render_single_frame,
             commands::handle_window_resize,
+            commands::capture_screenshot,
+            // Recording commands
+            commands::start_recording,
+            commands::stop_recording,
+            commands::get_recording_progress,
+            // Frame streaming commands
+            commands::start_frame_stream,
+            commands::stop_frame_stream,
+            commands::get_frame_stream_status,
+            // Deterministic replay commands
+            commands::start_deterministic_run,
+            commands::replay_input_log,
+            commands::stop_deterministic_run,
+            // Snapshot commands
+            commands::save_snapshot,
+            commands::load_snapshot,
+            // History commands
+            commands::undo,
+            commands::redo,
+            commands::get_history_status,
+            // Modulation commands
+            commands::add_modulator,
+            commands::remove_modulator,
+            commands::clear_modulators,
+            commands::get_modulators,
+            // Timeline commands
+            commands::start_timeline,
+            commands::stop_timeline,
+            commands::get_timeline_status,
+            // Audio-reactive commands
+            commands::start_audio_reactive,
+            commands::stop_audio_reactive,
+            commands::get_audio_status,
             // Preset commands
             commands::get_available_presets,
             commands::get_presets_for_simulation_type,
             commands::apply_preset,
             commands::save_preset,
             commands::delete_preset,
+            commands::export_preset_bundle,
+            commands::import_preset_bundle,
             // LUT commands
             commands::apply_lut_by_name,
             commands::apply_lut,
             commands::toggle_lut_reversed,
             commands::save_custom_lut,
+            commands::import_lut,
+            commands::import_css_gradient_lut,
+            commands::export_lut,
             commands::update_gradient_preview,
             commands::get_available_luts,
             commands::get_current_lut_colors,
@@ -252,6 +309,7 @@ fn main() {
             // Settings commands
             commands::update_simulation_setting,
             commands::get_current_settings,
+            commands::get_settings_schema,
             commands::get_current_state,
             commands::randomize_settings,
             // Slime mold specific commands
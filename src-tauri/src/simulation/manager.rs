@@ -1,3 +1,4 @@
+use std::path::Path;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::time::{Duration, Instant};
@@ -5,15 +6,23 @@ use tauri::{AppHandle, Emitter, Manager};
 use wgpu::{Device, Queue, SurfaceConfiguration};
 
 use crate::commands::AppSettings;
-use crate::error::{AppError, AppResult};
+use crate::error::{AppError, AppResult, PresetError, SimulationError};
+use crate::simulation::audio::{AudioAnalysis, AudioMapping, AudioReactive};
+use crate::simulation::history::{HistoryEntry, HistoryStatus, SettingsHistory};
+use crate::simulation::modulation::{ModulationEngine, Modulator};
+use crate::simulation::preset_bundle::{BundledLut, PresetBundle};
 use crate::simulation::preset_manager::SimulationPresetManager;
-use crate::simulations::gray_scott::{GrayScottModel, settings::Settings as GrayScottSettings};
-use crate::simulations::particle_life::{
-    ParticleLifeModel, settings::Settings as ParticleLifeSettings, simulation::ColorMode,
+use crate::simulation::recorder::{
+    FrameRecorder, RecordingOptions, RecordingProgress, RecordingSummary,
 };
-use crate::simulations::shared::LutData;
+use crate::simulation::replay::{DeterministicRun, InputEvent, InputLog};
+use crate::simulation::stream::FrameStream;
+use crate::simulation::timeline::{Timeline, TimelinePlayer};
+use crate::simulations::registry::{self, SimulationContext};
+use crate::simulations::shared::snapshot::CameraSnapshot;
+use crate::simulations::shared::{LutData, SimulationSnapshot};
 use crate::simulations::shared::{LutManager, SimulationLutManager, coordinates::ScreenCoords};
-use crate::simulations::slime_mold::{SlimeMoldModel, settings::Settings as SlimeMoldSettings};
+use crate::simulations::shared::{OffscreenTarget, save_png};
 use crate::simulations::traits::{Simulation, SimulationType};
 
 pub struct SimulationManager {
@@ -25,7 +34,21 @@ pub struct SimulationManager {
     pub fps_limit_enabled: Arc<AtomicBool>,
     pub fps_limit: Arc<AtomicU32>,
     pub is_paused: Arc<AtomicBool>,
+    /// Frames per second measured by the render loop over the last second
+    pub fps: Arc<AtomicU32>,
     pub app_settings: Arc<AppSettings>,
+    pub recorder: Option<FrameRecorder>,
+    pub frame_stream: Option<FrameStream>,
+    pub deterministic_run: Option<DeterministicRun>,
+    pub history: SettingsHistory,
+    pub modulation: ModulationEngine,
+    pub timeline: Option<TimelinePlayer>,
+    pub audio: Option<AudioReactive>,
+    /// Name of the preset most recently applied to the running simulation
+    pub current_preset: Option<String>,
+    /// Set while an edit is being recorded, so that edits made up of other
+    /// edits produce a single undo point
+    recording_edit: bool,
 }
 
 impl SimulationManager {
@@ -42,7 +65,17 @@ impl SimulationManager {
             fps_limit_enabled: Arc::new(AtomicBool::new(false)),
             fps_limit: Arc::new(AtomicU32::new(60)),
             is_paused: Arc::new(AtomicBool::new(true)), // Start paused to avoid race condition
+            fps: Arc::new(AtomicU32::new(0)),
             app_settings,
+            recorder: None,
+            frame_stream: None,
+            deterministic_run: None,
+            history: SettingsHistory::default(),
+            modulation: ModulationEngine::new(),
+            timeline: None,
+            audio: None,
+            current_preset: None,
+            recording_edit: false,
         }
     }
 
@@ -59,134 +92,129 @@ impl SimulationManager {
         surface_config: &SurfaceConfiguration,
         adapter_info: &wgpu::AdapterInfo,
     ) -> AppResult<()> {
-        match simulation_type.as_str() {
-            "slime_mold" => {
-                // Initialize slime mold simulation
-                let settings = SlimeMoldSettings::default();
-                let simulation = SlimeMoldModel::new(
-                    device,
-                    queue,
-                    surface_config,
-                    adapter_info,
-                    10_000_000,
-                    settings,
-                    &self.app_settings,
-                    &self.lut_manager,
-                )?;
-
-                self.current_simulation = Some(SimulationType::SlimeMold(Box::new(simulation)));
-
-                // Automatically unpause after successful initialization
-                self.resume();
-
-                Ok(())
-            }
-            "gray_scott" => {
-                // Initialize Gray-Scott simulation
-                let settings = GrayScottSettings::default();
-
-                let simulation = GrayScottModel::new(
-                    device,
-                    queue,
-                    surface_config,
-                    surface_config.width,
-                    surface_config.height,
-                    settings,
-                    &self.lut_manager,
-                    &self.app_settings,
-                )?;
-
-                self.current_simulation = Some(SimulationType::GrayScott(Box::new(simulation)));
-
-                // Automatically unpause after successful initialization
-                self.resume();
+        // Switching simulations ends any deterministic run
+        self.deterministic_run = None;
+        self.create_simulation(
+            &simulation_type,
+            device,
+            queue,
+            surface_config,
+            adapter_info,
+        )
+    }
 
-                Ok(())
+    /// Restart the simulation described by `log` with all randomness derived
+    /// from its seed and a fixed time step. When `replay` is set the logged
+    /// inputs are fed back on their frames; otherwise live input is logged.
+    pub fn start_deterministic_run(
+        &mut self,
+        log: InputLog,
+        replay: bool,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+        surface_config: &SurfaceConfiguration,
+        adapter_info: &wgpu::AdapterInfo,
+    ) -> AppResult<()> {
+        let mut run = if replay {
+            DeterministicRun::replay(log)?
+        } else {
+            DeterministicRun::record(log)?
+        };
+        let simulation_type = run.log().simulation_type.clone();
+        let settings = run.log().settings.clone();
+
+        self.deterministic_run = None;
+        run.scoped(|| -> AppResult<()> {
+            self.create_simulation(
+                &simulation_type,
+                device,
+                queue,
+                surface_config,
+                adapter_info,
+            )?;
+            if let Some(settings) = settings
+                && let Some(simulation) = &mut self.current_simulation
+            {
+                simulation.apply_settings(settings, device, queue)?;
+                simulation.reset_runtime_state(device, queue)?;
             }
-            "particle_life" => {
-                // Initialize Particle Life simulation
-                let settings = ParticleLifeSettings::default();
-                let simulation = ParticleLifeModel::new(
-                    device,
-                    queue,
-                    surface_config,
-                    adapter_info,
-                    15000, // Default particle count
-                    settings,
-                    &self.app_settings,
-                    &self.lut_manager,
-                    ColorMode::Lut,
-                )?;
-
-                self.current_simulation = Some(SimulationType::ParticleLife(Box::new(simulation)));
-
-                // Apply the "Default" preset to ensure consistent initial state
-                if let Some(simulation) = &mut self.current_simulation {
-                    self.preset_manager
-                        .apply_preset(simulation, "Default", device, queue)
-                        .map_err(|e| format!("Failed to apply Default preset: {}", e))?;
-                    tracing::info!("Applied Default preset to Particle Life simulation");
-                }
-
-                // Automatically unpause after successful initialization
-                self.resume();
+            Ok(())
+        })?;
+
+        tracing::info!(
+            "Started deterministic {} run of {} with seed {}",
+            if replay { "replay" } else { "recording" },
+            simulation_type,
+            run.seed()
+        );
+        self.deterministic_run = Some(run);
+        Ok(())
+    }
 
-                Ok(())
-            }
-            "flow" => {
-                // Initialize Flow simulation
-                let settings = crate::simulations::flow::settings::Settings::default();
-                let simulation = crate::simulations::flow::simulation::FlowModel::new(
-                    device,
-                    queue,
-                    surface_config,
-                    settings,
-                    &self.app_settings,
-                    &self.lut_manager,
-                )
-                .map_err(|e| format!("Failed to initialize Flow simulation: {}", e))?;
+    /// End the deterministic run, returning its input log
+    pub fn stop_deterministic_run(&mut self) -> Option<InputLog> {
+        let run = self.deterministic_run.take()?;
+        tracing::info!("Stopped deterministic run after {} frames", run.frame());
+        Some(run.into_log())
+    }
 
-                self.current_simulation = Some(SimulationType::Flow(Box::new(simulation)));
-                self.resume();
-                Ok(())
-            }
-            "pellets" => {
-                // Initialize Pellets simulation
-                let settings = crate::simulations::pellets::settings::Settings::default();
-                let simulation = crate::simulations::pellets::simulation::PelletsModel::new(
-                    device,
-                    queue,
-                    surface_config,
-                    settings,
-                    &self.app_settings,
-                    &self.lut_manager,
-                )
-                .map_err(|e| format!("Failed to initialize Pellets simulation: {}", e))?;
+    fn create_simulation(
+        &mut self,
+        simulation_type: &str,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+        surface_config: &SurfaceConfiguration,
+        adapter_info: &wgpu::AdapterInfo,
+    ) -> AppResult<()> {
+        let descriptor = registry::get(simulation_type)?;
+        let mut simulation = (descriptor.create)(&SimulationContext {
+            device,
+            queue,
+            surface_config,
+            adapter_info,
+            lut_manager: &self.lut_manager,
+            render_settings: &self.app_settings.render_settings(),
+        })?;
+
+        // Some simulations start from a preset for a consistent initial state
+        if let Some(preset) = descriptor.initial_preset {
+            self.preset_manager
+                .apply_preset(&mut simulation, preset, device, queue)
+                .map_err(|e| format!("Failed to apply {} preset: {}", preset, e))?;
+            tracing::info!(
+                "Applied {} preset to {} simulation",
+                preset,
+                descriptor.display_name
+            );
+        }
 
-                self.current_simulation = Some(SimulationType::Pellets(Box::new(simulation)));
-                self.resume();
-                Ok(())
-            }
-            "gradient" => {
-                // Initialize Gradient simulation
-                let simulation = crate::simulations::gradient::GradientSimulation::new(
-                    device,
-                    queue,
-                    surface_config.format,
-                    &self.app_settings,
-                );
+        self.current_simulation = Some(simulation);
+        self.current_preset = descriptor.initial_preset.map(str::to_string);
+        self.history.clear();
+        self.modulation.clear();
+        self.timeline = None;
+        self.audio = None;
 
-                self.current_simulation = Some(SimulationType::Gradient(Box::new(simulation)));
-                self.resume();
-                Ok(())
-            }
+        // Automatically unpause after successful initialization
+        self.resume();
 
-            _ => Err("Unknown simulation type".into()),
-        }
+        Ok(())
     }
 
     pub fn stop_simulation(&mut self) {
+        if let Some(recorder) = self.recorder.take()
+            && let Err(e) = recorder.finish()
+        {
+            tracing::error!("Failed to finish recording: {}", e);
+        }
+        self.deterministic_run = None;
         self.current_simulation = None;
+        self.current_preset = None;
+        self.fps.store(0, Ordering::Relaxed);
+        self.history.clear();
+        self.modulation.clear();
+        self.timeline = None;
+        self.audio = None;
     }
 
     pub fn render(
@@ -196,12 +224,192 @@ impl SimulationManager {
         surface_view: &wgpu::TextureView,
         delta_time: f32,
     ) -> AppResult<()> {
+        if self.current_simulation.is_none() {
+            return Ok(());
+        }
+        let Some(mut run) = self.deterministic_run.take() else {
+            return self.step_frame(device, queue, surface_view, delta_time);
+        };
+
+        // Replayed input lands before the frame it was recorded on, and the
+        // run's own time step replaces the measured one. The whole frame,
+        // modulation included, draws from the run's seeded randomness.
+        let inputs = run.take_due_inputs();
+        let delta_time = run.delta_time();
+        let result = run.scoped(|| -> AppResult<()> {
+            if let Some(simulation) = &mut self.current_simulation {
+                for event in inputs {
+                    event.apply(simulation, device, queue)?;
+                }
+            }
+            self.step_frame(device, queue, surface_view, delta_time)
+        });
+        if result.is_ok() {
+            run.advance_frame();
+        }
+        self.deterministic_run = Some(run);
+        result
+    }
+
+    /// Advance the timeline, modulators and audio, then render a frame
+    fn step_frame(
+        &mut self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+        surface_view: &wgpu::TextureView,
+        delta_time: f32,
+    ) -> AppResult<()> {
+        self.apply_timeline(delta_time, device, queue);
+        self.apply_modulation(delta_time, device, queue);
+        self.apply_audio(delta_time, device, queue);
         if let Some(simulation) = &mut self.current_simulation {
             simulation.render_frame(device, queue, surface_view, delta_time)?;
         }
         Ok(())
     }
 
+    /// Step the modulators and push their values through `update_setting`.
+    /// A modulator whose setting is rejected is detached.
+    fn apply_modulation(&mut self, delta_time: f32, device: &Arc<Device>, queue: &Arc<Queue>) {
+        let Some(simulation) = &mut self.current_simulation else {
+            return;
+        };
+        if self.modulation.is_empty() {
+            return;
+        }
+
+        let mut failed = Vec::new();
+        for update in self.modulation.step(delta_time) {
+            if let Err(e) =
+                simulation.update_setting(update.setting_name, update.value, device, queue)
+            {
+                tracing::warn!(
+                    "Removing modulator for {} after it failed: {}",
+                    update.setting_name,
+                    e
+                );
+                failed.push(update.index);
+            }
+        }
+        for index in failed.into_iter().rev() {
+            self.modulation.remove(index);
+        }
+    }
+
+    /// Advance the audio track and push its mapped values through
+    /// `update_setting`, stopping when the track ends or a setting is rejected
+    fn apply_audio(&mut self, delta_time: f32, device: &Arc<Device>, queue: &Arc<Queue>) {
+        let (Some(audio), Some(simulation)) = (&mut self.audio, &mut self.current_simulation)
+        else {
+            return;
+        };
+        for (setting_name, value) in audio.step(delta_time) {
+            if let Err(e) = simulation.update_setting(setting_name, value, device, queue) {
+                tracing::error!("Stopping audio-reactive playback: {}", e);
+                self.audio = None;
+                return;
+            }
+        }
+        if audio.is_finished() {
+            tracing::info!("Audio track finished after {:.1}s", audio.time());
+            self.audio = None;
+        }
+    }
+
+    /// Drive settings of the running simulation from an analysed audio track
+    pub fn start_audio(
+        &mut self,
+        analysis: AudioAnalysis,
+        mappings: Vec<AudioMapping>,
+    ) -> AppResult<()> {
+        self.ensure_not_deterministic("start audio-reactive playback")?;
+        let simulation = self
+            .current_simulation
+            .as_ref()
+            .ok_or(SimulationError::NotRunning)?;
+        let schema = simulation
+            .descriptor()
+            .settings_schema
+            .ok_or(SimulationError::UnsupportedOperation)?;
+        self.audio = Some(AudioReactive::new(analysis, mappings, &schema())?);
+        Ok(())
+    }
+
+    /// Advance the playing timeline, stopping it when it ends or fails
+    fn apply_timeline(&mut self, delta_time: f32, device: &Arc<Device>, queue: &Arc<Queue>) {
+        let (Some(player), Some(simulation)) = (&mut self.timeline, &mut self.current_simulation)
+        else {
+            return;
+        };
+        if let Err(e) = player.step(delta_time, simulation, &self.lut_manager, device, queue) {
+            tracing::error!("Stopping timeline: {}", e);
+            self.timeline = None;
+        } else if player.is_finished() {
+            tracing::info!("Timeline finished after {:.1}s", player.time());
+            self.timeline = None;
+        }
+    }
+
+    /// Play a timeline on the running simulation from its start
+    pub fn start_timeline(&mut self, timeline: Timeline) -> AppResult<()> {
+        self.ensure_not_deterministic("start a timeline")?;
+        let simulation = self
+            .current_simulation
+            .as_ref()
+            .ok_or(SimulationError::NotRunning)?;
+        if simulation.type_name() != timeline.simulation_type {
+            return Err(SimulationError::InvalidParameter(format!(
+                "Timeline is for {}, but {} is running",
+                timeline.simulation_type,
+                simulation.type_name()
+            ))
+            .into());
+        }
+        self.timeline = Some(TimelinePlayer::new(timeline)?);
+        Ok(())
+    }
+
+    pub fn stop_timeline(&mut self) {
+        self.timeline = None;
+    }
+
+    /// Attach a modulator to a numeric setting of the running simulation,
+    /// replacing any modulator already driving that setting
+    pub fn add_modulator(&mut self, modulator: Modulator) -> AppResult<()> {
+        self.ensure_not_deterministic("add a modulator")?;
+        let simulation = self
+            .current_simulation
+            .as_ref()
+            .ok_or(SimulationError::NotRunning)?;
+        let schema = simulation
+            .descriptor()
+            .settings_schema
+            .ok_or(SimulationError::UnsupportedOperation)?;
+        self.modulation.add(modulator, &schema())?;
+        Ok(())
+    }
+
+    pub fn remove_modulator(&mut self, index: usize) -> AppResult<Modulator> {
+        self.modulation.remove(index).ok_or_else(|| {
+            SimulationError::InvalidParameter(format!("No modulator at index {}", index)).into()
+        })
+    }
+
+    pub fn get_modulators(&self) -> Vec<Modulator> {
+        self.modulation.modulators()
+    }
+
+    /// Replace the attached modulators with those saved in a preset
+    fn set_modulators(&mut self, modulators: Vec<Modulator>) {
+        self.modulation.clear();
+        for modulator in modulators {
+            let setting_name = modulator.setting_name.clone();
+            if let Err(e) = self.add_modulator(modulator) {
+                tracing::warn!("Skipping modulator for {}: {}", setting_name, e);
+            }
+        }
+    }
+
     pub fn render_paused(
         &mut self,
         device: &Arc<Device>,
@@ -227,6 +435,261 @@ impl SimulationManager {
         Ok(())
     }
 
+    /// Re-render the current frame into an offscreen texture at `scale` times the
+    /// surface resolution and save it to `path` as a PNG. The simulation is not
+    /// stepped, so the image matches what is on screen, including the current LUT,
+    /// camera and post-processing. Particle simulations are drawn at the full
+    /// screenshot resolution; simulations whose state lives in a surface-sized
+    /// texture are upscaled (see `SimulationType::gains_resolution_when_scaled`).
+    /// Returns the size of the saved image.
+    pub fn capture_screenshot(
+        &mut self,
+        path: &Path,
+        scale: u32,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+        surface_config: &SurfaceConfiguration,
+    ) -> AppResult<(u32, u32)> {
+        let simulation = self
+            .current_simulation
+            .as_mut()
+            .ok_or(SimulationError::NotRunning)?;
+
+        if scale == 0 {
+            return Err(SimulationError::InvalidParameter(
+                "Screenshot scale must be at least 1".to_string(),
+            )
+            .into());
+        }
+
+        let width = surface_config.width.saturating_mul(scale);
+        let height = surface_config.height.saturating_mul(scale);
+        let max_dimension = device.limits().max_texture_dimension_2d;
+        if width > max_dimension || height > max_dimension {
+            return Err(SimulationError::InvalidParameter(format!(
+                "Screenshot size {}x{} exceeds the GPU texture limit of {}",
+                width, height, max_dimension
+            ))
+            .into());
+        }
+
+        if !OffscreenTarget::supports_format(surface_config.format) {
+            return Err(SimulationError::InvalidParameter(format!(
+                "Screenshots are not supported for surface format {:?}",
+                surface_config.format
+            ))
+            .into());
+        }
+
+        let target = OffscreenTarget::new(device, width, height, surface_config.format);
+        let full_resolution = simulation.render_frame_static_scaled(
+            device,
+            queue,
+            &target.view,
+            &target.surface_config(),
+            surface_config,
+        )?;
+        if scale > 1 && !full_resolution {
+            tracing::info!(
+                "{} renders at the window resolution, screenshot is upscaled",
+                simulation.descriptor().display_name
+            );
+        }
+        let pixels = target.read_pixels(device, queue)?;
+        save_png(path, width, height, &pixels)?;
+
+        tracing::info!(
+            "Saved {}x{} screenshot to {}",
+            width,
+            height,
+            path.display()
+        );
+        Ok((width, height))
+    }
+
+    /// Capture the full state of the current simulation, including the colors
+    /// of its LUT so the snapshot does not depend on the LUT still existing
+    pub fn take_snapshot(
+        &self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> AppResult<SimulationSnapshot> {
+        let simulation = self
+            .current_simulation
+            .as_ref()
+            .ok_or(SimulationError::NotRunning)?;
+
+        let mut snapshot = simulation.save_snapshot(device, queue)?;
+        if snapshot.lut.is_none() && !snapshot.lut_name.is_empty() {
+            snapshot.lut = self.lut_manager.get(&snapshot.lut_name).ok();
+        }
+        Ok(snapshot)
+    }
+
+    /// Restore a snapshot into the current simulation, which must be of the
+    /// same type and size as the one it was taken from
+    pub fn restore_snapshot(
+        &mut self,
+        snapshot: &SimulationSnapshot,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> AppResult<()> {
+        self.ensure_not_deterministic("restore a snapshot")?;
+        let simulation = self
+            .current_simulation
+            .as_mut()
+            .ok_or(SimulationError::NotRunning)?;
+        simulation.load_snapshot(snapshot, device, queue)?;
+        let is_particle_life = matches!(simulation, SimulationType::ParticleLife(_));
+
+        // Write the stored colors directly rather than applying the LUT by
+        // name, which makes some simulations clear their trails
+        let Some(lut) = &snapshot.lut else {
+            return Ok(());
+        };
+        if snapshot.lut_reversed && !is_particle_life {
+            self.apply_custom_lut(&lut.reversed(), device, queue)?;
+        } else {
+            // Particle Life applies its own reversal to custom LUTs
+            self.apply_custom_lut(lut, device, queue)?;
+        }
+
+        // Particle Life now refers to the temporary LUT; point it back at the
+        // original one if that still exists
+        if let Some(SimulationType::ParticleLife(simulation)) = &mut self.current_simulation
+            && self.lut_manager.get(&snapshot.lut_name).is_ok()
+        {
+            simulation.state.current_lut_name = snapshot.lut_name.clone();
+        }
+        Ok(())
+    }
+
+    /// Start recording the current simulation. Frames are captured from the
+    /// render loop at `options.scale` times the surface resolution.
+    pub fn start_recording(
+        &mut self,
+        options: RecordingOptions,
+        device: &Device,
+        surface_config: &SurfaceConfiguration,
+    ) -> AppResult<()> {
+        if self.current_simulation.is_none() {
+            return Err(SimulationError::NotRunning.into());
+        }
+        if self.recorder.is_some() {
+            return Err(AppError::Unknown(
+                "A recording is already in progress".to_string(),
+            ));
+        }
+
+        self.recorder = Some(FrameRecorder::start(
+            options,
+            device,
+            surface_config.width,
+            surface_config.height,
+            surface_config.format,
+        )?);
+        Ok(())
+    }
+
+    /// Stop the active recording, waiting for queued frames to be written.
+    /// Returns `None` if nothing was being recorded.
+    pub fn stop_recording(&mut self) -> AppResult<Option<RecordingSummary>> {
+        match self.recorder.take() {
+            Some(recorder) => Ok(Some(recorder.finish()?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Fixed time step to use instead of the measured frame time while recording
+    pub fn recording_delta_time(&self) -> Option<f32> {
+        self.recorder.as_ref().map(FrameRecorder::delta_time)
+    }
+
+    /// Time step the render loop should use instead of the measured frame
+    /// time, if a deterministic run or a recording is active
+    pub fn fixed_delta_time(&self) -> Option<f32> {
+        self.deterministic_run
+            .as_ref()
+            .map(DeterministicRun::delta_time)
+            .or_else(|| self.recording_delta_time())
+    }
+
+    /// Hand the frame that was just rendered to the active recorder
+    pub fn record_frame(
+        &mut self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+        surface_config: &SurfaceConfiguration,
+    ) -> AppResult<Option<RecordingProgress>> {
+        match (&mut self.current_simulation, &mut self.recorder) {
+            (Some(simulation), Some(recorder)) => {
+                recorder.record_frame(simulation, device, queue, surface_config)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Hand the composited frame that was just rendered to the active frame
+    /// stream, if any. Must be called before the frame is presented.
+    pub fn stream_frame(&mut self, frame: &wgpu::Texture, device: &Device, queue: &Queue) {
+        if let Some(stream) = &mut self.frame_stream {
+            stream.capture(frame, device, queue);
+        }
+    }
+
+    /// Record the frame that was just rendered and notify the frontend of the
+    /// progress. Stops the recording once it is complete or if it fails.
+    fn record_frame_and_notify(
+        &mut self,
+        app_handle: &AppHandle,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+        surface_config: &SurfaceConfiguration,
+    ) {
+        match self.record_frame(device, queue, surface_config) {
+            Ok(Some(progress)) => {
+                if let Err(e) = app_handle.emit("recording-progress", &progress) {
+                    tracing::warn!("Failed to emit recording progress: {}", e);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                tracing::error!("Recording failed: {}", e);
+                if let Err(e) = app_handle.emit("recording-error", e.to_string()) {
+                    tracing::warn!("Failed to emit recording error: {}", e);
+                }
+                if let Some(recorder) = self.recorder.take() {
+                    let _ = recorder.finish();
+                }
+                return;
+            }
+        }
+
+        if self
+            .recorder
+            .as_ref()
+            .is_some_and(FrameRecorder::is_complete)
+        {
+            match self.stop_recording() {
+                Ok(summary) => {
+                    if let Err(e) = app_handle.emit("recording-stopped", &summary) {
+                        tracing::warn!("Failed to emit recording stopped: {}", e);
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("Failed to finish recording: {}", e);
+                    if let Err(e) = app_handle.emit("recording-error", e.to_string()) {
+                        tracing::warn!("Failed to emit recording error: {}", e);
+                    }
+                }
+            }
+        }
+    }
+
     pub fn handle_mouse_interaction(
         &mut self,
         world_x: f32,
@@ -235,10 +698,15 @@ impl SimulationManager {
         device: &Arc<Device>,
         queue: &Arc<Queue>,
     ) -> AppResult<()> {
-        if let Some(simulation) = &mut self.current_simulation {
-            simulation.handle_mouse_interaction(world_x, world_y, mouse_button, device, queue)?;
-        }
-        Ok(())
+        self.handle_input(
+            InputEvent::MouseInteraction {
+                x: world_x,
+                y: world_y,
+                button: mouse_button,
+            },
+            device,
+            queue,
+        )
     }
 
     /// Handle mouse interaction using screen coordinates (physical pixels)
@@ -250,116 +718,76 @@ impl SimulationManager {
         device: &Arc<Device>,
         queue: &Arc<Queue>,
     ) -> AppResult<()> {
-        if let Some(simulation) = &mut self.current_simulation {
-            match simulation {
-                SimulationType::GrayScott(simulation) => {
-                    let camera = &simulation.renderer.camera;
-                    let screen = ScreenCoords::new(screen_x, screen_y);
-                    let world = camera.screen_to_world(screen);
-
-                    // Convert world coordinates [-1,1] to texture coordinates [0,1]
-                    // Gray-Scott simulation expects texture coordinates in [0,1] range
-                    // World space is [-1, 1] where (-1, -1) is bottom-left and (1, 1) is top-right
-                    // Texture space is [0, 1] where (0, 0) is top-left and (1, 1) is bottom-right
-                    let texture_x = (world.x + 1.0) * 0.5;
-                    let texture_y = (1.0 - world.y) * 0.5; // Flip Y axis to match texture coordinates
-
-                    simulation.handle_mouse_interaction(
-                        texture_x,
-                        texture_y,
-                        mouse_button,
-                        device,
-                        queue,
-                    )?;
-                }
-                SimulationType::ParticleLife(simulation) => {
-                    let camera = &simulation.camera;
-                    let screen = ScreenCoords::new(screen_x, screen_y);
+        let Some(simulation) = &self.current_simulation else {
+            return Ok(());
+        };
+        let screen = ScreenCoords::new(screen_x, screen_y);
+
+        let (x, y) = match simulation {
+            SimulationType::GrayScott(simulation) => {
+                let world = simulation.renderer.camera.screen_to_world(screen);
+
+                // Convert world coordinates [-1,1] to texture coordinates [0,1]
+                // Gray-Scott simulation expects texture coordinates in [0,1] range
+                // World space is [-1, 1] where (-1, -1) is bottom-left and (1, 1) is top-right
+                // Texture space is [0, 1] where (0, 0) is top-left and (1, 1) is bottom-right
+                let texture_x = (world.x + 1.0) * 0.5;
+                let texture_y = (1.0 - world.y) * 0.5; // Flip Y axis to match texture coordinates
+                (texture_x, texture_y)
+            }
+            // Everything else lives in [-1,1] world space, so use world coordinates directly
+            simulation => match simulation.camera() {
+                Some(camera) => {
                     let world = camera.screen_to_world(screen);
-
-                    // Particles now live in [-1,1] world space, so use world coordinates directly
-                    let particle_x = world.x;
-                    let particle_y = world.y;
-
-                    simulation.handle_mouse_interaction(
-                        particle_x,
-                        particle_y,
-                        mouse_button,
-                        device,
-                        queue,
-                    )?;
+                    (world.x, world.y)
                 }
-                SimulationType::Pellets(simulation) => {
-                    let camera = &simulation.camera;
-                    let screen = ScreenCoords::new(screen_x, screen_y);
-                    let world = camera.screen_to_world(screen);
+                None => return Ok(()),
+            },
+        };
 
-                    // Pellets particles also live in [-1,1] world space
-                    simulation.handle_mouse_interaction(
-                        world.x,
-                        world.y,
-                        mouse_button,
-                        device,
-                        queue,
-                    )?;
-                }
-                SimulationType::SlimeMold(simulation) => {
-                    let camera = &simulation.camera;
-                    let screen = ScreenCoords::new(screen_x, screen_y);
-                    let world = camera.screen_to_world(screen);
-                    let world_x = world.x;
-                    let world_y = world.y;
-                    simulation.handle_mouse_interaction(
-                        world_x,
-                        world_y,
-                        mouse_button,
-                        device,
-                        queue,
-                    )?;
-                }
-                SimulationType::Flow(simulation) => {
-                    let camera = &simulation.camera;
-                    let screen = ScreenCoords::new(screen_x, screen_y);
-                    let world = camera.screen_to_world(screen);
-                    let world_x = world.x;
-                    let world_y = world.y;
-                    simulation.handle_mouse_interaction(
-                        world_x,
-                        world_y,
-                        mouse_button,
-                        device,
-                        queue,
-                    )?;
-                }
+        self.handle_mouse_interaction(x, y, mouse_button, device, queue)
+    }
 
-                _ => (),
+    /// Handle mouse release events
+    pub fn handle_mouse_release(&mut self, mouse_button: u32, queue: &Arc<Queue>) -> AppResult<()> {
+        let Some(simulation) = &mut self.current_simulation else {
+            return Ok(());
+        };
+        let event = InputEvent::MouseRelease {
+            button: mouse_button,
+        };
+
+        match &mut self.deterministic_run {
+            Some(run) if run.is_replaying() => {}
+            Some(run) => {
+                run.record_input(event);
+                run.scoped(|| simulation.handle_mouse_release(mouse_button, queue))?;
             }
+            None => simulation.handle_mouse_release(mouse_button, queue)?,
         }
         Ok(())
     }
 
-    /// Handle mouse release events
-    pub fn handle_mouse_release(&mut self, mouse_button: u32, queue: &Arc<Queue>) -> AppResult<()> {
-        if let Some(simulation) = &mut self.current_simulation {
-            match simulation {
-                SimulationType::GrayScott(simulation) => {
-                    simulation.handle_mouse_release(mouse_button, queue)?;
-                }
-                SimulationType::ParticleLife(simulation) => {
-                    simulation.handle_mouse_release(mouse_button, queue)?;
-                }
-                SimulationType::SlimeMold(simulation) => {
-                    simulation.handle_mouse_release(mouse_button, queue)?;
-                }
-                SimulationType::Flow(simulation) => {
-                    simulation.handle_mouse_release(mouse_button, queue)?;
-                }
-                SimulationType::Pellets(simulation) => {
-                    simulation.handle_mouse_release(mouse_button, queue)?;
-                }
-
-                _ => (),
+    /// Apply a live input, logging it if a deterministic run is being recorded.
+    /// Live input is ignored while a run is replayed, as it would make the
+    /// replay diverge from the recording.
+    fn handle_input(
+        &mut self,
+        event: InputEvent,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> AppResult<()> {
+        let Some(simulation) = &mut self.current_simulation else {
+            return Ok(());
+        };
+
+        match &mut self.deterministic_run {
+            Some(run) if run.is_replaying() => {}
+            Some(run) => {
+                run.record_input(event.clone());
+                run.scoped(|| event.apply(simulation, device, queue))?;
             }
+            None => event.apply(simulation, device, queue)?,
         }
         Ok(())
     }
@@ -398,12 +826,126 @@ impl SimulationManager {
             value
         );
 
-        if let Some(simulation) = &mut self.current_simulation {
-            tracing::debug!("Calling simulation.update_setting for current simulation");
-            simulation.update_setting(setting_name, value.clone(), device, queue)?;
-            tracing::debug!("Simulation update_setting completed successfully");
-        } else {
+        if self.current_simulation.is_none() {
             tracing::warn!("No simulation running, cannot update setting");
+            return Ok(());
+        }
+        self.with_history(Some(setting_name), |manager| {
+            manager.handle_input(
+                InputEvent::UpdateSetting {
+                    name: setting_name.to_string(),
+                    value,
+                },
+                device,
+                queue,
+            )
+        })
+    }
+
+    /// Settings and LUT of the running simulation, as an undo point
+    pub fn history_entry(&self) -> Option<HistoryEntry> {
+        let simulation = self.current_simulation.as_ref()?;
+        Some(HistoryEntry {
+            settings: simulation.get_settings(),
+            lut: simulation
+                .current_lut()
+                .map(|(name, reversed)| (name.to_string(), reversed)),
+        })
+    }
+
+    /// Record `before` as an undo point if the state has changed since.
+    /// `key` names the setting edited so rapid changes to it are coalesced.
+    pub fn record_history(&mut self, before: Option<HistoryEntry>, key: Option<&str>) {
+        if self.recording_edit {
+            return;
+        }
+        if let Some(before) = before
+            && self.history_entry().as_ref() != Some(&before)
+        {
+            self.history.record(before, key, Instant::now());
+        }
+    }
+
+    /// Run `edit` and record the state from before it as one undo point
+    fn with_history<T>(
+        &mut self,
+        key: Option<&str>,
+        edit: impl FnOnce(&mut Self) -> AppResult<T>,
+    ) -> AppResult<T> {
+        if self.recording_edit {
+            return edit(self);
+        }
+        let before = self.history_entry();
+        self.recording_edit = true;
+        let result = edit(self);
+        self.recording_edit = false;
+        let result = result?;
+        self.record_history(before, key);
+        Ok(result)
+    }
+
+    /// Undo the last settings, LUT or matrix edit. Returns whether there was
+    /// anything to undo.
+    pub fn undo(&mut self, device: &Arc<Device>, queue: &Arc<Queue>) -> AppResult<bool> {
+        self.ensure_not_deterministic("undo")?;
+        let Some(current) = self.history_entry() else {
+            return Ok(false);
+        };
+        match self.history.undo(current) {
+            Some(entry) => self
+                .restore_history_entry(entry, device, queue)
+                .map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    /// Redo the last undone edit. Returns whether there was anything to redo.
+    pub fn redo(&mut self, device: &Arc<Device>, queue: &Arc<Queue>) -> AppResult<bool> {
+        self.ensure_not_deterministic("redo")?;
+        let Some(current) = self.history_entry() else {
+            return Ok(false);
+        };
+        match self.history.redo(current) {
+            Some(entry) => self
+                .restore_history_entry(entry, device, queue)
+                .map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    pub fn history_status(&self) -> HistoryStatus {
+        self.history.status()
+    }
+
+    fn restore_history_entry(
+        &mut self,
+        entry: HistoryEntry,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> AppResult<()> {
+        let Some(simulation) = &mut self.current_simulation else {
+            return Ok(());
+        };
+        simulation.apply_settings(entry.settings, device, queue)?;
+
+        let Some((lut_name, reversed)) = entry.lut else {
+            return Ok(());
+        };
+        let current = simulation
+            .current_lut()
+            .map(|(name, reversed)| (name.to_string(), reversed));
+        if current.as_ref().map(|(name, _)| name) != Some(&lut_name) {
+            // The LUT may have been a temporary one that no longer exists
+            if let Err(e) = simulation.apply_lut(&self.lut_manager, &lut_name, device, queue) {
+                tracing::warn!("Could not restore LUT '{}': {}", lut_name, e);
+                return Ok(());
+            }
+        }
+        let is_reversed = simulation
+            .current_lut()
+            .is_some_and(|(_, reversed)| reversed);
+        if is_reversed != reversed {
+            simulation.reverse_lut(&self.lut_manager, device, queue)?;
         }
         Ok(())
     }
@@ -440,13 +982,22 @@ impl SimulationManager {
         device: &Arc<Device>,
         queue: &Arc<Queue>,
     ) -> AppResult<()> {
-        if let Some(simulation) = &mut self.current_simulation {
-            self.preset_manager
-                .apply_preset(simulation, preset_name, device, queue)
-                .map_err(AppError::Preset)?;
-            simulation.reset_runtime_state(device, queue)?;
-        }
-        Ok(())
+        self.ensure_not_deterministic("apply a preset")?;
+        self.with_history(None, |manager| {
+            if let Some(simulation) = &mut manager.current_simulation {
+                manager
+                    .preset_manager
+                    .apply_preset(simulation, preset_name, device, queue)
+                    .map_err(AppError::Preset)?;
+                simulation.reset_runtime_state(device, queue)?;
+                let modulators = manager
+                    .preset_manager
+                    .get_preset_modulators(simulation.type_name(), preset_name);
+                manager.set_modulators(modulators);
+                manager.current_preset = Some(preset_name.to_string());
+            }
+            Ok(())
+        })
     }
 
     pub fn save_preset(
@@ -455,8 +1006,12 @@ impl SimulationManager {
         settings: &serde_json::Value,
     ) -> AppResult<()> {
         if let Some(simulation) = &self.current_simulation {
-            self.preset_manager
-                .save_preset(simulation, preset_name, settings)?;
+            self.preset_manager.save_preset(
+                simulation,
+                preset_name,
+                settings,
+                &self.modulation.modulators(),
+            )?;
         }
         Ok(())
     }
@@ -468,6 +1023,124 @@ impl SimulationManager {
         Ok(())
     }
 
+    /// Bundle the current settings, LUT and camera as a shareable preset
+    pub fn export_preset_bundle(&self, preset_name: &str) -> AppResult<PresetBundle> {
+        let simulation = self
+            .current_simulation
+            .as_ref()
+            .ok_or(SimulationError::NotRunning)?;
+
+        let mut bundle = PresetBundle::new(
+            simulation.type_name(),
+            preset_name,
+            simulation.get_settings(),
+        );
+        bundle.lut = simulation
+            .current_lut()
+            .map(|(name, reversed)| BundledLut::new(name, reversed, &self.lut_manager));
+        bundle.camera = simulation.camera().map(CameraSnapshot::of);
+        Ok(bundle)
+    }
+
+    /// Install a bundle as a user preset, along with its custom LUT. Existing
+    /// presets and custom LUTs with different colors are only replaced when
+    /// `overwrite` is set; built-in ones never are.
+    pub fn import_preset_bundle(
+        &mut self,
+        bundle: &PresetBundle,
+        overwrite: bool,
+    ) -> AppResult<()> {
+        bundle.validate(&self.preset_manager, &self.lut_manager)?;
+        let sim_name = bundle.simulation_type.as_str();
+
+        // Check every conflict before writing anything
+        let preset_manager = self
+            .preset_manager
+            .get_manager(sim_name)
+            .ok_or_else(|| PresetError::CompatibilityError(sim_name.to_string()))?;
+        let preset_exists = preset_manager.get_preset_names().contains(&bundle.name);
+        if preset_exists && (!overwrite || preset_manager.is_built_in_preset(&bundle.name)) {
+            return Err(PresetError::AlreadyExists(bundle.name.clone()).into());
+        }
+
+        let mut lut_to_save = None;
+        if let Some(lut) = &bundle.lut
+            && let Some(lut_data) = lut.lut_data()?
+        {
+            match self.lut_manager.get(&lut.name) {
+                Ok(existing) if existing == lut_data => {}
+                Ok(_) if !overwrite || self.lut_manager.get_custom(&lut.name).is_err() => {
+                    return Err(PresetError::AlreadyExists(format!("LUT '{}'", lut.name)).into());
+                }
+                _ => lut_to_save = Some(lut_data),
+            }
+        }
+
+        if let Some(lut_data) = lut_to_save {
+            self.lut_manager.save_custom(&lut_data.name, &lut_data)?;
+        }
+        if preset_exists {
+            self.preset_manager
+                .delete_preset_for_type(sim_name, &bundle.name)?;
+        }
+        self.preset_manager
+            .save_preset_for_type(sim_name, &bundle.name, &bundle.settings, &[])?;
+
+        tracing::info!(
+            "Imported {} preset '{}' exported by Vizza {}",
+            sim_name,
+            bundle.name,
+            bundle.app_version
+        );
+        Ok(())
+    }
+
+    /// Apply an imported bundle to the running simulation of the same type
+    pub fn apply_preset_bundle(
+        &mut self,
+        bundle: &PresetBundle,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> AppResult<()> {
+        let running_type = self
+            .current_simulation
+            .as_ref()
+            .map(|simulation| simulation.type_name());
+        if running_type != Some(bundle.simulation_type.as_str()) {
+            return Err(PresetError::CompatibilityError(format!(
+                "Bundle is for {}, but {} is running",
+                bundle.simulation_type,
+                running_type.unwrap_or("no simulation")
+            ))
+            .into());
+        }
+
+        self.with_history(None, |manager| {
+            manager.apply_preset(&bundle.name, device, queue)?;
+            if let Some(lut) = &bundle.lut {
+                manager.apply_lut(&lut.name, device, queue)?;
+                let reversed = manager
+                    .current_simulation
+                    .as_ref()
+                    .and_then(|simulation| simulation.current_lut())
+                    .is_some_and(|(_, reversed)| reversed);
+                if reversed != lut.reversed {
+                    manager.reverse_current_lut(device, queue)?;
+                }
+            }
+            Ok(())
+        })?;
+        if let Some(camera) = &bundle.camera
+            && let Some(simulation_camera) = self
+                .current_simulation
+                .as_mut()
+                .and_then(|simulation| simulation.camera_mut())
+        {
+            camera.apply_to(simulation_camera);
+        }
+        Ok(())
+    }
+
     pub fn get_current_settings(&self) -> Option<serde_json::Value> {
         self.current_simulation
             .as_ref()
@@ -506,85 +1179,13 @@ impl SimulationManager {
         device: &Arc<Device>,
         queue: &Arc<Queue>,
     ) -> AppResult<()> {
-        if let Some(simulation) = &mut self.current_simulation {
-            match simulation {
-                SimulationType::SlimeMold(simulation) => {
-                    // For slime mold, load the LUT data and apply it directly
-                    let mut lut_data = self.lut_manager.get(lut_name).map_err(|e| {
-                        AppError::Simulation(
-                            format!("Failed to load LUT '{}': {}", lut_name, e).into(),
-                        )
-                    })?;
-
-                    if simulation.lut_reversed {
-                        lut_data.reverse();
-                    }
-
-                    simulation.update_lut(&lut_data, queue);
-                    simulation.current_lut_name = lut_name.to_string();
-
-                    tracing::info!("LUT '{}' applied to slime mold simulation", lut_name);
-                }
-                SimulationType::GrayScott(simulation) => {
-                    // For Gray-Scott, load the LUT data and apply it to the renderer
-                    let mut lut_data = self.lut_manager.get(lut_name).map_err(|e| {
-                        AppError::Simulation(
-                            format!("Failed to load LUT '{}': {}", lut_name, e).into(),
-                        )
-                    })?;
-
-                    if simulation.lut_reversed {
-                        lut_data.reverse();
-                    }
-
-                    simulation.renderer.update_lut(&lut_data, queue);
-                    simulation.current_lut_name = lut_name.to_string();
-
-                    tracing::info!("LUT '{}' applied to Gray-Scott simulation", lut_name);
-                }
-                SimulationType::ParticleLife(simulation) => {
-                    // For particle life, use the existing update_setting method
-                    simulation.update_setting("lut", serde_json::json!(lut_name), device, queue)?;
-                }
-                SimulationType::Flow(simulation) => {
-                    // For Flow, use the existing update_setting method
-                    simulation.update_setting(
-                        "currentLut",
-                        serde_json::json!(lut_name),
-                        device,
-                        queue,
-                    )?;
-                    tracing::info!("LUT '{}' applied to Flow simulation", lut_name);
-                }
-                SimulationType::Pellets(simulation) => {
-                    // For Pellets, use the existing update_setting method
-                    simulation.update_setting(
-                        "currentLut",
-                        serde_json::json!(lut_name),
-                        device,
-                        queue,
-                    )?;
-                    tracing::info!("LUT '{}' applied to Pellets simulation", lut_name);
-                }
-                SimulationType::MainMenu(_) => {
-                    // Main menu doesn't support LUT changes
-                    tracing::warn!("LUT changes not supported for main menu simulation");
-                }
-                SimulationType::Gradient(simulation) => {
-                    // For gradient simulation, load the LUT data and apply it directly
-                    let lut_data = self.lut_manager.get(lut_name).map_err(|e| {
-                        AppError::Simulation(
-                            format!("Failed to load LUT '{}': {}", lut_name, e).into(),
-                        )
-                    })?;
-
-                    simulation.update_lut(device, queue, &lut_data);
-
-                    tracing::info!("LUT '{}' applied to gradient simulation", lut_name);
-                }
+        self.ensure_not_deterministic("change the LUT")?;
+        self.with_history(None, |manager| {
+            if let Some(simulation) = &mut manager.current_simulation {
+                simulation.apply_lut(&manager.lut_manager, lut_name, device, queue)?;
             }
-        }
-        Ok(())
+            Ok(())
+        })
     }
 
     pub fn reverse_current_lut(
@@ -592,103 +1193,13 @@ impl SimulationManager {
         device: &Arc<Device>,
         queue: &Arc<Queue>,
     ) -> AppResult<()> {
-        if let Some(simulation) = &mut self.current_simulation {
-            match simulation {
-                SimulationType::SlimeMold(simulation) => {
-                    // Toggle the reversed flag and reload the LUT
-                    simulation.lut_reversed = !simulation.lut_reversed;
-                    let mut lut_data =
-                        self.lut_manager
-                            .get(&simulation.current_lut_name)
-                            .map_err(|e| {
-                                AppError::Simulation(
-                                    format!(
-                                        "Failed to load LUT '{}': {}",
-                                        simulation.current_lut_name, e
-                                    )
-                                    .into(),
-                                )
-                            })?;
-
-                    if simulation.lut_reversed {
-                        lut_data.reverse();
-                    }
-
-                    simulation.update_lut(&lut_data, queue);
-                    tracing::info!("LUT reversed for slime mold simulation");
-                }
-                SimulationType::GrayScott(simulation) => {
-                    // Toggle the reversed flag and reload the LUT
-                    simulation.lut_reversed = !simulation.lut_reversed;
-                    let mut lut_data =
-                        self.lut_manager
-                            .get(&simulation.current_lut_name)
-                            .map_err(|e| {
-                                AppError::Simulation(
-                                    format!(
-                                        "Failed to load LUT '{}': {}",
-                                        simulation.current_lut_name, e
-                                    )
-                                    .into(),
-                                )
-                            })?;
-
-                    if simulation.lut_reversed {
-                        lut_data.reverse();
-                    }
-
-                    simulation.renderer.update_lut(&lut_data, queue);
-                    tracing::info!("LUT reversed for Gray-Scott simulation");
-                }
-                SimulationType::ParticleLife(simulation) => {
-                    // For particle life, we need to update the LUT with reversed flag
-                    let current_reversed = simulation.state.lut_reversed;
-                    let color_mode = simulation.state.color_mode;
-                    let current_lut_name = simulation.state.current_lut_name.clone();
-                    let lut_manager = &self.lut_manager;
-                    simulation.update_lut(
-                        device,
-                        queue,
-                        lut_manager,
-                        color_mode,
-                        Some(&current_lut_name),
-                        !current_reversed,
-                    )?;
-                }
-                SimulationType::Flow(simulation) => {
-                    // For Flow, use the built-in LUT reversal mechanism
-                    let current_reversed = simulation.lut_reversed;
-                    simulation.update_setting(
-                        "lutReversed",
-                        serde_json::json!(!current_reversed),
-                        device,
-                        queue,
-                    )?;
-                    tracing::info!("LUT reversed for Flow simulation");
-                }
-                SimulationType::Pellets(simulation) => {
-                    // For Pellets, use the built-in LUT reversal mechanism
-                    let current_reversed = simulation.state.lut_reversed;
-                    simulation.update_setting(
-                        "lut_reversed",
-                        serde_json::json!(!current_reversed),
-                        device,
-                        queue,
-                    )?;
-                    tracing::info!("LUT reversed for Pellets simulation");
-                }
-                SimulationType::MainMenu(_) => {
-                    // Main menu doesn't support LUT changes
-                    tracing::warn!("LUT reversal not supported for main menu simulation");
-                }
-
-                _ => {
-                    // Other simulations don't support LUT reversal
-                    tracing::warn!("LUT reversal not supported for this simulation type");
-                }
+        self.ensure_not_deterministic("change the LUT")?;
+        self.with_history(None, |manager| {
+            if let Some(simulation) = &mut manager.current_simulation {
+                simulation.reverse_lut(&manager.lut_manager, device, queue)?;
             }
-        }
-        Ok(())
+            Ok(())
+        })
     }
 
     /// Apply a custom LUT to any running simulation
@@ -698,82 +1209,12 @@ impl SimulationManager {
         device: &Arc<Device>,
         queue: &Arc<Queue>,
     ) -> AppResult<()> {
+        self.ensure_not_deterministic("change the LUT")?;
         // Store the custom LUT in temporary storage
         self.lut_manager.set_temp_lut(lut_data.clone());
 
         if let Some(simulation) = &mut self.current_simulation {
-            match simulation {
-                SimulationType::SlimeMold(simulation) => {
-                    simulation.update_lut(lut_data, queue);
-                    tracing::info!("Custom LUT applied to slime mold simulation");
-                }
-                SimulationType::GrayScott(simulation) => {
-                    simulation.renderer.update_lut(lut_data, queue);
-                    tracing::info!("Custom LUT applied to Gray-Scott simulation");
-                }
-                SimulationType::ParticleLife(simulation) => {
-                    let color_mode = simulation.state.color_mode;
-                    let lut_reversed = simulation.state.lut_reversed;
-
-                    simulation.update_lut(
-                        device,
-                        queue,
-                        &self.lut_manager,
-                        color_mode,
-                        Some("temp_lut"),
-                        lut_reversed,
-                    )?;
-                    tracing::info!("Custom LUT applied to particle life simulation");
-                }
-                SimulationType::Flow(simulation) => {
-                    // Direct-write the temporary LUT to the GPU buffer for immediate preview
-                    if let Ok(lut) = self.lut_manager.get("temp_lut") {
-                        let data_u32 = lut.to_u32_buffer();
-                        queue.write_buffer(
-                            &simulation.lut_buffer,
-                            0,
-                            bytemuck::cast_slice(&data_u32),
-                        );
-                        tracing::info!("Custom LUT directly written to Flow LUT buffer");
-                    } else {
-                        // Fallback to settings path if temp LUT missing
-                        simulation.update_setting(
-                            "currentLut",
-                            serde_json::json!("temp_lut"),
-                            device,
-                            queue,
-                        )?;
-                    }
-                }
-                SimulationType::Pellets(simulation) => {
-                    // Direct-write to the Pellets LUT buffer to avoid relying on the sim's internal LUT manager clone
-                    if let Ok(lut) = self.lut_manager.get("temp_lut") {
-                        let data_u32 = lut.to_u32_buffer();
-                        queue.write_buffer(
-                            &simulation.lut_buffer,
-                            0,
-                            bytemuck::cast_slice(&data_u32),
-                        );
-                        tracing::info!("Custom LUT directly written to Pellets LUT buffer");
-                    } else {
-                        // Fallback to sim path
-                        simulation.update_setting(
-                            "currentLut",
-                            serde_json::json!("temp_lut"),
-                            device,
-                            queue,
-                        )?;
-                    }
-                }
-                SimulationType::MainMenu(_) => {
-                    // Main menu doesn't support custom LUTs
-                    tracing::warn!("Custom LUT not supported for main menu simulation");
-                }
-                SimulationType::Gradient(simulation) => {
-                    simulation.update_lut(device, queue, lut_data);
-                    tracing::info!("Custom LUT applied to gradient simulation");
-                }
-            }
+            simulation.apply_custom_lut(&self.lut_manager, lut_data, device, queue)?;
         }
         Ok(())
     }
@@ -789,6 +1230,7 @@ impl SimulationManager {
         let fps_limit_enabled = self.fps_limit_enabled.clone();
         let fps_limit = self.fps_limit.clone();
         let is_paused = self.is_paused.clone();
+        let measured_fps = self.fps.clone();
 
         render_loop_running.store(true, Ordering::Relaxed);
 
@@ -812,11 +1254,15 @@ impl SimulationManager {
                                     .texture
                                     .create_view(&wgpu::TextureViewDescriptor::default());
 
-                                // Calculate delta time
+                                // Calculate delta time, stepping by a fixed amount while recording
+                                // or during a deterministic run
                                 let delta_time =
-                                    frame_start.duration_since(last_frame_time).as_secs_f32();
+                                    sim_manager.fixed_delta_time().unwrap_or_else(|| {
+                                        frame_start.duration_since(last_frame_time).as_secs_f32()
+                                    });
 
-                                let render_result = if is_paused.load(Ordering::Relaxed) {
+                                let paused = is_paused.load(Ordering::Relaxed);
+                                let render_result = if paused {
                                     // When paused, render without updating simulation state
                                     sim_manager.render_paused(
                                         &gpu_ctx.device,
@@ -834,7 +1280,25 @@ impl SimulationManager {
                                 };
 
                                 if render_result.is_ok() {
+                                    // The surface texture can't be read once presented
+                                    sim_manager.stream_frame(
+                                        &output.texture,
+                                        &gpu_ctx.device,
+                                        &gpu_ctx.queue,
+                                    );
                                     output.present();
+
+                                    // Only frames that advanced the simulation are recorded
+                                    if !paused && sim_manager.is_recording() {
+                                        let surface_config =
+                                            gpu_ctx.surface_config.lock().await.clone();
+                                        sim_manager.record_frame_and_notify(
+                                            &app_handle,
+                                            &gpu_ctx.device,
+                                            &gpu_ctx.queue,
+                                            &surface_config,
+                                        );
+                                    }
                                 }
                             }
                             Err(e) => {
@@ -907,6 +1371,7 @@ impl SimulationManager {
                 // Update FPS every second
                 if last_fps_update.elapsed() >= Duration::from_secs(1) {
                     let fps = (frame_count as f64 / last_fps_update.elapsed().as_secs_f64()) as u32;
+                    measured_fps.store(fps, Ordering::Relaxed);
 
                     // Emit FPS update to frontend
                     if let Err(e) = app_handle.emit("fps-update", fps) {
@@ -945,45 +1410,15 @@ impl SimulationManager {
 
     // Reset methods
     pub fn reset_trails(&mut self, device: &Arc<Device>, queue: &Arc<Queue>) -> AppResult<()> {
-        if let Some(simulation) = &mut self.current_simulation {
-            simulation.reset_runtime_state(device, queue)?;
-        }
-        Ok(())
+        self.handle_input(InputEvent::ResetTrails, device, queue)
     }
 
     pub fn reset_agents(&mut self, device: &Arc<Device>, queue: &Arc<Queue>) -> AppResult<()> {
-        if let Some(simulation) = &mut self.current_simulation {
-            match simulation {
-                SimulationType::SlimeMold(sim) => {
-                    // For slime mold, call the specific reset_agents method that repositions agents randomly
-                    sim.reset_agents(device, queue)
-                        .map_err(AppError::Simulation)?;
-                }
-                _ => {
-                    // For other simulations, use the generic reset_runtime_state
-                    simulation.reset_runtime_state(device, queue)?;
-                }
-            }
-        }
-        Ok(())
+        self.handle_input(InputEvent::ResetAgents, device, queue)
     }
 
     pub fn reset_simulation(&mut self, device: &Arc<Device>, queue: &Arc<Queue>) -> AppResult<()> {
-        if let Some(simulation) = &mut self.current_simulation {
-            match simulation {
-                SimulationType::GrayScott(sim) => {
-                    sim.reset();
-                }
-                SimulationType::ParticleLife(sim) => {
-                    sim.reset_particles_gpu(device, queue)?;
-                }
-
-                _ => {
-                    simulation.reset_runtime_state(device, queue)?;
-                }
-            }
-        }
-        Ok(())
+        self.handle_input(InputEvent::ResetSimulation, device, queue)
     }
 
     pub fn randomize_settings(
@@ -991,166 +1426,97 @@ impl SimulationManager {
         device: &Arc<Device>,
         queue: &Arc<Queue>,
     ) -> AppResult<()> {
-        if let Some(simulation) = &mut self.current_simulation {
-            simulation.randomize_settings(device, queue)?;
-        }
-        Ok(())
+        self.with_history(None, |manager| {
+            manager.handle_input(InputEvent::RandomizeSettings, device, queue)
+        })
     }
 
     // Note: seed_random_noise is Gray-Scott and CSA specific functionality
     pub fn seed_random_noise(&mut self, device: &Arc<Device>, queue: &Arc<Queue>) -> AppResult<()> {
-        if let Some(simulation) = &mut self.current_simulation {
-            match simulation {
-                SimulationType::GrayScott(sim) => {
-                    sim.seed_random_noise(device, queue)
-                        .map_err(AppError::Simulation)?;
-                }
-
-                _ => {
-                    // Seed random noise is only supported for Gray-Scott and CSA simulations
-                    tracing::warn!(
-                        "Seed random noise is only supported for Gray-Scott and CSA simulations"
-                    );
-                }
+        match &self.current_simulation {
+            Some(SimulationType::GrayScott(_)) => {
+                self.handle_input(InputEvent::SeedRandomNoise, device, queue)
             }
+            Some(_) => {
+                // Seed random noise is only supported for Gray-Scott and CSA simulations
+                tracing::warn!(
+                    "Seed random noise is only supported for Gray-Scott and CSA simulations"
+                );
+                Ok(())
+            }
+            None => Ok(()),
         }
-        Ok(())
     }
 
     // Camera control methods
     pub fn pan_camera(&mut self, delta_x: f32, delta_y: f32) {
-        if let Some(simulation) = &mut self.current_simulation {
-            match simulation {
-                SimulationType::SlimeMold(simulation) => simulation.pan_camera(delta_x, delta_y),
-                SimulationType::GrayScott(simulation) => {
-                    simulation.renderer.camera.pan(delta_x, delta_y)
-                }
-                SimulationType::ParticleLife(simulation) => simulation.camera.pan(delta_x, delta_y),
-                SimulationType::Flow(simulation) => simulation.pan_camera(delta_x, delta_y),
-                SimulationType::Pellets(simulation) => simulation.pan_camera(delta_x, delta_y),
-                SimulationType::MainMenu(_) => {}
-                _ => {}
-            }
-        }
+        self.handle_camera_input(InputEvent::PanCamera { delta_x, delta_y });
     }
 
     pub fn zoom_camera(&mut self, delta: f32) {
-        if let Some(simulation) = &mut self.current_simulation {
-            match simulation {
-                SimulationType::SlimeMold(simulation) => simulation.zoom_camera(delta),
-                SimulationType::GrayScott(simulation) => simulation.renderer.camera.zoom(delta),
-                SimulationType::ParticleLife(simulation) => simulation.camera.zoom(delta),
-                SimulationType::Flow(simulation) => simulation.camera.zoom(delta),
-                SimulationType::Pellets(simulation) => simulation.camera.zoom(delta),
-                SimulationType::MainMenu(_) => {}
-                _ => {}
-            }
-        }
+        self.handle_camera_input(InputEvent::ZoomCamera { delta });
     }
 
     pub fn zoom_camera_to_cursor(&mut self, delta: f32, cursor_x: f32, cursor_y: f32) {
-        if let Some(simulation) = &mut self.current_simulation {
-            match simulation {
-                SimulationType::SlimeMold(simulation) => {
-                    simulation.zoom_camera_to_cursor(delta, cursor_x, cursor_y)
-                }
-                SimulationType::GrayScott(simulation) => simulation
-                    .renderer
-                    .camera
-                    .zoom_to_cursor(delta, cursor_x, cursor_y),
-                SimulationType::ParticleLife(simulation) => {
-                    simulation.camera.zoom_to_cursor(delta, cursor_x, cursor_y)
-                }
-                SimulationType::Flow(simulation) => {
-                    simulation.camera.zoom_to_cursor(delta, cursor_x, cursor_y)
-                }
-                SimulationType::Pellets(simulation) => {
-                    simulation.camera.zoom_to_cursor(delta, cursor_x, cursor_y)
-                }
-                SimulationType::MainMenu(_) => {}
-                _ => {}
-            }
-        }
+        self.handle_camera_input(InputEvent::ZoomCameraToCursor {
+            delta,
+            cursor_x,
+            cursor_y,
+        });
     }
 
     pub fn reset_camera(&mut self) {
-        if let Some(simulation) = &mut self.current_simulation {
-            match simulation {
-                SimulationType::SlimeMold(simulation) => simulation.reset_camera(),
-                SimulationType::GrayScott(simulation) => simulation.renderer.camera.reset(),
-                SimulationType::ParticleLife(simulation) => simulation.camera.reset(),
-                SimulationType::Flow(simulation) => simulation.camera.reset(),
-                SimulationType::Pellets(simulation) => simulation.camera.reset(),
-                SimulationType::MainMenu(_) => {}
-                _ => {}
+        self.handle_camera_input(InputEvent::ResetCamera);
+    }
+
+    /// Move the camera, logging the movement like any other input while a
+    /// deterministic run is being recorded
+    fn handle_camera_input(&mut self, event: InputEvent) {
+        let Some(simulation) = &mut self.current_simulation else {
+            return;
+        };
+
+        match &mut self.deterministic_run {
+            Some(run) if run.is_replaying() => {}
+            Some(run) => {
+                event.apply_camera(simulation);
+                run.record_input(event);
             }
+            None => event.apply_camera(simulation),
         }
     }
 
     pub fn get_camera_state(&self) -> Option<serde_json::Value> {
-        if let Some(simulation) = &self.current_simulation {
-            match simulation {
-                SimulationType::SlimeMold(simulation) => Some(simulation.camera.get_state()),
-                SimulationType::GrayScott(simulation) => {
-                    Some(simulation.renderer.camera.get_state())
-                }
-                SimulationType::ParticleLife(simulation) => Some(simulation.get_camera_state()),
-                SimulationType::Flow(simulation) => Some(simulation.get_camera_state()),
-                SimulationType::Pellets(simulation) => Some(simulation.get_camera_state()),
-                SimulationType::MainMenu(_) => Some(serde_json::json!({})), // No camera for main menu background
-                _ => Some(serde_json::json!({})), // No camera for other simulations
-            }
-        } else {
-            None
-        }
+        let simulation = self.current_simulation.as_ref()?;
+        // The shared camera state includes the viewport, which Gray-Scott's
+        // frontend needs; simulations without a camera report their own
+        Some(
+            simulation
+                .camera()
+                .map(|camera| camera.get_state())
+                .unwrap_or_else(|| simulation.get_camera_state()),
+        )
     }
 
     /// Set the camera smoothing factor for the active simulation
     pub fn set_camera_smoothing(&mut self, smoothing_factor: f32) {
-        if let Some(simulation) = &mut self.current_simulation {
-            match simulation {
-                SimulationType::SlimeMold(simulation) => {
-                    simulation.camera.set_smoothing_factor(smoothing_factor)
-                }
-                SimulationType::GrayScott(simulation) => simulation
-                    .renderer
-                    .camera
-                    .set_smoothing_factor(smoothing_factor),
-                SimulationType::ParticleLife(simulation) => {
-                    simulation.camera.set_smoothing_factor(smoothing_factor)
-                }
-                SimulationType::Flow(simulation) => {
-                    simulation.camera.set_smoothing_factor(smoothing_factor)
-                }
-                SimulationType::Pellets(simulation) => {
-                    simulation.camera.set_smoothing_factor(smoothing_factor)
-                }
-                SimulationType::MainMenu(_) => {} // No camera for main menu background
-                _ => {}                           // No camera for other simulations
-            }
+        if let Some(camera) = self
+            .current_simulation
+            .as_mut()
+            .and_then(|simulation| simulation.camera_mut())
+        {
+            camera.set_smoothing_factor(smoothing_factor);
         }
     }
 
     /// Set the camera sensitivity for the active simulation
     pub fn set_camera_sensitivity(&mut self, sensitivity: f32) {
-        if let Some(simulation) = &mut self.current_simulation {
-            match simulation {
-                SimulationType::SlimeMold(simulation) => {
-                    simulation.camera.set_sensitivity(sensitivity)
-                }
-                SimulationType::GrayScott(simulation) => {
-                    simulation.renderer.camera.set_sensitivity(sensitivity)
-                }
-                SimulationType::ParticleLife(simulation) => {
-                    simulation.camera.set_sensitivity(sensitivity)
-                }
-                SimulationType::Flow(simulation) => simulation.camera.set_sensitivity(sensitivity),
-                SimulationType::Pellets(simulation) => {
-                    simulation.camera.set_sensitivity(sensitivity)
-                }
-                SimulationType::MainMenu(_) => {} // No camera for main menu background
-                _ => {}                           // No camera for other simulations
-            }
+        if let Some(camera) = self
+            .current_simulation
+            .as_mut()
+            .and_then(|simulation| simulation.camera_mut())
+        {
+            camera.set_sensitivity(sensitivity);
         }
     }
 
@@ -1161,60 +1527,7 @@ impl SimulationManager {
         device: &Arc<Device>,
         queue: &Arc<Queue>,
     ) -> AppResult<()> {
-        if let Some(simulation) = &mut self.current_simulation {
-            match simulation {
-                SimulationType::SlimeMold(simulation) => {
-                    simulation
-                        .update_setting(
-                            "cursor_size",
-                            serde_json::Value::Number(
-                                serde_json::Number::from_f64(size as f64).unwrap(),
-                            ),
-                            device,
-                            queue,
-                        )
-                        .map_err(AppError::Simulation)?;
-                }
-                SimulationType::ParticleLife(simulation) => {
-                    simulation
-                        .update_setting(
-                            "cursor_size",
-                            serde_json::Value::Number(
-                                serde_json::Number::from_f64(size as f64).unwrap(),
-                            ),
-                            device,
-                            queue,
-                        )
-                        .map_err(AppError::Simulation)?;
-                }
-                SimulationType::Pellets(simulation) => {
-                    simulation
-                        .update_setting(
-                            "cursor_size",
-                            serde_json::Value::Number(
-                                serde_json::Number::from_f64(size as f64).unwrap(),
-                            ),
-                            device,
-                            queue,
-                        )
-                        .map_err(AppError::Simulation)?;
-                }
-                _ => {
-                    return Err(AppError::Simulation(
-                        crate::error::SimulationError::InvalidParameter(
-                            "Cursor size not supported for this simulation type".to_string(),
-                        ),
-                    ));
-                }
-            }
-        } else {
-            return Err(AppError::Simulation(
-                crate::error::SimulationError::InvalidParameter(
-                    "No simulation running".to_string(),
-                ),
-            ));
-        }
-        Ok(())
+        self.update_cursor_setting("cursor_size", size, device, queue)
     }
 
     /// Update cursor strength for the active simulation
@@ -1224,59 +1537,556 @@ impl SimulationManager {
         device: &Arc<Device>,
         queue: &Arc<Queue>,
     ) -> AppResult<()> {
-        if let Some(simulation) = &mut self.current_simulation {
-            match simulation {
-                SimulationType::SlimeMold(simulation) => {
-                    simulation
-                        .update_setting(
-                            "cursor_strength",
-                            serde_json::Value::Number(
-                                serde_json::Number::from_f64(strength as f64).unwrap(),
-                            ),
-                            device,
-                            queue,
-                        )
-                        .map_err(AppError::Simulation)?;
-                }
-                SimulationType::ParticleLife(simulation) => {
-                    simulation
-                        .update_setting(
-                            "cursor_strength",
-                            serde_json::Value::Number(
-                                serde_json::Number::from_f64(strength as f64).unwrap(),
-                            ),
-                            device,
-                            queue,
+        self.update_cursor_setting("cursor_strength", strength, device, queue)
+    }
+
+    fn update_cursor_setting(
+        &mut self,
+        setting_name: &str,
+        value: f32,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> AppResult<()> {
+        let simulation = self.current_simulation.as_ref().ok_or_else(|| {
+            SimulationError::InvalidParameter("No simulation running".to_string())
+        })?;
+        let descriptor = simulation.descriptor();
+        if !descriptor.capabilities.cursor {
+            return Err(SimulationError::InvalidParameter(format!(
+                "{} does not support '{}'",
+                descriptor.display_name, setting_name
+            ))
+            .into());
+        }
+        self.handle_input(
+            InputEvent::UpdateSetting {
+                name: setting_name.to_string(),
+                value: serde_json::json!(value),
+            },
+            device,
+            queue,
+        )
+    }
+
+    /// Edits that are not logged as inputs would make a deterministic run
+    /// diverge from its replay, so they are refused while one is active
+    fn ensure_not_deterministic(&self, action: &str) -> AppResult<()> {
+        if self.deterministic_run.is_some() {
+            return Err(SimulationError::InvalidParameter(format!(
+                "Cannot {} during a deterministic run",
+                action
+            ))
+            .into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::headless::{DEFAULT_DELTA_TIME, HeadlessRenderer};
+    use crate::simulations::shared::settings_schema::SettingKind;
+    use vizza_core::settings::RenderSettings;
+
+    async fn renderer() -> HeadlessRenderer {
+        HeadlessRenderer::new(
+            64,
+            48,
+            wgpu::TextureFormat::Rgba8UnormSrgb,
+            RenderSettings::default(),
+        )
+        .await
+        .expect("Failed to create headless renderer")
+    }
+
+    #[tokio::test]
+    async fn test_capture_screenshot() {
+        let renderer = renderer().await;
+        let mut manager = SimulationManager::new(Arc::new(AppSettings::default()));
+        manager.current_simulation = Some(
+            renderer
+                .create_simulation("gray_scott")
+                .await
+                .expect("Failed to create simulation"),
+        );
+        manager
+            .render(
+                &renderer.device,
+                &renderer.queue,
+                &renderer.target.view,
+                DEFAULT_DELTA_TIME,
+            )
+            .expect("Failed to render frame");
+
+        let path = std::env::temp_dir()
+            .join(format!("vizza-screenshot-{}", std::process::id()))
+            .join("capture.png");
+        let size = manager
+            .capture_screenshot(
+                &path,
+                2,
+                &renderer.device,
+                &renderer.queue,
+                &renderer.surface_config(),
+            )
+            .expect("Failed to capture screenshot");
+        assert_eq!(size, (128, 96));
+
+        let decoder = png::Decoder::new(std::fs::File::open(&path).unwrap());
+        let reader = decoder.read_info().expect("Screenshot is not a valid PNG");
+        assert_eq!(reader.info().width, 128);
+        assert_eq!(reader.info().height, 96);
+        assert_eq!(reader.info().color_type, png::ColorType::Rgba);
+
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[tokio::test]
+    async fn test_capture_screenshot_rejects_invalid_requests() {
+        let renderer = renderer().await;
+        let mut manager = SimulationManager::new(Arc::new(AppSettings::default()));
+        let path = std::env::temp_dir().join("vizza-screenshot-unused.png");
+
+        // Nothing to capture without a simulation
+        assert!(
+            manager
+                .capture_screenshot(
+                    &path,
+                    1,
+                    &renderer.device,
+                    &renderer.queue,
+                    &renderer.surface_config(),
+                )
+                .is_err()
+        );
+
+        manager.current_simulation = Some(
+            renderer
+                .create_simulation("main_menu")
+                .await
+                .expect("Failed to create simulation"),
+        );
+        assert!(
+            manager
+                .capture_screenshot(
+                    &path,
+                    0,
+                    &renderer.device,
+                    &renderer.queue,
+                    &renderer.surface_config(),
+                )
+                .is_err()
+        );
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_recording_captures_every_nth_frame() {
+        let renderer = renderer().await;
+        let mut manager = SimulationManager::new(Arc::new(AppSettings::default()));
+        manager.current_simulation = Some(
+            renderer
+                .create_simulation("gray_scott")
+                .await
+                .expect("Failed to create simulation"),
+        );
+
+        let output_dir =
+            std::env::temp_dir().join(format!("vizza-recording-{}", std::process::id()));
+        let options = RecordingOptions {
+            output_dir: output_dir.clone(),
+            frame_interval: 2,
+            max_frames: Some(3),
+            video_path: Some(output_dir.join("recording.mp4")),
+            encoder_program: "vizza-missing-encoder".to_string(),
+            ..Default::default()
+        };
+        let surface_config = renderer.surface_config();
+        manager
+            .start_recording(options, &renderer.device, &surface_config)
+            .expect("Failed to start recording");
+        assert_eq!(manager.recording_delta_time(), Some(1.0 / 60.0));
+
+        let mut captured = 0;
+        for _ in 0..5 {
+            let delta_time = manager.recording_delta_time().unwrap();
+            manager
+                .render(
+                    &renderer.device,
+                    &renderer.queue,
+                    &renderer.target.view,
+                    delta_time,
+                )
+                .expect("Failed to render frame");
+            if manager
+                .record_frame(&renderer.device, &renderer.queue, &surface_config)
+                .expect("Failed to record frame")
+                .is_some()
+            {
+                captured += 1;
+            }
+        }
+        assert_eq!(captured, 3);
+        assert!(manager.recorder.as_ref().unwrap().is_complete());
+
+        let summary = manager
+            .stop_recording()
+            .expect("Failed to finish recording")
+            .expect("Recording was not active");
+        assert_eq!(summary.frames_captured, 3);
+        assert_eq!((summary.width, summary.height), (64, 48));
+        // The encoder does not exist, so only the PNG sequence is written
+        assert!(summary.video_path.is_none());
+        for index in 0..3 {
+            assert!(output_dir.join(format!("frame_{:06}.png", index)).exists());
+        }
+        assert!(!output_dir.join("frame_000003.png").exists());
+        assert!(!manager.is_recording());
+
+        std::fs::remove_dir_all(&output_dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_recording_rejects_invalid_requests() {
+        let renderer = renderer().await;
+        let mut manager = SimulationManager::new(Arc::new(AppSettings::default()));
+        let output_dir = std::env::temp_dir().join("vizza-recording-unused");
+
+        // Nothing to record without a simulation
+        assert!(
+            manager
+                .start_recording(
+                    RecordingOptions::default(),
+                    &renderer.device,
+                    &renderer.surface_config(),
+                )
+                .is_err()
+        );
+
+        manager.current_simulation = Some(
+            renderer
+                .create_simulation("main_menu")
+                .await
+                .expect("Failed to create simulation"),
+        );
+        let options = RecordingOptions {
+            output_dir: output_dir.clone(),
+            frame_interval: 0,
+            ..Default::default()
+        };
+        assert!(
+            manager
+                .start_recording(options, &renderer.device, &renderer.surface_config())
+                .is_err()
+        );
+        assert!(!manager.is_recording());
+        assert!(!output_dir.exists());
+        assert!(manager.stop_recording().unwrap().is_none());
+    }
+
+    /// Record a deterministic run with mouse input, camera movement, a setting
+    /// change, a randomization and a reset, then replay its log and return the final
+    /// frames of both runs
+    async fn record_and_replay(simulation_type: &str, seed: u64) -> (Vec<u8>, Vec<u8>) {
+        let renderer = renderer().await;
+        let surface_config = renderer.surface_config();
+        let mut manager = SimulationManager::new(Arc::new(AppSettings::default()));
+
+        // Move the first plain float setting to the middle of its range
+        let (setting_name, value) = registry::settings_schema(simulation_type)
+            .unwrap()
+            .into_iter()
+            .find_map(|field| match field.kind {
+                SettingKind::Float { min, max, .. } if !field.requires_reset => {
+                    Some((field.setting_name, (min + max) / 2.0))
+                }
+                _ => None,
+            })
+            .expect("No float setting to change");
+
+        let run_frames = |manager: &mut SimulationManager| {
+            for frame in 0..12 {
+                match frame {
+                    3 | 4 => manager
+                        .handle_mouse_interaction_screen_coords(
+                            20.0 + frame as f32 * 4.0,
+                            24.0,
+                            0,
+                            &renderer.device,
+                            &renderer.queue,
                         )
-                        .map_err(AppError::Simulation)?;
-                }
-                SimulationType::Pellets(simulation) => {
-                    simulation
+                        .unwrap(),
+                    5 => manager.pan_camera(0.05, -0.02),
+                    6 => manager.handle_mouse_release(0, &renderer.queue).unwrap(),
+                    7 => manager
                         .update_setting(
-                            "cursor_strength",
-                            serde_json::Value::Number(
-                                serde_json::Number::from_f64(strength as f64).unwrap(),
-                            ),
-                            device,
-                            queue,
+                            setting_name,
+                            serde_json::json!(value),
+                            &renderer.device,
+                            &renderer.queue,
                         )
-                        .map_err(AppError::Simulation)?;
-                }
-                _ => {
-                    return Err(AppError::Simulation(
-                        crate::error::SimulationError::InvalidParameter(
-                            "Cursor strength not supported for this simulation type".to_string(),
-                        ),
-                    ));
-                }
+                        .unwrap(),
+                    8 => manager
+                        .randomize_settings(&renderer.device, &renderer.queue)
+                        .unwrap(),
+                    9 => manager
+                        .reset_simulation(&renderer.device, &renderer.queue)
+                        .unwrap(),
+                    _ => {}
+                }
+                // The measured time step is ignored during a deterministic run
+                manager
+                    .render(
+                        &renderer.device,
+                        &renderer.queue,
+                        &renderer.target.view,
+                        frame as f32 * 0.1,
+                    )
+                    .expect("Failed to render frame");
             }
-        } else {
-            return Err(AppError::Simulation(
-                crate::error::SimulationError::InvalidParameter(
-                    "No simulation running".to_string(),
-                ),
-            ));
+            renderer.read_pixels().expect("Failed to read pixels")
+        };
+
+        manager
+            .start_deterministic_run(
+                InputLog::new(simulation_type, seed, DEFAULT_DELTA_TIME),
+                false,
+                &renderer.device,
+                &renderer.queue,
+                &surface_config,
+                &renderer.adapter_info,
+            )
+            .expect("Failed to start deterministic run");
+        let recorded = run_frames(&mut manager);
+        let log = manager.stop_deterministic_run().unwrap();
+        assert_eq!(log.events.len(), 7);
+
+        manager
+            .start_deterministic_run(
+                log,
+                true,
+                &renderer.device,
+                &renderer.queue,
+                &surface_config,
+                &renderer.adapter_info,
+            )
+            .expect("Failed to start replay");
+        let replayed = run_frames(&mut manager);
+        assert_eq!(manager.deterministic_run.as_ref().unwrap().frame(), 12);
+
+        (recorded, replayed)
+    }
+
+    #[tokio::test]
+    async fn test_deterministic_replay_reproduces_frames() {
+        let (recorded, replayed) = record_and_replay("gray_scott", 1234).await;
+        assert!(recorded == replayed, "Replay diverged from the recording");
+    }
+
+    #[tokio::test]
+    async fn test_deterministic_runs_reject_unlogged_edits() {
+        let renderer = renderer().await;
+        let surface_config = renderer.surface_config();
+        let mut manager = SimulationManager::new(Arc::new(AppSettings::default()));
+
+        // Their frames depend on GPU thread scheduling
+        for simulation_type in [
+            "slime_mold",
+            "flow",
+            "ecosystem",
+            "particle_life",
+            "pellets",
+        ] {
+            assert!(
+                manager
+                    .start_deterministic_run(
+                        InputLog::new(simulation_type, 1, DEFAULT_DELTA_TIME),
+                        false,
+                        &renderer.device,
+                        &renderer.queue,
+                        &surface_config,
+                        &renderer.adapter_info,
+                    )
+                    .is_err()
+            );
         }
-        Ok(())
+
+        manager
+            .start_deterministic_run(
+                InputLog::new("gray_scott", 1, DEFAULT_DELTA_TIME),
+                false,
+                &renderer.device,
+                &renderer.queue,
+                &surface_config,
+                &renderer.adapter_info,
+            )
+            .unwrap();
+        let preset = manager.get_available_presets()[0].clone();
+        assert!(
+            manager
+                .apply_preset(&preset, &renderer.device, &renderer.queue)
+                .is_err()
+        );
+        assert!(manager.undo(&renderer.device, &renderer.queue).is_err());
+        assert!(manager.redo(&renderer.device, &renderer.queue).is_err());
+        let lut = manager.get_available_luts()[0].clone();
+        assert!(
+            manager
+                .apply_lut(&lut, &renderer.device, &renderer.queue)
+                .is_err()
+        );
+        assert!(
+            manager
+                .reverse_current_lut(&renderer.device, &renderer.queue)
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_deterministic_runs_depend_on_seed() {
+        let (first, _) = record_and_replay("gray_scott", 1).await;
+        let (second, _) = record_and_replay("gray_scott", 2).await;
+        assert!(first != second);
+    }
+
+    /// Mean absolute difference of two frames, per channel
+    fn mean_difference(a: &[u8], b: &[u8]) -> f32 {
+        let total: u64 = a
+            .iter()
+            .zip(b)
+            .map(|(a, b)| u64::from(a.abs_diff(*b)))
+            .sum();
+        total as f32 / a.len() as f32
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_restores_simulation_state() {
+        let renderer = renderer().await;
+        let path = std::env::temp_dir()
+            .join(format!("vizza-snapshot-{}", std::process::id()))
+            .join("state.vzs");
+
+        let render_frames = |manager: &mut SimulationManager, frames: u32| {
+            for _ in 0..frames {
+                manager
+                    .render(
+                        &renderer.device,
+                        &renderer.queue,
+                        &renderer.target.view,
+                        DEFAULT_DELTA_TIME,
+                    )
+                    .expect("Failed to render frame");
+            }
+            renderer.read_pixels().expect("Failed to read pixels")
+        };
+
+        for simulation_type in [
+            "slime_mold",
+            "gray_scott",
+            "particle_life",
+            "flow",
+            "pellets",
+            "ecosystem",
+        ] {
+            let mut original = SimulationManager::new(Arc::new(AppSettings::default()));
+            original.current_simulation = Some(
+                renderer
+                    .create_simulation(simulation_type)
+                    .await
+                    .expect("Failed to create simulation"),
+            );
+            original.pan_camera(0.1, -0.05);
+            render_frames(&mut original, 5);
+            original
+                .take_snapshot(&renderer.device, &renderer.queue)
+                .and_then(|snapshot| snapshot.save(&path))
+                .expect("Failed to save snapshot");
+
+            // A fresh simulation starts from a different random layout
+            let mut restored = SimulationManager::new(Arc::new(AppSettings::default()));
+            restored.current_simulation = Some(
+                renderer
+                    .create_simulation(simulation_type)
+                    .await
+                    .expect("Failed to create simulation"),
+            );
+            let snapshot = SimulationSnapshot::load(&path).expect("Failed to load snapshot");
+            assert!(snapshot.lut.is_some());
+            restored
+                .restore_snapshot(&snapshot, &renderer.device, &renderer.queue)
+                .expect("Failed to restore snapshot");
+
+            let expected = render_frames(&mut original, 3);
+            let actual = render_frames(&mut restored, 3);
+            if registry::get(simulation_type)
+                .unwrap()
+                .capabilities
+                .deterministic
+            {
+                assert!(
+                    expected == actual,
+                    "{} diverged after restoring a snapshot",
+                    simulation_type
+                );
+                continue;
+            }
+
+            // Racing GPU passes make frames differ slightly from run to run,
+            // but far less than from an unrelated random layout
+            let mut unrelated = SimulationManager::new(Arc::new(AppSettings::default()));
+            unrelated.current_simulation = Some(
+                renderer
+                    .create_simulation(simulation_type)
+                    .await
+                    .expect("Failed to create simulation"),
+            );
+            unrelated.pan_camera(0.1, -0.05);
+            let unrelated = render_frames(&mut unrelated, 8);
+            let restored_difference = mean_difference(&expected, &actual);
+            let unrelated_difference = mean_difference(&expected, &unrelated);
+            assert!(
+                restored_difference < unrelated_difference * 0.5,
+                "{} diverged after restoring a snapshot ({} vs {})",
+                simulation_type,
+                restored_difference,
+                unrelated_difference
+            );
+        }
+
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_rejects_other_simulation_types() {
+        let renderer = renderer().await;
+        let mut manager = SimulationManager::new(Arc::new(AppSettings::default()));
+        assert!(
+            manager
+                .take_snapshot(&renderer.device, &renderer.queue)
+                .is_err()
+        );
+
+        manager.current_simulation = Some(
+            renderer
+                .create_simulation("gray_scott")
+                .await
+                .expect("Failed to create simulation"),
+        );
+        let snapshot = manager
+            .take_snapshot(&renderer.device, &renderer.queue)
+            .expect("Failed to take snapshot");
+
+        manager.current_simulation = Some(
+            renderer
+                .create_simulation("flow")
+                .await
+                .expect("Failed to create simulation"),
+        );
+        assert!(
+            manager
+                .restore_snapshot(&snapshot, &renderer.device, &renderer.queue)
+                .is_err()
+        );
     }
 }
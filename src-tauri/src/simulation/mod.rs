@@ -1,4 +1,4 @@
 pub mod manager;
-pub mod preset_manager;
 
 pub use manager::SimulationManager;
+pub use vizza_core::simulation::*;
@@ -1,4 +0,0 @@
-pub mod shaders;
-pub mod simulation;
-
-pub use simulation::MainMenuModel;
@@ -0,0 +1,11 @@
+//! # Vizza Core
+//!
+//! The simulation engine behind Vizza: the simulations themselves, presets,
+//! LUTs, headless rendering and the tooling built on it, with no dependency on
+//! Tauri. The desktop app drives it through its commands and render loop, and
+//! other programs can render the same simulations with `HeadlessRenderer`.
+
+pub mod error;
+pub mod settings;
+pub mod simulation;
+pub mod simulations;
@@ -0,0 +1,43 @@
+//! Settings the simulations need from the host application, and where Vizza
+//! keeps its user data.
+
+use dirs::home_dir;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum TextureFiltering {
+    Linear,
+    Nearest,
+    Lanczos,
+}
+
+impl From<TextureFiltering> for wgpu::FilterMode {
+    fn from(filtering: TextureFiltering) -> Self {
+        match filtering {
+            TextureFiltering::Linear => wgpu::FilterMode::Linear,
+            TextureFiltering::Nearest => wgpu::FilterMode::Nearest,
+            TextureFiltering::Lanczos => wgpu::FilterMode::Linear, // Lanczos uses Linear as base, custom shader handles the rest
+        }
+    }
+}
+
+/// Rendering options shared by every simulation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenderSettings {
+    pub texture_filtering: TextureFiltering,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            texture_filtering: TextureFiltering::Linear,
+        }
+    }
+}
+
+/// Directory holding settings, presets and custom LUTs
+pub fn data_dir() -> PathBuf {
+    let home_dir = home_dir().unwrap_or_else(|| PathBuf::from("."));
+    home_dir.join("Vizza")
+}
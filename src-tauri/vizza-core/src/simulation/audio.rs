@@ -0,0 +1,674 @@
+//! # Audio-Reactive Modulation
+//!
+//! Drives settings from a piece of music. A track is decoded from a WAV or
+//! FLAC file, or read as raw PCM from stdin, mixed down to mono and analysed
+//! once up front into one set of [`AudioFeatures`] per frame:
+//!
+//! - `rms`, the overall loudness
+//! - `bass`, `mid` and `treble`, the loudness below 150 Hz, between 150 Hz and
+//!   2 kHz, and above 2 kHz
+//! - `onset` and `kick`, how sharply the overall and bass loudness rose over
+//!   the previous few frames
+//!
+//! Every feature is normalized against the track's own loud passages so it
+//! lies in `0..=1`. [`AudioMapping`]s then shape a feature with a curve and
+//! map it onto a setting's range, e.g. kick to Particle Life `max_force`.
+//!
+//! Features are looked up by playback time rather than by wall clock. Offline
+//! renders analyse the track with the render's fixed time step, so frame `n`
+//! of the output always sees analysis frame `n` of the track.
+
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
+use std::io::Read;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AppResult, SimulationError, SimulationResult};
+use crate::simulation::modulation::SettingTarget;
+use crate::simulations::shared::settings_schema::SettingField;
+
+/// Upper edge of the bass band, in Hz
+const BASS_CUTOFF: f64 = 150.0;
+/// Lower edge of the treble band, in Hz
+const TREBLE_CUTOFF: f64 = 2000.0;
+/// Number of preceding frames an onset is measured against
+const ONSET_HISTORY: usize = 8;
+/// Features are normalized so this fraction of frames stays below 1
+const NORMALIZE_QUANTILE: f64 = 0.98;
+/// Smallest onset scale, as a fraction of the track's loudness, so steady
+/// tracks don't have their tiny fluctuations blown up into onsets
+const MIN_ONSET_SCALE: f64 = 0.1;
+
+/// Decoded mono audio
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioClip {
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+}
+
+impl AudioClip {
+    /// Decode a `.wav` or `.flac` file
+    pub fn load(path: &Path) -> AppResult<Self> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        let decode_error = |e: &dyn std::fmt::Display| {
+            SimulationError::InvalidParameter(format!("Could not decode {}: {}", path.display(), e))
+        };
+
+        match extension.as_deref() {
+            Some("wav") => {
+                let mut reader = hound::WavReader::open(path).map_err(|e| decode_error(&e))?;
+                let spec = reader.spec();
+                let samples = match spec.sample_format {
+                    hound::SampleFormat::Float => reader
+                        .samples::<f32>()
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|e| decode_error(&e))?,
+                    hound::SampleFormat::Int => {
+                        let scale = 1.0 / (1u64 << (spec.bits_per_sample - 1)) as f32;
+                        reader
+                            .samples::<i32>()
+                            .map(|sample| sample.map(|sample| sample as f32 * scale))
+                            .collect::<Result<Vec<_>, _>>()
+                            .map_err(|e| decode_error(&e))?
+                    }
+                };
+                Ok(Self::from_interleaved(
+                    &samples,
+                    spec.channels,
+                    spec.sample_rate,
+                ))
+            }
+            Some("flac") => {
+                let mut reader = claxon::FlacReader::open(path).map_err(|e| decode_error(&e))?;
+                let info = reader.streaminfo();
+                let scale = 1.0 / (1u64 << (info.bits_per_sample - 1)) as f32;
+                let samples = reader
+                    .samples()
+                    .map(|sample| sample.map(|sample| sample as f32 * scale))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| decode_error(&e))?;
+                Ok(Self::from_interleaved(
+                    &samples,
+                    info.channels as u16,
+                    info.sample_rate,
+                ))
+            }
+            _ => Err(SimulationError::InvalidParameter(format!(
+                "Unsupported audio file {}, expected .wav or .flac",
+                path.display()
+            ))
+            .into()),
+        }
+    }
+
+    /// Read interleaved signed 16-bit little-endian PCM until the end of the
+    /// stream
+    pub fn read_pcm(mut reader: impl Read, sample_rate: u32, channels: u16) -> AppResult<Self> {
+        if sample_rate == 0 || channels == 0 {
+            return Err(SimulationError::InvalidParameter(
+                "PCM input needs a sample rate and channel count".to_string(),
+            )
+            .into());
+        }
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        let samples = bytes
+            .chunks_exact(2)
+            .map(|pair| i16::from_le_bytes([pair[0], pair[1]]) as f32 / 32768.0)
+            .collect::<Vec<_>>();
+        Ok(Self::from_interleaved(&samples, channels, sample_rate))
+    }
+
+    /// Mix interleaved channels down to mono
+    pub fn from_interleaved(samples: &[f32], channels: u16, sample_rate: u32) -> Self {
+        let channels = channels.max(1) as usize;
+        Self {
+            samples: samples
+                .chunks_exact(channels)
+                .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+                .collect(),
+            sample_rate,
+        }
+    }
+
+    /// Length in seconds
+    pub fn duration(&self) -> f64 {
+        self.samples.len() as f64 / self.sample_rate as f64
+    }
+}
+
+/// Second-order IIR filter using the RBJ cookbook coefficients
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+    x: [f64; 2],
+    y: [f64; 2],
+}
+
+impl Biquad {
+    fn new(sample_rate: u32, cutoff: f64, high_pass: bool) -> Self {
+        // Keep the cutoff below Nyquist for low sample rates
+        let cutoff = cutoff.min(sample_rate as f64 * 0.45);
+        let w0 = 2.0 * PI * cutoff / sample_rate as f64;
+        let alpha = w0.sin() / (2.0 * FRAC_1_SQRT_2);
+        let cos = w0.cos();
+        let a0 = 1.0 + alpha;
+        let b = if high_pass {
+            [(1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0]
+        } else {
+            [(1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0]
+        };
+        Self {
+            b: b.map(|b| b / a0),
+            a: [-2.0 * cos / a0, (1.0 - alpha) / a0],
+            x: [0.0; 2],
+            y: [0.0; 2],
+        }
+    }
+
+    fn process(&mut self, input: f64) -> f64 {
+        let output = self.b[0] * input + self.b[1] * self.x[0] + self.b[2] * self.x[1]
+            - self.a[0] * self.y[0]
+            - self.a[1] * self.y[1];
+        self.x = [input, self.x[0]];
+        self.y = [output, self.y[0]];
+        output
+    }
+}
+
+/// Analysed audio for one frame, every value normalized to `0..=1`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct AudioFeatures {
+    pub rms: f32,
+    pub bass: f32,
+    pub mid: f32,
+    pub treble: f32,
+    pub onset: f32,
+    pub kick: f32,
+}
+
+/// A feature that can drive a setting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioFeature {
+    Rms,
+    Bass,
+    Mid,
+    Treble,
+    Onset,
+    Kick,
+}
+
+impl AudioFeatures {
+    pub fn get(&self, feature: AudioFeature) -> f32 {
+        match feature {
+            AudioFeature::Rms => self.rms,
+            AudioFeature::Bass => self.bass,
+            AudioFeature::Mid => self.mid,
+            AudioFeature::Treble => self.treble,
+            AudioFeature::Onset => self.onset,
+            AudioFeature::Kick => self.kick,
+        }
+    }
+}
+
+/// Per-frame features of a whole track
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioAnalysis {
+    /// Seconds covered by each frame
+    pub frame_duration: f32,
+    pub frames: Vec<AudioFeatures>,
+}
+
+impl AudioAnalysis {
+    /// Split `clip` into frames of `frame_duration` seconds and analyse each
+    pub fn analyze(clip: &AudioClip, frame_duration: f32) -> SimulationResult<Self> {
+        if !(frame_duration.is_finite() && frame_duration > 0.0) || clip.sample_rate == 0 {
+            return Err(SimulationError::InvalidParameter(
+                "Audio analysis needs a positive frame duration and sample rate".to_string(),
+            ));
+        }
+
+        let samples_per_frame = clip.sample_rate as f64 * frame_duration as f64;
+        let frame_count = (clip.samples.len() as f64 / samples_per_frame).ceil() as usize;
+        let mut bass_filter = Biquad::new(clip.sample_rate, BASS_CUTOFF, false);
+        let mut mid_high_pass = Biquad::new(clip.sample_rate, BASS_CUTOFF, true);
+        let mut mid_low_pass = Biquad::new(clip.sample_rate, TREBLE_CUTOFF, false);
+        let mut treble_filter = Biquad::new(clip.sample_rate, TREBLE_CUTOFF, true);
+
+        let mut rms = Vec::with_capacity(frame_count);
+        let mut bass = Vec::with_capacity(frame_count);
+        let mut mid = Vec::with_capacity(frame_count);
+        let mut treble = Vec::with_capacity(frame_count);
+        for frame in 0..frame_count {
+            let start = (frame as f64 * samples_per_frame).round() as usize;
+            let end =
+                (((frame + 1) as f64 * samples_per_frame).round() as usize).min(clip.samples.len());
+            let mut sums = [0.0f64; 4];
+            for &sample in &clip.samples[start..end] {
+                let sample = sample as f64;
+                let bands = [
+                    sample,
+                    bass_filter.process(sample),
+                    mid_low_pass.process(mid_high_pass.process(sample)),
+                    treble_filter.process(sample),
+                ];
+                for (sum, band) in sums.iter_mut().zip(bands) {
+                    *sum += band * band;
+                }
+            }
+            let count = (end - start).max(1) as f64;
+            let [all, low, middle, high] = sums.map(|sum| (sum / count).sqrt());
+            rms.push(all);
+            bass.push(low);
+            mid.push(middle);
+            treble.push(high);
+        }
+        let onset = onsets(&rms);
+        let kick = onsets(&bass);
+
+        // Bands share the track's loudness as their scale, so a band the track
+        // barely uses stays near 0 rather than having its filter leakage
+        // scaled up to full range
+        let loudness = reference_level(&rms);
+        let onset_scale = reference_level(&onset).max(loudness * MIN_ONSET_SCALE);
+        let [rms, bass, mid, treble] =
+            [rms, bass, mid, treble].map(|values| normalize(&values, loudness));
+        let [onset, kick] = [onset, kick].map(|values| normalize(&values, onset_scale));
+        let frames = (0..frame_count)
+            .map(|frame| AudioFeatures {
+                rms: rms[frame],
+                bass: bass[frame],
+                mid: mid[frame],
+                treble: treble[frame],
+                onset: onset[frame],
+                kick: kick[frame],
+            })
+            .collect();
+
+        Ok(Self {
+            frame_duration,
+            frames,
+        })
+    }
+
+    /// Features at `time` seconds into the track, or silence past its end
+    pub fn at(&self, time: f64) -> AudioFeatures {
+        // Nudge so accumulated fixed steps land on the frame they count to
+        let frame = (time / self.frame_duration as f64 + 1e-6).floor();
+        if frame < 0.0 {
+            return AudioFeatures::default();
+        }
+        self.frames.get(frame as usize).copied().unwrap_or_default()
+    }
+
+    pub fn duration(&self) -> f64 {
+        self.frames.len() as f64 * self.frame_duration as f64
+    }
+}
+
+/// Rise of each value above the average of the frames before it
+fn onsets(values: &[f64]) -> Vec<f64> {
+    (0..values.len())
+        .map(|frame| {
+            let history = &values[frame.saturating_sub(ONSET_HISTORY)..frame];
+            if history.is_empty() {
+                return 0.0;
+            }
+            let average = history.iter().sum::<f64>() / history.len() as f64;
+            (values[frame] - average).max(0.0)
+        })
+        .collect()
+}
+
+/// Level that all but the loudest few values stay below, or 0 for silence
+fn reference_level(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    sorted
+        .get(((sorted.len() as f64 - 1.0) * NORMALIZE_QUANTILE).round() as usize)
+        .copied()
+        .filter(|reference| *reference > 1e-9)
+        .or_else(|| sorted.last().copied())
+        .unwrap_or_default()
+}
+
+/// Scale values by `reference` into `0..=1`
+fn normalize(values: &[f64], reference: f64) -> Vec<f32> {
+    values
+        .iter()
+        .map(|value| {
+            if reference > 1e-9 {
+                (value / reference).clamp(0.0, 1.0) as f32
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+/// Response curve applied to a feature before it is mapped onto a setting
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Curve {
+    #[default]
+    Linear,
+    /// `x^exponent`; exponents above 1 emphasise peaks
+    Power {
+        exponent: f32,
+    },
+    Smoothstep,
+    /// 1 when the feature reaches `level`, otherwise 0
+    Threshold {
+        level: f32,
+    },
+}
+
+impl Curve {
+    pub fn apply(self, x: f32) -> f32 {
+        let x = x.clamp(0.0, 1.0);
+        match self {
+            Curve::Linear => x,
+            Curve::Power { exponent } => x.powf(exponent.max(0.0)),
+            Curve::Smoothstep => x * x * (3.0 - 2.0 * x),
+            Curve::Threshold { level } => {
+                if x >= level {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+/// Maps an audio feature onto a setting
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AudioMapping {
+    /// Setting to drive, by its field name or `update_setting` name
+    pub setting_name: String,
+    pub feature: AudioFeature,
+    /// Setting value when the shaped feature is 0
+    pub min: f32,
+    /// Setting value when the shaped feature is 1
+    pub max: f32,
+    #[serde(default)]
+    pub curve: Curve,
+    /// Seconds the value takes to fall back after a peak, so that short
+    /// hits like kicks stay visible. Rises are always immediate.
+    #[serde(default)]
+    pub release: f32,
+}
+
+/// Mappings as stored in an audio mapping TOML file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AudioMappingFile {
+    pub mappings: Vec<AudioMapping>,
+}
+
+impl AudioMappingFile {
+    pub fn load(path: &Path) -> AppResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        toml::from_str(&content).map_err(|e| {
+            SimulationError::InvalidParameter(format!(
+                "Invalid audio mappings {}: {}",
+                path.display(),
+                e
+            ))
+            .into()
+        })
+    }
+}
+
+struct ActiveMapping {
+    mapping: AudioMapping,
+    target: SettingTarget,
+    /// Shaped feature after the release smoothing
+    level: f32,
+}
+
+/// Plays an analysed track's features onto a simulation's settings
+pub struct AudioReactive {
+    analysis: AudioAnalysis,
+    mappings: Vec<ActiveMapping>,
+    /// Playback position in seconds
+    time: f64,
+}
+
+impl AudioReactive {
+    pub fn new(
+        analysis: AudioAnalysis,
+        mappings: Vec<AudioMapping>,
+        schema: &[SettingField],
+    ) -> SimulationResult<Self> {
+        let mappings = mappings
+            .into_iter()
+            .map(|mapping| {
+                if !(mapping.min.is_finite()
+                    && mapping.max.is_finite()
+                    && mapping.release.is_finite()
+                    && mapping.release >= 0.0)
+                {
+                    return Err(SimulationError::InvalidParameter(format!(
+                        "Audio mapping for {} needs finite bounds and a non-negative release",
+                        mapping.setting_name
+                    )));
+                }
+                Ok(ActiveMapping {
+                    target: SettingTarget::resolve(&mapping.setting_name, schema)?,
+                    mapping,
+                    level: 0.0,
+                })
+            })
+            .collect::<SimulationResult<Vec<_>>>()?;
+
+        Ok(Self {
+            analysis,
+            mappings,
+            time: 0.0,
+        })
+    }
+
+    /// Playback position in seconds
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    pub fn duration(&self) -> f64 {
+        self.analysis.duration()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.time >= self.analysis.duration()
+    }
+
+    /// Values for the mapped settings at the current position, then advance
+    /// the position by `delta_time`
+    pub fn step(&mut self, delta_time: f32) -> Vec<(&'static str, serde_json::Value)> {
+        let features = self.analysis.at(self.time);
+        self.time += delta_time as f64;
+
+        self.mappings
+            .iter_mut()
+            .map(|active| {
+                let mapping = &active.mapping;
+                let shaped = mapping.curve.apply(features.get(mapping.feature));
+                active.level = if shaped >= active.level || mapping.release <= 0.0 {
+                    shaped
+                } else {
+                    let decay = (-delta_time / mapping.release).exp();
+                    shaped + (active.level - shaped) * decay
+                };
+                let value = mapping.min + (mapping.max - mapping.min) * active.level;
+                (
+                    active.target.setting_name,
+                    active.target.value(value as f64),
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::TAU;
+
+    const SAMPLE_RATE: u32 = 22050;
+
+    /// Tone of `frequency` Hz lasting `seconds`, with bursts of a 60 Hz kick
+    /// at the given times
+    fn track(seconds: f32, frequency: f32, kicks: &[f32]) -> AudioClip {
+        let samples = (0..(seconds * SAMPLE_RATE as f32) as usize)
+            .map(|index| {
+                let t = index as f32 / SAMPLE_RATE as f32;
+                let tone = 0.1 * (TAU * frequency * t).sin();
+                let kick = kicks
+                    .iter()
+                    .filter(|start| t >= **start && t < **start + 0.1)
+                    .map(|_| 0.8 * (TAU * 60.0 * t).sin())
+                    .sum::<f32>();
+                tone + kick
+            })
+            .collect();
+        AudioClip {
+            samples,
+            sample_rate: SAMPLE_RATE,
+        }
+    }
+
+    #[test]
+    fn test_bands_follow_frequency() {
+        let frame = 1.0 / 30.0;
+        let low = AudioAnalysis::analyze(&track(1.0, 60.0, &[]), frame).unwrap();
+        let high = AudioAnalysis::analyze(&track(1.0, 5000.0, &[]), frame).unwrap();
+        let average = |analysis: &AudioAnalysis, feature| {
+            analysis
+                .frames
+                .iter()
+                .map(|frame| frame.get(feature))
+                .sum::<f32>()
+                / analysis.frames.len() as f32
+        };
+        assert!(average(&low, AudioFeature::Bass) > 0.5);
+        assert!(average(&high, AudioFeature::Treble) > 0.5);
+        assert!(average(&high, AudioFeature::Bass) < 0.1);
+        // A steady tone has no onsets to speak of
+        assert!(average(&high, AudioFeature::Kick) < 0.1);
+        assert!(average(&low, AudioFeature::Onset) < 0.1);
+    }
+
+    #[test]
+    fn test_kicks_land_on_their_frames() {
+        let frame = 1.0 / 60.0;
+        let analysis =
+            AudioAnalysis::analyze(&track(3.0, 3000.0, &[0.5, 1.5, 2.5]), frame).unwrap();
+        assert_eq!(analysis.frames.len(), 180);
+
+        // Each kick peaks within a few frames of its start and is quiet before
+        for start in [30, 90, 150] {
+            let peak = (start..start + 6)
+                .map(|frame| analysis.frames[frame].kick)
+                .fold(0.0, f32::max);
+            assert!(peak > 0.5, "no kick near frame {start}");
+            assert!(analysis.frames[start - 5].kick < 0.1);
+        }
+    }
+
+    #[test]
+    fn test_playback_is_locked_to_fixed_steps() {
+        let frame = 1.0 / 24.0;
+        let analysis = AudioAnalysis::analyze(&track(2.0, 3000.0, &[0.5, 1.25]), frame).unwrap();
+        let schema = crate::simulations::particle_life::settings::Settings::schema();
+        let mut reactive = AudioReactive::new(
+            analysis.clone(),
+            vec![AudioMapping {
+                setting_name: "max_force".to_string(),
+                feature: AudioFeature::Kick,
+                min: 0.0,
+                max: 4.0,
+                curve: Curve::Linear,
+                release: 0.0,
+            }],
+            &schema,
+        )
+        .unwrap();
+
+        for index in 0..analysis.frames.len() {
+            let (setting_name, value) = reactive.step(frame).remove(0);
+            assert_eq!(setting_name, "max_force");
+            let expected = 4.0 * analysis.frames[index].kick;
+            assert!((value.as_f64().unwrap() as f32 - expected).abs() < 1e-5);
+        }
+        assert!(reactive.is_finished());
+    }
+
+    #[test]
+    fn test_release_holds_peaks() {
+        let analysis = AudioAnalysis {
+            frame_duration: 0.1,
+            frames: vec![
+                AudioFeatures {
+                    kick: 1.0,
+                    ..Default::default()
+                },
+                AudioFeatures::default(),
+            ],
+        };
+        let schema = crate::simulations::particle_life::settings::Settings::schema();
+        let mapping = AudioMapping {
+            setting_name: "max_force".to_string(),
+            feature: AudioFeature::Kick,
+            min: 0.0,
+            max: 1.0,
+            curve: Curve::Linear,
+            release: 0.1,
+        };
+        let mut reactive = AudioReactive::new(analysis, vec![mapping], &schema).unwrap();
+        assert_eq!(reactive.step(0.1)[0].1, serde_json::json!(1.0));
+        let released = reactive.step(0.1)[0].1.as_f64().unwrap();
+        assert!((released - (-1.0f64).exp()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_curves() {
+        assert_eq!(Curve::Linear.apply(0.25), 0.25);
+        assert_eq!(Curve::Power { exponent: 2.0 }.apply(0.5), 0.25);
+        assert_eq!(Curve::Smoothstep.apply(0.5), 0.5);
+        assert_eq!(Curve::Threshold { level: 0.6 }.apply(0.5), 0.0);
+        assert_eq!(Curve::Threshold { level: 0.6 }.apply(0.7), 1.0);
+        assert_eq!(Curve::Linear.apply(2.0), 1.0);
+    }
+
+    #[test]
+    fn test_decodes_wav_and_pcm() {
+        let path = std::env::temp_dir().join(format!("vizza-audio-{}.wav", std::process::id()));
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: SAMPLE_RATE,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        let mut pcm = Vec::new();
+        for sample in [16384i16, -16384, 8192, 8192] {
+            writer.write_sample(sample).unwrap();
+            pcm.extend_from_slice(&sample.to_le_bytes());
+        }
+        writer.finalize().unwrap();
+
+        let clip = AudioClip::load(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(clip.sample_rate, SAMPLE_RATE);
+        assert_eq!(clip.samples, vec![0.0, 0.25]);
+        assert_eq!(
+            AudioClip::read_pcm(pcm.as_slice(), SAMPLE_RATE, 2).unwrap(),
+            clip
+        );
+        assert!(AudioClip::load(Path::new("track.mp3")).is_err());
+    }
+}
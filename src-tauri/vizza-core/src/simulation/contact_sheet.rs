@@ -0,0 +1,264 @@
+//! # Contact Sheet
+//!
+//! Lays out equally sized RGBA frames in a labelled grid. Labels are drawn
+//! with a small built-in 5x7 bitmap font so the sheet needs no font files.
+
+use std::io;
+use std::path::Path;
+
+use crate::simulations::shared::save_png;
+
+const GLYPH_WIDTH: u32 = 5;
+const GLYPH_HEIGHT: u32 = 7;
+/// Horizontal advance and line height in font pixels, including spacing
+const GLYPH_ADVANCE: u32 = GLYPH_WIDTH + 1;
+const LINE_HEIGHT: u32 = GLYPH_HEIGHT + 3;
+
+const BACKGROUND: [u8; 4] = [16, 16, 16, 255];
+const TEXT: [u8; 4] = [220, 220, 220, 255];
+
+/// Rows of each glyph, most significant of the low five bits on the left
+#[rustfmt::skip]
+const GLYPHS: &[(char, [u8; 7])] = &[
+    ('0', [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110]),
+    ('1', [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110]),
+    ('2', [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111]),
+    ('3', [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110]),
+    ('4', [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010]),
+    ('5', [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110]),
+    ('6', [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110]),
+    ('7', [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000]),
+    ('8', [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110]),
+    ('9', [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100]),
+    ('a', [0b00000, 0b00000, 0b01110, 0b00001, 0b01111, 0b10001, 0b01111]),
+    ('b', [0b10000, 0b10000, 0b10110, 0b11001, 0b10001, 0b10001, 0b11110]),
+    ('c', [0b00000, 0b00000, 0b01110, 0b10000, 0b10000, 0b10001, 0b01110]),
+    ('d', [0b00001, 0b00001, 0b01101, 0b10011, 0b10001, 0b10001, 0b01111]),
+    ('e', [0b00000, 0b00000, 0b01110, 0b10001, 0b11111, 0b10000, 0b01110]),
+    ('f', [0b00110, 0b01001, 0b01000, 0b11100, 0b01000, 0b01000, 0b01000]),
+    ('g', [0b00000, 0b01111, 0b10001, 0b10001, 0b01111, 0b00001, 0b01110]),
+    ('h', [0b10000, 0b10000, 0b10110, 0b11001, 0b10001, 0b10001, 0b10001]),
+    ('i', [0b00100, 0b00000, 0b01100, 0b00100, 0b00100, 0b00100, 0b01110]),
+    ('j', [0b00010, 0b00000, 0b00110, 0b00010, 0b00010, 0b10010, 0b01100]),
+    ('k', [0b10000, 0b10000, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010]),
+    ('l', [0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110]),
+    ('m', [0b00000, 0b00000, 0b11010, 0b10101, 0b10101, 0b10001, 0b10001]),
+    ('n', [0b00000, 0b00000, 0b10110, 0b11001, 0b10001, 0b10001, 0b10001]),
+    ('o', [0b00000, 0b00000, 0b01110, 0b10001, 0b10001, 0b10001, 0b01110]),
+    ('p', [0b00000, 0b00000, 0b11110, 0b10001, 0b11110, 0b10000, 0b10000]),
+    ('q', [0b00000, 0b00000, 0b01101, 0b10011, 0b01111, 0b00001, 0b00001]),
+    ('r', [0b00000, 0b00000, 0b10110, 0b11001, 0b10000, 0b10000, 0b10000]),
+    ('s', [0b00000, 0b00000, 0b01110, 0b10000, 0b01110, 0b00001, 0b11110]),
+    ('t', [0b01000, 0b01000, 0b11100, 0b01000, 0b01000, 0b01001, 0b00110]),
+    ('u', [0b00000, 0b00000, 0b10001, 0b10001, 0b10001, 0b10011, 0b01101]),
+    ('v', [0b00000, 0b00000, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100]),
+    ('w', [0b00000, 0b00000, 0b10001, 0b10001, 0b10101, 0b10101, 0b01010]),
+    ('x', [0b00000, 0b00000, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001]),
+    ('y', [0b00000, 0b00000, 0b10001, 0b10001, 0b01111, 0b00001, 0b01110]),
+    ('z', [0b00000, 0b00000, 0b11111, 0b00010, 0b00100, 0b01000, 0b11111]),
+    ('.', [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100]),
+    (',', [0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b00100, 0b01000]),
+    (':', [0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b01100, 0b00000]),
+    ('-', [0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000]),
+    ('+', [0b00000, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0b00000]),
+    ('=', [0b00000, 0b00000, 0b11111, 0b00000, 0b11111, 0b00000, 0b00000]),
+    ('_', [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b11111]),
+    ('/', [0b00000, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b00000]),
+    ('?', [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b00000, 0b00100]),
+];
+
+fn glyph(c: char) -> [u8; 7] {
+    if c == ' ' {
+        return [0; 7];
+    }
+    let c = c.to_ascii_lowercase();
+    GLYPHS
+        .iter()
+        .find(|(glyph_char, _)| *glyph_char == c)
+        .or_else(|| GLYPHS.iter().find(|(glyph_char, _)| *glyph_char == '?'))
+        .map(|(_, rows)| *rows)
+        .unwrap_or_default()
+}
+
+/// Width in pixels of `text` drawn at `scale`
+fn text_width(text: &str, scale: u32) -> u32 {
+    let count = text.chars().count() as u32;
+    (count * GLYPH_ADVANCE).saturating_sub(1) * scale
+}
+
+/// A grid of frames with labels for the columns and rows
+pub struct ContactSheet {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+    cell_width: u32,
+    cell_height: u32,
+    columns: u32,
+    rows: u32,
+    origin: (u32, u32),
+    gap: u32,
+    scale: u32,
+}
+
+impl ContactSheet {
+    /// Create an empty sheet. `title` is drawn above the grid, each column
+    /// label above its column and each row label left of its row.
+    pub fn new(
+        cell_width: u32,
+        cell_height: u32,
+        title: &str,
+        column_labels: &[String],
+        row_labels: &[String],
+    ) -> Self {
+        let columns = column_labels.len().max(1) as u32;
+        let rows = row_labels.len().max(1) as u32;
+        // Keep labels legible next to large cells
+        let scale = (cell_height / 160).clamp(1, 4);
+        let gap = 2 * scale;
+        let line_height = LINE_HEIGHT * scale;
+
+        let row_label_width = row_labels
+            .iter()
+            .map(|label| text_width(label, scale))
+            .max()
+            .map_or(0, |width| width + 2 * gap);
+        let column_label_height = if column_labels.is_empty() {
+            0
+        } else {
+            line_height
+        };
+        let origin = (
+            gap + row_label_width,
+            gap + line_height + column_label_height,
+        );
+
+        let grid_width = columns * (cell_width + gap) - gap;
+        let grid_height = rows * (cell_height + gap) - gap;
+        let width = (origin.0 + grid_width + gap).max(text_width(title, scale) + 2 * gap);
+        let height = origin.1 + grid_height + gap;
+
+        let mut sheet = Self {
+            width,
+            height,
+            pixels: BACKGROUND.repeat((width * height) as usize),
+            cell_width,
+            cell_height,
+            columns,
+            rows,
+            origin,
+            gap,
+            scale,
+        };
+
+        sheet.draw_text(title, gap, gap);
+        for (column, label) in column_labels.iter().enumerate() {
+            let (x, _) = sheet.cell_origin(column as u32, 0);
+            let centered = x + cell_width.saturating_sub(text_width(label, scale)) / 2;
+            sheet.draw_text(label, centered, gap + line_height);
+        }
+        for (row, label) in row_labels.iter().enumerate() {
+            let (_, y) = sheet.cell_origin(0, row as u32);
+            let centered = y + cell_height.saturating_sub(GLYPH_HEIGHT * scale) / 2;
+            sheet.draw_text(label, gap, centered);
+        }
+        sheet
+    }
+
+    fn cell_origin(&self, column: u32, row: u32) -> (u32, u32) {
+        (
+            self.origin.0 + column * (self.cell_width + self.gap),
+            self.origin.1 + row * (self.cell_height + self.gap),
+        )
+    }
+
+    /// Copy a tightly packed RGBA frame of the cell size into a grid cell
+    pub fn place(&mut self, column: u32, row: u32, pixels: &[u8]) {
+        debug_assert!(column < self.columns && row < self.rows);
+        debug_assert_eq!(
+            pixels.len(),
+            (self.cell_width * self.cell_height * 4) as usize
+        );
+
+        let (x, y) = self.cell_origin(column, row);
+        let row_bytes = (self.cell_width * 4) as usize;
+        for (line, source) in pixels.chunks_exact(row_bytes).enumerate() {
+            let start = (((y + line as u32) * self.width + x) * 4) as usize;
+            self.pixels[start..start + row_bytes].copy_from_slice(source);
+        }
+    }
+
+    fn draw_text(&mut self, text: &str, x: u32, y: u32) {
+        for (index, c) in text.chars().enumerate() {
+            let glyph_x = x + index as u32 * GLYPH_ADVANCE * self.scale;
+            for (glyph_row, bits) in glyph(c).iter().enumerate() {
+                for glyph_column in 0..GLYPH_WIDTH {
+                    if bits & (1 << (GLYPH_WIDTH - 1 - glyph_column)) != 0 {
+                        self.fill(
+                            glyph_x + glyph_column * self.scale,
+                            y + glyph_row as u32 * self.scale,
+                            self.scale,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Fill a square of text color, clipped to the sheet
+    fn fill(&mut self, x: u32, y: u32, size: u32) {
+        for py in y..(y + size).min(self.height) {
+            for px in x..(x + size).min(self.width) {
+                let start = ((py * self.width + px) * 4) as usize;
+                self.pixels[start..start + 4].copy_from_slice(&TEXT);
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        save_png(path, self.width, self.height, &self.pixels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_places_cells_and_draws_labels() {
+        let columns = vec!["0.01".to_string(), "0.02".to_string()];
+        let rows = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let mut sheet = ContactSheet::new(8, 6, "feed_rate x kill_rate", &columns, &rows);
+        assert_eq!(
+            sheet.pixels.len(),
+            (sheet.width * sheet.height * 4) as usize
+        );
+
+        let red = [255, 0, 0, 255].repeat(8 * 6);
+        sheet.place(1, 2, &red);
+        let (x, y) = sheet.cell_origin(1, 2);
+        let at = |x: u32, y: u32| {
+            let start = ((y * sheet.width + x) * 4) as usize;
+            sheet.pixels[start..start + 4].to_vec()
+        };
+        assert_eq!(at(x, y), vec![255, 0, 0, 255]);
+        assert_eq!(at(x + 7, y + 5), vec![255, 0, 0, 255]);
+        assert_eq!(at(sheet.width - 1, sheet.height - 1), BACKGROUND.to_vec());
+
+        // Labels leave text-colored pixels above and beside the grid
+        let (grid_x, grid_y) = sheet.cell_origin(0, 0);
+        assert!(
+            sheet.pixels[..(grid_y * sheet.width * 4) as usize]
+                .chunks_exact(4)
+                .any(|pixel| pixel == TEXT)
+        );
+        assert!((grid_y..sheet.height).any(|y| (0..grid_x).any(|x| at(x, y) == TEXT.to_vec())));
+    }
+
+    #[test]
+    fn test_unknown_characters_fall_back() {
+        assert_eq!(glyph('A'), glyph('a'));
+        assert_eq!(glyph('#'), glyph('?'));
+        assert_eq!(glyph(' '), [0; 7]);
+        assert_eq!(text_width("ab", 2), 22);
+    }
+}
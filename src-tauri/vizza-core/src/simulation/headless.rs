@@ -0,0 +1,299 @@
+//! # Headless Rendering
+//!
+//! Drives simulations without a window. The GPU device is created with no
+//! compatible surface, frames are rendered into an [`OffscreenTarget`] and the
+//! simulation is stepped with a fixed time step, so the output only depends on
+//! the number of frames rendered and not on how long each frame took.
+//!
+//! Adapter selection honours the usual `WGPU_BACKEND`, `WGPU_ADAPTER_NAME` and
+//! `WGPU_POWER_PREF` environment variables, which makes it possible to run on
+//! software adapters such as lavapipe or llvmpipe.
+
+use std::sync::Arc;
+use wgpu::{Device, Queue, SurfaceConfiguration, TextureFormat};
+
+use crate::error::{AppError, AppResult, GpuError, SimulationError};
+use crate::settings::RenderSettings;
+use crate::simulation::preset_manager::SimulationPresetManager;
+use crate::simulations::shared::{LutManager, OffscreenTarget};
+use crate::simulations::traits::{Simulation, SimulationType};
+
+/// Default time step used when stepping simulations headlessly (60 FPS)
+pub const DEFAULT_DELTA_TIME: f32 = 1.0 / 60.0;
+
+/// Owns a surfaceless GPU device and an offscreen target to render simulations into
+pub struct HeadlessRenderer {
+    pub device: Arc<Device>,
+    pub queue: Arc<Queue>,
+    pub adapter_info: wgpu::AdapterInfo,
+    pub target: OffscreenTarget,
+    pub lut_manager: LutManager,
+    pub preset_manager: SimulationPresetManager,
+    pub render_settings: RenderSettings,
+}
+
+impl HeadlessRenderer {
+    /// Create a device without a surface and an offscreen target of the given size
+    pub async fn new(
+        width: u32,
+        height: u32,
+        format: TextureFormat,
+        render_settings: RenderSettings,
+    ) -> AppResult<Self> {
+        if width == 0 || height == 0 {
+            return Err(AppError::Gpu(GpuError::TextureCreationFailed(format!(
+                "Invalid offscreen size {}x{}",
+                width, height
+            ))));
+        }
+        if !OffscreenTarget::supports_format(format) {
+            return Err(AppError::Gpu(GpuError::TextureCreationFailed(format!(
+                "Unsupported offscreen format {:?}",
+                format
+            ))));
+        }
+
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+            backends: wgpu::Backends::from_env().unwrap_or(wgpu::Backends::all()),
+            ..Default::default()
+        });
+
+        let adapter = wgpu::util::initialize_adapter_from_env_or_default(&instance, None)
+            .await
+            .ok_or(AppError::Gpu(GpuError::AdapterNotFound))?;
+
+        let adapter_info = adapter.get_info();
+        tracing::debug!("Using headless adapter: {:?}", adapter_info);
+
+        // Match the windowed context where the adapter allows it, but stay within
+        // what software adapters actually support
+        let adapter_limits = adapter.limits();
+        let limits = wgpu::Limits {
+            max_buffer_size: adapter_limits.max_buffer_size.min(2_147_483_647),
+            max_storage_buffer_binding_size: adapter_limits
+                .max_storage_buffer_binding_size
+                .min(2_147_483_647),
+            ..Default::default()
+        }
+        .using_resolution(adapter_limits);
+
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: Some("Headless GPU Device"),
+                    required_features: adapter.features()
+                        & wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES,
+                    required_limits: limits,
+                    memory_hints: wgpu::MemoryHints::Performance,
+                },
+                None,
+            )
+            .await
+            .map_err(|e| AppError::Gpu(GpuError::DeviceCreationFailed(e.to_string())))?;
+
+        let max_dimension = device.limits().max_texture_dimension_2d;
+        if width > max_dimension || height > max_dimension {
+            return Err(AppError::Gpu(GpuError::TextureCreationFailed(format!(
+                "Offscreen size {}x{} exceeds the device limit of {}",
+                width, height, max_dimension
+            ))));
+        }
+
+        let target = OffscreenTarget::new(&device, width, height, format);
+
+        Ok(Self {
+            device: Arc::new(device),
+            queue: Arc::new(queue),
+            adapter_info,
+            target,
+            lut_manager: LutManager::new(),
+            preset_manager: SimulationPresetManager::new(),
+            render_settings,
+        })
+    }
+
+    /// Surface configuration matching the offscreen target
+    pub fn surface_config(&self) -> SurfaceConfiguration {
+        self.target.surface_config()
+    }
+
+    /// Create a simulation sized for the offscreen target
+    pub async fn create_simulation(&self, simulation_type: &str) -> AppResult<SimulationType> {
+        SimulationType::new(
+            simulation_type,
+            &self.device,
+            &self.queue,
+            &self.surface_config(),
+            &self.adapter_info,
+            &self.lut_manager,
+            &self.render_settings,
+        )
+        .await
+        .map_err(|e| {
+            AppError::Simulation(SimulationError::InitializationFailed(format!(
+                "Failed to create {} simulation: {}",
+                simulation_type, e
+            )))
+        })
+    }
+
+    /// Apply a named preset to a simulation created by this renderer and reset
+    /// its runtime state, as the windowed app does
+    pub fn apply_preset(
+        &self,
+        simulation: &mut SimulationType,
+        preset_name: &str,
+    ) -> AppResult<()> {
+        self.preset_manager
+            .apply_preset(simulation, preset_name, &self.device, &self.queue)
+            .map_err(AppError::Preset)?;
+        simulation.reset_runtime_state(&self.device, &self.queue)?;
+        Ok(())
+    }
+
+    /// Apply a LUT from this renderer's LUT manager to a simulation
+    pub fn apply_lut(&self, simulation: &mut SimulationType, lut_name: &str) -> AppResult<()> {
+        simulation.apply_lut(&self.lut_manager, lut_name, &self.device, &self.queue)?;
+        Ok(())
+    }
+
+    /// Step the simulation once and render it into the offscreen target
+    pub fn render_frame(&self, simulation: &mut SimulationType, delta_time: f32) -> AppResult<()> {
+        simulation.render_frame(&self.device, &self.queue, &self.target.view, delta_time)?;
+        Ok(())
+    }
+
+    /// Step the simulation `frames` times with a fixed time step, calling
+    /// `on_frame` with the frame index after each frame has been rendered
+    pub fn render_frames<F>(
+        &self,
+        simulation: &mut SimulationType,
+        frames: u32,
+        delta_time: f32,
+        mut on_frame: F,
+    ) -> AppResult<()>
+    where
+        F: FnMut(&Self, u32) -> AppResult<()>,
+    {
+        for frame in 0..frames {
+            self.render_frame(simulation, delta_time)?;
+            on_frame(self, frame)?;
+        }
+        self.device.poll(wgpu::Maintain::Wait);
+        Ok(())
+    }
+
+    /// Read the last rendered frame back as tightly packed RGBA8 rows
+    pub fn read_pixels(&self) -> AppResult<Vec<u8>> {
+        Ok(self.target.read_pixels(&self.device, &self.queue)?)
+    }
+
+    /// Resize the offscreen target and let the simulation rebuild its resources
+    pub fn resize(
+        &mut self,
+        simulation: &mut SimulationType,
+        width: u32,
+        height: u32,
+    ) -> AppResult<()> {
+        self.target = OffscreenTarget::new(&self.device, width, height, self.target.format);
+        simulation.resize(&self.device, &self.queue, &self.surface_config())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn renderer() -> HeadlessRenderer {
+        HeadlessRenderer::new(
+            128,
+            96,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderSettings::default(),
+        )
+        .await
+        .expect("Failed to create headless renderer")
+    }
+
+    #[tokio::test]
+    async fn test_headless_renders_simulations() {
+        let renderer = renderer().await;
+
+        for simulation_type in [
+            "slime_mold",
+            "gray_scott",
+            "particle_life",
+            "flow",
+            "pellets",
+            "ecosystem",
+            "gradient",
+            "main_menu",
+        ] {
+            let mut simulation = renderer
+                .create_simulation(simulation_type)
+                .await
+                .expect("Failed to create simulation");
+            // The default ten million agents is far more than a test needs
+            if let SimulationType::SlimeMold(slime_mold) = &mut simulation {
+                slime_mold
+                    .update_agent_count(
+                        100_000,
+                        &renderer.device,
+                        &renderer.queue,
+                        &renderer.surface_config(),
+                    )
+                    .await
+                    .expect("Failed to reduce agent count");
+            }
+
+            let mut rendered = 0;
+            renderer
+                .render_frames(&mut simulation, 3, DEFAULT_DELTA_TIME, |_, _| {
+                    rendered += 1;
+                    Ok(())
+                })
+                .expect("Failed to render frames");
+            assert_eq!(rendered, 3);
+
+            let pixels = renderer.read_pixels().expect("Failed to read pixels");
+            assert_eq!(pixels.len(), 128 * 96 * 4);
+            assert!(
+                pixels
+                    .chunks_exact(4)
+                    .any(|p| p[0] != 0 || p[1] != 0 || p[2] != 0),
+                "{} rendered an all-black frame",
+                simulation_type
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_headless_resize() {
+        let mut renderer = renderer().await;
+        let mut simulation = renderer
+            .create_simulation("gray_scott")
+            .await
+            .expect("Failed to create simulation");
+
+        renderer
+            .resize(&mut simulation, 64, 48)
+            .expect("Failed to resize");
+        renderer
+            .render_frame(&mut simulation, DEFAULT_DELTA_TIME)
+            .expect("Failed to render frame");
+        assert_eq!(renderer.read_pixels().unwrap().len(), 64 * 48 * 4);
+    }
+
+    #[tokio::test]
+    async fn test_headless_rejects_invalid_size() {
+        let result = HeadlessRenderer::new(
+            0,
+            96,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderSettings::default(),
+        )
+        .await;
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,195 @@
+//! # Settings History
+//!
+//! Undo/redo for everything the user can tweak on a running simulation: its
+//! settings (including the Particle Life force matrix) and the active LUT.
+//!
+//! Each edit records the state from before the edit as an undo point. Edits
+//! to the same setting in quick succession, such as dragging a slider, are
+//! coalesced so that a single undo returns to where the drag started.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+/// Number of undo points kept before the oldest are dropped
+pub const HISTORY_CAPACITY: usize = 100;
+
+/// Edits to the same setting closer together than this share an undo point
+pub const COALESCE_WINDOW: Duration = Duration::from_millis(500);
+
+/// Settings and LUT of a simulation at one point in time
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    /// Settings as returned by `get_settings`
+    pub settings: serde_json::Value,
+    /// Name of the active LUT and whether it is reversed
+    pub lut: Option<(String, bool)>,
+}
+
+/// Whether undo and redo currently have anything to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct HistoryStatus {
+    pub can_undo: bool,
+    pub can_redo: bool,
+}
+
+/// Bounded undo and redo stacks of [`HistoryEntry`] values
+#[derive(Debug)]
+pub struct SettingsHistory {
+    undo: VecDeque<HistoryEntry>,
+    redo: Vec<HistoryEntry>,
+    capacity: usize,
+    /// Setting touched by the most recent edit and when
+    last_edit: Option<(String, Instant)>,
+}
+
+impl Default for SettingsHistory {
+    fn default() -> Self {
+        Self::new(HISTORY_CAPACITY)
+    }
+}
+
+impl SettingsHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            capacity: capacity.max(1),
+            last_edit: None,
+        }
+    }
+
+    /// Record `before`, the state preceding an edit made at `now`. Edits with
+    /// a `key` naming the setting they change may be coalesced; edits without
+    /// one, like applying a preset, always get their own undo point. Any redo
+    /// history is discarded, since it no longer follows on.
+    pub fn record(&mut self, before: HistoryEntry, key: Option<&str>, now: Instant) {
+        let coalesce = key.is_some_and(|key| {
+            self.last_edit.as_ref().is_some_and(|(last_key, at)| {
+                last_key == key && now.saturating_duration_since(*at) < COALESCE_WINDOW
+            })
+        });
+        self.last_edit = key.map(|key| (key.to_string(), now));
+        self.redo.clear();
+
+        if coalesce && !self.undo.is_empty() {
+            return;
+        }
+        self.undo.push_back(before);
+        if self.undo.len() > self.capacity {
+            self.undo.pop_front();
+        }
+    }
+
+    /// Step back, given the current state. Returns the state to restore.
+    pub fn undo(&mut self, current: HistoryEntry) -> Option<HistoryEntry> {
+        let entry = self.undo.pop_back()?;
+        self.redo.push(current);
+        self.last_edit = None;
+        Some(entry)
+    }
+
+    /// Step forward again after an undo. Returns the state to restore.
+    pub fn redo(&mut self, current: HistoryEntry) -> Option<HistoryEntry> {
+        let entry = self.redo.pop()?;
+        self.undo.push_back(current);
+        self.last_edit = None;
+        Some(entry)
+    }
+
+    /// Forget everything, e.g. when switching simulations
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.last_edit = None;
+    }
+
+    pub fn status(&self) -> HistoryStatus {
+        HistoryStatus {
+            can_undo: !self.undo.is_empty(),
+            can_redo: !self.redo.is_empty(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entry(value: f64) -> HistoryEntry {
+        HistoryEntry {
+            settings: json!({ "feed_rate": value }),
+            lut: Some(("MATPLOTLIB_viridis".to_string(), false)),
+        }
+    }
+
+    #[test]
+    fn test_undo_and_redo() {
+        let mut history = SettingsHistory::default();
+        let start = Instant::now();
+        history.record(entry(1.0), Some("feed_rate"), start);
+        history.record(entry(2.0), Some("kill_rate"), start);
+
+        assert_eq!(history.undo(entry(3.0)), Some(entry(2.0)));
+        assert_eq!(history.undo(entry(2.0)), Some(entry(1.0)));
+        assert_eq!(history.undo(entry(1.0)), None);
+        assert_eq!(history.redo(entry(1.0)), Some(entry(2.0)));
+        assert_eq!(
+            history.status(),
+            HistoryStatus {
+                can_undo: true,
+                can_redo: true
+            }
+        );
+
+        // A new edit discards what could have been redone
+        history.record(entry(2.0), Some("feed_rate"), start);
+        assert!(!history.status().can_redo);
+    }
+
+    #[test]
+    fn test_rapid_edits_coalesce() {
+        let mut history = SettingsHistory::default();
+        let start = Instant::now();
+        for step in 0..10 {
+            let now = start + Duration::from_millis(50 * step);
+            history.record(entry(step as f64), Some("feed_rate"), now);
+        }
+        // A different setting or a pause starts a new undo point
+        history.record(
+            entry(10.0),
+            Some("kill_rate"),
+            start + Duration::from_millis(500),
+        );
+        history.record(
+            entry(11.0),
+            Some("kill_rate"),
+            start + Duration::from_secs(5),
+        );
+        // Edits without a key, like applying presets, are never merged
+        history.record(entry(12.0), None, start + Duration::from_secs(5));
+        history.record(entry(13.0), None, start + Duration::from_secs(5));
+
+        assert_eq!(history.undo(entry(14.0)), Some(entry(13.0)));
+        assert_eq!(history.undo(entry(13.0)), Some(entry(12.0)));
+        assert_eq!(history.undo(entry(12.0)), Some(entry(11.0)));
+        assert_eq!(history.undo(entry(11.0)), Some(entry(10.0)));
+        assert_eq!(history.undo(entry(10.0)), Some(entry(0.0)));
+        assert!(!history.status().can_undo);
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut history = SettingsHistory::new(3);
+        let start = Instant::now();
+        for step in 0..5 {
+            history.record(entry(step as f64), None, start);
+        }
+        assert_eq!(history.undo(entry(5.0)), Some(entry(4.0)));
+        assert_eq!(history.undo(entry(4.0)), Some(entry(3.0)));
+        assert_eq!(history.undo(entry(3.0)), Some(entry(2.0)));
+        assert_eq!(history.undo(entry(2.0)), None);
+    }
+}
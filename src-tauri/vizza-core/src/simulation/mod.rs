@@ -0,0 +1,12 @@
+pub mod audio;
+pub mod contact_sheet;
+pub mod headless;
+pub mod history;
+pub mod modulation;
+pub mod preset_bundle;
+pub mod preset_manager;
+pub mod recorder;
+pub mod replay;
+pub mod stream;
+pub mod sweep;
+pub mod timeline;
@@ -0,0 +1,409 @@
+//! # Parameter Modulation
+//!
+//! Modulators animate numeric settings over time without the user touching
+//! them. Each one drives a single setting from an oscillator:
+//!
+//! `value = offset + depth * envelope(t) * wave(t * rate + phase)`
+//!
+//! where `wave` lies in `-1..=1`. The engine is stepped once per rendered
+//! frame and its output goes through the simulation's regular
+//! `update_setting`, so simulations need no modulation-specific code. Values
+//! are clamped to the range in the setting's schema and rounded for integer
+//! settings.
+
+use std::f32::consts::TAU;
+
+use rand::Rng;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{SimulationError, SimulationResult};
+use crate::simulations::shared::random;
+use crate::simulations::shared::settings_schema::{SettingField, SettingKind};
+
+/// Shape of a modulator's oscillator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Waveform {
+    Sine,
+    Triangle,
+    Saw,
+    /// Wanders randomly, moving at most `rate` full swings per second
+    RandomWalk,
+    /// Smooth value noise with `rate` new values per second
+    Noise,
+}
+
+/// Fades a modulator's depth in and out. With no `sustain` the depth stays
+/// at full strength once the attack is over.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Envelope {
+    /// Seconds to ramp up to full depth
+    pub attack: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sustain: Option<f32>,
+    /// Seconds to ramp back down after the sustain
+    #[serde(default)]
+    pub release: f32,
+}
+
+impl Envelope {
+    /// Depth multiplier `time` seconds after the modulator started
+    pub fn gain(&self, time: f32) -> f32 {
+        if time < self.attack {
+            return time / self.attack;
+        }
+        let Some(sustain) = self.sustain else {
+            return 1.0;
+        };
+        let released = time - self.attack - sustain;
+        if released <= 0.0 {
+            1.0
+        } else if released < self.release {
+            1.0 - released / self.release
+        } else {
+            0.0
+        }
+    }
+}
+
+/// An oscillator attached to a named setting, as stored in presets
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Modulator {
+    /// Setting to drive, by its field name or `update_setting` name
+    pub setting_name: String,
+    pub waveform: Waveform,
+    /// Cycles per second
+    pub rate: f32,
+    pub depth: f32,
+    /// Value the setting swings around
+    pub offset: f32,
+    /// Starting point in the cycle, from 0 to 1
+    #[serde(default)]
+    pub phase: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub envelope: Option<Envelope>,
+}
+
+impl Modulator {
+    fn validate(&self) -> SimulationResult<()> {
+        let finite = [self.rate, self.depth, self.offset, self.phase]
+            .iter()
+            .all(|value| value.is_finite());
+        if !finite || self.rate < 0.0 {
+            return Err(SimulationError::InvalidParameter(format!(
+                "Modulator for {} needs a non-negative rate and finite depth, offset and phase",
+                self.setting_name
+            )));
+        }
+        if let Some(envelope) = &self.envelope {
+            let times = [
+                envelope.attack,
+                envelope.sustain.unwrap_or(0.0),
+                envelope.release,
+            ];
+            if times.iter().any(|time| !time.is_finite() || *time < 0.0) {
+                return Err(SimulationError::InvalidParameter(format!(
+                    "Envelope times for {} must be non-negative",
+                    self.setting_name
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// New value for a setting produced by one step of the engine
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModulatedSetting {
+    /// Position of the modulator in [`ModulationEngine::modulators`]
+    pub index: usize,
+    pub setting_name: &'static str,
+    pub value: serde_json::Value,
+}
+
+/// A numeric setting that modulation sources write to, resolved against the
+/// simulation's schema
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingTarget {
+    /// Name accepted by `update_setting`
+    pub setting_name: &'static str,
+    min: f64,
+    max: f64,
+    integer: bool,
+}
+
+impl SettingTarget {
+    /// Look up a setting by its field name or `update_setting` name
+    pub fn resolve(name: &str, schema: &[SettingField]) -> SimulationResult<Self> {
+        let field = schema
+            .iter()
+            .find(|field| field.name == name || field.setting_name == name)
+            .ok_or_else(|| {
+                SimulationError::InvalidParameter(format!("Unknown setting {}", name))
+            })?;
+        let (min, max, integer) = match field.kind {
+            SettingKind::Float { min, max, .. } => (min, max, false),
+            SettingKind::Integer { min, max, .. } => (min as f64, max as f64, true),
+            _ => {
+                return Err(SimulationError::InvalidParameter(format!(
+                    "{} is not a numeric setting and cannot be modulated",
+                    name
+                )));
+            }
+        };
+        Ok(Self {
+            setting_name: field.setting_name,
+            min,
+            max,
+            integer,
+        })
+    }
+
+    /// Clamp `value` to the setting's range, rounding for integer settings
+    pub fn value(&self, value: f64) -> serde_json::Value {
+        let value = value.clamp(self.min, self.max);
+        if self.integer {
+            serde_json::json!(value.round() as i64)
+        } else {
+            serde_json::json!(value)
+        }
+    }
+}
+
+/// A modulator with its target and running state
+struct ActiveModulator {
+    modulator: Modulator,
+    target: SettingTarget,
+    /// Seconds since the modulator was attached
+    time: f32,
+    rng: StdRng,
+    walk: f32,
+    noise_seed: u64,
+}
+
+impl ActiveModulator {
+    fn new(modulator: Modulator, schema: &[SettingField]) -> SimulationResult<Self> {
+        modulator.validate()?;
+        let target = SettingTarget::resolve(&modulator.setting_name, schema)?;
+
+        let mut rng = random::rng();
+        let noise_seed = rng.random();
+        Ok(Self {
+            modulator,
+            target,
+            time: 0.0,
+            rng,
+            walk: 0.0,
+            noise_seed,
+        })
+    }
+
+    /// Advance by `delta_time` seconds and return the setting's new value
+    fn step(&mut self, delta_time: f32) -> serde_json::Value {
+        self.time += delta_time;
+        let modulator = &self.modulator;
+        let position = self.time * modulator.rate + modulator.phase;
+        let cycle = position.rem_euclid(1.0);
+
+        let wave = match modulator.waveform {
+            Waveform::Sine => (cycle * TAU).sin(),
+            Waveform::Triangle => 1.0 - 4.0 * (cycle - 0.5).abs(),
+            Waveform::Saw => 2.0 * cycle - 1.0,
+            Waveform::RandomWalk => {
+                let step = self.rng.random_range(-1.0..=1.0) * 2.0 * modulator.rate * delta_time;
+                self.walk = (self.walk + step).clamp(-1.0, 1.0);
+                self.walk
+            }
+            Waveform::Noise => value_noise(self.noise_seed, position),
+        };
+        let gain = modulator
+            .envelope
+            .map_or(1.0, |envelope| envelope.gain(self.time));
+
+        self.target
+            .value((modulator.offset + modulator.depth * gain * wave) as f64)
+    }
+}
+
+/// Smoothly interpolated random values at integer positions, in `-1..=1`
+fn value_noise(seed: u64, position: f32) -> f32 {
+    fn lattice(seed: u64, index: i64) -> f32 {
+        // SplitMix64 finalizer
+        let mut x = seed ^ (index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        x ^= x >> 31;
+        (x >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+    }
+
+    let index = position.floor();
+    let t = position - index;
+    let t = t * t * (3.0 - 2.0 * t);
+    let a = lattice(seed, index as i64);
+    let b = lattice(seed, index as i64 + 1);
+    a + (b - a) * t
+}
+
+/// The modulators attached to the running simulation
+#[derive(Default)]
+pub struct ModulationEngine {
+    active: Vec<ActiveModulator>,
+}
+
+impl ModulationEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Attach a modulator, checking it against the simulation's schema
+    pub fn add(&mut self, modulator: Modulator, schema: &[SettingField]) -> SimulationResult<()> {
+        let active = ActiveModulator::new(modulator, schema)?;
+        // A setting can only follow one modulator at a time
+        self.active
+            .retain(|existing| existing.target.setting_name != active.target.setting_name);
+        self.active.push(active);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<Modulator> {
+        (index < self.active.len()).then(|| self.active.remove(index).modulator)
+    }
+
+    pub fn clear(&mut self) {
+        self.active.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    pub fn modulators(&self) -> Vec<Modulator> {
+        self.active
+            .iter()
+            .map(|active| active.modulator.clone())
+            .collect()
+    }
+
+    /// Advance every modulator by `delta_time` seconds
+    pub fn step(&mut self, delta_time: f32) -> Vec<ModulatedSetting> {
+        self.active
+            .iter_mut()
+            .enumerate()
+            .map(|(index, active)| ModulatedSetting {
+                index,
+                setting_name: active.target.setting_name,
+                value: active.step(delta_time),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modulator(setting_name: &str, waveform: Waveform) -> Modulator {
+        Modulator {
+            setting_name: setting_name.to_string(),
+            waveform,
+            rate: 0.5,
+            depth: 0.01,
+            offset: 0.04,
+            phase: 0.0,
+            envelope: None,
+        }
+    }
+
+    fn gray_scott_schema() -> Vec<SettingField> {
+        crate::simulations::gray_scott::settings::Settings::schema()
+    }
+
+    #[test]
+    fn test_sine_sweeps_around_offset() {
+        let mut engine = ModulationEngine::new();
+        engine
+            .add(modulator("feed_rate", Waveform::Sine), &gray_scott_schema())
+            .unwrap();
+
+        // A quarter cycle in, the sine is at its peak
+        let update = engine.step(0.5).remove(0);
+        assert_eq!(update.setting_name, "feed_rate");
+        assert!((update.value.as_f64().unwrap() - 0.05).abs() < 1e-6);
+        let update = engine.step(1.0).remove(0);
+        assert!((update.value.as_f64().unwrap() - 0.03).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_values_stay_in_schema_range() {
+        let mut engine = ModulationEngine::new();
+        let mut wild = modulator("feed_rate", Waveform::RandomWalk);
+        wild.rate = 50.0;
+        wild.depth = 100.0;
+        engine.add(wild, &gray_scott_schema()).unwrap();
+        let mut noisy = modulator("kill_rate", Waveform::Noise);
+        noisy.depth = 100.0;
+        engine.add(noisy, &gray_scott_schema()).unwrap();
+
+        let schema = gray_scott_schema();
+        for _ in 0..200 {
+            for update in engine.step(1.0 / 60.0) {
+                let field = schema
+                    .iter()
+                    .find(|field| field.setting_name == update.setting_name)
+                    .unwrap();
+                assert!(field.validate(&update.value, None).is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejects_unknown_and_non_numeric_settings() {
+        let mut engine = ModulationEngine::new();
+        let schema = gray_scott_schema();
+        assert!(
+            engine
+                .add(modulator("feed", Waveform::Saw), &schema)
+                .is_err()
+        );
+        assert!(
+            engine
+                .add(modulator("nutrient_pattern", Waveform::Saw), &schema)
+                .is_err()
+        );
+        let mut backwards = modulator("feed_rate", Waveform::Saw);
+        backwards.rate = -1.0;
+        assert!(engine.add(backwards, &schema).is_err());
+        assert!(engine.is_empty());
+    }
+
+    #[test]
+    fn test_envelope() {
+        let envelope = Envelope {
+            attack: 1.0,
+            sustain: Some(2.0),
+            release: 1.0,
+        };
+        assert_eq!(envelope.gain(0.5), 0.5);
+        assert_eq!(envelope.gain(2.0), 1.0);
+        assert_eq!(envelope.gain(3.5), 0.5);
+        assert_eq!(envelope.gain(10.0), 0.0);
+
+        let held = Envelope {
+            sustain: None,
+            ..envelope
+        };
+        assert_eq!(held.gain(10.0), 1.0);
+    }
+
+    #[test]
+    fn test_noise_is_continuous() {
+        let mut previous = value_noise(7, 0.0);
+        for step in 1..1000 {
+            let value = value_noise(7, step as f32 * 0.01);
+            assert!((-1.0..=1.0).contains(&value));
+            assert!((value - previous).abs() < 0.1);
+            previous = value;
+        }
+    }
+}
@@ -0,0 +1,271 @@
+//! # Preset Bundles
+//!
+//! A preset bundle is a single TOML file for sharing a preset with someone who
+//! does not have the same setup. Besides the preset's settings and simulation
+//! type it carries the LUT and camera the preset was exported with and the
+//! version of Vizza that wrote it. Custom LUTs are embedded as the raw bytes
+//! stored by [`LutManager::save_custom`]; built-in LUTs are referenced by name.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+use crate::error::{PresetError, PresetResult};
+use crate::simulation::preset_manager::SimulationPresetManager;
+use crate::simulations::shared::snapshot::CameraSnapshot;
+use crate::simulations::shared::{LutData, LutManager};
+
+/// File extension used for exported bundles
+pub const BUNDLE_EXTENSION: &str = "vizzapreset";
+
+/// Bumped whenever the bundle layout changes incompatibly
+const FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PresetBundle {
+    pub format_version: u32,
+    /// Version of Vizza that exported the bundle
+    pub app_version: String,
+    pub simulation_type: String,
+    pub name: String,
+    /// Settings as returned by `Simulation::get_settings`
+    pub settings: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lut: Option<BundledLut>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub camera: Option<CameraSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundledLut {
+    pub name: String,
+    pub reversed: bool,
+    /// Hex encoded LUT bytes, present only for custom LUTs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+}
+
+impl BundledLut {
+    /// Reference a LUT by name, embedding its colors if it is a custom LUT
+    pub fn new(name: &str, reversed: bool, lut_manager: &LutManager) -> Self {
+        let data = lut_manager
+            .get_custom(name)
+            .ok()
+            .map(|lut| encode_hex(&lut.into_bytes()));
+        Self {
+            name: name.to_string(),
+            reversed,
+            data,
+        }
+    }
+
+    /// Decode the embedded colors, if any
+    pub fn lut_data(&self) -> PresetResult<Option<LutData>> {
+        let Some(data) = &self.data else {
+            return Ok(None);
+        };
+        let bytes = decode_hex(data).ok_or_else(|| {
+            PresetError::FormatError(format!("Invalid LUT data for '{}'", self.name))
+        })?;
+        LutData::from_bytes(self.name.clone(), &bytes)
+            .map(Some)
+            .map_err(|e| PresetError::FormatError(format!("Invalid LUT '{}': {}", self.name, e)))
+    }
+}
+
+impl PresetBundle {
+    pub fn new(simulation_type: &str, name: &str, settings: Value) -> Self {
+        Self {
+            format_version: FORMAT_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            simulation_type: simulation_type.to_string(),
+            name: name.to_string(),
+            settings,
+            lut: None,
+            camera: None,
+        }
+    }
+
+    /// Check that this build of Vizza can import the bundle: the format and
+    /// simulation type are known, the settings match the simulation's settings
+    /// and the LUT is either embedded or available by name
+    pub fn validate(
+        &self,
+        preset_manager: &SimulationPresetManager,
+        lut_manager: &LutManager,
+    ) -> PresetResult<()> {
+        if self.format_version > FORMAT_VERSION {
+            return Err(PresetError::CompatibilityError(format!(
+                "Bundle format {} was written by Vizza {} and is newer than this version supports",
+                self.format_version, self.app_version
+            )));
+        }
+        if self.name.trim().is_empty() {
+            return Err(PresetError::ValidationFailed(
+                "Preset name is empty".to_string(),
+            ));
+        }
+        if preset_manager.get_manager(&self.simulation_type).is_none() {
+            return Err(PresetError::CompatibilityError(format!(
+                "Unknown simulation type: {}",
+                self.simulation_type
+            )));
+        }
+
+        preset_manager
+            .preset_to_toml(&self.simulation_type, &self.name, &self.settings)
+            .map_err(|e| {
+                PresetError::CompatibilityError(format!(
+                    "Settings do not match {} settings: {}",
+                    self.simulation_type, e
+                ))
+            })?;
+
+        if let Some(lut) = &self.lut
+            && lut.lut_data()?.is_none()
+            && lut_manager.get(&lut.name).is_err()
+        {
+            return Err(PresetError::CompatibilityError(format!(
+                "LUT '{}' is not available",
+                lut.name
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn save(&self, path: &Path) -> PresetResult<()> {
+        let content = toml::to_string_pretty(self)
+            .map_err(|e| PresetError::SerializationFailed(e.to_string()))?;
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent).map_err(|e| file_error(parent, e))?;
+        }
+        std::fs::write(path, content).map_err(|e| file_error(path, e))
+    }
+
+    pub fn load(path: &Path) -> PresetResult<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| file_error(path, e))?;
+        toml::from_str(&content).map_err(|e| PresetError::DeserializationFailed(e.to_string()))
+    }
+}
+
+fn file_error(path: &Path, error: std::io::Error) -> PresetError {
+    PresetError::FileError {
+        path: PathBuf::from(path),
+        error: error.to_string(),
+    }
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(text.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundle() -> PresetBundle {
+        let mut bundle = PresetBundle::new(
+            "gray_scott",
+            "Shared Spots",
+            serde_json::to_value(crate::simulations::gray_scott::settings::Settings::default())
+                .unwrap(),
+        );
+        let lut = LutManager::new().get("MATPLOTLIB_viridis").unwrap();
+        bundle.lut = Some(BundledLut {
+            name: "shared_viridis".to_string(),
+            reversed: true,
+            data: Some(encode_hex(&lut.into_bytes())),
+        });
+        bundle.camera = Some(CameraSnapshot {
+            position: [0.25, -0.5],
+            zoom: 2.0,
+            target_position: [0.25, -0.5],
+            target_zoom: 2.0,
+        });
+        bundle
+    }
+
+    #[test]
+    fn test_bundle_round_trip() {
+        let bundle = bundle();
+        let path = std::env::temp_dir()
+            .join(format!("vizza-bundle-{}", std::process::id()))
+            .join(format!("shared.{}", BUNDLE_EXTENSION));
+        bundle.save(&path).unwrap();
+        let loaded = PresetBundle::load(&path).unwrap();
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+
+        assert_eq!(loaded.name, bundle.name);
+        assert_eq!(loaded.lut, bundle.lut);
+        assert_eq!(loaded.camera, bundle.camera);
+        let lut = loaded.lut.unwrap().lut_data().unwrap().unwrap();
+        let viridis = LutManager::new().get("MATPLOTLIB_viridis").unwrap();
+        assert_eq!((lut.red, lut.blue), (viridis.red, viridis.blue));
+    }
+
+    #[test]
+    fn test_validation() {
+        let preset_manager = SimulationPresetManager::new();
+        let lut_manager = LutManager::new();
+        assert!(bundle().validate(&preset_manager, &lut_manager).is_ok());
+
+        let mut newer = bundle();
+        newer.format_version = FORMAT_VERSION + 1;
+        assert!(matches!(
+            newer.validate(&preset_manager, &lut_manager),
+            Err(PresetError::CompatibilityError(_))
+        ));
+
+        let mut unknown = bundle();
+        unknown.simulation_type = "lava_lamp".to_string();
+        assert!(matches!(
+            unknown.validate(&preset_manager, &lut_manager),
+            Err(PresetError::CompatibilityError(_))
+        ));
+
+        let mut mismatched = bundle();
+        mismatched.settings = serde_json::json!({ "feed_rate": "fast" });
+        assert!(matches!(
+            mismatched.validate(&preset_manager, &lut_manager),
+            Err(PresetError::CompatibilityError(_))
+        ));
+
+        let mut missing_lut = bundle();
+        missing_lut.lut = Some(BundledLut {
+            name: "not_a_real_lut".to_string(),
+            reversed: false,
+            data: None,
+        });
+        assert!(matches!(
+            missing_lut.validate(&preset_manager, &lut_manager),
+            Err(PresetError::CompatibilityError(_))
+        ));
+
+        let mut corrupt_lut = bundle();
+        corrupt_lut.lut.as_mut().unwrap().data = Some("zz".to_string());
+        assert!(matches!(
+            corrupt_lut.validate(&preset_manager, &lut_manager),
+            Err(PresetError::FormatError(_))
+        ));
+    }
+
+    #[test]
+    fn test_hex_round_trip() {
+        let bytes = vec![0, 1, 127, 128, 255];
+        assert_eq!(decode_hex(&encode_hex(&bytes)), Some(bytes));
+        assert_eq!(decode_hex("abc"), None);
+        assert_eq!(decode_hex("g0"), None);
+    }
+}
@@ -0,0 +1,481 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use wgpu::Device;
+use wgpu::Queue;
+
+use crate::error::PresetError;
+use crate::error::PresetResult;
+use crate::settings::data_dir;
+use serde::{Deserialize, Serialize};
+use toml;
+
+use crate::simulation::modulation::Modulator;
+use crate::simulations::registry;
+use crate::simulations::shared::{SettingField, settings_schema};
+use crate::simulations::traits::Simulation;
+use crate::simulations::traits::SimulationType;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Preset<Settings> {
+    pub name: String,
+    pub settings: Settings,
+    /// Modulators animating the settings while the preset is active
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub modulators: Vec<Modulator>,
+}
+
+impl<Settings> Preset<Settings> {
+    pub fn new(name: String, settings: Settings) -> Self {
+        Self {
+            name,
+            settings,
+            modulators: Vec::new(),
+        }
+    }
+}
+
+pub struct PresetManager<Settings> {
+    presets: Vec<Preset<Settings>>,
+    user_presets_dir: PathBuf,
+    built_in_preset_names: Vec<String>,
+    /// Schema that user presets are validated against before they are loaded
+    schema: Option<fn() -> Vec<SettingField>>,
+}
+
+impl<Settings> PresetManager<Settings>
+where
+    Settings: Clone + Serialize + for<'de> Deserialize<'de>,
+{
+    pub fn new(simulation_name: String) -> Self {
+        let user_presets_dir = get_user_presets_dir(&simulation_name);
+        let schema = registry::get(&simulation_name)
+            .ok()
+            .and_then(|descriptor| descriptor.settings_schema);
+        let manager = Self {
+            presets: vec![],
+            user_presets_dir,
+            built_in_preset_names: vec![],
+            schema,
+        };
+
+        // Create the user presets directory if it doesn't exist
+        if let Err(e) = fs::create_dir_all(&manager.user_presets_dir) {
+            eprintln!("Warning: Could not create user presets directory: {}", e);
+        }
+
+        manager
+    }
+
+    pub fn add_preset(&mut self, preset: Preset<Settings>) {
+        self.presets.push(preset);
+    }
+
+    pub fn get_preset(&self, name: &str) -> Option<&Preset<Settings>> {
+        self.presets.iter().find(|p| p.name == name)
+    }
+
+    pub fn get_preset_names(&self) -> Vec<String> {
+        self.presets.iter().map(|p| p.name.clone()).collect()
+    }
+
+    /// Capture the current preset names as built-in presets
+    pub fn capture_built_in_presets(&mut self) {
+        self.built_in_preset_names = self.presets.iter().map(|p| p.name.clone()).collect();
+    }
+
+    /// Serialize a preset in the TOML format read by `load_user_presets`
+    pub fn preset_to_toml(name: &str, settings: &Settings) -> PresetResult<String> {
+        Self::to_toml(&Preset::new(name.to_string(), settings.clone()))
+    }
+
+    fn to_toml(preset: &Preset<Settings>) -> PresetResult<String> {
+        toml::to_string_pretty(preset).map_err(|e| PresetError::SerializationFailed(e.to_string()))
+    }
+
+    /// Save a preset to a TOML file in the user's Documents folder
+    pub fn save_user_preset(
+        &self,
+        name: &str,
+        settings: &Settings,
+        modulators: &[Modulator],
+    ) -> PresetResult<()> {
+        let mut preset = Preset::new(name.to_string(), settings.clone());
+        preset.modulators = modulators.to_vec();
+        let toml_content = Self::to_toml(&preset)?;
+        let path = self
+            .user_presets_dir
+            .join(format!("{}.toml", sanitize_filename(name)));
+        fs::write(&path, toml_content).map_err(|e| PresetError::FileError {
+            path,
+            error: e.to_string(),
+        })?;
+
+        Ok(())
+    }
+
+    /// Load user presets from TOML files in the user's Documents folder
+    pub fn load_user_presets(&mut self) -> PresetResult<()> {
+        if !self.user_presets_dir.exists() {
+            return Ok(());
+        }
+
+        let entries = fs::read_dir(&self.user_presets_dir).map_err(|e| PresetError::FileError {
+            path: self.user_presets_dir.clone(),
+            error: e.to_string(),
+        })?;
+
+        for entry in entries {
+            let entry = entry.map_err(|e| PresetError::FileError {
+                path: self.user_presets_dir.clone(),
+                error: e.to_string(),
+            })?;
+            let path = entry.path();
+
+            if path.extension().and_then(|s| s.to_str()) == Some("toml") {
+                match self.load_preset_from_file(&path) {
+                    Ok(preset) => {
+                        // Check if this preset name already exists (avoid duplicates)
+                        if !self.presets.iter().any(|p| p.name == preset.name) {
+                            self.presets.push(preset);
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Warning: Could not load preset from {:?}: {}", path, e);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Load a single preset from a TOML file
+    fn load_preset_from_file(&self, path: &PathBuf) -> PresetResult<Preset<Settings>> {
+        let content = fs::read_to_string(path).map_err(|e| PresetError::FileError {
+            path: path.clone(),
+            error: e.to_string(),
+        })?;
+        let preset: toml::Value = toml::from_str(&content)
+            .map_err(|e| PresetError::DeserializationFailed(e.to_string()))?;
+        if let Some(settings) = preset.get("settings") {
+            let settings = serde_json::to_value(settings)
+                .map_err(|e| PresetError::DeserializationFailed(e.to_string()))?;
+            self.validate(&settings)?;
+        }
+        preset
+            .try_into()
+            .map_err(|e| PresetError::DeserializationFailed(e.to_string()))
+    }
+
+    /// Check settings against the simulation's schema, if it has one
+    pub fn validate(&self, settings: &serde_json::Value) -> PresetResult<()> {
+        match self.schema {
+            Some(schema) => settings_schema::validate_settings(&schema(), settings)
+                .map_err(|e| PresetError::ValidationFailed(e.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    /// Delete a user preset file and remove it from memory
+    pub fn delete_user_preset(&mut self, name: &str) -> PresetResult<()> {
+        let sanitized_name = sanitize_filename(name);
+        let file_path = self
+            .user_presets_dir
+            .join(format!("{}.toml", sanitized_name));
+
+        // Remove from file system
+        if file_path.exists() {
+            fs::remove_file(&file_path).map_err(|e| PresetError::FileError {
+                path: file_path.clone(),
+                error: e.to_string(),
+            })?;
+        }
+
+        // Also remove from memory immediately
+        self.presets.retain(|p| p.name != name);
+
+        Ok(())
+    }
+
+    /// Whether `name` is one of the presets that ship with Vizza
+    pub fn is_built_in_preset(&self, name: &str) -> bool {
+        self.built_in_preset_names
+            .iter()
+            .any(|built_in| built_in == name)
+    }
+
+    /// Get a preset by name and return its settings
+    pub fn get_preset_settings(&self, name: &str) -> Option<&Settings> {
+        self.get_preset(name).map(|p| &p.settings)
+    }
+
+    /// Modulators saved with a preset, empty if it has none
+    pub fn get_preset_modulators(&self, name: &str) -> Vec<Modulator> {
+        self.get_preset(name)
+            .map(|p| p.modulators.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl<Settings> Default for PresetManager<Settings>
+where
+    Settings: Clone + Serialize + for<'de> Deserialize<'de>,
+{
+    fn default() -> Self {
+        Self::new("default".to_string())
+    }
+}
+
+/// Create the Vizza/simulation-specific presets subdirectory path
+fn get_user_presets_dir(simulation_name: &str) -> PathBuf {
+    data_dir().join(simulation_name).join("presets")
+}
+
+/// Sanitize filename to be safe for filesystem
+fn sanitize_filename(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | ' ' => '_',
+            _ => c,
+        })
+        .collect()
+}
+
+// Type aliases for convenience
+pub type SlimeMoldPresetManager = PresetManager<crate::simulations::slime_mold::settings::Settings>;
+pub type GrayScottPresetManager = PresetManager<crate::simulations::gray_scott::settings::Settings>;
+pub type ParticleLifePresetManager =
+    PresetManager<crate::simulations::particle_life::settings::Settings>;
+pub type PelletsPresetManager = PresetManager<crate::simulations::pellets::settings::Settings>;
+pub type FlowPresetManager = PresetManager<crate::simulations::flow::settings::Settings>;
+pub type EcosystemPresetManager = PresetManager<crate::simulations::ecosystem::settings::Settings>;
+
+// Trait for unified preset manager operations
+pub trait AnyPresetManager: Send + Sync {
+    fn get_preset_names(&self) -> Vec<String>;
+    fn get_preset_settings_json(&self, name: &str) -> PresetResult<Option<serde_json::Value>>;
+    fn load_user_presets(&mut self) -> PresetResult<()>;
+    fn delete_user_preset(&mut self, name: &str) -> PresetResult<()>;
+    fn get_preset_modulators(&self, name: &str) -> Vec<Modulator>;
+    fn save_user_preset_json(
+        &self,
+        name: &str,
+        settings: &serde_json::Value,
+        modulators: &[Modulator],
+    ) -> PresetResult<()>;
+    fn preset_to_toml_json(&self, name: &str, settings: &serde_json::Value)
+    -> PresetResult<String>;
+    fn is_built_in_preset(&self, name: &str) -> bool;
+}
+
+// Every simulation's preset manager converts between its typed settings and JSON
+impl<Settings> AnyPresetManager for PresetManager<Settings>
+where
+    Settings: Clone + Serialize + for<'de> Deserialize<'de> + Send + Sync,
+{
+    fn get_preset_names(&self) -> Vec<String> {
+        self.get_preset_names()
+    }
+
+    fn get_preset_settings_json(&self, name: &str) -> PresetResult<Option<serde_json::Value>> {
+        self.get_preset_settings(name)
+            .map(|settings| {
+                serde_json::to_value(settings)
+                    .map_err(|e| PresetError::SerializationFailed(e.to_string()))
+            })
+            .transpose()
+    }
+
+    fn get_preset_modulators(&self, name: &str) -> Vec<Modulator> {
+        self.get_preset_modulators(name)
+    }
+
+    fn load_user_presets(&mut self) -> PresetResult<()> {
+        self.load_user_presets()
+    }
+
+    fn is_built_in_preset(&self, name: &str) -> bool {
+        self.is_built_in_preset(name)
+    }
+
+    fn delete_user_preset(&mut self, name: &str) -> PresetResult<()> {
+        self.delete_user_preset(name)
+    }
+
+    fn save_user_preset_json(
+        &self,
+        name: &str,
+        settings: &serde_json::Value,
+        modulators: &[Modulator],
+    ) -> PresetResult<()> {
+        self.validate(settings)?;
+        let typed_settings: Settings = serde_json::from_value(settings.clone())
+            .map_err(|e| PresetError::DeserializationFailed(e.to_string()))?;
+        self.save_user_preset(name, &typed_settings, modulators)
+    }
+
+    fn preset_to_toml_json(
+        &self,
+        name: &str,
+        settings: &serde_json::Value,
+    ) -> PresetResult<String> {
+        let typed_settings: Settings = serde_json::from_value(settings.clone())
+            .map_err(|e| PresetError::DeserializationFailed(e.to_string()))?;
+        Self::preset_to_toml(name, &typed_settings)
+    }
+}
+
+// Wrapper struct holding the preset manager of every simulation that has presets
+pub struct SimulationPresetManager {
+    managers: HashMap<String, Box<dyn AnyPresetManager>>,
+}
+
+impl SimulationPresetManager {
+    pub fn new() -> Self {
+        let managers = registry::SIMULATIONS
+            .iter()
+            .filter_map(|descriptor| {
+                let presets = descriptor.presets?;
+                Some((descriptor.id.to_string(), presets()))
+            })
+            .collect();
+
+        Self { managers }
+    }
+
+    /// Preset manager of a simulation type, or an error naming the simulation
+    /// when it has no presets
+    fn manager_for(&self, sim_name: &str) -> PresetResult<&dyn AnyPresetManager> {
+        self.get_manager(sim_name)
+            .ok_or_else(|| Self::no_presets(sim_name))
+    }
+
+    fn no_presets(sim_name: &str) -> PresetError {
+        match registry::get(sim_name) {
+            Ok(descriptor) => format!("{} does not support presets", descriptor.display_name),
+            Err(_) => format!("No preset manager found for simulation type: {}", sim_name),
+        }
+        .into()
+    }
+
+    pub fn get_available_presets(&self, simulation_type: &SimulationType) -> Vec<String> {
+        let sim_name = simulation_type.type_name();
+        let presets = self
+            .managers
+            .get(sim_name)
+            .map(|manager| manager.get_preset_names())
+            .unwrap_or_default();
+
+        tracing::info!("{} presets: {:?}", sim_name, presets);
+        presets
+    }
+
+    pub fn apply_preset(
+        &self,
+        simulation: &mut SimulationType,
+        preset_name: &str,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> PresetResult<()> {
+        let sim_name = simulation.type_name();
+        let settings = self
+            .manager_for(sim_name)?
+            .get_preset_settings_json(preset_name)?
+            .ok_or_else(|| PresetError::NotFound(preset_name.to_string()))?;
+
+        simulation
+            .apply_settings(settings, device, queue)
+            .map_err(|e| PresetError::SimulationError(e.to_string()))?;
+        simulation
+            .reset_runtime_state(device, queue)
+            .map_err(|e| PresetError::SimulationError(e.to_string()))?;
+        tracing::info!("Applied {} preset '{}'", sim_name, preset_name);
+        Ok(())
+    }
+
+    pub fn save_preset(
+        &mut self,
+        simulation: &SimulationType,
+        preset_name: &str,
+        settings: &serde_json::Value,
+        modulators: &[Modulator],
+    ) -> PresetResult<()> {
+        self.save_preset_for_type(simulation.type_name(), preset_name, settings, modulators)
+    }
+
+    /// Save a user preset for a simulation type that need not be running
+    pub fn save_preset_for_type(
+        &mut self,
+        sim_name: &str,
+        preset_name: &str,
+        settings: &serde_json::Value,
+        modulators: &[Modulator],
+    ) -> PresetResult<()> {
+        self.manager_for(sim_name)?
+            .save_user_preset_json(preset_name, settings, modulators)?;
+
+        // Reload user presets to include the newly saved one
+        self.reload_user_presets(sim_name)
+    }
+
+    /// Reload user presets for a specific simulation type
+    pub fn reload_user_presets(&mut self, sim_name: &str) -> PresetResult<()> {
+        let manager = self
+            .managers
+            .get_mut(sim_name)
+            .ok_or_else(|| Self::no_presets(sim_name))?;
+        manager.load_user_presets()?;
+        tracing::info!("Reloaded user presets for {}", sim_name);
+        Ok(())
+    }
+
+    /// Serialize `settings` as a preset file that `reload_user_presets` can
+    /// load for the given simulation type
+    pub fn preset_to_toml(
+        &self,
+        sim_name: &str,
+        preset_name: &str,
+        settings: &serde_json::Value,
+    ) -> PresetResult<String> {
+        self.manager_for(sim_name)?
+            .preset_to_toml_json(preset_name, settings)
+    }
+
+    pub fn delete_preset(
+        &mut self,
+        simulation_type: &SimulationType,
+        preset_name: &str,
+    ) -> PresetResult<()> {
+        self.delete_preset_for_type(simulation_type.type_name(), preset_name)
+    }
+
+    /// Delete a user preset for a simulation type that need not be running
+    pub fn delete_preset_for_type(
+        &mut self,
+        sim_name: &str,
+        preset_name: &str,
+    ) -> PresetResult<()> {
+        let manager = self
+            .managers
+            .get_mut(sim_name)
+            .ok_or_else(|| Self::no_presets(sim_name))?;
+        manager.delete_user_preset(preset_name)?;
+        tracing::info!("Deleted {} preset '{}'", sim_name, preset_name);
+        Ok(())
+    }
+
+    /// Modulators saved with a preset, empty if the preset has none or does
+    /// not exist
+    pub fn get_preset_modulators(&self, sim_name: &str, preset_name: &str) -> Vec<Modulator> {
+        self.get_manager(sim_name)
+            .map(|manager| manager.get_preset_modulators(preset_name))
+            .unwrap_or_default()
+    }
+
+    // Getter methods for accessing the specific preset managers
+    pub fn get_manager(&self, sim_name: &str) -> Option<&dyn AnyPresetManager> {
+        self.managers.get(sim_name).map(|m| m.as_ref())
+    }
+}
@@ -0,0 +1,462 @@
+//! # Frame Recorder
+//!
+//! Records a running simulation to a numbered PNG sequence, and optionally to a
+//! video by piping raw RGBA frames into an external encoder such as ffmpeg.
+//!
+//! While a recording is active the render loop steps the simulation with a fixed
+//! time step instead of the measured frame time, so the recorded motion is
+//! smooth no matter how long each frame took to render and save. Encoding and
+//! file IO happen on a background thread so the render loop only pays for the
+//! GPU readback.
+
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::thread::JoinHandle;
+use std::time::Instant;
+use wgpu::{Device, Queue, SurfaceConfiguration, TextureFormat};
+
+use crate::error::{AppError, AppResult, SimulationError};
+use crate::simulations::shared::{OffscreenTarget, save_png};
+use crate::simulations::traits::SimulationType;
+
+/// Number of captured frames that may wait for the writer thread before the
+/// render loop blocks
+const FRAME_QUEUE_DEPTH: usize = 8;
+
+/// Options for a recording, as sent by the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecordingOptions {
+    /// Directory that receives the numbered PNG frames
+    pub output_dir: PathBuf,
+    /// Capture every Nth rendered frame
+    pub frame_interval: u32,
+    /// Fixed simulation time step used while recording, in seconds
+    pub delta_time: f32,
+    /// Output resolution as a multiple of the window resolution
+    pub scale: u32,
+    /// Stop automatically after this many captured frames
+    pub max_frames: Option<u32>,
+    /// Whether to write the PNG sequence
+    pub save_frames: bool,
+    /// Also encode a video to this path through an external encoder
+    pub video_path: Option<PathBuf>,
+    /// Encoder executable, invoked with ffmpeg-compatible arguments
+    pub encoder_program: String,
+}
+
+impl Default for RecordingOptions {
+    fn default() -> Self {
+        Self {
+            output_dir: PathBuf::from("recording"),
+            frame_interval: 1,
+            delta_time: 1.0 / 60.0,
+            scale: 1,
+            max_frames: None,
+            save_frames: true,
+            video_path: None,
+            encoder_program: "ffmpeg".to_string(),
+        }
+    }
+}
+
+impl RecordingOptions {
+    /// Frame rate of the recorded output, derived from the fixed time step
+    pub fn output_fps(&self) -> f32 {
+        1.0 / (self.delta_time * self.frame_interval as f32)
+    }
+
+    fn validate(&self) -> AppResult<()> {
+        if self.frame_interval == 0 {
+            return Err(invalid("Frame interval must be at least 1"));
+        }
+        if !(self.delta_time.is_finite() && self.delta_time > 0.0) {
+            return Err(invalid("Recording time step must be positive"));
+        }
+        if self.scale == 0 {
+            return Err(invalid("Recording scale must be at least 1"));
+        }
+        if !self.save_frames && self.video_path.is_none() {
+            return Err(invalid(
+                "Recording needs either a frame sequence or a video output",
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn invalid(message: &str) -> AppError {
+    SimulationError::InvalidParameter(message.to_string()).into()
+}
+
+/// Progress of an active recording, emitted as `recording-progress`
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordingProgress {
+    pub frames_rendered: u32,
+    pub frames_captured: u32,
+    pub max_frames: Option<u32>,
+    pub elapsed_seconds: f32,
+}
+
+/// Result of a finished recording, emitted as `recording-stopped`
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordingSummary {
+    pub output_dir: Option<PathBuf>,
+    pub video_path: Option<PathBuf>,
+    pub frames_captured: u32,
+    pub width: u32,
+    pub height: u32,
+    pub fps: f32,
+}
+
+struct CapturedFrame {
+    index: u32,
+    pixels: Vec<u8>,
+}
+
+/// Captures frames from the current simulation while a recording is active
+pub struct FrameRecorder {
+    options: RecordingOptions,
+    target: OffscreenTarget,
+    sender: Option<SyncSender<CapturedFrame>>,
+    writer: Option<JoinHandle<io::Result<()>>>,
+    encoder: Option<Child>,
+    video_path: Option<PathBuf>,
+    frames_rendered: u32,
+    frames_captured: u32,
+    started_at: Instant,
+}
+
+impl std::fmt::Debug for FrameRecorder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FrameRecorder")
+            .field("options", &self.options)
+            .field("frames_rendered", &self.frames_rendered)
+            .field("frames_captured", &self.frames_captured)
+            .finish()
+    }
+}
+
+impl FrameRecorder {
+    /// Prepare the output directory, start the encoder if a video was requested
+    /// and spawn the writer thread
+    pub fn start(
+        options: RecordingOptions,
+        device: &Device,
+        width: u32,
+        height: u32,
+        format: TextureFormat,
+    ) -> AppResult<Self> {
+        options.validate()?;
+
+        let (width, height) = capture_size(width, height, options.scale);
+        let max_dimension = device.limits().max_texture_dimension_2d;
+        if width > max_dimension || height > max_dimension {
+            return Err(SimulationError::InvalidParameter(format!(
+                "Recording size {}x{} exceeds the GPU texture limit of {}",
+                width, height, max_dimension
+            ))
+            .into());
+        }
+        if !OffscreenTarget::supports_format(format) {
+            return Err(SimulationError::InvalidParameter(format!(
+                "Recording is not supported for surface format {:?}",
+                format
+            ))
+            .into());
+        }
+
+        if options.save_frames {
+            std::fs::create_dir_all(&options.output_dir)?;
+        }
+
+        let mut encoder = None;
+        let mut encoder_stdin = None;
+        let mut video_path = None;
+        if let Some(path) = &options.video_path {
+            match spawn_encoder(&options, path, width, height) {
+                Ok(mut child) => {
+                    encoder_stdin = child.stdin.take();
+                    encoder = Some(child);
+                    video_path = Some(path.clone());
+                }
+                Err(e) if e.kind() == io::ErrorKind::NotFound && options.save_frames => {
+                    tracing::warn!(
+                        "Encoder '{}' not found, recording PNG frames only",
+                        options.encoder_program
+                    );
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        let (sender, receiver) = mpsc::sync_channel(FRAME_QUEUE_DEPTH);
+        let frame_dir = options.save_frames.then(|| options.output_dir.clone());
+        let writer = std::thread::Builder::new()
+            .name("vizza-frame-writer".to_string())
+            .spawn(move || write_frames(receiver, frame_dir, encoder_stdin, width, height))?;
+
+        tracing::info!(
+            "Started recording {}x{} at {:.2} FPS",
+            width,
+            height,
+            options.output_fps()
+        );
+
+        Ok(Self {
+            target: OffscreenTarget::new(device, width, height, format),
+            options,
+            sender: Some(sender),
+            writer: Some(writer),
+            encoder,
+            video_path,
+            frames_rendered: 0,
+            frames_captured: 0,
+            started_at: Instant::now(),
+        })
+    }
+
+    /// Fixed time step the simulation should advance by for each recorded frame
+    pub fn delta_time(&self) -> f32 {
+        self.options.delta_time
+    }
+
+    /// Whether the requested number of frames has been captured
+    pub fn is_complete(&self) -> bool {
+        self.options
+            .max_frames
+            .is_some_and(|max_frames| self.frames_captured >= max_frames)
+    }
+
+    /// Count a rendered frame and capture it if it falls on the frame interval.
+    /// `surface_config` is the window's current configuration, which the
+    /// simulation is restored to after rendering at the recording resolution
+    /// (see `SimulationType::render_frame_static_scaled`). Returns the
+    /// progress when a frame was captured.
+    pub fn record_frame(
+        &mut self,
+        simulation: &mut SimulationType,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+        surface_config: &SurfaceConfiguration,
+    ) -> AppResult<Option<RecordingProgress>> {
+        if self.is_complete() {
+            return Ok(None);
+        }
+
+        self.frames_rendered += 1;
+        if !(self.frames_rendered - 1).is_multiple_of(self.options.frame_interval) {
+            return Ok(None);
+        }
+
+        simulation.render_frame_static_scaled(
+            device,
+            queue,
+            &self.target.view,
+            &self.target.surface_config(),
+            surface_config,
+        )?;
+        let pixels = self.target.read_pixels(device, queue)?;
+
+        let frame = CapturedFrame {
+            index: self.frames_captured,
+            pixels,
+        };
+        let sender = self
+            .sender
+            .as_ref()
+            .ok_or_else(|| AppError::Unknown("Recording already finished".to_string()))?;
+        if sender.send(frame).is_err() {
+            // The writer thread only hangs up after an IO error, which finish() reports
+            return Err(self.join_writer().err().unwrap_or_else(|| {
+                AppError::Unknown("Frame writer stopped unexpectedly".to_string())
+            }));
+        }
+
+        self.frames_captured += 1;
+        Ok(Some(self.progress()))
+    }
+
+    pub fn progress(&self) -> RecordingProgress {
+        RecordingProgress {
+            frames_rendered: self.frames_rendered,
+            frames_captured: self.frames_captured,
+            max_frames: self.options.max_frames,
+            elapsed_seconds: self.started_at.elapsed().as_secs_f32(),
+        }
+    }
+
+    /// Flush all queued frames, close the encoder and wait for it to finish
+    pub fn finish(mut self) -> AppResult<RecordingSummary> {
+        let write_result = self.join_writer();
+
+        if let Some(mut encoder) = self.encoder.take() {
+            let status = encoder.wait()?;
+            if !status.success() {
+                return Err(AppError::Unknown(format!(
+                    "Encoder '{}' exited with {}",
+                    self.options.encoder_program, status
+                )));
+            }
+        }
+        write_result?;
+
+        tracing::info!(
+            "Finished recording {} frames in {:.1}s",
+            self.frames_captured,
+            self.started_at.elapsed().as_secs_f32()
+        );
+
+        Ok(RecordingSummary {
+            output_dir: self
+                .options
+                .save_frames
+                .then(|| self.options.output_dir.clone()),
+            video_path: self.video_path.clone(),
+            frames_captured: self.frames_captured,
+            width: self.target.width,
+            height: self.target.height,
+            fps: self.options.output_fps(),
+        })
+    }
+
+    fn join_writer(&mut self) -> AppResult<()> {
+        // Dropping the sender ends the writer loop once the queue is drained
+        self.sender = None;
+        match self.writer.take() {
+            Some(writer) => writer
+                .join()
+                .map_err(|_| AppError::Unknown("Frame writer thread panicked".to_string()))?
+                .map_err(AppError::from),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Size of the recorded frames for a window of the given size. yuv420p video
+/// needs even dimensions, so odd sizes are rounded down to the nearest even size.
+fn capture_size(width: u32, height: u32, scale: u32) -> (u32, u32) {
+    let even = |size: u32| (size.saturating_mul(scale) & !1).max(2);
+    (even(width), even(height))
+}
+
+/// Start an ffmpeg-compatible encoder reading raw RGBA frames from stdin
+fn spawn_encoder(
+    options: &RecordingOptions,
+    path: &std::path::Path,
+    width: u32,
+    height: u32,
+) -> io::Result<Child> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    Command::new(&options.encoder_program)
+        .args([
+            "-y",
+            "-loglevel",
+            "error",
+            "-f",
+            "rawvideo",
+            "-pix_fmt",
+            "rgba",
+        ])
+        .args(["-s", &format!("{}x{}", width, height)])
+        .args(["-r", &format!("{}", options.output_fps())])
+        .args(["-i", "-", "-pix_fmt", "yuv420p"])
+        .arg(path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+}
+
+/// Writer thread: saves each frame as a PNG and feeds it to the encoder
+fn write_frames(
+    receiver: Receiver<CapturedFrame>,
+    frame_dir: Option<PathBuf>,
+    mut encoder_stdin: Option<ChildStdin>,
+    width: u32,
+    height: u32,
+) -> io::Result<()> {
+    for frame in receiver {
+        if let Some(dir) = &frame_dir {
+            let path = dir.join(format!("frame_{:06}.png", frame.index));
+            save_png(&path, width, height, &frame.pixels)?;
+        }
+        if let Some(stdin) = encoder_stdin.as_mut() {
+            stdin.write_all(&frame.pixels)?;
+        }
+    }
+
+    // Closing stdin tells the encoder the stream has ended
+    if let Some(mut stdin) = encoder_stdin {
+        stdin.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::RenderSettings;
+    use crate::simulation::headless::HeadlessRenderer;
+
+    #[test]
+    fn test_capture_size_is_even() {
+        assert_eq!(capture_size(1280, 720, 1), (1280, 720));
+        assert_eq!(capture_size(801, 599, 1), (800, 598));
+        assert_eq!(capture_size(801, 599, 2), (1602, 1198));
+        assert_eq!(capture_size(1, 1, 1), (2, 2));
+    }
+
+    #[tokio::test]
+    async fn test_recording_odd_window_size() {
+        let renderer = HeadlessRenderer::new(
+            33,
+            25,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderSettings::default(),
+        )
+        .await
+        .unwrap();
+        let mut simulation = renderer.create_simulation("gray_scott").await.unwrap();
+
+        let output_dir =
+            std::env::temp_dir().join(format!("vizza-recording-{}", std::process::id()));
+        let options = RecordingOptions {
+            output_dir: output_dir.clone(),
+            max_frames: Some(2),
+            ..Default::default()
+        };
+        let mut recorder = FrameRecorder::start(
+            options,
+            &renderer.device,
+            33,
+            25,
+            TextureFormat::Rgba8UnormSrgb,
+        )
+        .unwrap();
+        while !recorder.is_complete() {
+            recorder
+                .record_frame(
+                    &mut simulation,
+                    &renderer.device,
+                    &renderer.queue,
+                    &renderer.surface_config(),
+                )
+                .unwrap();
+        }
+        let summary = recorder.finish().unwrap();
+
+        assert_eq!((summary.width, summary.height), (32, 24));
+        assert_eq!(summary.frames_captured, 2);
+
+        std::fs::remove_dir_all(&output_dir).ok();
+    }
+}
@@ -0,0 +1,403 @@
+//! # Deterministic Replay
+//!
+//! A deterministic run steps the simulation with a fixed time step and draws
+//! all CPU-side randomness from a single seed (see
+//! [`crate::simulations::shared::random`]). Mouse interactions, camera
+//! movement, setting changes, resets and randomizations made during the run
+//! are logged against the frame they happened before, mouse positions in
+//! simulation coordinates so that camera movement does not change their
+//! meaning.
+//!
+//! Only simulations whose GPU passes do not depend on thread scheduling can be
+//! run this way. Slime mold deposits pheromone and flow hands out spawn slots
+//! from racing invocations. Ecosystem agents claim prey, food and reproduction
+//! slots with atomics, and particle life and pellets bin their particles with
+//! an atomic counting sort, which changes the order forces are summed in. The
+//! frames of all of these differ between runs on most GPUs, so their
+//! descriptors leave the `deterministic` capability unset.
+//!
+//! Replaying the log recreates the simulation from the same seed and settings
+//! and feeds the interactions back on the same frames, so two runs with the
+//! same seed and input log produce identical frames.
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+use wgpu::{Device, Queue};
+
+use crate::error::{AppResult, SimulationError, SimulationResult};
+use crate::simulations::registry;
+use crate::simulations::shared::random;
+use crate::simulations::traits::{Simulation, SimulationType};
+
+/// A user input that affects simulation state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InputEvent {
+    /// Mouse press or drag, in the coordinates the simulation expects
+    MouseInteraction {
+        x: f32,
+        y: f32,
+        button: u32,
+    },
+    MouseRelease {
+        button: u32,
+    },
+    UpdateSetting {
+        name: String,
+        value: serde_json::Value,
+    },
+    RandomizeSettings,
+    ResetSimulation,
+    ResetAgents,
+    ResetTrails,
+    SeedRandomNoise,
+    PanCamera {
+        delta_x: f32,
+        delta_y: f32,
+    },
+    ZoomCamera {
+        delta: f32,
+    },
+    /// Zoom towards a cursor position in screen coordinates
+    ZoomCameraToCursor {
+        delta: f32,
+        cursor_x: f32,
+        cursor_y: f32,
+    },
+    ResetCamera,
+}
+
+impl InputEvent {
+    pub fn apply(
+        self,
+        simulation: &mut SimulationType,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()> {
+        match self {
+            InputEvent::MouseInteraction { x, y, button } => {
+                simulation.handle_mouse_interaction(x, y, button, device, queue)
+            }
+            InputEvent::MouseRelease { button } => simulation.handle_mouse_release(button, queue),
+            InputEvent::UpdateSetting { name, value } => {
+                simulation.update_setting(&name, value, device, queue)
+            }
+            InputEvent::RandomizeSettings => simulation.randomize_settings(device, queue),
+            InputEvent::ResetSimulation => match simulation {
+                SimulationType::GrayScott(simulation) => {
+                    simulation.reset();
+                    Ok(())
+                }
+                SimulationType::ParticleLife(simulation) => {
+                    simulation.reset_particles_gpu(device, queue)
+                }
+                simulation => simulation.reset_runtime_state(device, queue),
+            },
+            // Slime mold repositions its agents, everything else starts over
+            InputEvent::ResetAgents => match simulation {
+                SimulationType::SlimeMold(simulation) => simulation.reset_agents(device, queue),
+                simulation => simulation.reset_runtime_state(device, queue),
+            },
+            InputEvent::ResetTrails => simulation.reset_runtime_state(device, queue),
+            InputEvent::SeedRandomNoise => match simulation {
+                SimulationType::GrayScott(simulation) => {
+                    simulation.seed_random_noise(device, queue)
+                }
+                _ => Ok(()),
+            },
+            event @ (InputEvent::PanCamera { .. }
+            | InputEvent::ZoomCamera { .. }
+            | InputEvent::ZoomCameraToCursor { .. }
+            | InputEvent::ResetCamera) => {
+                event.apply_camera(simulation);
+                Ok(())
+            }
+        }
+    }
+
+    /// Apply a camera event, which unlike other events needs no GPU access.
+    /// Does nothing for other events.
+    pub fn apply_camera(&self, simulation: &mut SimulationType) {
+        match *self {
+            InputEvent::PanCamera { delta_x, delta_y } => simulation.pan_camera(delta_x, delta_y),
+            InputEvent::ZoomCamera { delta } => simulation.zoom_camera(delta),
+            InputEvent::ZoomCameraToCursor {
+                delta,
+                cursor_x,
+                cursor_y,
+            } => simulation.zoom_camera_to_cursor(delta, cursor_x, cursor_y),
+            InputEvent::ResetCamera => simulation.reset_camera(),
+            _ => {}
+        }
+    }
+}
+
+/// An input event applied before the simulation steps frame `frame`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedInput {
+    pub frame: u64,
+    pub event: InputEvent,
+}
+
+/// Everything needed to reproduce a deterministic run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputLog {
+    pub simulation_type: String,
+    pub seed: u64,
+    pub delta_time: f32,
+    /// Settings the run started from, as returned by `get_settings`
+    pub settings: Option<serde_json::Value>,
+    pub events: Vec<RecordedInput>,
+}
+
+impl InputLog {
+    pub fn new(simulation_type: &str, seed: u64, delta_time: f32) -> Self {
+        Self {
+            simulation_type: simulation_type.to_string(),
+            seed,
+            delta_time,
+            settings: None,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn load(path: &Path) -> AppResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self, path: &Path) -> AppResult<()> {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// State of an active deterministic run
+#[derive(Debug)]
+pub struct DeterministicRun {
+    log: InputLog,
+    rng: StdRng,
+    frame: u64,
+    /// Index of the next event to replay, or `None` while recording
+    replay_cursor: Option<usize>,
+}
+
+impl DeterministicRun {
+    /// Start a run that logs live input as it happens
+    pub fn record(log: InputLog) -> AppResult<Self> {
+        Self::new(log, None)
+    }
+
+    /// Start a run that feeds back the events of a previously recorded log
+    pub fn replay(log: InputLog) -> AppResult<Self> {
+        Self::new(log, Some(0))
+    }
+
+    fn new(mut log: InputLog, replay_cursor: Option<usize>) -> AppResult<Self> {
+        let descriptor = registry::get(&log.simulation_type)?;
+        if !descriptor.capabilities.deterministic {
+            return Err(SimulationError::InvalidParameter(format!(
+                "{} does not render reproducibly and cannot be run deterministically",
+                descriptor.display_name
+            ))
+            .into());
+        }
+        if !(log.delta_time.is_finite() && log.delta_time > 0.0) {
+            return Err(SimulationError::InvalidParameter(
+                "Deterministic time step must be positive".to_string(),
+            )
+            .into());
+        }
+        if replay_cursor.is_none() {
+            log.events.clear();
+        }
+        // Stable sort keeps events recorded on the same frame in order
+        log.events.sort_by_key(|input| input.frame);
+
+        Ok(Self {
+            rng: StdRng::seed_from_u64(log.seed),
+            log,
+            frame: 0,
+            replay_cursor,
+        })
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.log.seed
+    }
+
+    pub fn delta_time(&self) -> f32 {
+        self.log.delta_time
+    }
+
+    /// Number of frames stepped since the run started
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        self.replay_cursor.is_some()
+    }
+
+    pub fn log(&self) -> &InputLog {
+        &self.log
+    }
+
+    pub fn into_log(self) -> InputLog {
+        self.log
+    }
+
+    /// Run `f` with all simulation randomness derived from the run's seed
+    pub fn scoped<R>(&mut self, f: impl FnOnce() -> R) -> R {
+        random::scoped(&mut self.rng, f)
+    }
+
+    /// Log a live input against the next frame. Ignored while replaying.
+    pub fn record_input(&mut self, event: InputEvent) {
+        if self.replay_cursor.is_none() {
+            self.log.events.push(RecordedInput {
+                frame: self.frame,
+                event,
+            });
+        }
+    }
+
+    /// Logged inputs that are due before the next frame is stepped
+    pub fn take_due_inputs(&mut self) -> Vec<InputEvent> {
+        let Some(cursor) = self.replay_cursor.as_mut() else {
+            return Vec::new();
+        };
+
+        let due = self.log.events[*cursor..]
+            .iter()
+            .take_while(|input| input.frame <= self.frame)
+            .map(|input| input.event.clone())
+            .collect::<Vec<_>>();
+        *cursor += due.len();
+        due
+    }
+
+    pub fn advance_frame(&mut self) {
+        self.frame += 1;
+    }
+}
+
+/// Run `f` with randomness derived from the run's seed when a run is active
+pub fn seeded<R>(run: Option<&mut DeterministicRun>, f: impl FnOnce() -> R) -> R {
+    match run {
+        Some(run) => run.scoped(f),
+        None => f(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(frame: u64) -> RecordedInput {
+        RecordedInput {
+            frame,
+            event: InputEvent::MouseInteraction {
+                x: 0.25,
+                y: -0.5,
+                button: 0,
+            },
+        }
+    }
+
+    #[test]
+    fn test_recording_logs_inputs_against_frames() {
+        let mut run = DeterministicRun::record(InputLog::new("gray_scott", 7, 1.0 / 60.0)).unwrap();
+        run.advance_frame();
+        run.record_input(press(0).event);
+        run.advance_frame();
+        run.record_input(InputEvent::MouseRelease { button: 0 });
+
+        // Nothing is replayed while recording
+        assert!(run.take_due_inputs().is_empty());
+        let log = run.into_log();
+        assert_eq!(log.events.len(), 2);
+        assert_eq!(log.events[0].frame, 1);
+        assert_eq!(log.events[1].frame, 2);
+    }
+
+    #[test]
+    fn test_replay_feeds_inputs_on_their_frames() {
+        let mut log = InputLog::new("gray_scott", 7, 1.0 / 60.0);
+        log.events = vec![press(2), press(0), press(2)];
+        let mut run = DeterministicRun::replay(log).unwrap();
+
+        assert_eq!(run.take_due_inputs().len(), 1);
+        run.advance_frame();
+        assert!(run.take_due_inputs().is_empty());
+        run.advance_frame();
+        assert_eq!(run.take_due_inputs().len(), 2);
+        assert!(run.take_due_inputs().is_empty());
+
+        // Live input does not alter the log being replayed
+        run.record_input(InputEvent::MouseRelease { button: 0 });
+        assert_eq!(run.log().events.len(), 3);
+    }
+
+    #[test]
+    fn test_input_log_round_trip() {
+        let mut log = InputLog::new("pellets", 42, 0.02);
+        log.settings = Some(serde_json::json!({ "particle_count": 100 }));
+        log.events = vec![
+            press(3),
+            RecordedInput {
+                frame: 5,
+                event: InputEvent::MouseRelease { button: 0 },
+            },
+            RecordedInput {
+                frame: 6,
+                event: InputEvent::UpdateSetting {
+                    name: "particle_count".to_string(),
+                    value: serde_json::json!(200),
+                },
+            },
+            RecordedInput {
+                frame: 6,
+                event: InputEvent::ResetSimulation,
+            },
+        ];
+
+        let path = std::env::temp_dir()
+            .join(format!("vizza-replay-{}", std::process::id()))
+            .join("input.json");
+        log.save(&path).unwrap();
+        assert_eq!(InputLog::load(&path).unwrap(), log);
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_rejects_invalid_time_step() {
+        assert!(DeterministicRun::record(InputLog::new("gray_scott", 1, 0.0)).is_err());
+    }
+
+    #[test]
+    fn test_rejects_nondeterministic_simulations() {
+        for simulation_type in [
+            "slime_mold",
+            "flow",
+            "ecosystem",
+            "particle_life",
+            "pellets",
+            "not_a_simulation",
+        ] {
+            assert!(
+                DeterministicRun::record(InputLog::new(simulation_type, 1, 1.0 / 60.0)).is_err(),
+                "{} should not start a deterministic run",
+                simulation_type
+            );
+        }
+    }
+}
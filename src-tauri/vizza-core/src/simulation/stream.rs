@@ -0,0 +1,935 @@
+//! # Frame Streaming
+//!
+//! Serves the rendered output to other programs on the same machine, for
+//! projection setups that would otherwise rely on screen capture. While a
+//! stream is active the render loop hands it each composited frame, at most
+//! at the stream's frame rate. The frame is scaled into an offscreen target
+//! of the stream's own resolution, cropped to its aspect ratio, and read back
+//! through a small ring of staging buffers so that the render loop never
+//! waits on the GPU. The simulation is not rendered a second time, so the
+//! stream shows exactly what is on screen.
+//!
+//! Frames are published on two localhost servers:
+//!
+//! - an MJPEG stream over HTTP, `multipart/x-mixed-replace`, which browsers,
+//!   OBS, VLC and most VJ tools can open as a video source
+//! - raw RGBA on a plain TCP socket, where each frame is a 12-byte header of
+//!   width, height and pixel byte count as little-endian `u32`s, followed by
+//!   the pixels row by row from the top
+//!
+//! Clients that fall behind skip to the newest frame instead of queueing.
+
+use std::collections::VecDeque;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use wgpu::{Device, Queue, TextureFormat};
+
+use crate::error::{AppResult, SimulationError};
+use crate::simulations::shared::OffscreenTarget;
+
+/// Number of staging buffers frames are read back through
+const READBACK_SLOTS: usize = 3;
+
+/// Largest request header accepted from an MJPEG client
+const MAX_REQUEST_SIZE: usize = 8192;
+
+/// Multipart boundary separating MJPEG frames
+const MJPEG_BOUNDARY: &str = "vizzaframe";
+
+const STREAM_SCALE_SHADER: &str = include_str!("stream_scale.wgsl");
+
+/// Options for a frame stream, as sent by the frontend
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreamOptions {
+    pub width: u32,
+    pub height: u32,
+    /// Most frames per second to stream
+    pub fps: f32,
+    /// JPEG quality of the MJPEG stream, 1 to 100
+    pub jpeg_quality: u8,
+    /// Port of the MJPEG server, or `None` to not serve MJPEG. Port 0 picks
+    /// a free port.
+    pub mjpeg_port: Option<u16>,
+    /// Port of the raw RGBA server, or `None` to not serve raw frames
+    pub raw_port: Option<u16>,
+}
+
+impl Default for StreamOptions {
+    fn default() -> Self {
+        Self {
+            width: 1280,
+            height: 720,
+            fps: 30.0,
+            jpeg_quality: 80,
+            mjpeg_port: Some(8080),
+            raw_port: Some(8081),
+        }
+    }
+}
+
+impl StreamOptions {
+    fn validate(&self, device: &Device) -> AppResult<()> {
+        let max_dimension = device
+            .limits()
+            .max_texture_dimension_2d
+            .min(u16::MAX as u32);
+        if self.width == 0 || self.height == 0 {
+            return Err(invalid("Stream size must be at least 1x1"));
+        }
+        if self.width > max_dimension || self.height > max_dimension {
+            return Err(invalid(&format!(
+                "Stream size {}x{} exceeds the limit of {}",
+                self.width, self.height, max_dimension
+            )));
+        }
+        if !(self.fps.is_finite() && self.fps > 0.0) {
+            return Err(invalid("Stream frame rate must be positive"));
+        }
+        if !(1..=100).contains(&self.jpeg_quality) {
+            return Err(invalid("JPEG quality must be between 1 and 100"));
+        }
+        if self.mjpeg_port.is_none() && self.raw_port.is_none() {
+            return Err(invalid("Enable the MJPEG or raw server to stream"));
+        }
+        Ok(())
+    }
+}
+
+fn invalid(message: &str) -> crate::error::AppError {
+    SimulationError::InvalidParameter(message.to_string()).into()
+}
+
+/// A frame read back from the GPU as tightly packed RGBA8
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamFrame {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+type LatestFrame = Option<Arc<StreamFrame>>;
+
+/// State of an active stream, as reported to the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamStatus {
+    pub width: u32,
+    pub height: u32,
+    pub fps: f32,
+    /// URL to open the MJPEG stream at
+    pub mjpeg_url: Option<String>,
+    /// `host:port` to read raw frames from
+    pub raw_address: Option<String>,
+    pub frames_streamed: u64,
+    /// Frames skipped because the GPU had not finished reading back earlier ones
+    pub frames_dropped: u64,
+}
+
+const SLOT_IDLE: u8 = 0;
+const SLOT_PENDING: u8 = 1;
+const SLOT_READY: u8 = 2;
+const SLOT_FAILED: u8 = 3;
+
+struct ReadbackSlot {
+    buffer: wgpu::Buffer,
+    state: Arc<AtomicU8>,
+}
+
+/// Staging buffers that frames are copied into and mapped asynchronously, so
+/// that a frame is collected once the GPU is done with it instead of waiting
+/// for it
+struct ReadbackRing {
+    slots: Vec<ReadbackSlot>,
+    /// Slots in submission order that have not been collected yet
+    pending: VecDeque<usize>,
+    next: usize,
+    padded_bytes_per_row: u32,
+}
+
+impl ReadbackRing {
+    fn new(device: &Device, width: u32, height: u32) -> Self {
+        let padded_bytes_per_row = (width * 4).div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+            * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let slots = (0..READBACK_SLOTS)
+            .map(|_| ReadbackSlot {
+                buffer: device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("Stream Readback Buffer"),
+                    size: padded_bytes_per_row as u64 * height as u64,
+                    usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                }),
+                state: Arc::new(AtomicU8::new(SLOT_IDLE)),
+            })
+            .collect();
+
+        Self {
+            slots,
+            pending: VecDeque::new(),
+            next: 0,
+            padded_bytes_per_row,
+        }
+    }
+
+    /// Copy `target` into the next free slot. Returns false if every slot is
+    /// still waiting on the GPU, in which case the frame is dropped.
+    fn submit(&mut self, target: &OffscreenTarget, device: &Device, queue: &Queue) -> bool {
+        let index = self.next;
+        let slot = &self.slots[index];
+        if slot.state.load(Ordering::Acquire) != SLOT_IDLE {
+            return false;
+        }
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Stream Readback Encoder"),
+        });
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture: &target.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &slot.buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(self.padded_bytes_per_row),
+                    rows_per_image: Some(target.height),
+                },
+            },
+            wgpu::Extent3d {
+                width: target.width,
+                height: target.height,
+                depth_or_array_layers: 1,
+            },
+        );
+        queue.submit(std::iter::once(encoder.finish()));
+
+        slot.state.store(SLOT_PENDING, Ordering::Release);
+        let state = slot.state.clone();
+        slot.buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                let next = if result.is_ok() {
+                    SLOT_READY
+                } else {
+                    SLOT_FAILED
+                };
+                state.store(next, Ordering::Release);
+            });
+
+        self.pending.push_back(index);
+        self.next = (index + 1) % self.slots.len();
+        true
+    }
+
+    /// The newest frame whose readback has finished, if any, as RGBA8
+    fn collect(&mut self, target: &OffscreenTarget, device: &Device) -> Option<Vec<u8>> {
+        device.poll(wgpu::Maintain::Poll);
+
+        let mut latest = None;
+        while let Some(&index) = self.pending.front() {
+            let slot = &self.slots[index];
+            match slot.state.load(Ordering::Acquire) {
+                SLOT_READY => {
+                    {
+                        let mapped = slot.buffer.slice(..).get_mapped_range();
+                        latest = Some(unpad_rows(
+                            &mapped,
+                            self.padded_bytes_per_row,
+                            target.width,
+                            target.format,
+                        ));
+                    }
+                    slot.buffer.unmap();
+                }
+                SLOT_FAILED => tracing::warn!("Dropping a stream frame that failed to read back"),
+                _ => break,
+            }
+            slot.state.store(SLOT_IDLE, Ordering::Release);
+            self.pending.pop_front();
+        }
+        latest
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+struct ScaleParams {
+    uv_scale: [f32; 2],
+    uv_offset: [f32; 2],
+}
+
+impl ScaleParams {
+    /// Crop the middle of a source frame to the aspect ratio of the target
+    fn crop(source_width: u32, source_height: u32, width: u32, height: u32) -> Self {
+        let source_aspect = source_width as f32 / source_height as f32;
+        let aspect = width as f32 / height as f32;
+        let uv_scale = if source_aspect > aspect {
+            [aspect / source_aspect, 1.0]
+        } else {
+            [1.0, source_aspect / aspect]
+        };
+        Self {
+            uv_scale,
+            uv_offset: [(1.0 - uv_scale[0]) / 2.0, (1.0 - uv_scale[1]) / 2.0],
+        }
+    }
+}
+
+/// Draws a composited frame into the stream target at the stream's resolution
+struct FrameScaler {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    params_buffer: wgpu::Buffer,
+}
+
+impl FrameScaler {
+    fn new(device: &Device, format: TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Stream Scale Shader"),
+            source: wgpu::ShaderSource::Wgsl(STREAM_SCALE_SHADER.into()),
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Stream Scale Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Stream Scale Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Stream Scale Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Stream Scale Sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let params_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Stream Scale Params Buffer"),
+            size: std::mem::size_of::<ScaleParams>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            sampler,
+            params_buffer,
+        }
+    }
+
+    /// Draw `source`, which must allow texture binding, into `target`
+    fn scale(
+        &self,
+        source: &wgpu::Texture,
+        target: &OffscreenTarget,
+        device: &Device,
+        queue: &Queue,
+    ) {
+        let params =
+            ScaleParams::crop(source.width(), source.height(), target.width, target.height);
+        queue.write_buffer(&self.params_buffer, 0, bytemuck::bytes_of(&params));
+
+        // Surface textures change from frame to frame, so the bind group does too
+        let source_view = source.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Stream Scale Bind Group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&source_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.params_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Stream Scale Encoder"),
+        });
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Stream Scale Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &target.view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            render_pass.set_pipeline(&self.pipeline);
+            render_pass.set_bind_group(0, &bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+        queue.submit(std::iter::once(encoder.finish()));
+    }
+}
+
+/// Strip row padding and convert BGRA to RGBA
+fn unpad_rows(
+    mapped: &[u8],
+    padded_bytes_per_row: u32,
+    width: u32,
+    format: TextureFormat,
+) -> Vec<u8> {
+    let row_bytes = width as usize * 4;
+    let mut pixels = Vec::with_capacity(mapped.len());
+    for row in mapped.chunks(padded_bytes_per_row as usize) {
+        pixels.extend_from_slice(&row[..row_bytes]);
+    }
+    if matches!(
+        format,
+        TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb
+    ) {
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+    }
+    pixels
+}
+
+/// Output sink on the render loop that streams frames to local clients
+pub struct FrameStream {
+    options: StreamOptions,
+    target: OffscreenTarget,
+    scaler: FrameScaler,
+    readback: ReadbackRing,
+    frames: watch::Sender<LatestFrame>,
+    servers: StreamServers,
+    last_capture: Option<Instant>,
+    frames_streamed: u64,
+    frames_dropped: u64,
+}
+
+impl std::fmt::Debug for FrameStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FrameStream")
+            .field("options", &self.options)
+            .field("frames_streamed", &self.frames_streamed)
+            .finish()
+    }
+}
+
+impl FrameStream {
+    /// Create the offscreen target and start the servers. Frames captured
+    /// later must be of `format`. Must be called from within the async runtime.
+    pub async fn start(
+        options: StreamOptions,
+        device: &Device,
+        format: TextureFormat,
+    ) -> AppResult<Self> {
+        options.validate(device)?;
+        if !OffscreenTarget::supports_format(format) {
+            return Err(invalid(&format!(
+                "Streaming is not supported for surface format {:?}",
+                format
+            )));
+        }
+
+        let (frames, receiver) = watch::channel(None);
+        let servers = StreamServers::start(&options, receiver).await?;
+        tracing::info!(
+            "Streaming {}x{} at up to {} FPS",
+            options.width,
+            options.height,
+            options.fps
+        );
+
+        Ok(Self {
+            target: OffscreenTarget::new(device, options.width, options.height, format),
+            scaler: FrameScaler::new(device, format),
+            readback: ReadbackRing::new(device, options.width, options.height),
+            options,
+            frames,
+            servers,
+            last_capture: None,
+            frames_streamed: 0,
+            frames_dropped: 0,
+        })
+    }
+
+    /// Publish any frame that finished reading back, then scale `frame`, the
+    /// composited output that was just rendered, and submit it for readback
+    /// if the stream's frame interval has passed. `frame` must allow texture
+    /// binding.
+    pub fn capture(&mut self, frame: &wgpu::Texture, device: &Device, queue: &Queue) {
+        if let Some(pixels) = self.readback.collect(&self.target, device) {
+            self.frames.send_replace(Some(Arc::new(StreamFrame {
+                width: self.target.width,
+                height: self.target.height,
+                pixels,
+            })));
+            self.frames_streamed += 1;
+        }
+
+        let interval = Duration::from_secs_f32(1.0 / self.options.fps);
+        let now = Instant::now();
+        if self
+            .last_capture
+            .is_some_and(|last| now.duration_since(last) < interval)
+        {
+            return;
+        }
+        self.last_capture = Some(now);
+
+        self.scaler.scale(frame, &self.target, device, queue);
+        if !self.readback.submit(&self.target, device, queue) {
+            self.frames_dropped += 1;
+        }
+    }
+
+    pub fn status(&self) -> StreamStatus {
+        StreamStatus {
+            width: self.options.width,
+            height: self.options.height,
+            fps: self.options.fps,
+            mjpeg_url: self
+                .servers
+                .mjpeg_address
+                .map(|address| format!("http://{}/", address)),
+            raw_address: self.servers.raw_address.map(|address| address.to_string()),
+            frames_streamed: self.frames_streamed,
+            frames_dropped: self.frames_dropped,
+        }
+    }
+}
+
+/// Background servers handing published frames to clients
+struct StreamServers {
+    mjpeg_address: Option<SocketAddr>,
+    raw_address: Option<SocketAddr>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl StreamServers {
+    async fn start(
+        options: &StreamOptions,
+        frames: watch::Receiver<LatestFrame>,
+    ) -> io::Result<Self> {
+        let mut servers = Self {
+            mjpeg_address: None,
+            raw_address: None,
+            tasks: Vec::new(),
+        };
+
+        if let Some(port) = options.mjpeg_port {
+            let listener = TcpListener::bind(("127.0.0.1", port)).await?;
+            servers.mjpeg_address = Some(listener.local_addr()?);
+
+            // Frames are encoded once and shared by all MJPEG clients
+            let (jpegs, clients) = watch::channel(None);
+            servers.tasks.push(tokio::spawn(encode_jpegs(
+                frames.clone(),
+                jpegs,
+                options.jpeg_quality,
+            )));
+            servers
+                .tasks
+                .push(tokio::spawn(accept(listener, move |socket| {
+                    send_mjpeg(socket, clients.clone())
+                })));
+        }
+
+        if let Some(port) = options.raw_port {
+            let listener = TcpListener::bind(("127.0.0.1", port)).await?;
+            servers.raw_address = Some(listener.local_addr()?);
+            servers
+                .tasks
+                .push(tokio::spawn(accept(listener, move |socket| {
+                    send_raw(socket, frames.clone())
+                })));
+        }
+
+        Ok(servers)
+    }
+}
+
+impl Drop for StreamServers {
+    fn drop(&mut self) {
+        // Client tasks end on their own once the frame channels close
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+/// Accept clients until the server is stopped, serving each on its own task
+async fn accept<F, Fut>(listener: TcpListener, serve: F)
+where
+    F: Fn(TcpStream) -> Fut,
+    Fut: Future<Output = io::Result<()>> + Send + 'static,
+{
+    loop {
+        match listener.accept().await {
+            Ok((socket, address)) => {
+                tracing::debug!("Stream client connected from {}", address);
+                let client = serve(socket);
+                tokio::spawn(async move {
+                    if let Err(e) = client.await {
+                        tracing::debug!("Stream client {} disconnected: {}", address, e);
+                    }
+                });
+            }
+            Err(e) => tracing::warn!("Failed to accept stream client: {}", e),
+        }
+    }
+}
+
+/// Encode each published frame as JPEG while anyone is watching
+async fn encode_jpegs(
+    mut frames: watch::Receiver<LatestFrame>,
+    jpegs: watch::Sender<Option<Arc<Vec<u8>>>>,
+    quality: u8,
+) {
+    while frames.changed().await.is_ok() {
+        // The accepting task holds one receiver to hand out to clients
+        if jpegs.receiver_count() <= 1 {
+            continue;
+        }
+        let Some(frame) = frames.borrow_and_update().clone() else {
+            continue;
+        };
+        match tokio::task::spawn_blocking(move || encode_jpeg(&frame, quality)).await {
+            Ok(Ok(jpeg)) => {
+                jpegs.send_replace(Some(Arc::new(jpeg)));
+            }
+            Ok(Err(e)) => tracing::warn!("Failed to encode stream frame: {}", e),
+            Err(e) => tracing::warn!("Stream encoder stopped: {}", e),
+        }
+    }
+}
+
+fn encode_jpeg(frame: &StreamFrame, quality: u8) -> Result<Vec<u8>, jpeg_encoder::EncodingError> {
+    let mut jpeg = Vec::new();
+    jpeg_encoder::Encoder::new(&mut jpeg, quality).encode(
+        &frame.pixels,
+        frame.width as u16,
+        frame.height as u16,
+        jpeg_encoder::ColorType::Rgba,
+    )?;
+    Ok(jpeg)
+}
+
+/// Answer an HTTP request with a never-ending multipart stream of JPEGs
+async fn send_mjpeg(
+    mut socket: TcpStream,
+    mut jpegs: watch::Receiver<Option<Arc<Vec<u8>>>>,
+) -> io::Result<()> {
+    read_request_header(&mut socket).await?;
+    socket
+        .write_all(
+            format!(
+                "HTTP/1.1 200 OK\r\n\
+                 Content-Type: multipart/x-mixed-replace; boundary={}\r\n\
+                 Cache-Control: no-cache\r\n\
+                 Connection: close\r\n\r\n",
+                MJPEG_BOUNDARY
+            )
+            .as_bytes(),
+        )
+        .await?;
+
+    // Send the current frame straight away rather than waiting for the next
+    jpegs.mark_changed();
+    while jpegs.changed().await.is_ok() {
+        let Some(jpeg) = jpegs.borrow_and_update().clone() else {
+            continue;
+        };
+        let part = format!(
+            "--{}\r\nContent-Type: image/jpeg\r\nContent-Length: {}\r\n\r\n",
+            MJPEG_BOUNDARY,
+            jpeg.len()
+        );
+        socket.write_all(part.as_bytes()).await?;
+        socket.write_all(&jpeg).await?;
+        socket.write_all(b"\r\n").await?;
+    }
+    Ok(())
+}
+
+/// Read and discard an HTTP request header; any path is answered the same
+async fn read_request_header(socket: &mut TcpStream) -> io::Result<()> {
+    let mut request = Vec::new();
+    let mut buffer = [0; 1024];
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = socket.read(&mut buffer).await?;
+        if read == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        request.extend_from_slice(&buffer[..read]);
+        if request.len() > MAX_REQUEST_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Request header too large",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Header preceding each raw frame
+fn raw_header(frame: &StreamFrame) -> [u8; 12] {
+    let mut header = [0; 12];
+    header[0..4].copy_from_slice(&frame.width.to_le_bytes());
+    header[4..8].copy_from_slice(&frame.height.to_le_bytes());
+    header[8..12].copy_from_slice(&(frame.pixels.len() as u32).to_le_bytes());
+    header
+}
+
+/// Write every published frame to a raw client
+async fn send_raw(
+    mut socket: TcpStream,
+    mut frames: watch::Receiver<LatestFrame>,
+) -> io::Result<()> {
+    socket.set_nodelay(true)?;
+    frames.mark_changed();
+    while frames.changed().await.is_ok() {
+        let Some(frame) = frames.borrow_and_update().clone() else {
+            continue;
+        };
+        socket.write_all(&raw_header(&frame)).await?;
+        socket.write_all(&frame.pixels).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::RenderSettings;
+    use crate::simulation::headless::HeadlessRenderer;
+    use wgpu::util::DeviceExt;
+
+    fn frame(width: u32, height: u32, value: u8) -> Arc<StreamFrame> {
+        Arc::new(StreamFrame {
+            width,
+            height,
+            pixels: vec![value; (width * height * 4) as usize],
+        })
+    }
+
+    fn options() -> StreamOptions {
+        StreamOptions {
+            mjpeg_port: Some(0),
+            raw_port: Some(0),
+            ..StreamOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_unpad_rows_converts_bgra() {
+        let mapped = [1, 2, 3, 4, 0, 0, 0, 0, 5, 6, 7, 8, 0, 0, 0, 0];
+        assert_eq!(
+            unpad_rows(&mapped, 8, 1, TextureFormat::Bgra8Unorm),
+            vec![3, 2, 1, 4, 7, 6, 5, 8]
+        );
+        assert_eq!(
+            unpad_rows(&mapped, 8, 1, TextureFormat::Rgba8Unorm),
+            vec![1, 2, 3, 4, 5, 6, 7, 8]
+        );
+    }
+
+    #[test]
+    fn test_scale_crops_to_stream_aspect() {
+        // A wide window streamed at a square resolution keeps its middle
+        let params = ScaleParams::crop(200, 100, 64, 64);
+        assert_eq!(params.uv_scale, [0.5, 1.0]);
+        assert_eq!(params.uv_offset, [0.25, 0.0]);
+
+        let params = ScaleParams::crop(100, 200, 128, 64);
+        assert_eq!(params.uv_scale, [1.0, 0.25]);
+        assert_eq!(params.uv_offset, [0.0, 0.375]);
+
+        let params = ScaleParams::crop(1920, 1080, 1280, 720);
+        assert_eq!(params.uv_scale, [1.0, 1.0]);
+        assert_eq!(params.uv_offset, [0.0, 0.0]);
+    }
+
+    #[tokio::test]
+    async fn test_capture_scales_composited_frame() {
+        let renderer =
+            HeadlessRenderer::new(16, 16, TextureFormat::Rgba8Unorm, RenderSettings::default())
+                .await
+                .unwrap();
+        let (device, queue) = (&renderer.device, &renderer.queue);
+
+        // Red in the middle, green at the sides the square stream crops away
+        let (width, height) = (32, 16);
+        let pixels: Vec<u8> = (0..width * height)
+            .flat_map(|i| match i % width {
+                8..24 => [255, 0, 0, 255],
+                _ => [0, 255, 0, 255],
+            })
+            .collect();
+        let frame = device.create_texture_with_data(
+            queue,
+            &wgpu::TextureDescriptor {
+                label: None,
+                size: wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: TextureFormat::Rgba8Unorm,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            },
+            wgpu::util::TextureDataOrder::LayerMajor,
+            &pixels,
+        );
+
+        let options = StreamOptions {
+            width: 8,
+            height: 8,
+            ..options()
+        };
+        let mut stream = FrameStream::start(options, device, TextureFormat::Rgba8Unorm)
+            .await
+            .unwrap();
+        stream.capture(&frame, device, queue);
+        device.poll(wgpu::Maintain::Wait);
+        stream.capture(&frame, device, queue);
+
+        let streamed = stream.frames.borrow().clone().unwrap();
+        assert_eq!((streamed.width, streamed.height), (8, 8));
+        assert!(
+            streamed
+                .pixels
+                .chunks(4)
+                .all(|pixel| pixel == [255, 0, 0, 255])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_raw_clients_receive_frames() {
+        let (frames, receiver) = watch::channel(Some(frame(2, 1, 7)));
+        let servers = StreamServers::start(&options(), receiver).await.unwrap();
+        let mut client = TcpStream::connect(servers.raw_address.unwrap())
+            .await
+            .unwrap();
+
+        // The current frame arrives on connecting, later ones as published
+        for value in [7, 9] {
+            if value == 9 {
+                frames.send_replace(Some(frame(2, 1, 9)));
+            }
+            let mut header = [0; 12];
+            client.read_exact(&mut header).await.unwrap();
+            assert_eq!(header, raw_header(&frame(2, 1, value)));
+            let mut pixels = vec![0; 8];
+            client.read_exact(&mut pixels).await.unwrap();
+            assert_eq!(pixels, vec![value; 8]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mjpeg_clients_receive_jpegs() {
+        let (frames, receiver) = watch::channel(None);
+        let servers = StreamServers::start(&options(), receiver).await.unwrap();
+        let mut client = TcpStream::connect(servers.mjpeg_address.unwrap())
+            .await
+            .unwrap();
+        client
+            .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+
+        // Publish until the encoder has seen a frame with this client connected
+        let mut response = Vec::new();
+        let mut buffer = [0; 4096];
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !response.windows(2).any(|window| window == [0xFF, 0xD8]) {
+            assert!(Instant::now() < deadline, "no JPEG received");
+            frames.send_replace(Some(frame(16, 16, 128)));
+            if let Ok(read) =
+                tokio::time::timeout(Duration::from_millis(100), client.read(&mut buffer)).await
+            {
+                response.extend_from_slice(&buffer[..read.unwrap()]);
+            }
+        }
+
+        let response = String::from_utf8_lossy(&response);
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("multipart/x-mixed-replace; boundary=vizzaframe"));
+        assert!(response.contains("--vizzaframe\r\nContent-Type: image/jpeg\r\n"));
+    }
+}
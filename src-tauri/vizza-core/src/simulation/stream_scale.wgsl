@@ -0,0 +1,34 @@
+// Scales the composited frame to the resolution of a frame stream
+//
+// A single triangle covers the stream target and samples the source frame
+// bilinearly. The UV transform crops the source to the stream's aspect ratio.
+
+struct ScaleParams {
+    uv_scale: vec2<f32>,
+    uv_offset: vec2<f32>,
+}
+
+@group(0) @binding(0) var source: texture_2d<f32>;
+@group(0) @binding(1) var source_sampler: sampler;
+@group(0) @binding(2) var<uniform> params: ScaleParams;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    // (0, 0), (2, 0), (0, 2) in UV space covers the whole target
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    out.uv = uv * params.uv_scale + params.uv_offset;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(source, source_sampler, in.uv);
+}
@@ -0,0 +1,406 @@
+//! # Parameter Sweeps
+//!
+//! Renders a simulation once for every combination of values of one or two
+//! settings fields and assembles the results into a labelled contact sheet,
+//! e.g. a map of Gray-Scott behaviour over `feed_rate` x `kill_rate`.
+//!
+//! Every cell starts from the same base settings and runtime state seeded
+//! from the same value, so cells differ only in the swept fields. The settings
+//! of each cell are also written as a preset file, which can be copied into the
+//! simulation's user presets folder to keep exploring from a promising spot.
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use serde_json::Value;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use crate::error::{AppError, AppResult, SimulationError};
+use crate::simulation::contact_sheet::ContactSheet;
+use crate::simulation::headless::HeadlessRenderer;
+use crate::simulations::shared::random;
+use crate::simulations::traits::{Simulation, SimulationType};
+
+/// A settings field and the values it takes across the sweep
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepAxis {
+    pub field: String,
+    pub values: Vec<Value>,
+}
+
+impl SweepAxis {
+    /// Parse `field=start:end:count` for evenly spaced numbers, or
+    /// `field=a,b,c` for a list of values
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (field, range) = spec.split_once('=').ok_or_else(|| {
+            format!(
+                "Expected field=start:end:count or field=a,b,c, got '{}'",
+                spec
+            )
+        })?;
+        let field = field.trim();
+        if field.is_empty() {
+            return Err(format!("Missing field name in '{}'", spec));
+        }
+
+        let values = if range.contains(':') {
+            let parts = range.split(':').map(str::trim).collect::<Vec<_>>();
+            let [start, end, count] = parts[..] else {
+                return Err(format!("Expected start:end:count, got '{}'", range));
+            };
+            let number = |part: &str| {
+                part.parse::<f64>()
+                    .ok()
+                    .filter(|value| value.is_finite())
+                    .ok_or_else(|| format!("Invalid number '{}' in '{}'", part, spec))
+            };
+            let (start, end) = (number(start)?, number(end)?);
+            let count = count
+                .parse::<usize>()
+                .ok()
+                .filter(|count| *count > 0)
+                .ok_or_else(|| format!("Invalid value count '{}' in '{}'", count, spec))?;
+
+            (0..count)
+                .map(|index| {
+                    let t = if count == 1 {
+                        0.0
+                    } else {
+                        index as f64 / (count - 1) as f64
+                    };
+                    Value::from(round_significant(start + (end - start) * t))
+                })
+                .collect()
+        } else {
+            range
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| {
+                    serde_json::from_str(item).unwrap_or_else(|_| Value::String(item.to_string()))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        if values.is_empty() {
+            return Err(format!("No values given in '{}'", spec));
+        }
+        Ok(Self {
+            field: field.to_string(),
+            values,
+        })
+    }
+
+    /// Convert the values to the type of the field in `settings`, so that
+    /// numbers swept over an integer field stay integers
+    fn resolve(&self, settings: &Value) -> AppResult<Vec<Value>> {
+        let Some(current) = settings.get(&self.field) else {
+            let mut fields = settings
+                .as_object()
+                .map(|object| object.keys().cloned().collect::<Vec<_>>())
+                .unwrap_or_default();
+            fields.sort();
+            return Err(SimulationError::InvalidSetting {
+                setting_name: self.field.clone(),
+                message: format!("Unknown setting, expected one of: {}", fields.join(", ")),
+            }
+            .into());
+        };
+
+        Ok(self
+            .values
+            .iter()
+            .map(|value| match value.as_f64() {
+                Some(number) if current.is_u64() && number >= 0.0 => {
+                    Value::from(number.round() as u64)
+                }
+                Some(number) if current.is_i64() => Value::from(number.round() as i64),
+                _ => value.clone(),
+            })
+            .collect())
+    }
+}
+
+/// Round to seven significant digits, which hides floating point noise such
+/// as `0.030000000000000002` in labels and preset files
+fn round_significant(value: f64) -> f64 {
+    format!("{:.6e}", value).parse().unwrap_or(value)
+}
+
+fn label(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// What to sweep and how long to run each cell
+#[derive(Debug, Clone)]
+pub struct SweepOptions {
+    /// Field swept across the columns of the contact sheet
+    pub x: SweepAxis,
+    /// Field swept down the rows, if any
+    pub y: Option<SweepAxis>,
+    /// Number of frames each cell is stepped before it is captured
+    pub steps: u32,
+    /// Fixed simulation time step, in seconds
+    pub delta_time: f32,
+    /// Seed for the runtime state every cell starts from
+    pub seed: u64,
+    /// Directory that receives the contact sheet and the cell presets
+    pub out_dir: PathBuf,
+}
+
+/// Files written by a finished sweep
+#[derive(Debug, Clone)]
+pub struct SweepSummary {
+    pub contact_sheet: PathBuf,
+    pub presets: Vec<PathBuf>,
+}
+
+/// Render every combination of the swept values on `simulation`, starting
+/// each cell from the simulation's current settings
+pub fn run_sweep(
+    renderer: &HeadlessRenderer,
+    simulation: &mut SimulationType,
+    options: &SweepOptions,
+) -> AppResult<SweepSummary> {
+    if options.steps == 0 {
+        return Err(SimulationError::InvalidParameter(
+            "Sweep needs at least one step per cell".to_string(),
+        )
+        .into());
+    }
+    if options
+        .y
+        .as_ref()
+        .is_some_and(|y| y.field == options.x.field)
+    {
+        return Err(SimulationError::InvalidParameter(format!(
+            "Cannot sweep '{}' on both axes",
+            options.x.field
+        ))
+        .into());
+    }
+
+    let simulation_type = simulation.type_name();
+    let base_settings = simulation.get_settings();
+    let x_values = options.x.resolve(&base_settings)?;
+    let y_values = match &options.y {
+        Some(y) => y.resolve(&base_settings)?,
+        None => vec![Value::Null],
+    };
+
+    let title = match &options.y {
+        Some(y) => format!("{} {} x {}", simulation_type, options.x.field, y.field),
+        None => format!("{} {}", simulation_type, options.x.field),
+    };
+    let column_labels = x_values.iter().map(label).collect::<Vec<_>>();
+    let row_labels = match options.y {
+        Some(_) => y_values.iter().map(label).collect::<Vec<_>>(),
+        None => Vec::new(),
+    };
+    let mut sheet = ContactSheet::new(
+        renderer.target.width,
+        renderer.target.height,
+        &title,
+        &column_labels,
+        &row_labels,
+    );
+
+    let presets_dir = options.out_dir.join("presets");
+    std::fs::create_dir_all(&presets_dir)?;
+
+    let started_at = Instant::now();
+    let mut presets = Vec::new();
+    for (row, y_value) in y_values.iter().enumerate() {
+        for (column, x_value) in x_values.iter().enumerate() {
+            let mut settings = base_settings.clone();
+            let mut name = format!("Sweep {}={}", options.x.field, label(x_value));
+            settings[&options.x.field] = x_value.clone();
+            if let Some(y) = &options.y {
+                name.push_str(&format!(" {}={}", y.field, label(y_value)));
+                settings[&y.field] = y_value.clone();
+            }
+
+            let pixels = render_cell(renderer, simulation, settings, options)?;
+            sheet.place(column as u32, row as u32, &pixels);
+
+            let preset = renderer
+                .preset_manager
+                .preset_to_toml(simulation_type, &name, &simulation.get_settings())
+                .map_err(AppError::Preset)?;
+            let path = presets_dir.join(format!("cell_r{:02}_c{:02}.toml", row, column));
+            std::fs::write(&path, preset)?;
+            presets.push(path);
+
+            tracing::info!(
+                "Rendered sweep cell {}/{}: {}",
+                presets.len(),
+                x_values.len() * y_values.len(),
+                name
+            );
+        }
+    }
+
+    let contact_sheet = options.out_dir.join("contact_sheet.png");
+    sheet.save(&contact_sheet)?;
+    tracing::info!(
+        "Finished {} cell sweep in {:.1}s",
+        presets.len(),
+        started_at.elapsed().as_secs_f32()
+    );
+
+    Ok(SweepSummary {
+        contact_sheet,
+        presets,
+    })
+}
+
+/// Apply a cell's settings, reset the simulation from the sweep's seed and
+/// return the frame it reaches after the sweep's number of steps
+fn render_cell(
+    renderer: &HeadlessRenderer,
+    simulation: &mut SimulationType,
+    settings: Value,
+    options: &SweepOptions,
+) -> AppResult<Vec<u8>> {
+    simulation.apply_settings(settings, &renderer.device, &renderer.queue)?;
+    let mut rng = StdRng::seed_from_u64(options.seed);
+    random::scoped(&mut rng, || -> AppResult<()> {
+        simulation.reset_runtime_state(&renderer.device, &renderer.queue)?;
+        renderer.render_frames(simulation, options.steps, options.delta_time, |_, _| Ok(()))
+    })?;
+    renderer.read_pixels()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::RenderSettings;
+    use crate::simulation::preset_manager::Preset;
+    use wgpu::TextureFormat;
+
+    #[test]
+    fn test_parses_ranges_and_lists() {
+        let axis = SweepAxis::parse("feed_rate=0.01:0.05:5").unwrap();
+        assert_eq!(axis.field, "feed_rate");
+        assert_eq!(
+            axis.values,
+            vec![
+                Value::from(0.01),
+                Value::from(0.02),
+                Value::from(0.03),
+                Value::from(0.04),
+                Value::from(0.05)
+            ]
+        );
+
+        let axis = SweepAxis::parse("nutrient_pattern=Uniform, Checkerboard").unwrap();
+        assert_eq!(
+            axis.values,
+            vec![Value::from("Uniform"), Value::from("Checkerboard")]
+        );
+        assert_eq!(
+            SweepAxis::parse("enabled=true,false").unwrap().values,
+            vec![Value::from(true), Value::from(false)]
+        );
+
+        assert!(SweepAxis::parse("feed_rate").is_err());
+        assert!(SweepAxis::parse("feed_rate=0:1").is_err());
+        assert!(SweepAxis::parse("feed_rate=0:1:0").is_err());
+        assert!(SweepAxis::parse("=1,2").is_err());
+    }
+
+    #[test]
+    fn test_resolves_values_to_field_types() {
+        let settings = serde_json::json!({ "count": 10, "rate": 0.5 });
+        let axis = SweepAxis::parse("count=0:10:4").unwrap();
+        assert_eq!(
+            axis.resolve(&settings).unwrap(),
+            vec![
+                Value::from(0u64),
+                Value::from(3u64),
+                Value::from(7u64),
+                Value::from(10u64)
+            ]
+        );
+        assert!(
+            SweepAxis::parse("missing=1,2")
+                .unwrap()
+                .resolve(&settings)
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sweep_writes_contact_sheet_and_presets() {
+        let renderer = HeadlessRenderer::new(
+            32,
+            24,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderSettings::default(),
+        )
+        .await
+        .unwrap();
+        let mut simulation = renderer.create_simulation("gray_scott").await.unwrap();
+
+        let out_dir = std::env::temp_dir().join(format!("vizza-sweep-{}", std::process::id()));
+        let options = SweepOptions {
+            x: SweepAxis::parse("feed_rate=0.03:0.05:3").unwrap(),
+            y: Some(SweepAxis::parse("kill_rate=0.06,0.065").unwrap()),
+            steps: 2,
+            delta_time: 1.0 / 60.0,
+            seed: 1,
+            out_dir: out_dir.clone(),
+        };
+        let summary = run_sweep(&renderer, &mut simulation, &options).unwrap();
+
+        assert!(summary.contact_sheet.exists());
+        assert_eq!(summary.presets.len(), 6);
+
+        // The last cell's preset holds both swept values
+        let content = std::fs::read_to_string(&summary.presets[5]).unwrap();
+        let preset: Preset<crate::simulations::gray_scott::settings::Settings> =
+            toml::from_str(&content).unwrap();
+        assert_eq!(preset.name, "Sweep feed_rate=0.05 kill_rate=0.065");
+        assert!((preset.settings.feed_rate - 0.05).abs() < 1e-6);
+        assert!((preset.settings.kill_rate - 0.065).abs() < 1e-6);
+
+        std::fs::remove_dir_all(&out_dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_cells_do_not_depend_on_earlier_cells() {
+        let renderer = HeadlessRenderer::new(
+            32,
+            24,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderSettings::default(),
+        )
+        .await
+        .unwrap();
+        let mut simulation = renderer.create_simulation("gray_scott").await.unwrap();
+        let options = SweepOptions {
+            x: SweepAxis::parse("feed_rate=0.05").unwrap(),
+            y: None,
+            steps: 20,
+            delta_time: 1.0 / 60.0,
+            seed: 3,
+            out_dir: PathBuf::new(),
+        };
+        let settings = |feed_rate: f64| {
+            let mut settings = simulation.get_settings();
+            settings["feed_rate"] = Value::from(feed_rate);
+            settings
+        };
+        let (first_settings, other_settings) = (settings(0.05), settings(0.02));
+
+        // The same cell renders the same after a fresh start and after another cell
+        let first =
+            render_cell(&renderer, &mut simulation, first_settings.clone(), &options).unwrap();
+        render_cell(&renderer, &mut simulation, other_settings, &options).unwrap();
+        let again = render_cell(&renderer, &mut simulation, first_settings, &options).unwrap();
+        assert!(first == again);
+    }
+}
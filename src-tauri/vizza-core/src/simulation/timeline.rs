@@ -0,0 +1,571 @@
+//! # Keyframe Timelines
+//!
+//! A timeline scripts a simulation over time for rendered pieces. Each
+//! keyframe holds any subset of the settings fields, a LUT name and a camera
+//! pose, and the state between keyframes is interpolated:
+//!
+//! - numeric fields (and number pairs and matrices of the same shape) are
+//!   eased from one keyframe that sets them to the next, and rounded when the
+//!   schema declares them integers
+//! - everything else, such as `noise_type` or the LUT, snaps to the new value
+//!   when its keyframe is reached. So do fields that require a reset, since
+//!   changing them every frame would respawn the simulation every frame
+//! - the camera position is eased linearly and the zoom geometrically, so
+//!   zooming in feels steady
+//!
+//! Before a field's first keyframe it holds that keyframe's value, and after
+//! its last keyframe it keeps the last value. Timelines are stored as TOML:
+//!
+//! ```toml
+//! simulation_type = "gray_scott"
+//!
+//! [[keyframes]]
+//! time = 0.0
+//! lut = "MATPLOTLIB_viridis"
+//! easing = "ease_in_out"
+//! settings = { feed_rate = 0.055, kill_rate = 0.062 }
+//! camera = { position = [0.0, 0.0], zoom = 1.0 }
+//!
+//! [[keyframes]]
+//! time = 20.0
+//! settings = { feed_rate = 0.03 }
+//! camera = { position = [0.2, 0.1], zoom = 4.0 }
+//! ```
+
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use wgpu::{Device, Queue};
+
+use crate::error::{AppResult, SimulationError, SimulationResult};
+use crate::simulations::registry;
+use crate::simulations::shared::LutManager;
+use crate::simulations::shared::settings_schema::{self, SettingField, SettingKind};
+use crate::simulations::traits::{Simulation, SimulationType};
+
+/// How values move from a keyframe to the next one
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    /// Hold the keyframe's values until the next keyframe
+    Step,
+}
+
+impl Easing {
+    /// Map linear progress `t` in `0..=1` to eased progress
+    pub fn apply(self, t: f32) -> f32 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            Easing::Step => 0.0,
+        }
+    }
+}
+
+/// Camera position and zoom at a keyframe
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CameraPose {
+    pub position: [f32; 2],
+    pub zoom: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Keyframe {
+    /// Seconds from the start of the timeline
+    pub time: f32,
+    /// Easing used on the way from this keyframe to the next
+    #[serde(default)]
+    pub easing: Easing,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lut: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub camera: Option<CameraPose>,
+    /// Settings fields set at this keyframe, keyed as in presets
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub settings: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Timeline {
+    pub simulation_type: String,
+    pub keyframes: Vec<Keyframe>,
+}
+
+/// Interpolated state of a timeline at one point in time
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TimelineFrame {
+    pub settings: Map<String, Value>,
+    pub lut: Option<String>,
+    pub camera: Option<CameraPose>,
+}
+
+impl Timeline {
+    pub fn load(path: &Path) -> AppResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let mut timeline: Self = toml::from_str(&content).map_err(|e| {
+            SimulationError::InvalidParameter(format!("Invalid timeline {}: {}", path.display(), e))
+        })?;
+        timeline.validate()?;
+        Ok(timeline)
+    }
+
+    pub fn save(&self, path: &Path) -> AppResult<()> {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = toml::to_string_pretty(self)
+            .map_err(|e| SimulationError::InvalidParameter(e.to_string()))?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Check the keyframes against the simulation's schema and put them in
+    /// time order
+    pub fn validate(&mut self) -> SimulationResult<()> {
+        let descriptor = registry::get(&self.simulation_type)?;
+        if self.keyframes.is_empty() {
+            return Err(SimulationError::InvalidParameter(
+                "Timeline has no keyframes".to_string(),
+            ));
+        }
+
+        for keyframe in &self.keyframes {
+            if !(keyframe.time.is_finite() && keyframe.time >= 0.0) {
+                return Err(SimulationError::InvalidParameter(format!(
+                    "Keyframe time must be a non-negative number, got {}",
+                    keyframe.time
+                )));
+            }
+            if let Some(camera) = &keyframe.camera
+                && !(camera.zoom.is_finite() && camera.zoom > 0.0)
+            {
+                return Err(SimulationError::InvalidParameter(format!(
+                    "Keyframe at {}s has invalid zoom {}",
+                    keyframe.time, camera.zoom
+                )));
+            }
+            if let Some(schema) = descriptor.settings_schema {
+                settings_schema::validate_settings(
+                    &schema(),
+                    &Value::Object(keyframe.settings.clone()),
+                )?;
+            }
+        }
+
+        self.keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Ok(())
+    }
+
+    /// Time of the last keyframe
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |keyframe| keyframe.time)
+    }
+
+    /// Interpolated state `time` seconds into the timeline
+    pub fn sample(&self, time: f32) -> TimelineFrame {
+        let schema = registry::settings_schema(&self.simulation_type).unwrap_or_default();
+        self.sample_with_schema(time, &schema)
+    }
+
+    /// Interpolated state `time` seconds into the timeline, with the settings
+    /// fields described by `schema`
+    fn sample_with_schema(&self, time: f32, schema: &[SettingField]) -> TimelineFrame {
+        let mut frame = TimelineFrame {
+            lut: self.snap(time, |keyframe| keyframe.lut.clone()),
+            camera: self.interpolate(
+                time,
+                |keyframe| keyframe.camera,
+                |from, to, t| {
+                    let zoom = from.zoom * (to.zoom / from.zoom).powf(t);
+                    CameraPose {
+                        position: [
+                            from.position[0] + (to.position[0] - from.position[0]) * t,
+                            from.position[1] + (to.position[1] - from.position[1]) * t,
+                        ],
+                        zoom,
+                    }
+                },
+            ),
+            ..Default::default()
+        };
+
+        let mut names = Vec::new();
+        for keyframe in &self.keyframes {
+            for name in keyframe.settings.keys() {
+                if !names.contains(name) {
+                    names.push(name.clone());
+                }
+            }
+        }
+        for name in names {
+            let field = schema.iter().find(|field| field.name == name);
+            let value = self.interpolate(
+                time,
+                |keyframe| keyframe.settings.get(&name).cloned(),
+                |from, to, t| lerp_setting(field, &from, &to, t).unwrap_or(from),
+            );
+            if let Some(value) = value {
+                frame.settings.insert(name, value);
+            }
+        }
+        frame
+    }
+
+    /// Value of the last keyframe at or before `time` that has one, or of the
+    /// first keyframe that has one
+    fn snap<T>(&self, time: f32, get: impl Fn(&Keyframe) -> Option<T>) -> Option<T> {
+        self.keyframes
+            .iter()
+            .rev()
+            .filter(|keyframe| keyframe.time <= time)
+            .find_map(&get)
+            .or_else(|| self.keyframes.iter().find_map(get))
+    }
+
+    /// Ease between the keyframes around `time` that have a value
+    fn interpolate<T: Clone>(
+        &self,
+        time: f32,
+        get: impl Fn(&Keyframe) -> Option<T>,
+        lerp: impl Fn(T, T, f32) -> T,
+    ) -> Option<T> {
+        let before = self
+            .keyframes
+            .iter()
+            .rev()
+            .filter(|keyframe| keyframe.time <= time)
+            .find_map(|keyframe| get(keyframe).map(|value| (keyframe, value)));
+        let after = self
+            .keyframes
+            .iter()
+            .filter(|keyframe| keyframe.time > time)
+            .find_map(|keyframe| get(keyframe).map(|value| (keyframe, value)));
+
+        match (before, after) {
+            (Some((from_keyframe, from)), Some((to_keyframe, to))) => {
+                let span = to_keyframe.time - from_keyframe.time;
+                let t = from_keyframe
+                    .easing
+                    .apply(((time - from_keyframe.time) / span).clamp(0.0, 1.0));
+                Some(lerp(from, to, t))
+            }
+            (Some((_, value)), None) | (None, Some((_, value))) => Some(value),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Interpolate a settings value as its schema field describes it. Fields
+/// missing from the schema are treated as floats. Returns `None` for values
+/// that can only snap.
+fn lerp_setting(field: Option<&SettingField>, from: &Value, to: &Value, t: f32) -> Option<Value> {
+    let Some(field) = field else {
+        return lerp_value(from, to, t, false);
+    };
+    if field.requires_reset {
+        return None;
+    }
+    match field.kind {
+        SettingKind::Integer { .. } => lerp_value(from, to, t, true),
+        SettingKind::Float { .. } | SettingKind::FloatRange { .. } | SettingKind::Matrix { .. } => {
+            lerp_value(from, to, t, false)
+        }
+        SettingKind::Bool | SettingKind::Enum { .. } => None,
+    }
+}
+
+/// Interpolate numbers and equally shaped arrays and objects of numbers,
+/// rounding every number when `integer` is set. Returns `None` for values
+/// that can only snap.
+fn lerp_value(from: &Value, to: &Value, t: f32, integer: bool) -> Option<Value> {
+    match (from, to) {
+        (Value::Number(a), Value::Number(b)) => {
+            let (a, b) = (a.as_f64()?, b.as_f64()?);
+            let value = a + (b - a) * t as f64;
+            Some(match integer {
+                true => Value::from(value.round() as i64),
+                false => Value::from(value),
+            })
+        }
+        (Value::Array(a), Value::Array(b)) if a.len() == b.len() => a
+            .iter()
+            .zip(b)
+            .map(|(a, b)| lerp_value(a, b, t, integer))
+            .collect::<Option<Vec<_>>>()
+            .map(Value::Array),
+        (Value::Object(a), Value::Object(b)) if a.len() == b.len() => a
+            .iter()
+            .map(|(key, a)| Some((key.clone(), lerp_value(a, b.get(key)?, t, integer)?)))
+            .collect::<Option<Map<_, _>>>()
+            .map(Value::Object),
+        _ => None,
+    }
+}
+
+/// Plays a timeline on a simulation, one frame at a time. Only values that
+/// changed since the previous frame are sent to the simulation, so discrete
+/// settings are applied once when their keyframe is reached.
+#[derive(Debug, Clone)]
+pub struct TimelinePlayer {
+    timeline: Timeline,
+    schema: Vec<SettingField>,
+    time: f32,
+    applied: TimelineFrame,
+}
+
+impl TimelinePlayer {
+    pub fn new(mut timeline: Timeline) -> SimulationResult<Self> {
+        timeline.validate()?;
+        let schema = registry::settings_schema(&timeline.simulation_type).unwrap_or_default();
+        Ok(Self {
+            timeline,
+            schema,
+            time: 0.0,
+            applied: TimelineFrame::default(),
+        })
+    }
+
+    pub fn timeline(&self) -> &Timeline {
+        &self.timeline
+    }
+
+    /// Seconds played so far
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.time > self.timeline.duration()
+    }
+
+    /// Apply the timeline's state at the current time, then advance the
+    /// clock by `delta_time`
+    pub fn step(
+        &mut self,
+        delta_time: f32,
+        simulation: &mut SimulationType,
+        lut_manager: &LutManager,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()> {
+        if simulation.type_name() != self.timeline.simulation_type {
+            return Err(SimulationError::InvalidParameter(format!(
+                "Timeline is for {}, but {} is running",
+                self.timeline.simulation_type,
+                simulation.type_name()
+            )));
+        }
+
+        let frame = self.timeline.sample_with_schema(self.time, &self.schema);
+        for (name, value) in &frame.settings {
+            if self.applied.settings.get(name) == Some(value) {
+                continue;
+            }
+            // Timelines are keyed like presets; update_setting may expect
+            // a different spelling
+            let setting_name = self
+                .schema
+                .iter()
+                .find(|field| field.name == name)
+                .map_or(name.as_str(), |field| field.setting_name);
+            simulation.update_setting(setting_name, value.clone(), device, queue)?;
+        }
+        if let Some(lut) = &frame.lut
+            && self.applied.lut.as_ref() != Some(lut)
+        {
+            simulation.apply_lut(lut_manager, lut, device, queue)?;
+        }
+        if let Some(pose) = frame.camera
+            && self.applied.camera != Some(pose)
+            && let Some(camera) = simulation.camera_mut()
+        {
+            camera.set_view(pose.position, pose.zoom);
+        }
+
+        self.applied = frame;
+        self.time += delta_time;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn keyframe(time: f32, settings: Value) -> Keyframe {
+        Keyframe {
+            time,
+            easing: Easing::Linear,
+            lut: None,
+            camera: None,
+            settings: settings.as_object().unwrap().clone(),
+        }
+    }
+
+    fn flow_timeline() -> Timeline {
+        let mut start = keyframe(
+            0.0,
+            json!({ "noise_scale": 1.0, "noise_type": "OpenSimplex", "particle_size": 2 }),
+        );
+        start.lut = Some("MATPLOTLIB_viridis".to_string());
+        start.camera = Some(CameraPose {
+            position: [0.0, 0.0],
+            zoom: 1.0,
+        });
+        let mut end = keyframe(
+            10.0,
+            json!({ "noise_scale": 3.0, "noise_type": "Worley", "particle_size": 5 }),
+        );
+        end.lut = Some("MATPLOTLIB_magma".to_string());
+        end.camera = Some(CameraPose {
+            position: [1.0, -1.0],
+            zoom: 4.0,
+        });
+        Timeline {
+            simulation_type: "flow".to_string(),
+            keyframes: vec![end, start],
+        }
+    }
+
+    #[test]
+    fn test_interpolates_numbers_and_snaps_the_rest() {
+        let mut timeline = flow_timeline();
+        timeline.validate().unwrap();
+        assert_eq!(timeline.keyframes[0].time, 0.0);
+
+        let frame = timeline.sample(5.0);
+        assert_eq!(frame.settings["noise_scale"], json!(2.0));
+        assert_eq!(frame.settings["particle_size"], json!(4));
+        assert_eq!(frame.settings["noise_type"], json!("OpenSimplex"));
+        assert_eq!(frame.lut.as_deref(), Some("MATPLOTLIB_viridis"));
+        let camera = frame.camera.unwrap();
+        assert_eq!(camera.position, [0.5, -0.5]);
+        assert!((camera.zoom - 2.0).abs() < 1e-6);
+
+        let frame = timeline.sample(10.0);
+        assert_eq!(frame.settings["noise_type"], json!("Worley"));
+        assert_eq!(frame.lut.as_deref(), Some("MATPLOTLIB_magma"));
+
+        // Values hold outside the keyframes
+        assert_eq!(timeline.sample(20.0).settings["noise_scale"], json!(3.0));
+    }
+
+    #[test]
+    fn test_fields_interpolate_between_their_own_keyframes() {
+        let mut timeline = Timeline {
+            simulation_type: "gray_scott".to_string(),
+            keyframes: vec![
+                keyframe(0.0, json!({ "feed_rate": 0.02 })),
+                keyframe(5.0, json!({ "kill_rate": 0.06 })),
+                keyframe(10.0, json!({ "feed_rate": 0.04 })),
+            ],
+        };
+        timeline.keyframes[0].easing = Easing::Step;
+        timeline.validate().unwrap();
+
+        let frame = timeline.sample(7.5);
+        assert_eq!(frame.settings["feed_rate"], json!(0.02));
+        assert_eq!(frame.settings["kill_rate"], json!(0.06));
+        assert!(frame.camera.is_none());
+    }
+
+    #[test]
+    fn test_interpolates_by_schema_kind() {
+        let mut timeline = Timeline {
+            simulation_type: "slime_mold".to_string(),
+            keyframes: vec![
+                keyframe(
+                    0.0,
+                    json!({
+                        "agent_speed_max": 300,
+                        "agent_possible_starting_headings": [0.0, 360.0],
+                    }),
+                ),
+                keyframe(
+                    10.0,
+                    json!({
+                        "agent_speed_max": 301,
+                        "agent_possible_starting_headings": [90.0, 180.0],
+                    }),
+                ),
+            ],
+        };
+        timeline.validate().unwrap();
+
+        // A float field written with whole numbers is not rounded
+        let frame = timeline.sample(5.0);
+        assert_eq!(frame.settings["agent_speed_max"], json!(300.5));
+        // Fields that respawn the simulation snap instead
+        assert_eq!(
+            frame.settings["agent_possible_starting_headings"],
+            json!([0.0, 360.0])
+        );
+        assert_eq!(
+            timeline.sample(10.0).settings["agent_possible_starting_headings"],
+            json!([90.0, 180.0])
+        );
+    }
+
+    #[test]
+    fn test_easing_endpoints() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+        ] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-6);
+        }
+        assert!(Easing::EaseIn.apply(0.5) < 0.5);
+        assert!(Easing::EaseOut.apply(0.5) > 0.5);
+    }
+
+    #[test]
+    fn test_rejects_invalid_timelines() {
+        let mut timeline = flow_timeline();
+        timeline.keyframes[0]
+            .settings
+            .insert("typo".to_string(), json!(1));
+        assert!(timeline.validate().is_err());
+
+        let mut timeline = flow_timeline();
+        timeline.keyframes[1].time = -1.0;
+        assert!(timeline.validate().is_err());
+
+        let mut timeline = flow_timeline();
+        timeline.simulation_type = "lava_lamp".to_string();
+        assert!(timeline.validate().is_err());
+    }
+
+    #[test]
+    fn test_timeline_round_trip() {
+        let mut timeline = flow_timeline();
+        timeline.validate().unwrap();
+
+        let path = std::env::temp_dir()
+            .join(format!("vizza-timeline-{}", std::process::id()))
+            .join("timeline.toml");
+        timeline.save(&path).unwrap();
+        assert_eq!(Timeline::load(&path).unwrap(), timeline);
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+}
@@ -0,0 +1,158 @@
+//! # Ecosystem Simulation Module
+//!
+//! A GPU-accelerated artificial life simulation where agents of several species
+//! forage for food, hunt each other, reproduce and die. Populations rise and
+//! collapse as the food web plays out across a wrapping world.
+//!
+//! ## Concept
+//!
+//! Every agent carries an energy budget. Moving and simply staying alive cost
+//! energy; grazing on the regrowing food field or catching prey replenishes it.
+//! Agents with a surplus split off offspring, and agents that run dry or grow too
+//! old die, freeing their slot for the next generation.
+//!
+//! ## Key Features
+//!
+//! - **Chemotaxis**: Agents steer along food and scent gradients, chasing prey and fleeing predators
+//! - **Food Webs**: Species prey on each other in either a cycle or a strict chain
+//! - **Population Dynamics**: Births, starvation and predation are tracked per frame
+//! - **Real-time Interaction**: Paint food into the world or clear it away with the mouse
+//!
+//! ## Architecture
+//!
+//! Agents live in a fixed-capacity GPU buffer with a separate status buffer that
+//! marks each slot as dead, alive or newborn. Reproduction claims free slots with
+//! atomic exchanges, so the whole life cycle runs on the GPU without any
+//! CPU round trips beyond a periodic asynchronous read of the population
+//! counters.
+
+pub mod settings;
+pub mod shaders;
+pub mod simulation;
+pub mod state;
+
+#[cfg(test)]
+mod tests;
+
+pub use settings::{FoodChain, FoodPattern, Settings};
+pub use simulation::EcosystemModel;
+
+use crate::error::{SimulationError, SimulationResult};
+use crate::simulation::preset_manager::{AnyPresetManager, EcosystemPresetManager};
+use crate::simulations::registry::{
+    SimulationCapabilities, SimulationContext, SimulationDescriptor,
+};
+use crate::simulations::traits::SimulationType;
+
+/// Initialize default presets for the Ecosystem simulation.
+///
+/// Creates a set of predefined configurations that highlight different
+/// food webs and population dynamics.
+pub fn init_presets(preset_manager: &mut EcosystemPresetManager) {
+    let presets = vec![
+        ("Default", Settings::default()),
+        (
+            "Food Chain",
+            Settings {
+                food_chain: FoodChain::Chain,
+                hunting_strength: 2.0,
+                predation_efficiency: 0.8,
+                metabolism_rate: 0.03,
+                ..Settings::default()
+            },
+        ),
+        (
+            "Rock Paper Scissors",
+            Settings {
+                species_count: 3,
+                food_chain: FoodChain::Cycle,
+                food_growth_rate: 0.01,
+                metabolism_rate: 0.02,
+                predation_efficiency: 0.9,
+                hunting_strength: 3.0,
+                fear_strength: 0.5,
+                ..Settings::default()
+            },
+        ),
+        (
+            "Oases",
+            Settings {
+                food_pattern: FoodPattern::Patches,
+                food_growth_rate: 0.15,
+                sensor_distance: 0.06,
+                ..Settings::default()
+            },
+        ),
+        (
+            "Grazers",
+            Settings {
+                species_count: 1,
+                agent_count: 2000,
+                food_pattern: FoodPattern::Stripes,
+                wander_strength: 4.0,
+                ..Settings::default()
+            },
+        ),
+    ];
+
+    for (name, settings) in presets {
+        preset_manager.add_preset(crate::simulation::preset_manager::Preset::new(
+            name.to_string(),
+            settings,
+        ));
+    }
+
+    // Capture all the built-in preset names we just added
+    preset_manager.capture_built_in_presets();
+
+    // Load user presets from TOML files
+    if let Err(e) = preset_manager.load_user_presets() {
+        eprintln!("Warning: Could not load user presets: {}", e);
+    }
+
+    let preset_count = preset_manager.get_preset_names().len();
+    tracing::info!("Initialized {} ecosystem presets", preset_count);
+}
+
+/// Registry entry for the Ecosystem simulation
+pub const DESCRIPTOR: SimulationDescriptor = SimulationDescriptor {
+    id: "ecosystem",
+    display_name: "Ecosystem",
+    description: "Artificial life with chemotaxis",
+    hidden: false,
+    capabilities: SimulationCapabilities {
+        camera: true,
+        luts: true,
+        cursor: true,
+        deterministic: false,
+    },
+    create,
+    presets: Some(preset_manager),
+    initial_preset: None,
+    settings_schema: Some(settings::Settings::schema),
+    runtime_settings: &[
+        "currentLut",
+        "lut_reversed",
+        "cursor_size",
+        "cursor_strength",
+    ],
+};
+
+fn create(context: &SimulationContext) -> SimulationResult<SimulationType> {
+    let simulation = EcosystemModel::new(
+        context.device,
+        context.queue,
+        context.surface_config,
+        Settings::default(),
+        context.render_settings,
+        context.lut_manager,
+    )
+    .map_err(|e| SimulationError::InitializationFailed(e.to_string()))?;
+    Ok(SimulationType::Ecosystem(Box::new(simulation)))
+}
+
+fn preset_manager() -> Box<dyn AnyPresetManager> {
+    let mut preset_manager = EcosystemPresetManager::new(DESCRIPTOR.id.to_string());
+    init_presets(&mut preset_manager);
+    Box::new(preset_manager)
+}
@@ -0,0 +1,303 @@
+//! # Ecosystem Settings Module
+//!
+//! Defines the user-configurable parameters of the Ecosystem simulation: how
+//! many agents exist, how they move and sense, how energy flows between food,
+//! prey and predators, and how the environment regrows.
+//!
+//! ## Balancing
+//!
+//! The interesting regimes sit between extinction and overpopulation. Energy
+//! income (food and prey) has to roughly match the metabolic and movement costs,
+//! and the reproduction threshold decides how quickly a surplus turns into
+//! offspring. Small changes can tip a stable food web into boom and bust cycles.
+
+use crate::simulations::shared::SettingField;
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of species supported by the GPU buffers
+pub const MAX_SPECIES: u32 = 4;
+
+/// Who hunts whom, and which species graze on the food field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FoodChain {
+    /// Each species hunts the next and everyone grazes
+    Cycle,
+    /// Each species hunts the next and only the last one grazes
+    Chain,
+}
+
+/// Spatial layout of food regrowth, in the order the shader expects
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FoodPattern {
+    Uniform,
+    Patches,
+    Stripes,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Settings {
+    /// Number of agents spawned when the simulation is reset
+    pub agent_count: u32,
+
+    /// Capacity of the agent buffer; reproduction stops when every slot is taken
+    pub max_agents: u32,
+
+    /// Number of species (1 to 4)
+    pub species_count: u32,
+
+    /// Food web shared by the species
+    pub food_chain: FoodChain,
+
+    /// Rendered radius of each agent in world units
+    pub agent_size: f32,
+
+    // Movement parameters
+    /// Distance travelled per second in world units
+    pub agent_speed: f32,
+
+    /// Maximum turning speed in radians per second
+    pub turn_rate: f32,
+
+    /// Angle between the forward sensor and the side sensors in radians
+    pub sensor_angle: f32,
+
+    /// Distance of the sensors ahead of the agent
+    pub sensor_distance: f32,
+
+    /// Strength of the random heading jitter
+    pub wander_strength: f32,
+
+    /// How strongly predators follow the scent of their prey
+    pub hunting_strength: f32,
+
+    /// How strongly prey avoid the scent of their predators
+    pub fear_strength: f32,
+
+    // Energy parameters
+    /// Energy given to agents when the simulation is reset
+    pub initial_energy: f32,
+
+    /// Energy spent per second just to stay alive
+    pub metabolism_rate: f32,
+
+    /// Energy spent per world unit travelled
+    pub movement_cost: f32,
+
+    /// Food consumed per second while grazing
+    pub feeding_rate: f32,
+
+    /// Energy gained per unit of food eaten
+    pub food_energy: f32,
+
+    /// Distance within which a predator catches its prey
+    pub predation_radius: f32,
+
+    /// Fraction of the prey's energy transferred to the predator
+    pub predation_efficiency: f32,
+
+    /// Energy required before an agent reproduces
+    pub reproduction_threshold: f32,
+
+    /// Fraction of the parent's energy handed to the offspring
+    pub reproduction_share: f32,
+
+    /// Lifespan in seconds before an agent dies of old age
+    pub max_age: f32,
+
+    // Environment parameters
+    /// Food regrown per cell per second
+    pub food_growth_rate: f32,
+
+    /// Maximum amount of food a cell can hold
+    pub food_capacity: f32,
+
+    /// Spatial layout of food regrowth
+    pub food_pattern: FoodPattern,
+
+    /// Scent left behind by each agent per second
+    pub scent_deposit: f32,
+
+    /// Rate at which scent evaporates per second
+    pub scent_decay_rate: f32,
+
+    /// Opacity of the food field in the background (0.0 = hidden)
+    pub food_visibility: f32,
+
+    /// Random seed for the food pattern layout
+    pub random_seed: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            agent_count: 3000,
+            max_agents: 20000,
+            species_count: 3,
+            food_chain: FoodChain::Chain,
+            agent_size: 0.006,
+            agent_speed: 0.15,
+            turn_rate: 6.0,
+            sensor_angle: 0.6,
+            sensor_distance: 0.04,
+            wander_strength: 2.0,
+            hunting_strength: 1.0,
+            fear_strength: 1.0,
+            initial_energy: 1.0,
+            metabolism_rate: 0.05,
+            movement_cost: 0.2,
+            feeding_rate: 0.5,
+            food_energy: 1.0,
+            predation_radius: 0.012,
+            predation_efficiency: 0.6,
+            reproduction_threshold: 2.0,
+            reproduction_share: 0.5,
+            max_age: 60.0,
+            food_growth_rate: 0.05,
+            food_capacity: 1.0,
+            food_pattern: FoodPattern::Uniform,
+            scent_deposit: 1.0,
+            scent_decay_rate: 1.5,
+            food_visibility: 0.5,
+            random_seed: 0,
+        }
+    }
+}
+
+impl Settings {
+    /// Randomize behavioral settings within reasonable bounds
+    pub fn randomize(&mut self) {
+        use rand::Rng;
+        let mut rng = crate::simulations::shared::random::rng();
+
+        self.species_count = rng.random_range(1..=MAX_SPECIES);
+        self.food_chain = if rng.random_bool(0.5) {
+            FoodChain::Cycle
+        } else {
+            FoodChain::Chain
+        };
+
+        self.agent_speed = rng.random_range(0.05..0.3);
+        self.turn_rate = rng.random_range(2.0..10.0);
+        self.sensor_angle = rng.random_range(0.2..1.2);
+        self.sensor_distance = rng.random_range(0.01..0.08);
+        self.wander_strength = rng.random_range(0.0..5.0);
+        self.hunting_strength = rng.random_range(0.0..3.0);
+        self.fear_strength = rng.random_range(0.0..3.0);
+
+        self.metabolism_rate = rng.random_range(0.01..0.1);
+        self.movement_cost = rng.random_range(0.05..0.5);
+        self.feeding_rate = rng.random_range(0.2..1.0);
+        self.predation_efficiency = rng.random_range(0.3..0.95);
+        self.reproduction_threshold = rng.random_range(1.5..3.0);
+        self.reproduction_share = rng.random_range(0.3..0.6);
+        self.max_age = rng.random_range(20.0..120.0);
+
+        self.food_growth_rate = rng.random_range(0.01..0.2);
+        self.food_pattern = match rng.random_range(0..3) {
+            0 => FoodPattern::Uniform,
+            1 => FoodPattern::Patches,
+            _ => FoodPattern::Stripes,
+        };
+        self.random_seed = rng.random();
+    }
+    /// Fields of these settings with the ranges they are balanced for
+    pub fn schema() -> Vec<SettingField> {
+        use std::f64::consts::PI;
+
+        vec![
+            SettingField::integer("agent_count", 0, 100_000)
+                .step(100)
+                .group("Population")
+                .description("Agents spawned on reset")
+                .requires_reset(),
+            SettingField::integer("max_agents", 1, 200_000)
+                .step(1000)
+                .group("Population")
+                .description("Agent capacity; reproduction stops when it is reached")
+                .requires_reset(),
+            SettingField::integer("species_count", 1, MAX_SPECIES as i64)
+                .group("Population")
+                .description("Number of species in the food web")
+                .requires_reset(),
+            SettingField::enumeration("food_chain", &["Cycle", "Chain"])
+                .group("Population")
+                .description("Who hunts whom, and which species graze"),
+            SettingField::float("agent_size", 0.001, 0.05, 0.001)
+                .group("Population")
+                .description("Rendered agent radius in world units"),
+            SettingField::float("agent_speed", 0.0, 1.0, 0.01)
+                .group("Movement")
+                .description("Distance travelled per second"),
+            SettingField::float("turn_rate", 0.0, 20.0, 0.1)
+                .group("Movement")
+                .description("Turning speed in radians per second"),
+            SettingField::float("sensor_angle", 0.0, PI, 0.01)
+                .group("Movement")
+                .description("Angle in radians between the forward and side sensors"),
+            SettingField::float("sensor_distance", 0.0, 0.2, 0.005)
+                .group("Movement")
+                .description("How far ahead agents sense food and scent"),
+            SettingField::float("wander_strength", 0.0, 10.0, 0.1)
+                .group("Movement")
+                .description("Random heading jitter"),
+            SettingField::float("hunting_strength", 0.0, 5.0, 0.1)
+                .group("Movement")
+                .description("How strongly predators follow prey scent"),
+            SettingField::float("fear_strength", 0.0, 5.0, 0.1)
+                .group("Movement")
+                .description("How strongly prey avoid predator scent"),
+            SettingField::float("initial_energy", 0.0, 5.0, 0.1)
+                .group("Energy")
+                .description("Energy agents are spawned with")
+                .requires_reset(),
+            SettingField::float("metabolism_rate", 0.0, 0.5, 0.005)
+                .group("Energy")
+                .description("Energy spent per second to stay alive"),
+            SettingField::float("movement_cost", 0.0, 2.0, 0.01)
+                .group("Energy")
+                .description("Energy spent per unit travelled"),
+            SettingField::float("feeding_rate", 0.0, 2.0, 0.01)
+                .group("Energy")
+                .description("Food eaten per second while grazing"),
+            SettingField::float("food_energy", 0.0, 5.0, 0.1)
+                .group("Energy")
+                .description("Energy gained per unit of food"),
+            SettingField::float("predation_radius", 0.0, 0.05, 0.001)
+                .group("Energy")
+                .description("Distance within which predators catch prey"),
+            SettingField::float("predation_efficiency", 0.0, 1.0, 0.01)
+                .group("Energy")
+                .description("Fraction of the prey's energy the predator gains"),
+            SettingField::float("reproduction_threshold", 0.1, 10.0, 0.1)
+                .group("Energy")
+                .description("Energy needed before an agent reproduces"),
+            SettingField::float("reproduction_share", 0.0, 1.0, 0.01)
+                .group("Energy")
+                .description("Fraction of the parent's energy given to offspring"),
+            SettingField::float("max_age", 1.0, 600.0, 1.0)
+                .group("Energy")
+                .description("Lifespan in seconds"),
+            SettingField::float("food_growth_rate", 0.0, 1.0, 0.005)
+                .group("Environment")
+                .description("Food regrown per cell per second"),
+            SettingField::float("food_capacity", 0.1, 5.0, 0.1)
+                .group("Environment")
+                .description("Most food a cell can hold"),
+            SettingField::enumeration("food_pattern", &["Uniform", "Patches", "Stripes"])
+                .group("Environment")
+                .description("Layout of food regrowth"),
+            SettingField::float("scent_deposit", 0.0, 5.0, 0.1)
+                .group("Environment")
+                .description("Scent left by each agent per second"),
+            SettingField::float("scent_decay_rate", 0.0, 10.0, 0.1)
+                .group("Environment")
+                .description("Rate at which scent evaporates"),
+            SettingField::float("food_visibility", 0.0, 1.0, 0.01)
+                .group("Environment")
+                .description("Opacity of the food field in the background"),
+            SettingField::seed("random_seed")
+                .group("Environment")
+                .description("Seed for the food pattern layout"),
+        ]
+    }
+}
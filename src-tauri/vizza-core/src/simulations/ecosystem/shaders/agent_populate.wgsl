@@ -0,0 +1,102 @@
+// Agent populate: activates newborn agents, records one occupant per species
+// in each occupancy cell for predation lookups, counts the living population
+// and lets every agent leave its scent on the environment grid.
+
+struct Agent {
+    position: vec2<f32>,
+    heading: f32,
+    energy: f32,
+    age: f32,
+    species: u32,
+    rng_state: u32,
+    _pad: u32,
+}
+
+struct SimParams {
+    prey_mask: vec4<u32>,   // bit t of prey_mask[s] set => species s hunts species t
+    eats_food: vec4<u32>,   // non-zero => species grazes on the food field
+    mouse_position: vec2<f32>,
+    mouse_pressed: u32,
+    mouse_mode: u32,        // 0 = none, 1 = add food, 2 = remove food
+    cursor_size: f32,
+    cursor_strength: f32,
+    max_agents: u32,
+    species_count: u32,
+    food_grid_size: u32,
+    occupancy_grid_size: u32,
+    dt: f32,
+    time: f32,
+    frame_index: u32,
+    random_seed: u32,
+    agent_speed: f32,
+    turn_rate: f32,
+    sensor_angle: f32,
+    sensor_distance: f32,
+    wander_strength: f32,
+    hunting_strength: f32,
+    fear_strength: f32,
+    metabolism_rate: f32,
+    movement_cost: f32,
+    feeding_rate: f32,
+    food_energy: f32,
+    predation_radius: f32,
+    predation_efficiency: f32,
+    reproduction_threshold: f32,
+    reproduction_share: f32,
+    max_age: f32,
+    food_growth_rate: f32,
+    food_capacity: f32,
+    food_pattern: u32,      // 0 = uniform, 1 = patches, 2 = stripes
+    scent_deposit: f32,
+    scent_decay_rate: f32,
+    _pad: u32,
+}
+
+const MAX_SPECIES: u32 = 4u;
+const SCENT_SCALE: f32 = 1000.0;
+
+// Agent slot status values
+const STATUS_DEAD: u32 = 0u;
+const STATUS_ALIVE: u32 = 1u;
+const STATUS_NEWBORN: u32 = 2u;
+
+@group(0) @binding(0) var<storage, read_write> agents: array<Agent>;
+@group(0) @binding(1) var<storage, read_write> status: array<atomic<u32>>;
+@group(0) @binding(3) var<storage, read_write> scent: array<atomic<u32>>;
+@group(0) @binding(4) var<storage, read_write> occupancy: array<atomic<u32>>;
+@group(0) @binding(5) var<storage, read_write> stats: array<atomic<u32>>;
+@group(0) @binding(6) var<uniform> params: SimParams;
+
+fn cell_index(pos: vec2<f32>, size: u32) -> u32 {
+    let uv = clamp((pos + 1.0) * 0.5, vec2<f32>(0.0), vec2<f32>(0.99999));
+    let cell = vec2<u32>(uv * f32(size));
+    return cell.y * size + cell.x;
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let index = global_id.x;
+    if (index >= params.max_agents) {
+        return;
+    }
+
+    let current = atomicLoad(&status[index]);
+    if (current == STATUS_NEWBORN) {
+        atomicStore(&status[index], STATUS_ALIVE);
+    } else if (current != STATUS_ALIVE) {
+        return;
+    }
+
+    let agent = agents[index];
+    let species = min(agent.species, MAX_SPECIES - 1u);
+
+    // Last writer wins; one candidate per cell is enough for predators to find
+    let occupancy_cell = cell_index(agent.position, params.occupancy_grid_size);
+    atomicStore(&occupancy[occupancy_cell * MAX_SPECIES + species], index + 1u);
+
+    atomicAdd(&stats[species], 1u);
+
+    let food_cell = cell_index(agent.position, params.food_grid_size);
+    let deposit = u32(params.scent_deposit * params.dt * SCENT_SCALE);
+    atomicAdd(&scent[food_cell * MAX_SPECIES + species], deposit);
+}
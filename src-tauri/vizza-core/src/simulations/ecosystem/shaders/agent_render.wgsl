@@ -0,0 +1,136 @@
+// Agent render: draws every living agent as a circle colored by species.
+// Each agent is drawn 9 times (center + 8 wrapped copies) so agents crossing
+// the world edge stay visible on both sides.
+
+struct Agent {
+    position: vec2<f32>,
+    heading: f32,
+    energy: f32,
+    age: f32,
+    species: u32,
+    rng_state: u32,
+    _pad: u32,
+}
+
+struct RenderParams {
+    agent_size: f32,
+    aspect_ratio: f32,
+    species_count: u32,
+    food_grid_size: u32,
+    food_capacity: f32,
+    food_visibility: f32,
+    energy_reference: f32,
+    _pad: u32,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec3<f32>,
+}
+
+const STATUS_ALIVE: u32 = 1u;
+
+@group(0) @binding(0) var<storage, read> agents: array<Agent>;
+@group(0) @binding(1) var<storage, read> status: array<u32>;
+@group(0) @binding(3) var<storage, read> lut: array<u32>;
+@group(0) @binding(4) var<uniform> params: RenderParams;
+
+// Convert from sRGB (gamma-corrected) to linear RGB
+fn srgb_to_linear(srgb: f32) -> f32 {
+    if (srgb <= 0.04045) {
+        return srgb / 12.92;
+    } else {
+        return pow((srgb + 0.055) / 1.055, 2.4);
+    }
+}
+
+fn get_lut_color(index: u32) -> vec3<f32> {
+    let r_srgb = f32(lut[index]) / 255.0;
+    let g_srgb = f32(lut[index + 256u]) / 255.0;
+    let b_srgb = f32(lut[index + 512u]) / 255.0;
+    return vec3<f32>(
+        srgb_to_linear(r_srgb),
+        srgb_to_linear(g_srgb),
+        srgb_to_linear(b_srgb)
+    );
+}
+
+// Species are spread evenly over the upper part of the LUT, leaving
+// index 0 for the background
+fn species_color(species: u32) -> vec3<f32> {
+    let count = max(params.species_count, 1u);
+    let index = (species + 1u) * 255u / count;
+    return get_lut_color(min(index, 255u));
+}
+
+fn wrap_offset(wrap_instance: u32) -> vec2<f32> {
+    let offsets = array<vec2<f32>, 9>(
+        vec2<f32>( 0.0,  0.0),
+        vec2<f32>(-2.0, -2.0),
+        vec2<f32>( 0.0, -2.0),
+        vec2<f32>( 2.0, -2.0),
+        vec2<f32>(-2.0,  0.0),
+        vec2<f32>( 2.0,  0.0),
+        vec2<f32>(-2.0,  2.0),
+        vec2<f32>( 0.0,  2.0),
+        vec2<f32>( 2.0,  2.0),
+    );
+    return offsets[wrap_instance];
+}
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) vertex_index: u32,
+    @builtin(instance_index) instance_index: u32,
+) -> VertexOutput {
+    let agent_index = instance_index / 9u;
+    let wrap_instance = instance_index % 9u;
+
+    var out: VertexOutput;
+
+    if (status[agent_index] != STATUS_ALIVE) {
+        // Degenerate triangle, culled by the rasterizer
+        out.position = vec4<f32>(0.0);
+        out.uv = vec2<f32>(0.0);
+        out.color = vec3<f32>(0.0);
+        return out;
+    }
+
+    let agent = agents[agent_index];
+
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>( 1.0, -1.0),
+        vec2<f32>(-1.0,  1.0),
+        vec2<f32>(-1.0,  1.0),
+        vec2<f32>( 1.0, -1.0),
+        vec2<f32>( 1.0,  1.0),
+    );
+    let corner = corners[vertex_index];
+
+    // Well-fed agents are drawn slightly larger
+    let energy_factor = clamp(agent.energy / max(params.energy_reference, 0.0001), 0.0, 1.0);
+    let radius = params.agent_size * (0.6 + 0.4 * energy_factor);
+
+    // The display texture is stretched over the world square, so squash x to keep circles round
+    let extent = vec2<f32>(radius / params.aspect_ratio, radius);
+    let center = agent.position + wrap_offset(wrap_instance);
+
+    out.position = vec4<f32>(center + corner * extent, 0.0, 1.0);
+    out.uv = corner;
+    out.color = species_color(agent.species);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let dist = length(in.uv);
+    if (dist > 1.0) {
+        discard;
+    }
+
+    // Soft edge for a little anti-aliasing
+    let alpha = 1.0 - smoothstep(0.8, 1.0, dist);
+    return vec4<f32>(in.color, alpha);
+}
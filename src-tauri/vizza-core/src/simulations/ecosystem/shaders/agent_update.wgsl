@@ -0,0 +1,304 @@
+// Agent update: sensing, movement, feeding, predation, reproduction and death.
+// Dispatched once per agent slot; dead and newborn slots are skipped.
+
+struct Agent {
+    position: vec2<f32>,
+    heading: f32,
+    energy: f32,
+    age: f32,
+    species: u32,
+    rng_state: u32,
+    _pad: u32,
+}
+
+struct SimParams {
+    prey_mask: vec4<u32>,   // bit t of prey_mask[s] set => species s hunts species t
+    eats_food: vec4<u32>,   // non-zero => species grazes on the food field
+    mouse_position: vec2<f32>,
+    mouse_pressed: u32,
+    mouse_mode: u32,        // 0 = none, 1 = add food, 2 = remove food
+    cursor_size: f32,
+    cursor_strength: f32,
+    max_agents: u32,
+    species_count: u32,
+    food_grid_size: u32,
+    occupancy_grid_size: u32,
+    dt: f32,
+    time: f32,
+    frame_index: u32,
+    random_seed: u32,
+    agent_speed: f32,
+    turn_rate: f32,
+    sensor_angle: f32,
+    sensor_distance: f32,
+    wander_strength: f32,
+    hunting_strength: f32,
+    fear_strength: f32,
+    metabolism_rate: f32,
+    movement_cost: f32,
+    feeding_rate: f32,
+    food_energy: f32,
+    predation_radius: f32,
+    predation_efficiency: f32,
+    reproduction_threshold: f32,
+    reproduction_share: f32,
+    max_age: f32,
+    food_growth_rate: f32,
+    food_capacity: f32,
+    food_pattern: u32,      // 0 = uniform, 1 = patches, 2 = stripes
+    scent_deposit: f32,
+    scent_decay_rate: f32,
+    _pad: u32,
+}
+
+const MAX_SPECIES: u32 = 4u;
+const FOOD_SCALE: f32 = 1000.0;
+const SCENT_SCALE: f32 = 1000.0;
+const TWO_PI: f32 = 6.28318530718;
+const REPRODUCTION_ATTEMPTS: u32 = 4u;
+
+// Agent slot status values
+const STATUS_DEAD: u32 = 0u;
+const STATUS_ALIVE: u32 = 1u;
+const STATUS_NEWBORN: u32 = 2u;
+
+// Indices into the stats buffer (0..MAX_SPECIES hold population counts)
+const STAT_BIRTHS: u32 = 4u;
+const STAT_STARVATION_DEATHS: u32 = 5u;
+const STAT_OLD_AGE_DEATHS: u32 = 6u;
+const STAT_PREDATION_DEATHS: u32 = 7u;
+
+@group(0) @binding(0) var<storage, read_write> agents: array<Agent>;
+@group(0) @binding(1) var<storage, read_write> status: array<atomic<u32>>;
+@group(0) @binding(2) var<storage, read_write> food: array<atomic<i32>>;
+@group(0) @binding(3) var<storage, read_write> scent: array<atomic<u32>>;
+@group(0) @binding(4) var<storage, read_write> occupancy: array<atomic<u32>>;
+@group(0) @binding(5) var<storage, read_write> stats: array<atomic<u32>>;
+@group(0) @binding(6) var<uniform> params: SimParams;
+
+fn pcg(state: ptr<function, u32>) -> u32 {
+    let old = *state;
+    *state = old * 747796405u + 2891336453u;
+    let word = ((old >> ((old >> 28u) + 4u)) ^ old) * 277803737u;
+    return (word >> 22u) ^ word;
+}
+
+fn random_float(state: ptr<function, u32>) -> f32 {
+    return f32(pcg(state) & 0xffffffu) / 16777215.0;
+}
+
+fn wrap_position(pos: vec2<f32>) -> vec2<f32> {
+    return pos - 2.0 * floor((pos + 1.0) * 0.5);
+}
+
+fn wrapped_delta(from_pos: vec2<f32>, to_pos: vec2<f32>) -> vec2<f32> {
+    let d = to_pos - from_pos;
+    return d - 2.0 * round(d * 0.5);
+}
+
+fn cell_coords(pos: vec2<f32>, size: u32) -> vec2<i32> {
+    let uv = clamp((wrap_position(pos) + 1.0) * 0.5, vec2<f32>(0.0), vec2<f32>(0.99999));
+    return vec2<i32>(uv * f32(size));
+}
+
+fn cell_index(pos: vec2<f32>, size: u32) -> u32 {
+    let cell = cell_coords(pos, size);
+    return u32(cell.y) * size + u32(cell.x);
+}
+
+fn hunts(predator: u32, prey: u32) -> bool {
+    return ((params.prey_mask[predator] >> prey) & 1u) != 0u;
+}
+
+// Attractiveness of a position for the given species: food for grazers,
+// prey scent for hunters, minus the scent of anything that hunts it
+fn sense(pos: vec2<f32>, species: u32) -> f32 {
+    let cell = cell_index(pos, params.food_grid_size);
+    var value = 0.0;
+
+    if (params.eats_food[species] != 0u) {
+        value += f32(max(atomicLoad(&food[cell]), 0)) / FOOD_SCALE;
+    }
+
+    for (var other = 0u; other < params.species_count; other++) {
+        let other_scent = f32(atomicLoad(&scent[cell * MAX_SPECIES + other])) / SCENT_SCALE;
+        if (hunts(species, other)) {
+            value += params.hunting_strength * other_scent;
+        }
+        if (hunts(other, species)) {
+            value -= params.fear_strength * other_scent;
+        }
+    }
+
+    return value;
+}
+
+fn heading_vector(heading: f32) -> vec2<f32> {
+    return vec2<f32>(cos(heading), sin(heading));
+}
+
+// Try to catch one prey agent in the surrounding occupancy cells.
+// Returns the energy gained, or 0.0 if nothing was caught.
+fn try_predation(index: u32, agent: Agent) -> f32 {
+    let species = agent.species;
+    if (params.prey_mask[species] == 0u) {
+        return 0.0;
+    }
+
+    let size = i32(params.occupancy_grid_size);
+    let center = cell_coords(agent.position, params.occupancy_grid_size);
+    let radius = min(params.predation_radius, 2.0 / f32(size));
+
+    for (var dy = -1; dy <= 1; dy++) {
+        for (var dx = -1; dx <= 1; dx++) {
+            let cx = (center.x + dx + size) % size;
+            let cy = (center.y + dy + size) % size;
+            let cell = u32(cy * size + cx);
+
+            for (var prey_species = 0u; prey_species < params.species_count; prey_species++) {
+                if (!hunts(species, prey_species)) {
+                    continue;
+                }
+
+                let occupant = atomicLoad(&occupancy[cell * MAX_SPECIES + prey_species]);
+                if (occupant == 0u || occupant - 1u == index) {
+                    continue;
+                }
+
+                let prey_index = occupant - 1u;
+                let prey = agents[prey_index];
+                if (length(wrapped_delta(agent.position, prey.position)) > radius) {
+                    continue;
+                }
+
+                // Only one predator gets to eat each prey. Exchange is used instead of
+                // compare-exchange so the shader also runs on the GL backend; a slot
+                // that turned out not to be alive gets its previous status back.
+                let previous = atomicExchange(&status[prey_index], STATUS_DEAD);
+                if (previous == STATUS_ALIVE) {
+                    atomicAdd(&stats[STAT_PREDATION_DEATHS], 1u);
+                    return max(prey.energy, 0.0) * params.predation_efficiency;
+                }
+                if (previous != STATUS_DEAD) {
+                    atomicStore(&status[prey_index], previous);
+                }
+            }
+        }
+    }
+
+    return 0.0;
+}
+
+// A predator may have eaten this agent since it was loaded. Its slot is then
+// free for newborns, so the agent must neither write to it nor reproduce.
+fn was_eaten(index: u32) -> bool {
+    return atomicLoad(&status[index]) != STATUS_ALIVE;
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let index = global_id.x;
+    if (index >= params.max_agents) {
+        return;
+    }
+    if (atomicLoad(&status[index]) != STATUS_ALIVE) {
+        return;
+    }
+
+    var agent = agents[index];
+    var rng = agent.rng_state;
+    let species = min(agent.species, MAX_SPECIES - 1u);
+    agent.species = species;
+    let dt = params.dt;
+
+    // Chemotaxis: compare three sensors and turn towards the most attractive
+    let forward = sense(agent.position + heading_vector(agent.heading) * params.sensor_distance, species);
+    let left = sense(agent.position + heading_vector(agent.heading + params.sensor_angle) * params.sensor_distance, species);
+    let right = sense(agent.position + heading_vector(agent.heading - params.sensor_angle) * params.sensor_distance, species);
+
+    if (forward < left || forward < right) {
+        if (left > right) {
+            agent.heading += params.turn_rate * dt;
+        } else if (right > left) {
+            agent.heading -= params.turn_rate * dt;
+        }
+    }
+    agent.heading += (random_float(&rng) - 0.5) * params.wander_strength * dt;
+    agent.heading = agent.heading - TWO_PI * floor(agent.heading / TWO_PI);
+
+    // Movement
+    let step = params.agent_speed * dt;
+    agent.position = wrap_position(agent.position + heading_vector(agent.heading) * step);
+
+    // Metabolism
+    agent.age += dt;
+    agent.energy -= params.metabolism_rate * dt + params.movement_cost * step;
+
+    // Grazing: take a bite and hand back whatever the cell could not provide
+    if (params.eats_food[species] != 0u) {
+        let cell = cell_index(agent.position, params.food_grid_size);
+        let bite = i32(params.feeding_rate * dt * FOOD_SCALE);
+        if (bite > 0) {
+            let previous = atomicSub(&food[cell], bite);
+            let eaten = clamp(previous, 0, bite);
+            if (eaten < bite) {
+                atomicAdd(&food[cell], bite - eaten);
+            }
+            agent.energy += f32(eaten) / FOOD_SCALE * params.food_energy;
+        }
+    }
+
+    // Hunting
+    agent.energy += try_predation(index, agent);
+    if (was_eaten(index)) {
+        return;
+    }
+
+    // Death
+    if (agent.energy <= 0.0) {
+        atomicStore(&status[index], STATUS_DEAD);
+        atomicAdd(&stats[STAT_STARVATION_DEATHS], 1u);
+        return;
+    }
+    if (agent.age >= params.max_age) {
+        atomicStore(&status[index], STATUS_DEAD);
+        atomicAdd(&stats[STAT_OLD_AGE_DEATHS], 1u);
+        return;
+    }
+
+    // Reproduction: claim a free slot for the offspring. Newborns are activated
+    // by the populate pass so they are not updated in the frame they are born.
+    if (agent.energy >= params.reproduction_threshold) {
+        for (var attempt = 0u; attempt < REPRODUCTION_ATTEMPTS; attempt++) {
+            let slot = pcg(&rng) % params.max_agents;
+            let previous = atomicExchange(&status[slot], STATUS_NEWBORN);
+            if (previous != STATUS_DEAD) {
+                atomicStore(&status[slot], previous);
+                continue;
+            }
+
+            let child_energy = agent.energy * params.reproduction_share;
+            agent.energy -= child_energy;
+
+            var child: Agent;
+            let offset = vec2<f32>(random_float(&rng) - 0.5, random_float(&rng) - 0.5);
+            child.position = wrap_position(agent.position + offset * params.sensor_distance);
+            child.heading = random_float(&rng) * TWO_PI;
+            child.energy = child_energy;
+            child.age = 0.0;
+            child.species = species;
+            child.rng_state = pcg(&rng) ^ (slot * 2654435761u);
+            child._pad = 0u;
+            agents[slot] = child;
+
+            atomicAdd(&stats[STAT_BIRTHS], 1u);
+            break;
+        }
+    }
+
+    agent.rng_state = rng;
+    if (was_eaten(index)) {
+        return;
+    }
+    agents[index] = agent;
+}
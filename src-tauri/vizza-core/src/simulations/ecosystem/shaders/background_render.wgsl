@@ -0,0 +1,79 @@
+// Background render: draws the food field into the display texture, blending
+// from the LUT's background color towards the food color by food amount.
+
+struct RenderParams {
+    agent_size: f32,
+    aspect_ratio: f32,
+    species_count: u32,
+    food_grid_size: u32,
+    food_capacity: f32,
+    food_visibility: f32,
+    energy_reference: f32,
+    _pad: u32,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+const FOOD_SCALE: f32 = 1000.0;
+
+@group(0) @binding(2) var<storage, read> food: array<i32>;
+@group(0) @binding(3) var<storage, read> lut: array<u32>;
+@group(0) @binding(4) var<uniform> params: RenderParams;
+
+// Convert from sRGB (gamma-corrected) to linear RGB
+fn srgb_to_linear(srgb: f32) -> f32 {
+    if (srgb <= 0.04045) {
+        return srgb / 12.92;
+    } else {
+        return pow((srgb + 0.055) / 1.055, 2.4);
+    }
+}
+
+fn get_lut_color(index: u32) -> vec3<f32> {
+    let r_srgb = f32(lut[index]) / 255.0;
+    let g_srgb = f32(lut[index + 256u]) / 255.0;
+    let b_srgb = f32(lut[index + 512u]) / 255.0;
+    return vec3<f32>(
+        srgb_to_linear(r_srgb),
+        srgb_to_linear(g_srgb),
+        srgb_to_linear(b_srgb)
+    );
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    var positions = array<vec2<f32>, 6>(
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>( 1.0, -1.0),
+        vec2<f32>(-1.0,  1.0),
+        vec2<f32>(-1.0,  1.0),
+        vec2<f32>( 1.0, -1.0),
+        vec2<f32>( 1.0,  1.0),
+    );
+
+    let pos = positions[vertex_index];
+    var out: VertexOutput;
+    out.position = vec4<f32>(pos, 0.0, 1.0);
+    // World space position of this fragment, y up
+    out.uv = pos;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let size = params.food_grid_size;
+    let uv = clamp((in.uv + 1.0) * 0.5, vec2<f32>(0.0), vec2<f32>(0.99999));
+    let cell = vec2<u32>(uv * f32(size));
+    let amount = f32(max(food[cell.y * size + cell.x], 0)) / FOOD_SCALE;
+    let density = clamp(amount / max(params.food_capacity, 0.0001), 0.0, 1.0);
+
+    let background = get_lut_color(0u);
+    let food_index = 128u / max(params.species_count, 1u);
+    let food_color = get_lut_color(food_index);
+
+    let color = mix(background, food_color, density * params.food_visibility);
+    return vec4<f32>(color, 1.0);
+}
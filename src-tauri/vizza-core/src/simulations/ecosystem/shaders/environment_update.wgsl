@@ -0,0 +1,142 @@
+// Environment update: regrows food, evaporates scent and clears the per-frame
+// occupancy grid and population counters. Dispatched once per food cell.
+
+struct SimParams {
+    prey_mask: vec4<u32>,   // bit t of prey_mask[s] set => species s hunts species t
+    eats_food: vec4<u32>,   // non-zero => species grazes on the food field
+    mouse_position: vec2<f32>,
+    mouse_pressed: u32,
+    mouse_mode: u32,        // 0 = none, 1 = add food, 2 = remove food
+    cursor_size: f32,
+    cursor_strength: f32,
+    max_agents: u32,
+    species_count: u32,
+    food_grid_size: u32,
+    occupancy_grid_size: u32,
+    dt: f32,
+    time: f32,
+    frame_index: u32,
+    random_seed: u32,
+    agent_speed: f32,
+    turn_rate: f32,
+    sensor_angle: f32,
+    sensor_distance: f32,
+    wander_strength: f32,
+    hunting_strength: f32,
+    fear_strength: f32,
+    metabolism_rate: f32,
+    movement_cost: f32,
+    feeding_rate: f32,
+    food_energy: f32,
+    predation_radius: f32,
+    predation_efficiency: f32,
+    reproduction_threshold: f32,
+    reproduction_share: f32,
+    max_age: f32,
+    food_growth_rate: f32,
+    food_capacity: f32,
+    food_pattern: u32,      // 0 = uniform, 1 = patches, 2 = stripes
+    scent_deposit: f32,
+    scent_decay_rate: f32,
+    _pad: u32,
+}
+
+const MAX_SPECIES: u32 = 4u;
+const FOOD_SCALE: f32 = 1000.0;
+const SCENT_SCALE: f32 = 1000.0;
+const PI: f32 = 3.14159265359;
+
+@group(0) @binding(2) var<storage, read_write> food: array<atomic<i32>>;
+@group(0) @binding(3) var<storage, read_write> scent: array<atomic<u32>>;
+@group(0) @binding(4) var<storage, read_write> occupancy: array<atomic<u32>>;
+@group(0) @binding(5) var<storage, read_write> stats: array<atomic<u32>>;
+@group(0) @binding(6) var<uniform> params: SimParams;
+
+fn hash(x: u32) -> u32 {
+    var v = x;
+    v = v ^ (v >> 16u);
+    v = v * 0x7feb352du;
+    v = v ^ (v >> 15u);
+    v = v * 0x846ca68bu;
+    v = v ^ (v >> 16u);
+    return v;
+}
+
+fn seed_phase(salt: u32) -> f32 {
+    return f32(hash(params.random_seed ^ salt) & 0xffffu) / 65535.0 * 2.0 * PI;
+}
+
+// Relative food capacity at a world position. Every pattern uses whole
+// periods across the [-1,1] world so the field tiles seamlessly.
+fn food_pattern(pos: vec2<f32>) -> f32 {
+    if (params.food_pattern == 1u) {
+        let a = sin(pos.x * PI * 2.0 + seed_phase(1u)) * sin(pos.y * PI * 2.0 + seed_phase(2u));
+        let b = sin(pos.x * PI * 3.0 + seed_phase(3u)) * sin(pos.y * PI * 1.0 + seed_phase(4u));
+        let v = clamp(0.5 + 0.5 * (a + b) * 0.5, 0.0, 1.0);
+        return smoothstep(0.45, 0.85, v);
+    } else if (params.food_pattern == 2u) {
+        let s = 0.5 + 0.5 * sin(pos.x * PI * 3.0 + pos.y * PI + seed_phase(5u));
+        return s * s;
+    }
+    return 1.0;
+}
+
+fn wrapped_distance(a: vec2<f32>, b: vec2<f32>) -> f32 {
+    var d = abs(a - b);
+    d = min(d, vec2<f32>(2.0) - d);
+    return length(d);
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let index = global_id.x;
+
+    // Population counts are rebuilt every frame by the populate pass
+    if (index < MAX_SPECIES) {
+        atomicStore(&stats[index], 0u);
+    }
+
+    let occupancy_cells = params.occupancy_grid_size * params.occupancy_grid_size * MAX_SPECIES;
+    if (index < occupancy_cells) {
+        atomicStore(&occupancy[index], 0u);
+    }
+
+    let size = params.food_grid_size;
+    if (index >= size * size) {
+        return;
+    }
+
+    let cell = vec2<u32>(index % size, index / size);
+    let world_pos = (vec2<f32>(cell) + 0.5) / f32(size) * 2.0 - 1.0;
+
+    // Regrow food towards the local capacity; painted food above it is left alone
+    let capacity = params.food_capacity * food_pattern(world_pos);
+    var amount = max(f32(atomicLoad(&food[index])) / FOOD_SCALE, 0.0);
+    if (amount < capacity) {
+        amount = min(amount + params.food_growth_rate * params.dt, capacity);
+    }
+
+    // Paint or erase food under the cursor
+    if (params.mouse_pressed != 0u) {
+        let distance = wrapped_distance(world_pos, params.mouse_position);
+        if (distance < params.cursor_size) {
+            let falloff = 1.0 - distance / params.cursor_size;
+            let change = params.cursor_strength * falloff * params.dt * 5.0;
+            if (params.mouse_mode == 1u) {
+                amount = min(amount + change, params.food_capacity);
+            } else if (params.mouse_mode == 2u) {
+                amount = max(amount - change, 0.0);
+            }
+        }
+    }
+
+    atomicStore(&food[index], i32(amount * FOOD_SCALE));
+
+    // Evaporate scent trails
+    let retention = exp(-params.scent_decay_rate * params.dt);
+    for (var s = 0u; s < MAX_SPECIES; s++) {
+        let scent_index = index * MAX_SPECIES + s;
+        let value = f32(atomicLoad(&scent[scent_index])) * retention;
+        atomicStore(&scent[scent_index], u32(value));
+    }
+}
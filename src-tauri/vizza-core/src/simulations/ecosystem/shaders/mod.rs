@@ -0,0 +1,17 @@
+//! # Ecosystem Shaders Module
+//!
+//! GPU programs for the Ecosystem simulation. The compute passes run in a fixed
+//! order every frame: the environment pass regrows food and clears per-frame
+//! data, the populate pass indexes living agents, and the update pass runs the
+//! agent life cycle. Render shaders then draw the food field and the agents
+//! into an offscreen texture that is tiled onto the screen.
+
+// Compute shaders
+pub const ENVIRONMENT_UPDATE_SHADER: &str = include_str!("environment_update.wgsl");
+pub const AGENT_POPULATE_SHADER: &str = include_str!("agent_populate.wgsl");
+pub const AGENT_UPDATE_SHADER: &str = include_str!("agent_update.wgsl");
+
+// Offscreen rendering shaders
+pub const BACKGROUND_RENDER_SHADER: &str = include_str!("background_render.wgsl");
+pub const AGENT_RENDER_SHADER: &str = include_str!("agent_render.wgsl");
+pub const RENDER_INFINITE_SHADER: &str = crate::simulations::shared::INFINITE_RENDER_SHADER;
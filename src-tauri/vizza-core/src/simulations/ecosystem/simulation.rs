@@ -0,0 +1,1519 @@
+//! # Ecosystem Simulation Implementation
+//!
+//! Owns the GPU resources of the Ecosystem simulation and drives the per-frame
+//! pipeline: three compute passes for the environment and agent life cycle,
+//! an offscreen render of the food field and agents, and the shared infinite
+//! tiling pass that puts the result on screen through the camera.
+//!
+//! ## Agent Storage
+//!
+//! Agents occupy a fixed-capacity buffer. A parallel status buffer marks each
+//! slot as dead, alive or newborn, which lets the GPU kill agents and claim free
+//! slots for offspring with atomics instead of compacting the buffer. The CPU
+//! only touches agent data when the population is reset.
+
+use crate::error::{SimulationError, SimulationResult};
+use crate::settings::{RenderSettings, TextureFiltering};
+use crate::simulations::shared::{BufferReadback, LutManager, SimulationSnapshot, camera::Camera};
+use bytemuck::{Pod, Zeroable};
+use serde_json::Value;
+use std::sync::Arc;
+use wgpu::util::DeviceExt;
+use wgpu::{Device, Queue, SurfaceConfiguration, TextureView};
+
+use super::settings::{FoodChain, MAX_SPECIES, Settings};
+use super::shaders::{
+    AGENT_POPULATE_SHADER, AGENT_RENDER_SHADER, AGENT_UPDATE_SHADER, BACKGROUND_RENDER_SHADER,
+    ENVIRONMENT_UPDATE_SHADER, RENDER_INFINITE_SHADER,
+};
+use super::state::State;
+
+/// Resolution of the food and scent grids covering the [-1,1] world
+pub const FOOD_GRID_SIZE: u32 = 256;
+/// Resolution of the occupancy grid used for predation lookups
+pub const OCCUPANCY_GRID_SIZE: u32 = 128;
+/// Fixed-point scale of the food grid (matches FOOD_SCALE in the shaders)
+const FOOD_SCALE: f32 = 1000.0;
+/// Population counters per species followed by births and three death causes
+pub const STATS_LEN: usize = MAX_SPECIES as usize + 4;
+/// How often the population counters are read back from the GPU
+const STATS_READBACK_INTERVAL: u64 = 30;
+/// Physics runs at a fixed timestep, independent of the display frame rate
+const SIMULATION_DT: f32 = 1.0 / 60.0;
+
+const STATUS_DEAD: u32 = 0;
+const STATUS_ALIVE: u32 = 1;
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable, Debug)]
+pub struct Agent {
+    pub position: [f32; 2],
+    pub heading: f32,
+    pub energy: f32,
+    pub age: f32,
+    pub species: u32,
+    pub rng_state: u32,
+    pub _pad: u32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct SimParams {
+    pub prey_mask: [u32; 4],
+    pub eats_food: [u32; 4],
+    pub mouse_position: [f32; 2],
+    pub mouse_pressed: u32,
+    pub mouse_mode: u32,
+    pub cursor_size: f32,
+    pub cursor_strength: f32,
+    pub max_agents: u32,
+    pub species_count: u32,
+    pub food_grid_size: u32,
+    pub occupancy_grid_size: u32,
+    pub dt: f32,
+    pub time: f32,
+    pub frame_index: u32,
+    pub random_seed: u32,
+    pub agent_speed: f32,
+    pub turn_rate: f32,
+    pub sensor_angle: f32,
+    pub sensor_distance: f32,
+    pub wander_strength: f32,
+    pub hunting_strength: f32,
+    pub fear_strength: f32,
+    pub metabolism_rate: f32,
+    pub movement_cost: f32,
+    pub feeding_rate: f32,
+    pub food_energy: f32,
+    pub predation_radius: f32,
+    pub predation_efficiency: f32,
+    pub reproduction_threshold: f32,
+    pub reproduction_share: f32,
+    pub max_age: f32,
+    pub food_growth_rate: f32,
+    pub food_capacity: f32,
+    pub food_pattern: u32,
+    pub scent_deposit: f32,
+    pub scent_decay_rate: f32,
+    pub _pad: u32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct RenderParams {
+    pub agent_size: f32,
+    pub aspect_ratio: f32,
+    pub species_count: u32,
+    pub food_grid_size: u32,
+    pub food_capacity: f32,
+    pub food_visibility: f32,
+    pub energy_reference: f32,
+    pub _pad: u32,
+}
+
+/// Which species hunt which, and which graze on the food field.
+///
+/// Returns `(prey_mask, eats_food)` where bit `t` of `prey_mask[s]` is set
+/// when species `s` hunts species `t`.
+pub fn food_web(food_chain: FoodChain, species_count: u32) -> ([u32; 4], [u32; 4]) {
+    let count = species_count.clamp(1, MAX_SPECIES);
+    let mut prey_mask = [0u32; 4];
+    let mut eats_food = [0u32; 4];
+
+    for species in 0..count {
+        let next = species + 1;
+        match food_chain {
+            FoodChain::Cycle => {
+                if count > 1 {
+                    prey_mask[species as usize] = 1 << (next % count);
+                }
+                eats_food[species as usize] = 1;
+            }
+            FoodChain::Chain => {
+                // Each species hunts the one below it, only the bottom grazes
+                if next < count {
+                    prey_mask[species as usize] = 1 << next;
+                } else {
+                    eats_food[species as usize] = 1;
+                }
+            }
+        }
+    }
+
+    (prey_mask, eats_food)
+}
+
+fn storage_entry(
+    binding: u32,
+    visibility: wgpu::ShaderStages,
+    read_only: bool,
+) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Storage { read_only },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    }
+}
+
+fn uniform_entry(binding: u32, visibility: wgpu::ShaderStages) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    }
+}
+
+pub struct EcosystemModel {
+    // Simulation buffers
+    pub agent_buffer: wgpu::Buffer,
+    pub status_buffer: wgpu::Buffer,
+    pub food_buffer: wgpu::Buffer,
+    pub scent_buffer: wgpu::Buffer,
+    pub occupancy_buffer: wgpu::Buffer,
+    pub stats_buffer: wgpu::Buffer,
+    pub stats_readback: BufferReadback,
+    pub sim_params_buffer: wgpu::Buffer,
+    pub render_params_buffer: wgpu::Buffer,
+    pub texture_render_params_buffer: wgpu::Buffer,
+    pub lut_buffer: wgpu::Buffer,
+
+    // Compute pipelines (all three share one bind group)
+    pub compute_bind_group_layout: wgpu::BindGroupLayout,
+    pub compute_bind_group: wgpu::BindGroup,
+    pub environment_pipeline: wgpu::ComputePipeline,
+    pub populate_pipeline: wgpu::ComputePipeline,
+    pub agent_update_pipeline: wgpu::ComputePipeline,
+
+    // Offscreen rendering resources
+    pub render_bind_group_layout: wgpu::BindGroupLayout,
+    pub render_bind_group: wgpu::BindGroup,
+    pub background_render_pipeline: wgpu::RenderPipeline,
+    pub agent_render_pipeline: wgpu::RenderPipeline,
+    pub display_texture: wgpu::Texture,
+    pub display_view: wgpu::TextureView,
+    pub display_sampler: wgpu::Sampler,
+
+    // Infinite tiling to the surface
+    pub render_infinite_bind_group_layout: wgpu::BindGroupLayout,
+    pub render_infinite_pipeline: wgpu::RenderPipeline,
+    pub render_infinite_bind_group: wgpu::BindGroup,
+    pub camera_bind_group: wgpu::BindGroup,
+
+    // Simulation state and settings
+    pub settings: Settings,
+    pub state: State,
+    pub camera: Camera,
+    pub lut_manager: Arc<LutManager>,
+    pub render_settings: RenderSettings,
+    pub surface_config: SurfaceConfiguration,
+    pub frame_count: u64,
+}
+
+impl EcosystemModel {
+    pub fn new(
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+        surface_config: &SurfaceConfiguration,
+        settings: Settings,
+        render_settings: &RenderSettings,
+        lut_manager: &LutManager,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let max_agents = settings.max_agents.max(1);
+        let (agents, status) = Self::initialize_agents(&settings, max_agents);
+
+        let agent_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Ecosystem Agent Buffer"),
+            contents: bytemuck::cast_slice(&agents),
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
+        });
+
+        let status_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Ecosystem Status Buffer"),
+            contents: bytemuck::cast_slice(&status),
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
+        });
+
+        let food = Self::initialize_food(&settings);
+        let food_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Ecosystem Food Buffer"),
+            contents: bytemuck::cast_slice(&food),
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
+        });
+
+        let scent_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Ecosystem Scent Buffer"),
+            size: (FOOD_GRID_SIZE * FOOD_GRID_SIZE * MAX_SPECIES) as u64
+                * std::mem::size_of::<u32>() as u64,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        let occupancy_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Ecosystem Occupancy Buffer"),
+            size: (OCCUPANCY_GRID_SIZE * OCCUPANCY_GRID_SIZE * MAX_SPECIES) as u64
+                * std::mem::size_of::<u32>() as u64,
+            usage: wgpu::BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+
+        let stats_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Ecosystem Stats Buffer"),
+            contents: bytemuck::cast_slice(&[0u32; STATS_LEN]),
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_SRC
+                | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let stats_readback = BufferReadback::new(
+            device,
+            "Ecosystem Stats Staging Buffer",
+            stats_buffer.size(),
+        );
+
+        let sim_params_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Ecosystem Sim Params Buffer"),
+            size: std::mem::size_of::<SimParams>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let render_params_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Ecosystem Render Params Buffer"),
+            size: std::mem::size_of::<RenderParams>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let filtering_mode = match render_settings.texture_filtering {
+            TextureFiltering::Nearest => 0u32,
+            TextureFiltering::Linear => 1u32,
+            TextureFiltering::Lanczos => 2u32,
+        };
+        let texture_render_params_buffer =
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Ecosystem Texture Render Params Buffer"),
+                contents: bytemuck::cast_slice(&[filtering_mode, 0u32, 0u32, 0u32]),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            });
+
+        let state = State::default();
+        let lut = lut_manager
+            .get(&state.current_lut_name)
+            .unwrap_or_else(|_| lut_manager.get_default());
+        let lut_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Ecosystem LUT Buffer"),
+            contents: bytemuck::cast_slice(&lut.to_u32_buffer()),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+        });
+
+        // Compute pipelines
+        let compute_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Ecosystem Compute Bind Group Layout"),
+                entries: &[
+                    storage_entry(0, wgpu::ShaderStages::COMPUTE, false),
+                    storage_entry(1, wgpu::ShaderStages::COMPUTE, false),
+                    storage_entry(2, wgpu::ShaderStages::COMPUTE, false),
+                    storage_entry(3, wgpu::ShaderStages::COMPUTE, false),
+                    storage_entry(4, wgpu::ShaderStages::COMPUTE, false),
+                    storage_entry(5, wgpu::ShaderStages::COMPUTE, false),
+                    uniform_entry(6, wgpu::ShaderStages::COMPUTE),
+                ],
+            });
+
+        let compute_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Ecosystem Compute Pipeline Layout"),
+                bind_group_layouts: &[&compute_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        let create_compute_pipeline = |label: &str, source: &str| {
+            let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some(label),
+                source: wgpu::ShaderSource::Wgsl(source.into()),
+            });
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(label),
+                layout: Some(&compute_pipeline_layout),
+                module: &module,
+                entry_point: Some("main"),
+                compilation_options: Default::default(),
+                cache: None,
+            })
+        };
+
+        let environment_pipeline =
+            create_compute_pipeline("Ecosystem Environment Update", ENVIRONMENT_UPDATE_SHADER);
+        let populate_pipeline =
+            create_compute_pipeline("Ecosystem Agent Populate", AGENT_POPULATE_SHADER);
+        let agent_update_pipeline =
+            create_compute_pipeline("Ecosystem Agent Update", AGENT_UPDATE_SHADER);
+
+        let compute_bind_group = Self::create_compute_bind_group(
+            device,
+            &compute_bind_group_layout,
+            &agent_buffer,
+            &status_buffer,
+            &food_buffer,
+            &scent_buffer,
+            &occupancy_buffer,
+            &stats_buffer,
+            &sim_params_buffer,
+        );
+
+        // Offscreen render pipelines
+        let render_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Ecosystem Render Bind Group Layout"),
+                entries: &[
+                    storage_entry(0, wgpu::ShaderStages::VERTEX, true),
+                    storage_entry(1, wgpu::ShaderStages::VERTEX, true),
+                    storage_entry(2, wgpu::ShaderStages::FRAGMENT, true),
+                    storage_entry(
+                        3,
+                        wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                        true,
+                    ),
+                    uniform_entry(4, wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT),
+                ],
+            });
+
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Ecosystem Render Pipeline Layout"),
+                bind_group_layouts: &[&render_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        let create_offscreen_pipeline = |label: &str, source: &str, blend: wgpu::BlendState| {
+            let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some(label),
+                source: wgpu::ShaderSource::Wgsl(source.into()),
+            });
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&render_pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &module,
+                    entry_point: Some("vs_main"),
+                    buffers: &[],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &module,
+                    entry_point: Some("fs_main"),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: wgpu::TextureFormat::Rgba8Unorm,
+                        blend: Some(blend),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+                cache: None,
+            })
+        };
+
+        let background_render_pipeline = create_offscreen_pipeline(
+            "Ecosystem Background Render Pipeline",
+            BACKGROUND_RENDER_SHADER,
+            wgpu::BlendState::REPLACE,
+        );
+        let agent_render_pipeline = create_offscreen_pipeline(
+            "Ecosystem Agent Render Pipeline",
+            AGENT_RENDER_SHADER,
+            wgpu::BlendState::ALPHA_BLENDING,
+        );
+
+        let render_bind_group = Self::create_render_bind_group(
+            device,
+            &render_bind_group_layout,
+            &agent_buffer,
+            &status_buffer,
+            &food_buffer,
+            &lut_buffer,
+            &render_params_buffer,
+        );
+
+        let (display_texture, display_view, display_sampler) =
+            Self::create_display_texture(device, surface_config, render_settings);
+
+        // Infinite tiling pipeline
+        let camera = Camera::new(
+            device,
+            surface_config.width as f32,
+            surface_config.height as f32,
+        )?;
+
+        let camera_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Ecosystem Camera Bind Group Layout"),
+                entries: &[uniform_entry(
+                    0,
+                    wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                )],
+            });
+
+        let camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Ecosystem Camera Bind Group"),
+            layout: &camera_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: camera.buffer().as_entire_binding(),
+            }],
+        });
+
+        let render_infinite_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Ecosystem Render Infinite Bind Group Layout"),
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                    uniform_entry(2, wgpu::ShaderStages::FRAGMENT),
+                ],
+            });
+
+        let render_infinite_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Ecosystem Render Infinite Shader"),
+            source: wgpu::ShaderSource::Wgsl(RENDER_INFINITE_SHADER.into()),
+        });
+
+        let render_infinite_pipeline =
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("Ecosystem Render Infinite Pipeline"),
+                layout: Some(
+                    &device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                        label: Some("Ecosystem Render Infinite Pipeline Layout"),
+                        bind_group_layouts: &[
+                            &render_infinite_bind_group_layout,
+                            &camera_bind_group_layout,
+                        ],
+                        push_constant_ranges: &[],
+                    }),
+                ),
+                vertex: wgpu::VertexState {
+                    module: &render_infinite_shader,
+                    entry_point: Some("vs_main"),
+                    buffers: &[],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &render_infinite_shader,
+                    entry_point: Some("fs_main_texture"),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: surface_config.format,
+                        blend: Some(wgpu::BlendState::REPLACE),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+                cache: None,
+            });
+
+        let render_infinite_bind_group = Self::create_render_infinite_bind_group(
+            device,
+            &render_infinite_bind_group_layout,
+            &display_view,
+            &display_sampler,
+            &texture_render_params_buffer,
+        );
+
+        let model = Self {
+            agent_buffer,
+            status_buffer,
+            food_buffer,
+            scent_buffer,
+            occupancy_buffer,
+            stats_buffer,
+            stats_readback,
+            sim_params_buffer,
+            render_params_buffer,
+            texture_render_params_buffer,
+            lut_buffer,
+            compute_bind_group_layout,
+            compute_bind_group,
+            environment_pipeline,
+            populate_pipeline,
+            agent_update_pipeline,
+            render_bind_group_layout,
+            render_bind_group,
+            background_render_pipeline,
+            agent_render_pipeline,
+            display_texture,
+            display_view,
+            display_sampler,
+            render_infinite_bind_group_layout,
+            render_infinite_pipeline,
+            render_infinite_bind_group,
+            camera_bind_group,
+            settings,
+            state,
+            camera,
+            lut_manager: Arc::new(lut_manager.clone()),
+            render_settings: render_settings.clone(),
+            surface_config: surface_config.clone(),
+            frame_count: 0,
+        };
+
+        model.update_sim_params(queue);
+        model.update_render_params(queue);
+
+        Ok(model)
+    }
+
+    /// Spawn the initial population spread over all species. Slots beyond
+    /// `agent_count` start out dead and are filled in by reproduction.
+    fn initialize_agents(settings: &Settings, max_agents: u32) -> (Vec<Agent>, Vec<u32>) {
+        use rand::Rng;
+        let mut rng = crate::simulations::shared::random::rng();
+
+        let species_count = settings.species_count.clamp(1, MAX_SPECIES);
+        let alive = settings.agent_count.min(max_agents);
+        let mut agents = Vec::with_capacity(max_agents as usize);
+        let mut status = Vec::with_capacity(max_agents as usize);
+
+        for i in 0..max_agents {
+            if i < alive {
+                agents.push(Agent {
+                    position: [rng.random_range(-1.0..1.0), rng.random_range(-1.0..1.0)],
+                    heading: rng.random_range(0.0..std::f32::consts::TAU),
+                    energy: settings.initial_energy * rng.random_range(0.5..1.0),
+                    // Stagger ages so the first generation doesn't die all at once
+                    age: rng.random_range(0.0..settings.max_age.max(0.001) * 0.5),
+                    species: i % species_count,
+                    rng_state: rng.random(),
+                    _pad: 0,
+                });
+                status.push(STATUS_ALIVE);
+            } else {
+                agents.push(Agent::zeroed());
+                status.push(STATUS_DEAD);
+            }
+        }
+
+        (agents, status)
+    }
+
+    /// Start every food cell half full; the environment pass grows it into the pattern
+    fn initialize_food(settings: &Settings) -> Vec<i32> {
+        let amount = (settings.food_capacity * 0.5 * FOOD_SCALE) as i32;
+        vec![amount; (FOOD_GRID_SIZE * FOOD_GRID_SIZE) as usize]
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn create_compute_bind_group(
+        device: &Arc<Device>,
+        layout: &wgpu::BindGroupLayout,
+        agent_buffer: &wgpu::Buffer,
+        status_buffer: &wgpu::Buffer,
+        food_buffer: &wgpu::Buffer,
+        scent_buffer: &wgpu::Buffer,
+        occupancy_buffer: &wgpu::Buffer,
+        stats_buffer: &wgpu::Buffer,
+        sim_params_buffer: &wgpu::Buffer,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Ecosystem Compute Bind Group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: agent_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: status_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: food_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: scent_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: occupancy_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 5,
+                    resource: stats_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 6,
+                    resource: sim_params_buffer.as_entire_binding(),
+                },
+            ],
+        })
+    }
+
+    fn create_render_bind_group(
+        device: &Arc<Device>,
+        layout: &wgpu::BindGroupLayout,
+        agent_buffer: &wgpu::Buffer,
+        status_buffer: &wgpu::Buffer,
+        food_buffer: &wgpu::Buffer,
+        lut_buffer: &wgpu::Buffer,
+        render_params_buffer: &wgpu::Buffer,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Ecosystem Render Bind Group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: agent_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: status_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: food_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: lut_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: render_params_buffer.as_entire_binding(),
+                },
+            ],
+        })
+    }
+
+    fn create_render_infinite_bind_group(
+        device: &Arc<Device>,
+        layout: &wgpu::BindGroupLayout,
+        display_view: &wgpu::TextureView,
+        display_sampler: &wgpu::Sampler,
+        texture_render_params_buffer: &wgpu::Buffer,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Ecosystem Render Infinite Bind Group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(display_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(display_sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: texture_render_params_buffer.as_entire_binding(),
+                },
+            ],
+        })
+    }
+
+    fn create_display_texture(
+        device: &Arc<Device>,
+        surface_config: &SurfaceConfiguration,
+        render_settings: &RenderSettings,
+    ) -> (wgpu::Texture, wgpu::TextureView, wgpu::Sampler) {
+        let display_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Ecosystem Display Texture"),
+            size: wgpu::Extent3d {
+                width: surface_config.width.max(1),
+                height: surface_config.height.max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+
+        let display_view = display_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let filter_mode = if render_settings.texture_filtering == TextureFiltering::Linear {
+            wgpu::FilterMode::Linear
+        } else {
+            wgpu::FilterMode::Nearest
+        };
+
+        let display_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Ecosystem Display Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: filter_mode,
+            min_filter: filter_mode,
+            mipmap_filter: filter_mode,
+            ..Default::default()
+        });
+
+        (display_texture, display_view, display_sampler)
+    }
+
+    fn update_sim_params(&self, queue: &Arc<Queue>) {
+        let (prey_mask, eats_food) =
+            food_web(self.settings.food_chain, self.settings.species_count);
+
+        let sim_params = SimParams {
+            prey_mask,
+            eats_food,
+            mouse_position: self.state.mouse_position,
+            mouse_pressed: if self.state.mouse_pressed { 1 } else { 0 },
+            mouse_mode: self.state.mouse_mode,
+            cursor_size: self.state.cursor_size,
+            cursor_strength: self.state.cursor_strength,
+            max_agents: self.agent_capacity(),
+            species_count: self.settings.species_count.clamp(1, MAX_SPECIES),
+            food_grid_size: FOOD_GRID_SIZE,
+            occupancy_grid_size: OCCUPANCY_GRID_SIZE,
+            dt: SIMULATION_DT,
+            time: self.state.simulation_time,
+            frame_index: self.frame_count as u32,
+            random_seed: self.settings.random_seed,
+            agent_speed: self.settings.agent_speed,
+            turn_rate: self.settings.turn_rate,
+            sensor_angle: self.settings.sensor_angle,
+            sensor_distance: self.settings.sensor_distance,
+            wander_strength: self.settings.wander_strength,
+            hunting_strength: self.settings.hunting_strength,
+            fear_strength: self.settings.fear_strength,
+            metabolism_rate: self.settings.metabolism_rate,
+            movement_cost: self.settings.movement_cost,
+            feeding_rate: self.settings.feeding_rate,
+            food_energy: self.settings.food_energy,
+            predation_radius: self.settings.predation_radius,
+            predation_efficiency: self.settings.predation_efficiency,
+            reproduction_threshold: self.settings.reproduction_threshold,
+            reproduction_share: self.settings.reproduction_share,
+            max_age: self.settings.max_age,
+            food_growth_rate: self.settings.food_growth_rate,
+            food_capacity: self.settings.food_capacity,
+            food_pattern: self.settings.food_pattern as u32,
+            scent_deposit: self.settings.scent_deposit,
+            scent_decay_rate: self.settings.scent_decay_rate,
+            _pad: 0,
+        };
+
+        queue.write_buffer(
+            &self.sim_params_buffer,
+            0,
+            bytemuck::cast_slice(&[sim_params]),
+        );
+    }
+
+    fn update_render_params(&self, queue: &Arc<Queue>) {
+        let render_params = RenderParams {
+            agent_size: self.settings.agent_size,
+            aspect_ratio: self.surface_config.width as f32
+                / self.surface_config.height.max(1) as f32,
+            species_count: self.settings.species_count.clamp(1, MAX_SPECIES),
+            food_grid_size: FOOD_GRID_SIZE,
+            food_capacity: self.settings.food_capacity,
+            food_visibility: self.settings.food_visibility,
+            energy_reference: self.settings.reproduction_threshold,
+            _pad: 0,
+        };
+
+        queue.write_buffer(
+            &self.render_params_buffer,
+            0,
+            bytemuck::cast_slice(&[render_params]),
+        );
+    }
+
+    /// Number of agent slots in the GPU buffers
+    fn agent_capacity(&self) -> u32 {
+        (self.agent_buffer.size() / std::mem::size_of::<Agent>() as u64) as u32
+    }
+
+    fn calculate_tile_count(&self) -> u32 {
+        let visible_world_size = 2.0 / self.camera.zoom;
+        let tiles_needed = (visible_world_size / 2.0).ceil() as u32 + 6;
+        let min_tiles = if self.camera.zoom < 0.1 { 7 } else { 5 };
+        tiles_needed.max(min_tiles).min(1024)
+    }
+
+    /// Run one fixed timestep of the environment and agent life cycle
+    pub fn step_simulation(&mut self, device: &Arc<Device>, queue: &Arc<Queue>) {
+        self.collect_stats(device);
+        self.frame_count += 1;
+        self.state.simulation_time += SIMULATION_DT;
+        self.update_sim_params(queue);
+
+        let capacity = self.agent_capacity();
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Ecosystem Compute Encoder"),
+        });
+
+        let workgroup_size = 64;
+        let passes = [
+            (
+                "Ecosystem Environment Update Pass",
+                &self.environment_pipeline,
+                FOOD_GRID_SIZE * FOOD_GRID_SIZE,
+            ),
+            (
+                "Ecosystem Agent Populate Pass",
+                &self.populate_pipeline,
+                capacity,
+            ),
+            (
+                "Ecosystem Agent Update Pass",
+                &self.agent_update_pipeline,
+                capacity,
+            ),
+        ];
+
+        for (label, pipeline, invocations) in passes {
+            let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some(label),
+                timestamp_writes: None,
+            });
+            compute_pass.set_pipeline(pipeline);
+            compute_pass.set_bind_group(0, &self.compute_bind_group, &[]);
+            compute_pass.dispatch_workgroups(invocations.div_ceil(workgroup_size), 1, 1);
+        }
+
+        queue.submit(std::iter::once(encoder.finish()));
+
+        if self.frame_count % STATS_READBACK_INTERVAL == 1 {
+            self.stats_readback
+                .request(&self.stats_buffer, device, queue);
+        }
+    }
+
+    /// Copy the population counters into the state once a requested
+    /// readback has arrived
+    fn collect_stats(&mut self, device: &Arc<Device>) {
+        let Some(stats) = self.stats_readback.collect::<u32>(device) else {
+            return;
+        };
+        let species_count = self.settings.species_count.clamp(1, MAX_SPECIES) as usize;
+        self.state.population = stats[..species_count].to_vec();
+        self.state.births = stats[MAX_SPECIES as usize];
+        self.state.starvation_deaths = stats[MAX_SPECIES as usize + 1];
+        self.state.old_age_deaths = stats[MAX_SPECIES as usize + 2];
+        self.state.predation_deaths = stats[MAX_SPECIES as usize + 3];
+    }
+
+    /// Draw the food field and agents into the display texture and tile it onto the surface
+    fn render_to_surface(
+        &mut self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+        surface_view: &TextureView,
+        delta_time: f32,
+    ) {
+        self.camera.update(delta_time);
+        self.camera.upload_to_gpu(queue);
+        self.update_render_params(queue);
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Ecosystem Render Encoder"),
+        });
+
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Ecosystem Offscreen Render Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &self.display_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+
+            render_pass.set_bind_group(0, &self.render_bind_group, &[]);
+
+            render_pass.set_pipeline(&self.background_render_pipeline);
+            render_pass.draw(0..6, 0..1);
+
+            // Agents (9 instances per agent for wrapping)
+            render_pass.set_pipeline(&self.agent_render_pipeline);
+            render_pass.draw(0..6, 0..self.agent_capacity() * 9);
+        }
+
+        let tile_count = self.calculate_tile_count();
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Ecosystem Infinite Surface Render Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: surface_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+            render_pass.set_pipeline(&self.render_infinite_pipeline);
+            render_pass.set_bind_group(0, &self.render_infinite_bind_group, &[]);
+            render_pass.set_bind_group(1, &self.camera_bind_group, &[]);
+            render_pass.draw(0..6, 0..tile_count * tile_count);
+        }
+
+        queue.submit(std::iter::once(encoder.finish()));
+    }
+
+    /// Rebuild the agent population and environment from the current settings.
+    /// Agent buffers are recreated when `max_agents` changes.
+    fn reset_population(&mut self, device: &Arc<Device>, queue: &Arc<Queue>) {
+        let max_agents = self.settings.max_agents.max(1);
+        let (agents, status) = Self::initialize_agents(&self.settings, max_agents);
+
+        if max_agents != self.agent_capacity() {
+            self.agent_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Ecosystem Agent Buffer"),
+                contents: bytemuck::cast_slice(&agents),
+                usage: wgpu::BufferUsages::STORAGE
+                    | wgpu::BufferUsages::COPY_DST
+                    | wgpu::BufferUsages::COPY_SRC,
+            });
+            self.status_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Ecosystem Status Buffer"),
+                contents: bytemuck::cast_slice(&status),
+                usage: wgpu::BufferUsages::STORAGE
+                    | wgpu::BufferUsages::COPY_DST
+                    | wgpu::BufferUsages::COPY_SRC,
+            });
+
+            // Bind groups reference the old buffers
+            self.compute_bind_group = Self::create_compute_bind_group(
+                device,
+                &self.compute_bind_group_layout,
+                &self.agent_buffer,
+                &self.status_buffer,
+                &self.food_buffer,
+                &self.scent_buffer,
+                &self.occupancy_buffer,
+                &self.stats_buffer,
+                &self.sim_params_buffer,
+            );
+            self.render_bind_group = Self::create_render_bind_group(
+                device,
+                &self.render_bind_group_layout,
+                &self.agent_buffer,
+                &self.status_buffer,
+                &self.food_buffer,
+                &self.lut_buffer,
+                &self.render_params_buffer,
+            );
+        } else {
+            queue.write_buffer(&self.agent_buffer, 0, bytemuck::cast_slice(&agents));
+            queue.write_buffer(&self.status_buffer, 0, bytemuck::cast_slice(&status));
+        }
+
+        let food = Self::initialize_food(&self.settings);
+        queue.write_buffer(&self.food_buffer, 0, bytemuck::cast_slice(&food));
+        let scent = vec![0u32; (FOOD_GRID_SIZE * FOOD_GRID_SIZE * MAX_SPECIES) as usize];
+        queue.write_buffer(&self.scent_buffer, 0, bytemuck::cast_slice(&scent));
+        queue.write_buffer(
+            &self.stats_buffer,
+            0,
+            bytemuck::cast_slice(&[0u32; STATS_LEN]),
+        );
+
+        self.frame_count = 0;
+        self.state.simulation_time = 0.0;
+        self.state.population.clear();
+        self.state.births = 0;
+        self.state.starvation_deaths = 0;
+        self.state.old_age_deaths = 0;
+        self.state.predation_deaths = 0;
+
+        self.update_sim_params(queue);
+        self.update_render_params(queue);
+    }
+
+    pub fn update_lut(
+        &mut self,
+        _device: &Arc<Device>,
+        queue: &Arc<Queue>,
+        lut_name: &str,
+        lut_reversed: bool,
+    ) -> SimulationResult<()> {
+        let mut lut =
+            self.lut_manager
+                .get(lut_name)
+                .map_err(|e| SimulationError::InvalidSetting {
+                    setting_name: "current_lut".to_string(),
+                    message: format!("Failed to load LUT '{}': {}", lut_name, e),
+                })?;
+
+        if lut_reversed {
+            lut = lut.reversed();
+        }
+
+        queue.write_buffer(
+            &self.lut_buffer,
+            0,
+            bytemuck::cast_slice(&lut.to_u32_buffer()),
+        );
+
+        self.state.current_lut_name = lut_name.to_string();
+        self.state.lut_reversed = lut_reversed;
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for EcosystemModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EcosystemModel")
+            .field("agent_capacity", &self.agent_capacity())
+            .field("settings", &self.settings)
+            .field("state", &self.state)
+            .field("frame_count", &self.frame_count)
+            .finish()
+    }
+}
+
+impl crate::simulations::traits::Simulation for EcosystemModel {
+    fn render_frame(
+        &mut self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+        surface_view: &TextureView,
+        delta_time: f32,
+    ) -> SimulationResult<()> {
+        self.step_simulation(device, queue);
+        self.render_to_surface(device, queue, surface_view, delta_time);
+        Ok(())
+    }
+
+    fn render_frame_static(
+        &mut self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+        surface_view: &TextureView,
+    ) -> SimulationResult<()> {
+        self.render_to_surface(device, queue, surface_view, 0.0);
+        Ok(())
+    }
+
+    fn resize(
+        &mut self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+        new_config: &SurfaceConfiguration,
+    ) -> SimulationResult<()> {
+        self.surface_config = new_config.clone();
+        self.camera
+            .resize(new_config.width as f32, new_config.height as f32);
+
+        let (display_texture, display_view, display_sampler) =
+            Self::create_display_texture(device, new_config, &self.render_settings);
+        self.display_texture = display_texture;
+        self.display_view = display_view;
+        self.display_sampler = display_sampler;
+
+        self.render_infinite_bind_group = Self::create_render_infinite_bind_group(
+            device,
+            &self.render_infinite_bind_group_layout,
+            &self.display_view,
+            &self.display_sampler,
+            &self.texture_render_params_buffer,
+        );
+
+        self.update_render_params(queue);
+        Ok(())
+    }
+
+    fn update_setting(
+        &mut self,
+        setting_name: &str,
+        value: Value,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()> {
+        tracing::debug!(
+            "Ecosystem::update_setting called with setting_name: '{}', value: {:?}",
+            setting_name,
+            value
+        );
+
+        let as_f32 = |value: &Value| -> SimulationResult<f32> {
+            value
+                .as_f64()
+                .map(|v| v as f32)
+                .ok_or_else(|| SimulationError::InvalidSetting {
+                    setting_name: setting_name.to_string(),
+                    message: "Expected a number".to_string(),
+                })
+        };
+        let as_u32 = |value: &Value| -> SimulationResult<u32> {
+            value
+                .as_u64()
+                .map(|v| v as u32)
+                .ok_or_else(|| SimulationError::InvalidSetting {
+                    setting_name: setting_name.to_string(),
+                    message: "Expected a non-negative integer".to_string(),
+                })
+        };
+        fn as_enum<T: serde::de::DeserializeOwned>(
+            setting_name: &str,
+            value: &Value,
+        ) -> SimulationResult<T> {
+            serde_json::from_value(value.clone()).map_err(|e| SimulationError::InvalidSetting {
+                setting_name: setting_name.to_string(),
+                message: e.to_string(),
+            })
+        }
+        let as_string = |value: &Value| -> SimulationResult<String> {
+            value
+                .as_str()
+                .map(|v| v.to_string())
+                .ok_or_else(|| SimulationError::InvalidSetting {
+                    setting_name: setting_name.to_string(),
+                    message: "Expected a string".to_string(),
+                })
+        };
+
+        match setting_name {
+            // Population layout changes respawn the agents
+            "agent_count" => {
+                self.settings.agent_count = as_u32(&value)?;
+                self.reset_population(device, queue);
+            }
+            "max_agents" => {
+                self.settings.max_agents = as_u32(&value)?.max(1);
+                self.reset_population(device, queue);
+            }
+            "species_count" => {
+                self.settings.species_count = as_u32(&value)?.clamp(1, MAX_SPECIES);
+                self.reset_population(device, queue);
+            }
+            "food_chain" => self.settings.food_chain = as_enum(setting_name, &value)?,
+            "agent_size" => self.settings.agent_size = as_f32(&value)?,
+            "agent_speed" => self.settings.agent_speed = as_f32(&value)?,
+            "turn_rate" => self.settings.turn_rate = as_f32(&value)?,
+            "sensor_angle" => self.settings.sensor_angle = as_f32(&value)?,
+            "sensor_distance" => self.settings.sensor_distance = as_f32(&value)?,
+            "wander_strength" => self.settings.wander_strength = as_f32(&value)?,
+            "hunting_strength" => self.settings.hunting_strength = as_f32(&value)?,
+            "fear_strength" => self.settings.fear_strength = as_f32(&value)?,
+            "initial_energy" => self.settings.initial_energy = as_f32(&value)?,
+            "metabolism_rate" => self.settings.metabolism_rate = as_f32(&value)?,
+            "movement_cost" => self.settings.movement_cost = as_f32(&value)?,
+            "feeding_rate" => self.settings.feeding_rate = as_f32(&value)?,
+            "food_energy" => self.settings.food_energy = as_f32(&value)?,
+            "predation_radius" => self.settings.predation_radius = as_f32(&value)?,
+            "predation_efficiency" => self.settings.predation_efficiency = as_f32(&value)?,
+            "reproduction_threshold" => {
+                self.settings.reproduction_threshold = as_f32(&value)?;
+            }
+            "reproduction_share" => {
+                self.settings.reproduction_share = as_f32(&value)?.clamp(0.0, 1.0);
+            }
+            "max_age" => self.settings.max_age = as_f32(&value)?,
+            "food_growth_rate" => self.settings.food_growth_rate = as_f32(&value)?,
+            "food_capacity" => self.settings.food_capacity = as_f32(&value)?,
+            "food_pattern" => self.settings.food_pattern = as_enum(setting_name, &value)?,
+            "scent_deposit" => self.settings.scent_deposit = as_f32(&value)?,
+            "scent_decay_rate" => self.settings.scent_decay_rate = as_f32(&value)?,
+            "food_visibility" => {
+                self.settings.food_visibility = as_f32(&value)?.clamp(0.0, 1.0);
+            }
+            "random_seed" => self.settings.random_seed = as_u32(&value)?,
+            "currentLut" => {
+                let lut_name = as_string(&value)?;
+                self.update_lut(device, queue, &lut_name, self.state.lut_reversed)?;
+            }
+            "lut_reversed" => {
+                if let Some(reversed) = value.as_bool() {
+                    let lut_name = self.state.current_lut_name.clone();
+                    self.update_lut(device, queue, &lut_name, reversed)?;
+                }
+            }
+            "cursor_size" => {
+                self.state.cursor_size = as_f32(&value)?.clamp(0.01, 1.0);
+            }
+            "cursor_strength" => {
+                self.state.cursor_strength = as_f32(&value)?.clamp(0.0, 1.0);
+            }
+            _ => {
+                return Err(SimulationError::InvalidSetting {
+                    setting_name: setting_name.to_string(),
+                    message: "Unknown setting".to_string(),
+                });
+            }
+        }
+
+        self.update_sim_params(queue);
+        self.update_render_params(queue);
+        Ok(())
+    }
+
+    fn get_settings(&self) -> Value {
+        serde_json::to_value(&self.settings).unwrap_or(Value::Null)
+    }
+
+    fn get_state(&self) -> Value {
+        let mut state = serde_json::to_value(&self.state).unwrap_or(Value::Null);
+        if let Value::Object(map) = &mut state {
+            map.insert(
+                "total_population".to_string(),
+                Value::from(self.state.total_population()),
+            );
+        }
+        state
+    }
+
+    fn handle_mouse_interaction(
+        &mut self,
+        world_x: f32,
+        world_y: f32,
+        mouse_button: u32,
+        _device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()> {
+        // The world tiles infinitely, so fold the cursor back into the [-1,1] square
+        let wrap = |v: f32| (v + 1.0).rem_euclid(2.0) - 1.0;
+
+        self.state.mouse_pressed = true;
+        self.state.mouse_mode = match mouse_button {
+            0 => 1, // Left click adds food
+            2 => 2, // Right click removes food
+            _ => 0,
+        };
+        self.state.mouse_position = [wrap(world_x), wrap(world_y)];
+        self.update_sim_params(queue);
+        Ok(())
+    }
+
+    fn handle_mouse_release(
+        &mut self,
+        _mouse_button: u32,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()> {
+        self.state.reset_mouse();
+        self.update_sim_params(queue);
+        Ok(())
+    }
+
+    fn pan_camera(&mut self, delta_x: f32, delta_y: f32) {
+        self.camera.pan(delta_x, delta_y);
+        self.state.camera_position = self.camera.position;
+    }
+
+    fn zoom_camera(&mut self, delta: f32) {
+        self.camera.zoom(delta);
+        self.state.camera_zoom = self.camera.zoom;
+    }
+
+    fn zoom_camera_to_cursor(&mut self, delta: f32, cursor_x: f32, cursor_y: f32) {
+        self.camera.zoom_to_cursor(delta, cursor_x, cursor_y);
+        self.state.camera_position = self.camera.position;
+        self.state.camera_zoom = self.camera.zoom;
+    }
+
+    fn reset_camera(&mut self) {
+        self.camera.reset();
+        self.state.reset_camera();
+    }
+
+    fn get_camera_state(&self) -> Value {
+        serde_json::json!({
+            "position": self.camera.position,
+            "zoom": self.camera.zoom,
+        })
+    }
+
+    fn save_preset(&self, _preset_name: &str) -> SimulationResult<()> {
+        // Presets are saved through the preset manager
+        Ok(())
+    }
+
+    fn load_preset(&mut self, _preset_name: &str, _queue: &Arc<Queue>) -> SimulationResult<()> {
+        // Presets are loaded through the preset manager
+        Ok(())
+    }
+
+    fn apply_settings(
+        &mut self,
+        settings: serde_json::Value,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()> {
+        let mut new_settings: Settings = serde_json::from_value(settings).map_err(|e| {
+            SimulationError::InvalidParameter(format!("Invalid ecosystem settings: {}", e))
+        })?;
+        new_settings.max_agents = new_settings.max_agents.max(1);
+        new_settings.species_count = new_settings.species_count.clamp(1, MAX_SPECIES);
+
+        // The population is laid out for these, so it is respawned when they change
+        let respawn = new_settings.agent_count != self.settings.agent_count
+            || new_settings.max_agents != self.settings.max_agents
+            || new_settings.species_count != self.settings.species_count;
+        self.settings = new_settings;
+        if respawn {
+            self.reset_population(device, queue);
+        } else {
+            self.update_sim_params(queue);
+            self.update_render_params(queue);
+        }
+        Ok(())
+    }
+
+    fn reset_runtime_state(
+        &mut self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()> {
+        self.reset_population(device, queue);
+        self.camera.reset();
+        self.state.reset();
+        Ok(())
+    }
+
+    fn toggle_gui(&mut self) -> bool {
+        self.state.gui_visible = !self.state.gui_visible;
+        self.state.gui_visible
+    }
+
+    fn is_gui_visible(&self) -> bool {
+        self.state.gui_visible
+    }
+
+    fn randomize_settings(
+        &mut self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()> {
+        self.settings.randomize();
+        self.reset_population(device, queue);
+        Ok(())
+    }
+
+    fn save_snapshot(
+        &self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<SimulationSnapshot> {
+        let mut snapshot = SimulationSnapshot::new("ecosystem", self.get_settings())
+            .with_lut(&self.state.current_lut_name, self.state.lut_reversed)
+            .with_camera(&self.camera)
+            .with_state(serde_json::json!({
+                "frame_count": self.frame_count,
+                "simulation_time": self.state.simulation_time,
+                "population": self.state.population,
+                "births": self.state.births,
+                "starvation_deaths": self.state.starvation_deaths,
+                "old_age_deaths": self.state.old_age_deaths,
+                "predation_deaths": self.state.predation_deaths,
+            }));
+        snapshot.capture_buffer("agents", &self.agent_buffer, device, queue)?;
+        snapshot.capture_buffer("status", &self.status_buffer, device, queue)?;
+        snapshot.capture_buffer("food", &self.food_buffer, device, queue)?;
+        snapshot.capture_buffer("scent", &self.scent_buffer, device, queue)?;
+        snapshot.capture_buffer("stats", &self.stats_buffer, device, queue)?;
+        Ok(snapshot)
+    }
+
+    fn load_snapshot(
+        &mut self,
+        snapshot: &SimulationSnapshot,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()> {
+        snapshot.expect_type("ecosystem")?;
+        // Resizes the agent buffers if needed before they are overwritten
+        self.apply_settings(snapshot.settings.clone(), device, queue)?;
+
+        snapshot.restore_buffer("agents", &self.agent_buffer, queue)?;
+        snapshot.restore_buffer("status", &self.status_buffer, queue)?;
+        snapshot.restore_buffer("food", &self.food_buffer, queue)?;
+        snapshot.restore_buffer("scent", &self.scent_buffer, queue)?;
+        snapshot.restore_buffer("stats", &self.stats_buffer, queue)?;
+
+        let state = &snapshot.state;
+        self.frame_count = state["frame_count"].as_u64().unwrap_or(0);
+        self.state.simulation_time = state["simulation_time"].as_f64().unwrap_or(0.0) as f32;
+        self.state.population =
+            serde_json::from_value(state["population"].clone()).unwrap_or_default();
+        let counter = |key: &str| state[key].as_u64().unwrap_or(0) as u32;
+        self.state.births = counter("births");
+        self.state.starvation_deaths = counter("starvation_deaths");
+        self.state.old_age_deaths = counter("old_age_deaths");
+        self.state.predation_deaths = counter("predation_deaths");
+
+        if let Some(camera) = &snapshot.camera {
+            camera.apply_to(&mut self.camera);
+        }
+        // Upload the palette as apply_lut does, falling back to the colors
+        // stored in the snapshot for LUTs that no longer exist
+        match &snapshot.lut {
+            Some(lut) if self.lut_manager.get(&snapshot.lut_name).is_err() => {
+                let lut = if snapshot.lut_reversed {
+                    lut.reversed()
+                } else {
+                    lut.clone()
+                };
+                queue.write_buffer(
+                    &self.lut_buffer,
+                    0,
+                    bytemuck::cast_slice(&lut.to_u32_buffer()),
+                );
+                self.state.current_lut_name = snapshot.lut_name.clone();
+                self.state.lut_reversed = snapshot.lut_reversed;
+            }
+            _ => self.update_lut(device, queue, &snapshot.lut_name, snapshot.lut_reversed)?,
+        }
+        self.update_sim_params(queue);
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+//! # Ecosystem State Module
+//!
+//! Runtime state of the Ecosystem simulation: user interaction, camera and LUT
+//! selection, plus the population statistics read back from the GPU. None of
+//! this is part of a preset; it is rebuilt whenever the simulation resets.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct State {
+    /// Current mouse interaction state
+    pub mouse_pressed: bool,
+    /// 0 = no mouse, 1 = add food (left click), 2 = remove food (right click)
+    pub mouse_mode: u32,
+    pub mouse_position: [f32; 2],
+
+    /// Cursor interaction parameters
+    pub cursor_size: f32,
+    pub cursor_strength: f32,
+
+    /// Current LUT state (runtime)
+    pub current_lut_name: String,
+    pub lut_reversed: bool,
+
+    /// UI visibility state
+    pub gui_visible: bool,
+
+    /// Camera state (position and zoom)
+    pub camera_position: [f32; 2],
+    pub camera_zoom: f32,
+
+    /// Simulation runtime state
+    pub simulation_time: f32,
+
+    /// Living agents per species, as of the last GPU readback
+    pub population: Vec<u32>,
+    /// Cumulative life cycle counters since the last reset
+    pub births: u32,
+    pub starvation_deaths: u32,
+    pub old_age_deaths: u32,
+    pub predation_deaths: u32,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            mouse_pressed: false,
+            mouse_mode: 0,
+            mouse_position: [0.0, 0.0],
+            cursor_size: 0.15,
+            cursor_strength: 1.0,
+            current_lut_name: "MATPLOTLIB_viridis".to_string(),
+            lut_reversed: false,
+            gui_visible: true,
+            camera_position: [0.0, 0.0],
+            camera_zoom: 1.0,
+            simulation_time: 0.0,
+            population: Vec::new(),
+            births: 0,
+            starvation_deaths: 0,
+            old_age_deaths: 0,
+            predation_deaths: 0,
+        }
+    }
+}
+
+impl State {
+    /// Reset interaction, camera and statistics, keeping the LUT and cursor choices
+    pub fn reset(&mut self) {
+        self.reset_mouse();
+        self.reset_camera();
+        self.gui_visible = true;
+        self.simulation_time = 0.0;
+        self.population.clear();
+        self.births = 0;
+        self.starvation_deaths = 0;
+        self.old_age_deaths = 0;
+        self.predation_deaths = 0;
+    }
+
+    /// Reset only the camera state
+    pub fn reset_camera(&mut self) {
+        self.camera_position = [0.0, 0.0];
+        self.camera_zoom = 1.0;
+    }
+
+    /// Reset only the mouse interaction state
+    pub fn reset_mouse(&mut self) {
+        self.mouse_pressed = false;
+        self.mouse_mode = 0;
+        self.mouse_position = [0.0, 0.0];
+    }
+
+    /// Total number of living agents across all species
+    pub fn total_population(&self) -> u32 {
+        self.population.iter().sum()
+    }
+}
@@ -0,0 +1,349 @@
+//! # Ecosystem Testing Module
+//!
+//! Validates the parts of the Ecosystem simulation that tend to break silently:
+//! shader compilation, agreement between the Rust uniform structs and their WGSL
+//! counterparts, the food web encoding that decides who hunts whom, and
+//! respawning when settings the population is laid out for change.
+
+use super::settings::{FoodChain, Settings};
+use super::shaders::{
+    AGENT_POPULATE_SHADER, AGENT_RENDER_SHADER, AGENT_UPDATE_SHADER, BACKGROUND_RENDER_SHADER,
+    ENVIRONMENT_UPDATE_SHADER,
+};
+use super::simulation::{Agent, RenderParams, SimParams, food_web};
+use crate::settings::RenderSettings;
+use crate::simulation::headless::HeadlessRenderer;
+use crate::simulations::traits::{Simulation, SimulationType};
+use std::mem;
+
+/// Test framework for validating Ecosystem shader compilation and pipeline layouts
+struct EcosystemValidator {
+    device: wgpu::Device,
+    _queue: wgpu::Queue,
+}
+
+impl EcosystemValidator {
+    async fn new() -> Self {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+            backends: wgpu::Backends::all(),
+            flags: wgpu::InstanceFlags::default(),
+            backend_options: Default::default(),
+        });
+
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::default(),
+                force_fallback_adapter: false,
+                compatible_surface: None,
+            })
+            .await
+            .expect("Failed to find an appropriate adapter");
+
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: None,
+                    required_features: wgpu::Features::empty(),
+                    required_limits: wgpu::Limits::default(),
+                    memory_hints: Default::default(),
+                },
+                None,
+            )
+            .await
+            .expect("Failed to create device");
+
+        Self {
+            device,
+            _queue: queue,
+        }
+    }
+
+    /// Builds a compute pipeline against the shared compute bind group layout,
+    /// which fails validation if the shader's bindings disagree with it
+    async fn validate_compute_shader(&self, label: &str, source: &str) -> Result<(), String> {
+        self.device.push_error_scope(wgpu::ErrorFilter::Validation);
+
+        let storage = |binding: u32| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only: false },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+
+        let layout = self
+            .device
+            .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Test Compute Bind Group Layout"),
+                entries: &[
+                    storage(0),
+                    storage(1),
+                    storage(2),
+                    storage(3),
+                    storage(4),
+                    storage(5),
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 6,
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: wgpu::BufferSize::new(
+                                mem::size_of::<SimParams>() as u64
+                            ),
+                        },
+                        count: None,
+                    },
+                ],
+            });
+
+        let pipeline_layout = self
+            .device
+            .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Test Compute Pipeline Layout"),
+                bind_group_layouts: &[&layout],
+                push_constant_ranges: &[],
+            });
+
+        let module = self
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some(label),
+                source: wgpu::ShaderSource::Wgsl(source.into()),
+            });
+
+        let _ = self
+            .device
+            .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                module: &module,
+                entry_point: Some("main"),
+                compilation_options: Default::default(),
+                cache: None,
+            });
+
+        match self.device.pop_error_scope().await {
+            Some(error) => Err(format!("{}: {}", label, error)),
+            None => Ok(()),
+        }
+    }
+
+    /// Builds a render pipeline against the shared render bind group layout
+    async fn validate_render_shader(&self, label: &str, source: &str) -> Result<(), String> {
+        self.device.push_error_scope(wgpu::ErrorFilter::Validation);
+
+        let storage = |binding: u32, visibility: wgpu::ShaderStages| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only: true },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let both = wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT;
+
+        let layout = self
+            .device
+            .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Test Render Bind Group Layout"),
+                entries: &[
+                    storage(0, wgpu::ShaderStages::VERTEX),
+                    storage(1, wgpu::ShaderStages::VERTEX),
+                    storage(2, wgpu::ShaderStages::FRAGMENT),
+                    storage(3, both),
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 4,
+                        visibility: both,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: wgpu::BufferSize::new(
+                                mem::size_of::<RenderParams>() as u64
+                            ),
+                        },
+                        count: None,
+                    },
+                ],
+            });
+
+        let pipeline_layout = self
+            .device
+            .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Test Render Pipeline Layout"),
+                bind_group_layouts: &[&layout],
+                push_constant_ranges: &[],
+            });
+
+        let module = self
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some(label),
+                source: wgpu::ShaderSource::Wgsl(source.into()),
+            });
+
+        let _ = self
+            .device
+            .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &module,
+                    entry_point: Some("vs_main"),
+                    buffers: &[],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &module,
+                    entry_point: Some("fs_main"),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: wgpu::TextureFormat::Rgba8Unorm,
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+                cache: None,
+            });
+
+        match self.device.pop_error_scope().await {
+            Some(error) => Err(format!("{}: {}", label, error)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_ecosystem_compute_shaders() {
+    let validator = EcosystemValidator::new().await;
+
+    for (label, source) in [
+        ("Environment Update", ENVIRONMENT_UPDATE_SHADER),
+        ("Agent Populate", AGENT_POPULATE_SHADER),
+        ("Agent Update", AGENT_UPDATE_SHADER),
+    ] {
+        validator
+            .validate_compute_shader(label, source)
+            .await
+            .expect("Compute shader validation failed");
+    }
+}
+
+#[tokio::test]
+async fn test_ecosystem_render_shaders() {
+    let validator = EcosystemValidator::new().await;
+
+    for (label, source) in [
+        ("Background Render", BACKGROUND_RENDER_SHADER),
+        ("Agent Render", AGENT_RENDER_SHADER),
+    ] {
+        validator
+            .validate_render_shader(label, source)
+            .await
+            .expect("Render shader validation failed");
+    }
+}
+
+#[test]
+fn test_struct_sizes() {
+    // Must match the WGSL struct layouts exactly
+    assert_eq!(mem::size_of::<Agent>(), 32);
+    assert_eq!(mem::size_of::<SimParams>(), 176);
+    assert_eq!(mem::size_of::<RenderParams>(), 32);
+    assert_eq!(mem::size_of::<SimParams>() % 16, 0);
+}
+
+#[test]
+fn test_food_chain_web() {
+    let (prey_mask, eats_food) = food_web(FoodChain::Chain, 3);
+    assert_eq!(prey_mask, [0b010, 0b100, 0, 0]);
+    assert_eq!(eats_food, [0, 0, 1, 0]);
+}
+
+#[test]
+fn test_food_cycle_web() {
+    let (prey_mask, eats_food) = food_web(FoodChain::Cycle, 3);
+    assert_eq!(prey_mask, [0b010, 0b100, 0b001, 0]);
+    assert_eq!(eats_food, [1, 1, 1, 0]);
+}
+
+#[test]
+fn test_single_species_grazes() {
+    for chain in [FoodChain::Chain, FoodChain::Cycle] {
+        let (prey_mask, eats_food) = food_web(chain, 1);
+        assert_eq!(prey_mask, [0, 0, 0, 0]);
+        assert_eq!(eats_food, [1, 0, 0, 0]);
+    }
+}
+
+#[test]
+fn test_settings_round_trip() {
+    let settings = Settings::default();
+    let toml = toml::to_string(&settings).expect("Failed to serialize settings");
+    let parsed: Settings = toml::from_str(&toml).expect("Failed to deserialize settings");
+    assert_eq!(parsed.agent_count, settings.agent_count);
+    assert_eq!(parsed.food_chain, settings.food_chain);
+    assert_eq!(parsed.food_pattern, settings.food_pattern);
+}
+
+#[test]
+fn test_settings_reject_unknown_food_web() {
+    let toml = toml::to_string(&Settings::default())
+        .unwrap()
+        .replace("food_chain = \"Chain\"", "food_chain = \"Web\"");
+    assert!(toml::from_str::<Settings>(&toml).is_err());
+}
+
+#[tokio::test]
+async fn test_applied_settings_rebuild_the_population() {
+    let renderer = HeadlessRenderer::new(
+        64,
+        64,
+        wgpu::TextureFormat::Rgba8UnormSrgb,
+        RenderSettings::default(),
+    )
+    .await
+    .expect("Failed to create renderer");
+    let SimulationType::Ecosystem(mut simulation) = renderer
+        .create_simulation("ecosystem")
+        .await
+        .expect("Failed to create simulation")
+    else {
+        unreachable!();
+    };
+    for _ in 0..3 {
+        simulation.step_simulation(&renderer.device, &renderer.queue);
+    }
+
+    // A preset or undo with a different capacity and species count respawns
+    let mut settings = simulation.settings.clone();
+    settings.max_agents += 1000;
+    settings.species_count = if settings.species_count == 1 { 2 } else { 1 };
+    let value = serde_json::to_value(&settings).unwrap();
+    simulation
+        .apply_settings(value, &renderer.device, &renderer.queue)
+        .unwrap();
+    assert_eq!(
+        simulation.agent_buffer.size(),
+        settings.max_agents as u64 * mem::size_of::<Agent>() as u64
+    );
+    assert_eq!(simulation.frame_count, 0);
+
+    // Other changes leave the running population alone
+    simulation.step_simulation(&renderer.device, &renderer.queue);
+    settings.agent_speed /= 2.0;
+    let value = serde_json::to_value(&settings).unwrap();
+    simulation
+        .apply_settings(value, &renderer.device, &renderer.queue)
+        .unwrap();
+    assert_eq!(simulation.frame_count, 1);
+}
@@ -0,0 +1,83 @@
+pub mod settings;
+pub mod shaders;
+pub mod simulation;
+
+#[cfg(test)]
+mod tests;
+
+// pub use simulation::FlowModel; // Not needed for preset functionality
+
+use crate::error::SimulationResult;
+use crate::simulation::preset_manager::{AnyPresetManager, FlowPresetManager, Preset};
+use crate::simulations::registry::{
+    SimulationCapabilities, SimulationContext, SimulationDescriptor,
+};
+use crate::simulations::traits::SimulationType;
+
+/// Initialize Flow presets with built-in configurations
+pub fn init_presets(preset_manager: &mut FlowPresetManager) {
+    use settings::Settings;
+
+    // Add default presets
+    let all_presets = vec![("Default", Settings::default())];
+
+    for (preset_name, settings) in all_presets {
+        preset_manager.add_preset(Preset::new(preset_name.to_string(), settings));
+    }
+
+    // Capture all the built-in preset names we just added
+    preset_manager.capture_built_in_presets();
+
+    // Load user presets from TOML files
+    if let Err(e) = preset_manager.load_user_presets() {
+        eprintln!("Warning: Could not load user presets: {}", e);
+    }
+
+    let preset_count = preset_manager.get_preset_names().len();
+    tracing::info!("Initialized {} Flow presets", preset_count);
+}
+
+/// Registry entry for the Flow simulation
+pub const DESCRIPTOR: SimulationDescriptor = SimulationDescriptor {
+    id: "flow",
+    display_name: "Flow Field",
+    description: "Particle flow through vector fields",
+    hidden: false,
+    capabilities: SimulationCapabilities {
+        camera: true,
+        luts: true,
+        cursor: false,
+        deterministic: false,
+    },
+    create,
+    presets: Some(preset_manager),
+    initial_preset: None,
+    settings_schema: Some(settings::Settings::schema),
+    runtime_settings: &[
+        "background",
+        "currentLut",
+        "lutReversed",
+        "cursor_size",
+        "showParticles",
+        "displayMode",
+        "trailMapFiltering",
+    ],
+};
+
+fn create(context: &SimulationContext) -> SimulationResult<SimulationType> {
+    let simulation = simulation::FlowModel::new(
+        context.device,
+        context.queue,
+        context.surface_config,
+        settings::Settings::default(),
+        context.render_settings,
+        context.lut_manager,
+    )?;
+    Ok(SimulationType::Flow(Box::new(simulation)))
+}
+
+fn preset_manager() -> Box<dyn AnyPresetManager> {
+    let mut preset_manager = FlowPresetManager::new(DESCRIPTOR.id.to_string());
+    init_presets(&mut preset_manager);
+    Box::new(preset_manager)
+}
@@ -1,3 +1,4 @@
+use crate::simulations::shared::SettingField;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
 
@@ -76,6 +77,8 @@ pub enum ParticleShape {
     Circle,
     Square,
     Triangle,
+    // Drawn as a flower, which is the name the frontend uses
+    #[serde(alias = "Flower")]
     Star,
     Diamond,
 }
@@ -212,3 +215,107 @@ impl Default for Settings {
         }
     }
 }
+
+impl Settings {
+    /// Fields of these settings with the ranges offered in the UI
+    pub fn schema() -> Vec<SettingField> {
+        vec![
+            SettingField::enumeration(
+                "noise_type",
+                &[
+                    "OpenSimplex",
+                    "Worley",
+                    "Value",
+                    "Fbm",
+                    "FBMBillow",
+                    "FBMClouds",
+                    "FBMRidged",
+                    "Billow",
+                    "RidgedMulti",
+                    "Cylinders",
+                    "Checkerboard",
+                ],
+            )
+            .setting_name("noiseType")
+            .group("Flow Field")
+            .description("Noise function the flow field is sampled from"),
+            SettingField::seed("noise_seed")
+                .setting_name("noiseSeed")
+                .group("Flow Field")
+                .description("Seed of the noise function"),
+            SettingField::float("noise_scale", 0.001, 10.0, 0.01)
+                .setting_name("noiseScale")
+                .group("Flow Field")
+                .description("Zoom of the noise; larger values give finer detail"),
+            SettingField::float("noise_x", -1000.0, 1000.0, 1.0)
+                .setting_name("noiseX")
+                .group("Flow Field")
+                .description("Horizontal offset into the noise"),
+            SettingField::float("noise_y", -1000.0, 1000.0, 1.0)
+                .setting_name("noiseY")
+                .group("Flow Field")
+                .description("Vertical offset into the noise"),
+            SettingField::float("noise_dt_multiplier", 0.0, 10.0, 0.1)
+                .setting_name("noiseDtMultiplier")
+                .group("Flow Field")
+                .description("How quickly the flow field evolves over time"),
+            SettingField::float("vector_magnitude", 0.001, 5.0, 0.1)
+                .setting_name("vectorMagnitude")
+                .group("Flow Field")
+                .description("Strength of the flow vectors"),
+            SettingField::integer("total_pool_size", 1, 1_000_000)
+                .step(1000)
+                .setting_name("autospawnLimit")
+                .group("Particles")
+                .description("Number of particles shared by autospawn and the brush"),
+            SettingField::float("particle_lifetime", 0.1, 60.0, 0.1)
+                .setting_name("particleLifetime")
+                .group("Particles")
+                .description("Seconds a particle lives before respawning"),
+            SettingField::float("particle_speed", 0.001, 100.0, 0.001)
+                .setting_name("particleSpeed")
+                .group("Particles")
+                .description("How far particles follow the flow each second"),
+            SettingField::integer("particle_size", 1, 50)
+                .setting_name("particleSize")
+                .group("Particles")
+                .description("Particle size in pixels"),
+            // Presets store the star as "Star", the frontend sends "Flower"
+            SettingField::enumeration(
+                "particle_shape",
+                &["Circle", "Square", "Triangle", "Star", "Flower", "Diamond"],
+            )
+            .setting_name("particleShape")
+            .group("Particles")
+            .description("Shape particles are drawn with"),
+            SettingField::bool("particle_autospawn")
+                .setting_name("particleAutospawn")
+                .group("Particles")
+                .description("Continuously spawn particles at random positions"),
+            SettingField::integer("autospawn_rate", 0, 10000)
+                .setting_name("autospawnRate")
+                .group("Particles")
+                .description("Particles spawned per second by autospawn"),
+            SettingField::integer("brush_spawn_rate", 1, 10000)
+                .setting_name("brushSpawnRate")
+                .group("Particles")
+                .description("Particles spawned per second under the cursor"),
+            SettingField::float("trail_decay_rate", 0.0, 1.0, 0.001)
+                .setting_name("trailDecayRate")
+                .group("Trails")
+                .description("How quickly trails fade"),
+            SettingField::float("trail_deposition_rate", 0.0, 1.0, 0.01)
+                .setting_name("trailDepositionRate")
+                .group("Trails")
+                .description("How much trail particles leave behind"),
+            SettingField::float("trail_diffusion_rate", 0.0, 1.0, 0.01)
+                .setting_name("trailDiffusionRate")
+                .group("Trails")
+                .description("How quickly trails blur"),
+            SettingField::float("trail_wash_out_rate", 0.0, 1.0, 0.01)
+                .setting_name("trailWashOutRate")
+                .group("Trails")
+                .description("How quickly saturated trails fade back toward the background"),
+        ]
+    }
+}
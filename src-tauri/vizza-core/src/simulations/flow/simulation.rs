@@ -4,11 +4,12 @@ use super::shaders::{
     PARTICLE_UPDATE_SHADER, RENDER_INFINITE_SHADER, SHAPE_DRAWING_SHADER,
     TRAIL_DECAY_DIFFUSION_SHADER, TRAIL_RENDER_SHADER,
 };
-use crate::commands::AppSettings;
+use crate::settings::RenderSettings;
 use crate::simulations::shared::camera::Camera;
 use crate::simulations::shared::{
     AverageColorResources, BindGroupBuilder, CommonBindGroupLayouts, ComputePipelineBuilder,
-    LutManager, PostProcessingResources, PostProcessingState, ShaderManager,
+    LutManager, PostProcessingResources, PostProcessingState, ShaderManager, SimulationSnapshot,
+    random,
 };
 use crate::simulations::traits::Simulation;
 use bytemuck::{Pod, Zeroable};
@@ -223,7 +224,7 @@ pub struct FlowModel {
     // Post-processing state and resources
     pub post_processing_state: PostProcessingState,
     pub post_processing_resources: PostProcessingResources,
-    pub app_settings: AppSettings,
+    pub render_settings: RenderSettings,
 
     // GPU flow vector generation
     pub flow_vector_compute_pipeline: wgpu::ComputePipeline,
@@ -332,7 +333,7 @@ impl FlowModel {
         queue: &Arc<Queue>,
         surface_config: &SurfaceConfiguration,
         settings: Settings,
-        app_settings: &AppSettings,
+        render_settings: &RenderSettings,
         lut_manager: &LutManager,
     ) -> Result<Self, crate::error::SimulationError> {
         // Initialize camera
@@ -386,7 +387,9 @@ impl FlowModel {
         let particle_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Particle Buffer"),
             contents: bytemuck::cast_slice(&particles),
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
         });
 
         let flow_vector_buffer = device.create_buffer(&wgpu::BufferDescriptor {
@@ -486,7 +489,8 @@ impl FlowModel {
             usage: wgpu::TextureUsages::STORAGE_BINDING
                 | wgpu::TextureUsages::TEXTURE_BINDING
                 | wgpu::TextureUsages::RENDER_ATTACHMENT
-                | wgpu::TextureUsages::COPY_DST,
+                | wgpu::TextureUsages::COPY_DST
+                | wgpu::TextureUsages::COPY_SRC,
             view_formats: &[],
         });
 
@@ -502,9 +506,9 @@ impl FlowModel {
             address_mode_u: wgpu::AddressMode::ClampToEdge,
             address_mode_v: wgpu::AddressMode::ClampToEdge,
             address_mode_w: wgpu::AddressMode::ClampToEdge,
-            mag_filter: app_settings.texture_filtering.into(),
-            min_filter: app_settings.texture_filtering.into(),
-            mipmap_filter: app_settings.texture_filtering.into(),
+            mag_filter: render_settings.texture_filtering.into(),
+            min_filter: render_settings.texture_filtering.into(),
+            mipmap_filter: render_settings.texture_filtering.into(),
             ..Default::default()
         });
 
@@ -1225,9 +1229,9 @@ impl FlowModel {
             address_mode_u: wgpu::AddressMode::ClampToEdge,
             address_mode_v: wgpu::AddressMode::ClampToEdge,
             address_mode_w: wgpu::AddressMode::ClampToEdge,
-            mag_filter: app_settings.texture_filtering.into(),
-            min_filter: app_settings.texture_filtering.into(),
-            mipmap_filter: app_settings.texture_filtering.into(),
+            mag_filter: render_settings.texture_filtering.into(),
+            min_filter: render_settings.texture_filtering.into(),
+            mipmap_filter: render_settings.texture_filtering.into(),
             ..Default::default()
         });
 
@@ -1555,7 +1559,7 @@ impl FlowModel {
             average_color_uniform_buffer,
             post_processing_state: PostProcessingState::default(),
             post_processing_resources: PostProcessingResources::new(device, surface_config)?,
-            app_settings: app_settings.clone(),
+            render_settings: render_settings.clone(),
 
             // GPU flow vector generation
             flow_vector_compute_pipeline,
@@ -2162,7 +2166,8 @@ impl Simulation for FlowModel {
             usage: wgpu::TextureUsages::STORAGE_BINDING
                 | wgpu::TextureUsages::TEXTURE_BINDING
                 | wgpu::TextureUsages::RENDER_ATTACHMENT
-                | wgpu::TextureUsages::COPY_DST,
+                | wgpu::TextureUsages::COPY_DST
+                | wgpu::TextureUsages::COPY_SRC,
             view_formats: &[],
         });
 
@@ -2465,9 +2470,9 @@ impl Simulation for FlowModel {
             address_mode_u: wgpu::AddressMode::ClampToEdge,
             address_mode_v: wgpu::AddressMode::ClampToEdge,
             address_mode_w: wgpu::AddressMode::ClampToEdge,
-            mag_filter: self.app_settings.texture_filtering.into(),
-            min_filter: self.app_settings.texture_filtering.into(),
-            mipmap_filter: self.app_settings.texture_filtering.into(),
+            mag_filter: self.render_settings.texture_filtering.into(),
+            min_filter: self.render_settings.texture_filtering.into(),
+            mipmap_filter: self.render_settings.texture_filtering.into(),
             ..Default::default()
         });
 
@@ -3020,7 +3025,7 @@ impl Simulation for FlowModel {
                         "Circle" => super::settings::ParticleShape::Circle,
                         "Square" => super::settings::ParticleShape::Square,
                         "Triangle" => super::settings::ParticleShape::Triangle,
-                        "Flower" => super::settings::ParticleShape::Star,
+                        "Star" | "Flower" => super::settings::ParticleShape::Star,
                         "Diamond" => super::settings::ParticleShape::Diamond,
                         _ => super::settings::ParticleShape::Circle,
                     };
@@ -3072,7 +3077,12 @@ impl Simulation for FlowModel {
                     self.update_trail_sampler(device);
                 }
             }
-            _ => {}
+            _ => {
+                return Err(crate::error::SimulationError::InvalidSetting {
+                    setting_name: setting_name.to_string(),
+                    message: "Unknown setting".to_string(),
+                });
+            }
         }
 
         // After handling the specific setting, always update the GPU uniform so changes take effect immediately
@@ -3343,7 +3353,7 @@ impl Simulation for FlowModel {
         device: &Arc<Device>,
         queue: &Arc<Queue>,
     ) -> crate::error::SimulationResult<()> {
-        let mut rng = rand::rng();
+        let mut rng = random::rng();
 
         // Randomize noise type
         let noise_types = [
@@ -3372,6 +3382,64 @@ impl Simulation for FlowModel {
 
         Ok(())
     }
+
+    fn save_snapshot(
+        &self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> crate::error::SimulationResult<SimulationSnapshot> {
+        let mut snapshot = SimulationSnapshot::new("flow", self.get_settings())
+            .with_lut(&self.current_lut, self.lut_reversed)
+            .with_camera(&self.camera)
+            .with_state(serde_json::json!({
+                "background": self.background,
+                "showParticles": self.show_particles,
+                "displayMode": self.display_mode,
+                "time": self.time,
+                "autospawnAccumulator": self.autospawn_accumulator,
+                "brushSpawnAccumulator": self.brush_spawn_accumulator,
+            }));
+        snapshot.capture_buffer("particles", &self.particle_buffer, device, queue)?;
+        snapshot.capture_texture("trail", &self.trail_texture, device, queue)?;
+        Ok(snapshot)
+    }
+
+    fn load_snapshot(
+        &mut self,
+        snapshot: &SimulationSnapshot,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> crate::error::SimulationResult<()> {
+        snapshot.expect_type("flow")?;
+        let state = &snapshot.state;
+
+        // The background color depends on the LUT, so set both before the
+        // settings are applied
+        self.current_lut = snapshot.lut_name.clone();
+        self.lut_reversed = snapshot.lut_reversed;
+        if let Ok(background) = serde_json::from_value(state["background"].clone()) {
+            self.background = background;
+        }
+        if let Ok(display_mode) = serde_json::from_value(state["displayMode"].clone()) {
+            self.display_mode = display_mode;
+        }
+        if let Some(show_particles) = state["showParticles"].as_bool() {
+            self.show_particles = show_particles;
+        }
+        self.apply_settings(snapshot.settings.clone(), device, queue)?;
+        snapshot.restore_buffer("particles", &self.particle_buffer, queue)?;
+        snapshot.restore_texture("trail", &self.trail_texture, queue)?;
+
+        self.time = state["time"].as_f64().unwrap_or(0.0) as f32;
+        self.autospawn_accumulator = state["autospawnAccumulator"].as_f64().unwrap_or(0.0) as f32;
+        self.brush_spawn_accumulator =
+            state["brushSpawnAccumulator"].as_f64().unwrap_or(0.0) as f32;
+
+        if let Some(camera) = &snapshot.camera {
+            camera.apply_to(&mut self.camera);
+        }
+        Ok(())
+    }
 }
 
 impl FlowModel {
@@ -3514,9 +3582,9 @@ impl FlowModel {
             address_mode_u: wgpu::AddressMode::ClampToEdge,
             address_mode_v: wgpu::AddressMode::ClampToEdge,
             address_mode_w: wgpu::AddressMode::ClampToEdge,
-            mag_filter: self.app_settings.texture_filtering.into(),
-            min_filter: self.app_settings.texture_filtering.into(),
-            mipmap_filter: self.app_settings.texture_filtering.into(),
+            mag_filter: self.render_settings.texture_filtering.into(),
+            min_filter: self.render_settings.texture_filtering.into(),
+            mipmap_filter: self.render_settings.texture_filtering.into(),
             ..Default::default()
         });
     }
@@ -0,0 +1,39 @@
+pub mod shaders;
+pub mod simulation;
+
+pub use simulation::GradientSimulation;
+
+use crate::error::SimulationResult;
+use crate::simulations::registry::{
+    SimulationCapabilities, SimulationContext, SimulationDescriptor,
+};
+use crate::simulations::traits::SimulationType;
+
+/// Registry entry for the gradient editor, which previews a LUT full screen
+pub const DESCRIPTOR: SimulationDescriptor = SimulationDescriptor {
+    id: "gradient",
+    display_name: "Gradient Editor",
+    description: "Advanced color gradient editor",
+    hidden: false,
+    capabilities: SimulationCapabilities {
+        camera: false,
+        luts: true,
+        cursor: false,
+        deterministic: true,
+    },
+    create,
+    presets: None,
+    initial_preset: None,
+    settings_schema: None,
+    runtime_settings: &[],
+};
+
+fn create(context: &SimulationContext) -> SimulationResult<SimulationType> {
+    let simulation = GradientSimulation::new(
+        context.device,
+        context.queue,
+        context.surface_config.format,
+        context.render_settings,
+    );
+    Ok(SimulationType::Gradient(Box::new(simulation)))
+}
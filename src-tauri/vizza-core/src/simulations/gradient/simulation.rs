@@ -1,7 +1,9 @@
-use crate::commands::app_settings::AppSettings;
 use crate::error::SimulationResult;
+use crate::settings::RenderSettings;
 use crate::simulations::gradient::shaders::GRADIENT_SHADER;
-use crate::simulations::shared::{BindGroupBuilder, RenderPipelineBuilder, lut::LutData};
+use crate::simulations::shared::{
+    BindGroupBuilder, RenderPipelineBuilder, SimulationSnapshot, lut::LutData,
+};
 use crate::simulations::traits::Simulation;
 use serde_json::Value;
 use std::sync::Arc;
@@ -32,7 +34,7 @@ impl GradientSimulation {
         device: &Device,
         queue: &Queue,
         format: TextureFormat,
-        _app_settings: &AppSettings,
+        _render_settings: &RenderSettings,
     ) -> Self {
         // Create vertex buffer for a full-screen quad
         let vertices: [f32; 16] = [
@@ -359,4 +361,27 @@ impl Simulation for GradientSimulation {
         // No settings to randomize for gradient simulation
         Ok(())
     }
+
+    fn save_snapshot(
+        &self,
+        _device: &Arc<Device>,
+        _queue: &Arc<Queue>,
+    ) -> crate::error::SimulationResult<SimulationSnapshot> {
+        // The gradient being edited is the only state worth keeping
+        let mut snapshot = SimulationSnapshot::new("gradient", self.get_settings());
+        if let Some(lut) = &self.current_lut {
+            snapshot = snapshot.with_lut(&lut.name, false);
+            snapshot.lut = Some(lut.clone());
+        }
+        Ok(snapshot)
+    }
+
+    fn load_snapshot(
+        &mut self,
+        snapshot: &SimulationSnapshot,
+        _device: &Arc<Device>,
+        _queue: &Arc<Queue>,
+    ) -> crate::error::SimulationResult<()> {
+        snapshot.expect_type("gradient")
+    }
 }
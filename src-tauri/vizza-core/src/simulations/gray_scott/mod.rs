@@ -8,7 +8,12 @@ mod tests;
 
 pub use simulation::GrayScottModel;
 
-use crate::simulation::preset_manager::{GrayScottPresetManager, Preset};
+use crate::error::SimulationResult;
+use crate::simulation::preset_manager::{AnyPresetManager, GrayScottPresetManager, Preset};
+use crate::simulations::registry::{
+    SimulationCapabilities, SimulationContext, SimulationDescriptor,
+};
+use crate::simulations::traits::SimulationType;
 
 /// Initialize Gray-Scott presets with built-in configurations
 pub fn init_presets(preset_manager: &mut GrayScottPresetManager) {
@@ -58,3 +63,42 @@ pub fn init_presets(preset_manager: &mut GrayScottPresetManager) {
     let preset_count = preset_manager.get_preset_names().len();
     tracing::info!("Initialized {} Gray-Scott presets", preset_count);
 }
+
+/// Registry entry for the Gray-Scott simulation
+pub const DESCRIPTOR: SimulationDescriptor = SimulationDescriptor {
+    id: "gray_scott",
+    display_name: "Gray-Scott",
+    description: "Reaction-diffusion simulation",
+    hidden: false,
+    capabilities: SimulationCapabilities {
+        camera: true,
+        luts: true,
+        cursor: false,
+        deterministic: true,
+    },
+    create,
+    presets: Some(preset_manager),
+    initial_preset: None,
+    settings_schema: Some(settings::Settings::schema),
+    runtime_settings: &["cursor_size", "cursor_strength"],
+};
+
+fn create(context: &SimulationContext) -> SimulationResult<SimulationType> {
+    let simulation = GrayScottModel::new(
+        context.device,
+        context.queue,
+        context.surface_config,
+        context.surface_config.width,
+        context.surface_config.height,
+        settings::Settings::default(),
+        context.lut_manager,
+        context.render_settings,
+    )?;
+    Ok(SimulationType::GrayScott(Box::new(simulation)))
+}
+
+fn preset_manager() -> Box<dyn AnyPresetManager> {
+    let mut preset_manager = GrayScottPresetManager::new(DESCRIPTOR.id.to_string());
+    init_presets(&mut preset_manager);
+    Box::new(preset_manager)
+}
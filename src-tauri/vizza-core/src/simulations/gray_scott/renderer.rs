@@ -51,7 +51,7 @@ impl Renderer {
         width: u32,
         height: u32,
         lut_manager: &crate::simulations::shared::LutManager,
-        app_settings: &crate::commands::app_settings::AppSettings,
+        render_settings: &crate::settings::RenderSettings,
     ) -> SimulationResult<Self> {
         let settings = Settings::default();
 
@@ -73,10 +73,10 @@ impl Renderer {
             });
 
         // Create render parameters buffer
-        let filtering_mode = match app_settings.texture_filtering {
-            crate::commands::app_settings::TextureFiltering::Nearest => 0u32,
-            crate::commands::app_settings::TextureFiltering::Linear => 1u32,
-            crate::commands::app_settings::TextureFiltering::Lanczos => 2u32,
+        let filtering_mode = match render_settings.texture_filtering {
+            crate::settings::TextureFiltering::Nearest => 0u32,
+            crate::settings::TextureFiltering::Linear => 1u32,
+            crate::settings::TextureFiltering::Lanczos => 2u32,
         };
         let render_params = [filtering_mode, 0u32, 0u32, 0u32];
         let render_params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
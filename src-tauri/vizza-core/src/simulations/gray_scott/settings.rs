@@ -1,3 +1,4 @@
+use crate::simulations::shared::SettingField;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -136,7 +137,7 @@ impl Settings {
     /// Randomize all settings within reasonable bounds
     pub fn randomize(&mut self) {
         use rand::Rng;
-        let mut rng = rand::rng();
+        let mut rng = crate::simulations::shared::random::rng();
 
         self.feed_rate = rng.random_range(0.02..0.08);
         self.kill_rate = rng.random_range(0.04..0.08);
@@ -160,4 +161,58 @@ impl Settings {
 
         self.nutrient_pattern_reversed = rng.random_bool(0.5);
     }
+    /// Fields of these settings with the ranges offered in the UI
+    pub fn schema() -> Vec<SettingField> {
+        vec![
+            SettingField::float("feed_rate", 0.0, 0.1, 0.001)
+                .group("Reaction")
+                .description("Rate at which chemical U is replenished"),
+            SettingField::float("kill_rate", 0.0, 0.1, 0.001)
+                .group("Reaction")
+                .description("Rate at which chemical V is removed"),
+            SettingField::float("diffusion_rate_u", 0.0, 1.0, 0.001)
+                .group("Diffusion")
+                .description("How quickly chemical U spreads"),
+            SettingField::float("diffusion_rate_v", 0.0, 1.0, 0.001)
+                .group("Diffusion")
+                .description("How quickly chemical V spreads"),
+            SettingField::float("timestep", 0.1, 3.0, 0.1)
+                .group("Reaction")
+                .description("Simulated time advanced per update"),
+            SettingField::enumeration(
+                "nutrient_pattern",
+                &[
+                    "Uniform",
+                    "Checkerboard",
+                    "Diagonal Gradient",
+                    "Radial Gradient",
+                    "Vertical Stripes",
+                    "Horizontal Stripes",
+                    "Enhanced Noise",
+                    "Wave Function",
+                    "Cosine Grid",
+                ],
+            )
+            .group("Nutrients")
+            .description("Spatial pattern that scales the feed rate"),
+            SettingField::bool("nutrient_pattern_reversed")
+                .group("Nutrients")
+                .description("Invert the nutrient pattern"),
+            SettingField::float("max_timestep", 0.1, 10.0, 0.1)
+                .group("Optimization")
+                .description("Largest timestep the adaptive timestep may use"),
+            SettingField::float("stability_factor", 0.0, 1.0, 0.01)
+                .group("Optimization")
+                .description("Safety margin applied to the adaptive timestep"),
+            SettingField::bool("enable_adaptive_timestep")
+                .group("Optimization")
+                .description("Grow the timestep while the reaction is stable"),
+            SettingField::float("change_threshold", 0.0, 0.1, 0.0001)
+                .group("Optimization")
+                .description("Smallest change a cell needs to keep being updated"),
+            SettingField::bool("enable_selective_updates")
+                .group("Optimization")
+                .description("Skip cells that have stopped changing"),
+        ]
+    }
 }
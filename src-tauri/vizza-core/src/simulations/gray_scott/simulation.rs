@@ -1,5 +1,6 @@
 use crate::error::{SimulationError, SimulationResult};
 use bytemuck::{Pod, Zeroable};
+use rand::Rng;
 use serde_json::Value;
 use std::sync::Arc;
 use wgpu::util::DeviceExt;
@@ -10,6 +11,7 @@ use super::settings::{NutrientPattern, Settings};
 use super::shaders::REACTION_DIFFUSION_SHADER;
 use super::shaders::noise_seed::NoiseSeedCompute;
 use crate::simulations::shared::coordinates::TextureCoords;
+use crate::simulations::shared::{SimulationSnapshot, random};
 
 #[repr(C)]
 #[derive(Copy, Clone, Pod, Zeroable)]
@@ -102,7 +104,7 @@ impl GrayScottModel {
         height: u32,
         settings: Settings,
         lut_manager: &crate::simulations::shared::LutManager,
-        app_settings: &crate::commands::app_settings::AppSettings,
+        render_settings: &crate::settings::RenderSettings,
     ) -> SimulationResult<Self> {
         let vec_capacity = (width * height) as usize;
         let mut uvs: Vec<UVPair> =
@@ -292,7 +294,7 @@ impl GrayScottModel {
             width,
             height,
             lut_manager,
-            app_settings,
+            render_settings,
         )?;
         let noise_seed_compute = NoiseSeedCompute::new(device);
 
@@ -414,7 +416,7 @@ impl GrayScottModel {
         queue: &Arc<Queue>,
     ) -> SimulationResult<()> {
         // Generate a random seed for this noise generation
-        let seed = rand::random::<u32>();
+        let seed = random::rng().random::<u32>();
 
         // Use GPU-based noise seeding for both buffers
         for buffer in &self.uvs_buffers {
@@ -521,7 +523,12 @@ impl GrayScottModel {
                     self.cursor_strength = v as f32;
                 }
             }
-            _ => {}
+            _ => {
+                return Err(SimulationError::InvalidSetting {
+                    setting_name: setting_name.to_string(),
+                    message: "Unknown setting".to_string(),
+                });
+            }
         }
 
         // Update params buffer
@@ -555,12 +562,9 @@ impl GrayScottModel {
         device: &Arc<Device>,
         queue: &Arc<Queue>,
         surface_view: &TextureView,
-        _delta_time: f32,
+        delta_time: f32,
     ) -> SimulationResult<()> {
-        // Calculate delta time
-        let now = std::time::Instant::now();
-        let delta_time = now.duration_since(self.last_frame_time).as_secs_f32();
-        self.last_frame_time = now;
+        self.last_frame_time = std::time::Instant::now();
 
         // Update camera for smooth movement
         self.renderer.camera.update(delta_time);
@@ -928,11 +932,12 @@ impl crate::simulations::traits::Simulation for GrayScottModel {
 
     fn reset_runtime_state(
         &mut self,
-        _device: &Arc<Device>,
-        _queue: &Arc<Queue>,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
     ) -> SimulationResult<()> {
-        // No-op for Gray-Scott
-        Ok(())
+        // Start over from fresh noise, as the mode does when it opens
+        self.reset();
+        self.seed_random_noise(device, queue)
     }
 
     fn toggle_gui(&mut self) -> bool {
@@ -952,4 +957,37 @@ impl crate::simulations::traits::Simulation for GrayScottModel {
         self.update_settings(self.settings.clone(), queue);
         Ok(())
     }
+
+    fn save_snapshot(
+        &self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<SimulationSnapshot> {
+        let mut snapshot = SimulationSnapshot::new("gray_scott", self.get_settings())
+            .with_lut(&self.current_lut_name, self.lut_reversed)
+            .with_camera(&self.renderer.camera);
+        // The current buffer holds the output of the last step
+        snapshot.capture_buffer("uvs", &self.uvs_buffers[self.current_buffer], device, queue)?;
+        Ok(snapshot)
+    }
+
+    fn load_snapshot(
+        &mut self,
+        snapshot: &SimulationSnapshot,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()> {
+        snapshot.expect_type("gray_scott")?;
+        self.apply_settings(snapshot.settings.clone(), device, queue)?;
+        for buffer in &self.uvs_buffers {
+            snapshot.restore_buffer("uvs", buffer, queue)?;
+        }
+
+        if let Some(camera) = &snapshot.camera {
+            camera.apply_to(&mut self.renderer.camera);
+        }
+        self.current_lut_name = snapshot.lut_name.clone();
+        self.lut_reversed = snapshot.lut_reversed;
+        Ok(())
+    }
 }
@@ -0,0 +1,39 @@
+pub mod shaders;
+pub mod simulation;
+
+pub use simulation::MainMenuModel;
+
+use crate::error::SimulationResult;
+use crate::simulations::registry::{
+    SimulationCapabilities, SimulationContext, SimulationDescriptor,
+};
+use crate::simulations::traits::SimulationType;
+
+/// Registry entry for the animated background behind the main menu
+pub const DESCRIPTOR: SimulationDescriptor = SimulationDescriptor {
+    id: "main_menu",
+    display_name: "Main Menu Background",
+    description: "Animated background behind the main menu",
+    hidden: true,
+    capabilities: SimulationCapabilities {
+        camera: false,
+        luts: false,
+        cursor: false,
+        deterministic: true,
+    },
+    create,
+    presets: None,
+    initial_preset: None,
+    settings_schema: None,
+    runtime_settings: &[],
+};
+
+fn create(context: &SimulationContext) -> SimulationResult<SimulationType> {
+    let simulation = MainMenuModel::new(
+        context.device,
+        context.surface_config,
+        context.lut_manager,
+        context.render_settings,
+    )?;
+    Ok(SimulationType::MainMenu(Box::new(simulation)))
+}
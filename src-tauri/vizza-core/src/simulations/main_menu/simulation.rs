@@ -1,7 +1,7 @@
-use crate::commands::app_settings::AppSettings;
 use crate::error::SimulationResult;
+use crate::settings::RenderSettings;
 use crate::simulations::shared::{
-    BindGroupBuilder, CommonBindGroupLayouts, LutManager, RenderPipelineBuilder,
+    BindGroupBuilder, CommonBindGroupLayouts, LutManager, RenderPipelineBuilder, SimulationSnapshot,
 };
 use crate::simulations::traits::Simulation;
 use serde_json::Value;
@@ -19,7 +19,7 @@ pub struct MainMenuModel {
     start_time: Instant,
     gui_visible: bool,
     // App settings for consistency
-    _app_settings: AppSettings,
+    _render_settings: RenderSettings,
 }
 
 impl MainMenuModel {
@@ -27,7 +27,7 @@ impl MainMenuModel {
         device: &Arc<Device>,
         surface_config: &SurfaceConfiguration,
         lut_manager: &LutManager,
-        _app_settings: &AppSettings,
+        _render_settings: &RenderSettings,
     ) -> SimulationResult<Self> {
         // Create common layouts
         let common_layouts = CommonBindGroupLayouts::new(device);
@@ -104,7 +104,7 @@ impl MainMenuModel {
             lut_bind_group,
             start_time,
             gui_visible: false,
-            _app_settings: _app_settings.clone(),
+            _render_settings: _render_settings.clone(),
         })
     }
 
@@ -314,4 +314,23 @@ impl Simulation for MainMenuModel {
         // No settings to randomize for this simulation
         Ok(())
     }
+
+    fn save_snapshot(
+        &self,
+        _device: &Arc<Device>,
+        _queue: &Arc<Queue>,
+    ) -> SimulationResult<SimulationSnapshot> {
+        // The background animation is driven by wall-clock time, so there is
+        // no state to capture
+        Ok(SimulationSnapshot::new("main_menu", self.get_settings()))
+    }
+
+    fn load_snapshot(
+        &mut self,
+        snapshot: &SimulationSnapshot,
+        _device: &Arc<Device>,
+        _queue: &Arc<Queue>,
+    ) -> SimulationResult<()> {
+        snapshot.expect_type("main_menu")
+    }
 }
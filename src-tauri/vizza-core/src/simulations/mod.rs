@@ -18,12 +18,14 @@
 //! The unified interface enables users to seamlessly transition between
 //! different types of complex system exploration.
 
+pub mod ecosystem;
 pub mod flow;
 pub mod gradient;
 pub mod gray_scott;
 pub mod main_menu;
 pub mod particle_life;
 pub mod pellets;
+pub mod registry;
 pub mod shared;
 pub mod slime_mold;
 pub mod traits;
@@ -0,0 +1,96 @@
+pub mod matrix_operations;
+pub mod settings;
+pub mod shaders;
+pub mod simulation;
+
+#[cfg(test)]
+mod tests;
+
+pub use simulation::ParticleLifeModel;
+
+use crate::error::SimulationResult;
+use crate::simulation::preset_manager::{AnyPresetManager, ParticleLifePresetManager, Preset};
+use crate::simulations::registry::{
+    SimulationCapabilities, SimulationContext, SimulationDescriptor,
+};
+use crate::simulations::traits::SimulationType;
+
+/// Initialize Particle Life presets with built-in configurations
+pub fn init_presets(preset_manager: &mut ParticleLifePresetManager) {
+    use settings::Settings;
+
+    // Add default presets
+    let all_presets = vec![("Default", Settings::default())];
+
+    for (preset_name, settings) in all_presets {
+        preset_manager.add_preset(Preset::new(preset_name.to_string(), settings));
+    }
+
+    // Capture all the built-in preset names we just added
+    preset_manager.capture_built_in_presets();
+
+    // Load user presets from TOML files
+    if let Err(e) = preset_manager.load_user_presets() {
+        eprintln!("Warning: Could not load user presets: {}", e);
+    }
+
+    let preset_count = preset_manager.get_preset_names().len();
+    tracing::info!("Initialized {} Particle Life presets", preset_count);
+}
+
+/// Registry entry for the Particle Life simulation
+pub const DESCRIPTOR: SimulationDescriptor = SimulationDescriptor {
+    id: "particle_life",
+    display_name: "Particle Life",
+    description: "Multi-species particle simulation",
+    hidden: false,
+    capabilities: SimulationCapabilities {
+        camera: true,
+        luts: true,
+        cursor: true,
+        deterministic: false,
+    },
+    create,
+    presets: Some(preset_manager),
+    // Applied on start so the species matrix is in a consistent initial state
+    initial_preset: Some("Default"),
+    settings_schema: Some(settings::Settings::schema),
+    runtime_settings: &[
+        "particle_count",
+        "particle_size",
+        "dt",
+        "random_seed",
+        "position_generator",
+        "type_generator",
+        "matrix_generator",
+        "color_mode",
+        "lut_name",
+        "lut_reversed",
+        "traces_enabled",
+        "trace_fade",
+        "edge_fade_strength",
+        "cursor_size",
+        "cursor_strength",
+    ],
+};
+
+fn create(context: &SimulationContext) -> SimulationResult<SimulationType> {
+    let simulation = ParticleLifeModel::new(
+        context.device,
+        context.queue,
+        context.surface_config,
+        context.adapter_info,
+        15000, // Default particle count
+        settings::Settings::default(),
+        context.render_settings,
+        context.lut_manager,
+        simulation::ColorMode::Lut,
+    )?;
+    Ok(SimulationType::ParticleLife(Box::new(simulation)))
+}
+
+fn preset_manager() -> Box<dyn AnyPresetManager> {
+    let mut preset_manager = ParticleLifePresetManager::new(DESCRIPTOR.id.to_string());
+    init_presets(&mut preset_manager);
+    Box::new(preset_manager)
+}
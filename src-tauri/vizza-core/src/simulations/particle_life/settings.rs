@@ -1,4 +1,5 @@
 use super::matrix_operations;
+use crate::simulations::shared::SettingField;
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
@@ -184,8 +185,8 @@ impl Settings {
 
     /// Randomize the interaction force matrix using the specified generator
     pub fn randomize_force_matrix(&mut self, generator: &MatrixGenerator) {
-        use rand::{Rng, SeedableRng};
-        let mut rng = rand::rngs::StdRng::seed_from_u64(rand::random::<u64>());
+        use rand::Rng;
+        let mut rng = crate::simulations::shared::random::rng();
 
         match generator {
             MatrixGenerator::Random => {
@@ -716,4 +717,44 @@ impl Settings {
     pub fn flip_sign(&mut self) {
         matrix_operations::flip_sign(&mut self.force_matrix);
     }
+    /// Fields of these settings with the ranges offered in the UI
+    pub fn schema() -> Vec<SettingField> {
+        vec![
+            SettingField::integer("species_count", 2, 8)
+                .group("Species")
+                .description("Number of particle species")
+                .requires_reset(),
+            SettingField::matrix("force_matrix", -1.0, 1.0, 0.1, "species_count")
+                .group("Species")
+                .description(
+                    "Attraction (positive) or repulsion (negative) of each species to each other",
+                ),
+            SettingField::float("max_force", 0.0, 10.0, 0.01)
+                .group("Physics")
+                .description("Largest force applied to a particle"),
+            SettingField::float("friction", 0.0, 1.0, 0.01)
+                .group("Physics")
+                .description("Fraction of velocity lost each second"),
+            SettingField::bool("wrap_edges")
+                .group("Physics")
+                .description("Wrap particles around the screen edges"),
+            SettingField::float("force_beta", 0.0, 1.0, 0.01)
+                .group("Physics")
+                .description(
+                    "Fraction of the interaction radius where repulsion gives way to attraction",
+                ),
+            SettingField::float("repulsion_strength", 0.0, 10.0, 0.1)
+                .group("Physics")
+                .description("Multiplier on the close-range repulsion"),
+            SettingField::float("min_distance", 0.0, 0.1, 0.001)
+                .group("Physics")
+                .description("Distance below which forces stop growing"),
+            SettingField::float("max_distance", 0.001, 1.0, 0.001)
+                .group("Physics")
+                .description("Interaction radius"),
+            SettingField::float("brownian_motion", 0.0, 1.0, 0.01)
+                .group("Physics")
+                .description("Strength of random thermal motion"),
+        ]
+    }
 }
@@ -26,15 +26,28 @@ struct SimParams {
     cursor_strength: f32,  // Cursor force strength
     cursor_active: u32,  // Whether cursor interaction is active (0 = inactive, 1 = attract, 2 = repel)
     brownian_motion: f32,  // Brownian motion strength (0.0-1.0)
+    particle_size: f32,  // Particle size in world space units
     aspect_ratio: f32,  // Screen aspect ratio for cursor distance calculation
     _pad1: u32,
-    _pad2: u32,
+}
+
+struct GridParams {
+    grid_size: u32,   // Cells per axis across the [-1,1] world
+    cell_count: u32,  // grid_size * grid_size
+    cell_size: f32,   // World units per cell, always >= max_distance
+    _pad1: u32,
 }
 
 @group(0) @binding(0) var<storage, read_write> particles: array<Particle>;
 @group(0) @binding(1) var<uniform> params: SimParams;
 @group(0) @binding(2) var<storage, read> force_matrix: array<f32>;
 
+// Spatial hash built by the grid passes earlier in the frame
+@group(1) @binding(0) var<uniform> grid: GridParams;
+@group(1) @binding(1) var<storage, read_write> cell_counts: array<u32>;
+@group(1) @binding(2) var<storage, read_write> cell_starts: array<u32>;
+@group(1) @binding(4) var<storage, read_write> sorted_indices: array<u32>;
+
 // Simple random number generator
 var<private> rng_state: u32;
 
@@ -127,6 +140,32 @@ fn wrapped_distance(pos_a: vec2<f32>, pos_b: vec2<f32>) -> vec2<f32> {
     return delta;
 }
 
+// Convert a [-1,1] world position to clamped grid coordinates
+fn world_to_grid(pos: vec2<f32>) -> vec2<i32> {
+    let normalized_pos = (pos + vec2<f32>(1.0, 1.0)) * 0.5;
+    let cell = vec2<i32>(floor(normalized_pos * f32(grid.grid_size)));
+    let max_cell = i32(grid.grid_size) - 1;
+    return clamp(cell, vec2<i32>(0), vec2<i32>(max_cell));
+}
+
+// Resolve one axis of a neighbor cell, returning -1 if it falls off the grid.
+// Grids narrower than three cells are scanned in full so no cell is visited twice.
+fn neighbor_axis(center: i32, offset: i32) -> i32 {
+    let size = i32(grid.grid_size);
+    if (size < 3) {
+        return offset;
+    }
+
+    let cell = center + offset - 1;
+    if (params.wrap_edges == 1u) {
+        return (cell + size) % size;
+    }
+    if (cell < 0 || cell >= size) {
+        return -1;
+    }
+    return cell;
+}
+
 @compute @workgroup_size(64)
 fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     let index = global_id.x;
@@ -139,38 +178,58 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     var particle = particles[index];
     var force = vec2<f32>(0.0, 0.0);
     
-    // Calculate forces from all other particles
-    // This is the O(n²) part - in a real implementation you'd use spatial partitioning
-    for (var i = 0u; i < params.particle_count; i++) {
-        if (i == index) {
+    // Calculate forces from particles in the surrounding cells. Cells are at least
+    // max_distance wide, so the 3x3 neighborhood covers every particle in range.
+    let center = world_to_grid(particle.position);
+    let span = min(3, i32(grid.grid_size));
+    for (var oy = 0; oy < span; oy++) {
+        let cell_y = neighbor_axis(center.y, oy);
+        if (cell_y < 0) {
             continue;
         }
+
+        for (var ox = 0; ox < span; ox++) {
+            let cell_x = neighbor_axis(center.x, ox);
+            if (cell_x < 0) {
+                continue;
+            }
+
+            let cell_index = u32(cell_y) * grid.grid_size + u32(cell_x);
+            let cell_start = cell_starts[cell_index];
+            let cell_end = cell_start + cell_counts[cell_index];
+            for (var slot = cell_start; slot < cell_end; slot++) {
+                let i = sorted_indices[slot];
+                if (i == index) {
+                    continue;
+                }
         
-        let other = particles[i];
-        let delta = wrapped_distance(particle.position, other.position);
-        let distance_sq = dot(delta, delta);
+                let other = particles[i];
+                let delta = wrapped_distance(particle.position, other.position);
+                let distance_sq = dot(delta, delta);
         
-        // Skip if too far (using squared distance for efficiency)
-        if (distance_sq > params.max_distance * params.max_distance) {
-            continue;
-        }
+                // Skip if too far (using squared distance for efficiency)
+                if (distance_sq > params.max_distance * params.max_distance) {
+                    continue;
+                }
         
-        let distance = sqrt(distance_sq);
+                let distance = sqrt(distance_sq);
         
-        // Skip if too close to avoid singularities
-        if (distance < 0.001) {
-            continue;
-        }
+                // Skip if too close to avoid singularities
+                if (distance < 0.001) {
+                    continue;
+                }
         
-        // Get force strength from force matrix
-        let attraction = get_force(particle.species, other.species);
+                // Get force strength from force matrix
+                let attraction = get_force(particle.species, other.species);
         
-        // Calculate force magnitude using the same model as standalone
-        let force_magnitude = calculate_force(distance, attraction);
+                // Calculate force magnitude using the same model as standalone
+                let force_magnitude = calculate_force(distance, attraction);
         
-        // Apply force in direction between particles
-        let direction = delta / distance;
-        force += direction * force_magnitude;
+                // Apply force in direction between particles
+                let direction = delta / distance;
+                force += direction * force_magnitude;
+            }
+        }
     }
     
     // Calculate cursor interaction force
@@ -0,0 +1,21 @@
+// Clear the per-cell particle counts of the spatial hash grid
+
+struct GridParams {
+    grid_size: u32,   // Cells per axis across the [-1,1] world
+    cell_count: u32,  // grid_size * grid_size
+    cell_size: f32,   // World units per cell, always >= max_distance
+    _pad1: u32,
+}
+
+@group(1) @binding(0) var<uniform> grid: GridParams;
+@group(1) @binding(1) var<storage, read_write> cell_counts: array<atomic<u32>>;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let index = global_id.x;
+    if (index >= grid.cell_count) {
+        return;
+    }
+
+    atomicStore(&cell_counts[index], 0u);
+}
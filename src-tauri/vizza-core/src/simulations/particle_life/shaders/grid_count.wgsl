@@ -0,0 +1,69 @@
+// Bin every particle into its grid cell and count the particles per cell.
+// The slot returned by the atomic increment is kept so the scatter pass can
+// place the particle without a second round of atomics.
+
+struct Particle {
+    position: vec2<f32>,
+    velocity: vec2<f32>,
+    species: u32,
+    _pad: u32,
+}
+
+struct SimParams {
+    particle_count: u32,
+    species_count: u32,
+    max_force: f32,
+    max_distance: f32,
+    friction: f32,
+    wrap_edges: u32,
+    width: f32,
+    height: f32,
+    random_seed: u32,
+    dt: f32,
+    beta: f32,
+    cursor_x: f32,
+    cursor_y: f32,
+    cursor_size: f32,
+    cursor_strength: f32,
+    cursor_active: u32,
+    brownian_motion: f32,
+    particle_size: f32,
+    aspect_ratio: f32,
+    _pad1: u32,
+}
+
+struct GridParams {
+    grid_size: u32,
+    cell_count: u32,
+    cell_size: f32,
+    _pad1: u32,
+}
+
+@group(0) @binding(0) var<storage, read_write> particles: array<Particle>;
+@group(0) @binding(1) var<uniform> params: SimParams;
+
+@group(1) @binding(0) var<uniform> grid: GridParams;
+@group(1) @binding(1) var<storage, read_write> cell_counts: array<atomic<u32>>;
+// Per particle: x = cell index, y = slot within the cell
+@group(1) @binding(3) var<storage, read_write> particle_bins: array<vec2<u32>>;
+
+// Convert a [-1,1] world position to clamped grid coordinates
+fn world_to_grid(pos: vec2<f32>) -> vec2<u32> {
+    let normalized_pos = (pos + vec2<f32>(1.0, 1.0)) * 0.5;
+    let cell = vec2<i32>(floor(normalized_pos * f32(grid.grid_size)));
+    let max_cell = i32(grid.grid_size) - 1;
+    return vec2<u32>(clamp(cell, vec2<i32>(0), vec2<i32>(max_cell)));
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let index = global_id.x;
+    if (index >= params.particle_count) {
+        return;
+    }
+
+    let coord = world_to_grid(particles[index].position);
+    let cell_index = coord.y * grid.grid_size + coord.x;
+    let slot = atomicAdd(&cell_counts[cell_index], 1u);
+    particle_bins[index] = vec2<u32>(cell_index, slot);
+}
@@ -0,0 +1,54 @@
+// Exclusive prefix sum over the per-cell counts, producing the offset of each
+// cell's first particle in the sorted index buffer.
+//
+// Runs as a single workgroup: each thread serially sums a contiguous chunk of
+// cells, the chunk totals are scanned in shared memory, and each thread then
+// writes the offsets for its own chunk. The grid is capped at 256x256 cells,
+// so a chunk is at most 256 cells long.
+
+struct GridParams {
+    grid_size: u32,
+    cell_count: u32,
+    cell_size: f32,
+    _pad1: u32,
+}
+
+const WORKGROUP_SIZE: u32 = 256u;
+
+@group(1) @binding(0) var<uniform> grid: GridParams;
+@group(1) @binding(1) var<storage, read_write> cell_counts: array<u32>;
+@group(1) @binding(2) var<storage, read_write> cell_starts: array<u32>;
+
+var<workgroup> chunk_sums: array<u32, WORKGROUP_SIZE>;
+
+@compute @workgroup_size(256)
+fn main(@builtin(local_invocation_id) local_id: vec3<u32>) {
+    let thread = local_id.x;
+    let chunk_size = (grid.cell_count + WORKGROUP_SIZE - 1u) / WORKGROUP_SIZE;
+    let begin = min(thread * chunk_size, grid.cell_count);
+    let end = min(begin + chunk_size, grid.cell_count);
+
+    var chunk_total = 0u;
+    for (var i = begin; i < end; i++) {
+        chunk_total += cell_counts[i];
+    }
+    chunk_sums[thread] = chunk_total;
+    workgroupBarrier();
+
+    // Inclusive Hillis-Steele scan of the chunk totals
+    for (var offset = 1u; offset < WORKGROUP_SIZE; offset *= 2u) {
+        var addend = 0u;
+        if (thread >= offset) {
+            addend = chunk_sums[thread - offset];
+        }
+        workgroupBarrier();
+        chunk_sums[thread] += addend;
+        workgroupBarrier();
+    }
+
+    var running = chunk_sums[thread] - chunk_total;
+    for (var i = begin; i < end; i++) {
+        cell_starts[i] = running;
+        running += cell_counts[i];
+    }
+}
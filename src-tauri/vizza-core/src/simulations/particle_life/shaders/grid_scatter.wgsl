@@ -0,0 +1,49 @@
+// Write each particle's index into its cell's range of the sorted index buffer
+
+struct SimParams {
+    particle_count: u32,
+    species_count: u32,
+    max_force: f32,
+    max_distance: f32,
+    friction: f32,
+    wrap_edges: u32,
+    width: f32,
+    height: f32,
+    random_seed: u32,
+    dt: f32,
+    beta: f32,
+    cursor_x: f32,
+    cursor_y: f32,
+    cursor_size: f32,
+    cursor_strength: f32,
+    cursor_active: u32,
+    brownian_motion: f32,
+    particle_size: f32,
+    aspect_ratio: f32,
+    _pad1: u32,
+}
+
+struct GridParams {
+    grid_size: u32,
+    cell_count: u32,
+    cell_size: f32,
+    _pad1: u32,
+}
+
+@group(0) @binding(1) var<uniform> params: SimParams;
+
+@group(1) @binding(0) var<uniform> grid: GridParams;
+@group(1) @binding(2) var<storage, read_write> cell_starts: array<u32>;
+@group(1) @binding(3) var<storage, read_write> particle_bins: array<vec2<u32>>;
+@group(1) @binding(4) var<storage, read_write> sorted_indices: array<u32>;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let index = global_id.x;
+    if (index >= params.particle_count) {
+        return;
+    }
+
+    let bin = particle_bins[index];
+    sorted_indices[cell_starts[bin.x] + bin.y] = index;
+}
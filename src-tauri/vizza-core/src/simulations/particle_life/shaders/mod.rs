@@ -10,3 +10,7 @@ pub const BACKGROUND_RENDER_SHADER: &str = include_str!("background_render.wgsl"
 pub const INFINITE_RENDER_SHADER: &str = crate::simulations::shared::INFINITE_RENDER_SHADER;
 pub const POST_EFFECT_SHADER: &str = include_str!("post_effect.wgsl");
 pub const TILE_RENDER_SHADER: &str = include_str!("tile_render.wgsl");
+pub const GRID_CLEAR_SHADER: &str = include_str!("grid_clear.wgsl");
+pub const GRID_COUNT_SHADER: &str = include_str!("grid_count.wgsl");
+pub const GRID_PREFIX_SUM_SHADER: &str = include_str!("grid_prefix_sum.wgsl");
+pub const GRID_SCATTER_SHADER: &str = include_str!("grid_scatter.wgsl");
@@ -1,8 +1,9 @@
 use crate::error::{SimulationError, SimulationResult};
 use crate::simulations::shared::{
-    BindGroupBuilder, ComputePipelineBuilder, LutManager, PositionGenerator,
+    BindGroupBuilder, ComputePipelineBuilder, LutManager, PositionGenerator, SimulationSnapshot,
     camera::Camera,
     post_processing::{PostProcessingResources, PostProcessingState},
+    random,
 };
 use bytemuck::{Pod, Zeroable};
 use rand::{Rng, SeedableRng};
@@ -58,6 +59,39 @@ pub struct ForceRandomizeParams {
     pub max_force: f32,
 }
 
+/// Upper bound on spatial hash cells per axis. Keeps the prefix sum within a
+/// single 256-thread workgroup (at most 256 cells per thread).
+pub const MAX_GRID_SIZE: u32 = 256;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub struct GridParams {
+    pub grid_size: u32,  // Cells per axis across the [-1,1] world
+    pub cell_count: u32, // grid_size * grid_size
+    pub cell_size: f32,  // World units per cell, always >= max_distance
+    pub _pad1: u32,
+}
+
+impl GridParams {
+    /// Pick the finest grid whose cells are still at least `max_distance` wide,
+    /// so every interacting pair lies in the same or an adjacent cell
+    pub fn new(max_distance: f32) -> Self {
+        let cells_per_axis = if max_distance > 0.0 {
+            (2.0 / max_distance).floor()
+        } else {
+            MAX_GRID_SIZE as f32
+        };
+        let grid_size = (cells_per_axis as u32).clamp(1, MAX_GRID_SIZE);
+
+        Self {
+            grid_size,
+            cell_count: grid_size * grid_size,
+            cell_size: 2.0 / grid_size as f32,
+            _pad1: 0,
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Debug, Copy, Clone, Pod, Zeroable)]
 pub struct FadeUniforms {
@@ -277,6 +311,19 @@ pub struct ParticleLifeModel {
     pub compute_bind_group: wgpu::BindGroup,
     pub compute_bind_group_layout: wgpu::BindGroupLayout,
 
+    // Spatial hash grid for neighbor search (counting sort of particles by cell)
+    pub grid_params_buffer: wgpu::Buffer,
+    pub cell_counts_buffer: wgpu::Buffer,
+    pub cell_starts_buffer: wgpu::Buffer,
+    pub particle_bins_buffer: wgpu::Buffer,
+    pub sorted_indices_buffer: wgpu::Buffer,
+    pub grid_bind_group_layout: wgpu::BindGroupLayout,
+    pub grid_bind_group: wgpu::BindGroup,
+    pub grid_clear_pipeline: wgpu::ComputePipeline,
+    pub grid_count_pipeline: wgpu::ComputePipeline,
+    pub grid_prefix_sum_pipeline: wgpu::ComputePipeline,
+    pub grid_scatter_pipeline: wgpu::ComputePipeline,
+
     // Initialization pipeline
     pub init_pipeline: wgpu::ComputePipeline,
     pub init_bind_group: wgpu::BindGroup,
@@ -554,7 +601,9 @@ impl ParticleLifeModel {
                 dimension: wgpu::TextureDimension::D2,
                 format: wgpu::TextureFormat::Rgba8Unorm,
                 usage: wgpu::TextureUsages::RENDER_ATTACHMENT
-                    | wgpu::TextureUsages::TEXTURE_BINDING,
+                    | wgpu::TextureUsages::TEXTURE_BINDING
+                    | wgpu::TextureUsages::COPY_SRC
+                    | wgpu::TextureUsages::COPY_DST,
                 view_formats: &[],
             });
 
@@ -579,7 +628,9 @@ impl ParticleLifeModel {
                 dimension: wgpu::TextureDimension::D2,
                 format: wgpu::TextureFormat::Rgba8Unorm,
                 usage: wgpu::TextureUsages::RENDER_ATTACHMENT
-                    | wgpu::TextureUsages::TEXTURE_BINDING,
+                    | wgpu::TextureUsages::TEXTURE_BINDING
+                    | wgpu::TextureUsages::COPY_SRC
+                    | wgpu::TextureUsages::COPY_DST,
                 view_formats: &[],
             });
 
@@ -715,7 +766,7 @@ impl ParticleLifeModel {
         _adapter_info: &wgpu::AdapterInfo,
         particle_count: usize,
         settings: Settings,
-        app_settings: &crate::commands::app_settings::AppSettings,
+        render_settings: &crate::settings::RenderSettings,
         lut_manager: &LutManager,
         color_mode: ColorMode, // Add color mode param
     ) -> SimulationResult<Self> {
@@ -813,7 +864,8 @@ impl ParticleLifeModel {
             size: particle_buffer_size,
             usage: wgpu::BufferUsages::STORAGE
                 | wgpu::BufferUsages::VERTEX
-                | wgpu::BufferUsages::COPY_DST,
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
             mapped_at_creation: false,
         });
 
@@ -889,12 +941,134 @@ impl ParticleLifeModel {
                 ],
             });
 
+        // Spatial hash grid resources. Cell buffers are sized for the largest grid
+        // so changing max_distance only needs a uniform update.
+        let grid_params = GridParams::new(settings.max_distance);
+        let grid_params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Particle Life Grid Params Buffer"),
+            contents: bytemuck::cast_slice(&[grid_params]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let max_cell_buffer_size =
+            (MAX_GRID_SIZE * MAX_GRID_SIZE) as u64 * std::mem::size_of::<u32>() as u64;
+        let cell_counts_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Particle Life Cell Counts Buffer"),
+            size: max_cell_buffer_size,
+            usage: wgpu::BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+        let cell_starts_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Particle Life Cell Starts Buffer"),
+            size: max_cell_buffer_size,
+            usage: wgpu::BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+        let (particle_bins_buffer, sorted_indices_buffer) =
+            Self::create_particle_grid_buffers(device, particle_count);
+
+        let grid_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Particle Life Grid Bind Group Layout"),
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Storage { read_only: false },
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 2,
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Storage { read_only: false },
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 3,
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Storage { read_only: false },
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 4,
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Storage { read_only: false },
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                ],
+            });
+
+        let grid_bind_group = BindGroupBuilder::new(device, &grid_bind_group_layout)
+            .add_buffer(0, &grid_params_buffer)
+            .add_buffer(1, &cell_counts_buffer)
+            .add_buffer(2, &cell_starts_buffer)
+            .add_buffer(3, &particle_bins_buffer)
+            .add_buffer(4, &sorted_indices_buffer)
+            .with_label("Particle Life Grid Bind Group".to_string())
+            .build();
+
+        // The force pass and all grid passes share the compute and grid bind groups
         let compute_pipeline = ComputePipelineBuilder::new(device.clone())
             .with_shader(compute_shader)
-            .with_bind_group_layouts(vec![compute_bind_group_layout.clone()])
+            .with_bind_group_layouts(vec![
+                compute_bind_group_layout.clone(),
+                grid_bind_group_layout.clone(),
+            ])
             .with_label("Particle Life Compute Pipeline".to_string())
             .build();
 
+        let create_grid_pipeline = |label: &str, source: &str| {
+            let shader = Arc::new(device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some(label),
+                source: wgpu::ShaderSource::Wgsl(source.into()),
+            }));
+            ComputePipelineBuilder::new(device.clone())
+                .with_shader(shader)
+                .with_bind_group_layouts(vec![
+                    compute_bind_group_layout.clone(),
+                    grid_bind_group_layout.clone(),
+                ])
+                .with_label(format!("{} Pipeline", label))
+                .build()
+        };
+
+        let grid_clear_pipeline =
+            create_grid_pipeline("Particle Life Grid Clear", shaders::GRID_CLEAR_SHADER);
+        let grid_count_pipeline =
+            create_grid_pipeline("Particle Life Grid Count", shaders::GRID_COUNT_SHADER);
+        let grid_prefix_sum_pipeline = create_grid_pipeline(
+            "Particle Life Grid Prefix Sum",
+            shaders::GRID_PREFIX_SUM_SHADER,
+        );
+        let grid_scatter_pipeline =
+            create_grid_pipeline("Particle Life Grid Scatter", shaders::GRID_SCATTER_SHADER);
+
         let compute_bind_group = BindGroupBuilder::new(device, &compute_bind_group_layout)
             .add_buffer(0, &particle_buffer)
             .add_buffer(1, &sim_params_buffer)
@@ -1710,9 +1884,9 @@ impl ParticleLifeModel {
             address_mode_u: wgpu::AddressMode::ClampToEdge,
             address_mode_v: wgpu::AddressMode::ClampToEdge,
             address_mode_w: wgpu::AddressMode::ClampToEdge,
-            mag_filter: app_settings.texture_filtering.into(),
-            min_filter: app_settings.texture_filtering.into(),
-            mipmap_filter: app_settings.texture_filtering.into(),
+            mag_filter: render_settings.texture_filtering.into(),
+            min_filter: render_settings.texture_filtering.into(),
+            mipmap_filter: render_settings.texture_filtering.into(),
             ..Default::default()
         });
 
@@ -1748,7 +1922,10 @@ impl ParticleLifeModel {
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
             format: wgpu::TextureFormat::Rgba8Unorm, // Use RGBA format for proper alpha support
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC
+                | wgpu::TextureUsages::COPY_DST,
             view_formats: &[],
         });
 
@@ -1770,7 +1947,10 @@ impl ParticleLifeModel {
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
             format: wgpu::TextureFormat::Rgba8Unorm, // Use RGBA format for proper alpha support
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC
+                | wgpu::TextureUsages::COPY_DST,
             view_formats: &[],
         });
 
@@ -1914,9 +2094,9 @@ impl ParticleLifeModel {
             address_mode_u: wgpu::AddressMode::ClampToEdge,
             address_mode_v: wgpu::AddressMode::ClampToEdge,
             address_mode_w: wgpu::AddressMode::ClampToEdge,
-            mag_filter: app_settings.texture_filtering.into(),
-            min_filter: app_settings.texture_filtering.into(),
-            mipmap_filter: app_settings.texture_filtering.into(),
+            mag_filter: render_settings.texture_filtering.into(),
+            min_filter: render_settings.texture_filtering.into(),
+            mipmap_filter: render_settings.texture_filtering.into(),
             ..Default::default()
         });
 
@@ -1926,9 +2106,9 @@ impl ParticleLifeModel {
             address_mode_u: wgpu::AddressMode::ClampToEdge,
             address_mode_v: wgpu::AddressMode::ClampToEdge,
             address_mode_w: wgpu::AddressMode::ClampToEdge,
-            mag_filter: app_settings.texture_filtering.into(),
-            min_filter: app_settings.texture_filtering.into(),
-            mipmap_filter: app_settings.texture_filtering.into(),
+            mag_filter: render_settings.texture_filtering.into(),
+            min_filter: render_settings.texture_filtering.into(),
+            mipmap_filter: render_settings.texture_filtering.into(),
             ..Default::default()
         });
 
@@ -1938,9 +2118,9 @@ impl ParticleLifeModel {
             address_mode_u: wgpu::AddressMode::ClampToEdge,
             address_mode_v: wgpu::AddressMode::ClampToEdge,
             address_mode_w: wgpu::AddressMode::ClampToEdge,
-            mag_filter: app_settings.texture_filtering.into(),
-            min_filter: app_settings.texture_filtering.into(),
-            mipmap_filter: app_settings.texture_filtering.into(),
+            mag_filter: render_settings.texture_filtering.into(),
+            min_filter: render_settings.texture_filtering.into(),
+            mipmap_filter: render_settings.texture_filtering.into(),
             ..Default::default()
         });
 
@@ -2397,6 +2577,17 @@ impl ParticleLifeModel {
             compute_pipeline,
             compute_bind_group,
             compute_bind_group_layout,
+            grid_params_buffer,
+            cell_counts_buffer,
+            cell_starts_buffer,
+            particle_bins_buffer,
+            sorted_indices_buffer,
+            grid_bind_group_layout,
+            grid_bind_group,
+            grid_clear_pipeline,
+            grid_count_pipeline,
+            grid_prefix_sum_pipeline,
+            grid_scatter_pipeline,
             init_pipeline,
             init_bind_group,
             init_bind_group_layout,
@@ -2588,6 +2779,78 @@ impl ParticleLifeModel {
             0,
             bytemuck::cast_slice(&[sim_params]),
         );
+
+        let grid_params = GridParams::new(self.settings.max_distance);
+        queue.write_buffer(
+            &self.grid_params_buffer,
+            0,
+            bytemuck::cast_slice(&[grid_params]),
+        );
+    }
+
+    /// Per-particle buffers of the spatial hash: the (cell, slot) bin of each
+    /// particle and the particle indices sorted by cell
+    fn create_particle_grid_buffers(
+        device: &Arc<Device>,
+        particle_count: usize,
+    ) -> (wgpu::Buffer, wgpu::Buffer) {
+        let particle_count = particle_count.max(1) as u64;
+        let particle_bins_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Particle Life Particle Bins Buffer"),
+            size: particle_count * 2 * std::mem::size_of::<u32>() as u64,
+            usage: wgpu::BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+        let sorted_indices_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Particle Life Sorted Indices Buffer"),
+            size: particle_count * std::mem::size_of::<u32>() as u64,
+            usage: wgpu::BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+        (particle_bins_buffer, sorted_indices_buffer)
+    }
+
+    /// Bin particles into the spatial hash grid: clear the cell counts, count
+    /// particles per cell, prefix-sum the counts into cell offsets, and scatter
+    /// particle indices into cell order for the force pass
+    fn encode_grid_passes(&self, encoder: &mut wgpu::CommandEncoder) {
+        let workgroup_size = 64;
+        let cell_count = GridParams::new(self.settings.max_distance).cell_count;
+        let particle_workgroups = (self.state.particle_count as u32).div_ceil(workgroup_size);
+
+        let passes = [
+            (
+                "Particle Life Grid Clear Pass",
+                &self.grid_clear_pipeline,
+                cell_count.div_ceil(workgroup_size),
+            ),
+            (
+                "Particle Life Grid Count Pass",
+                &self.grid_count_pipeline,
+                particle_workgroups,
+            ),
+            (
+                "Particle Life Grid Prefix Sum Pass",
+                &self.grid_prefix_sum_pipeline,
+                1,
+            ),
+            (
+                "Particle Life Grid Scatter Pass",
+                &self.grid_scatter_pipeline,
+                particle_workgroups,
+            ),
+        ];
+
+        for (label, pipeline, workgroups) in passes {
+            let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some(label),
+                timestamp_writes: None,
+            });
+            compute_pass.set_pipeline(pipeline);
+            compute_pass.set_bind_group(0, &self.compute_bind_group, &[]);
+            compute_pass.set_bind_group(1, &self.grid_bind_group, &[]);
+            compute_pass.dispatch_workgroups(workgroups, 1, 1);
+        }
     }
 
     fn update_viewport_params(&mut self, queue: &Arc<Queue>) {
@@ -3541,6 +3804,9 @@ impl Simulation for ParticleLifeModel {
             label: Some("Particle Life Compute Encoder"),
         });
 
+        // Rebuild the spatial hash from the current particle positions
+        self.encode_grid_passes(&mut compute_encoder);
+
         // Single physics step per frame for proper timing
         {
             let mut compute_pass =
@@ -3551,6 +3817,7 @@ impl Simulation for ParticleLifeModel {
 
             compute_pass.set_pipeline(&self.compute_pipeline);
             compute_pass.set_bind_group(0, &self.compute_bind_group, &[]);
+            compute_pass.set_bind_group(1, &self.grid_bind_group, &[]);
 
             let workgroup_size = 64;
             let num_workgroups = self.state.particle_count.div_ceil(workgroup_size);
@@ -4109,7 +4376,12 @@ impl Simulation for ParticleLifeModel {
                     self.state.particle_size = size as f32;
                 }
             }
-            _ => {}
+            _ => {
+                return Err(SimulationError::InvalidSetting {
+                    setting_name: setting_name.to_string(),
+                    message: "Unknown setting".to_string(),
+                });
+            }
         }
         Ok(())
     }
@@ -4312,9 +4584,7 @@ impl Simulation for ParticleLifeModel {
         queue: &Arc<Queue>,
     ) -> SimulationResult<()> {
         // Update random seed for reset
-        use rand::Rng;
-        let mut rng = rand::rng();
-        self.state.random_seed = rng.random();
+        self.state.random_seed = random::rng().random();
 
         // Update sim params with new random seed
         self.update_sim_params(device, queue);
@@ -4369,6 +4639,70 @@ impl Simulation for ParticleLifeModel {
 
         Ok(())
     }
+    fn save_snapshot(
+        &self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<SimulationSnapshot> {
+        let mut state = self.get_state();
+        state["current_trail_is_a"] = Value::Bool(self.current_trail_is_a);
+
+        let mut snapshot = SimulationSnapshot::new("particle_life", self.get_settings())
+            .with_lut(&self.state.current_lut_name, self.state.lut_reversed)
+            .with_camera(&self.camera)
+            .with_state(state);
+        snapshot.capture_buffer("particles", &self.particle_buffer, device, queue)?;
+        if self.state.traces_enabled {
+            snapshot.capture_texture("trail_a", &self.trail_texture_a, device, queue)?;
+            snapshot.capture_texture("trail_b", &self.trail_texture_b, device, queue)?;
+        }
+        Ok(snapshot)
+    }
+
+    fn load_snapshot(
+        &mut self,
+        snapshot: &SimulationSnapshot,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()> {
+        snapshot.expect_type("particle_life")?;
+        self.apply_settings(snapshot.settings.clone(), device, queue)?;
+
+        // Runtime values that live outside the settings. Particle count goes
+        // first since changing it recreates the particle buffer.
+        for key in [
+            "particle_count",
+            "dt",
+            "random_seed",
+            "traces_enabled",
+            "trace_fade",
+            "edge_fade_strength",
+            "particle_size",
+            "color_mode",
+        ] {
+            if let Some(value) = snapshot.state.get(key)
+                && self.get_state().get(key) != Some(value)
+            {
+                self.update_setting(key, value.clone(), device, queue)?;
+            }
+        }
+
+        snapshot.restore_buffer("particles", &self.particle_buffer, queue)?;
+        if self.state.traces_enabled {
+            snapshot.restore_texture("trail_a", &self.trail_texture_a, queue)?;
+            snapshot.restore_texture("trail_b", &self.trail_texture_b, queue)?;
+            if let Some(current_trail_is_a) = snapshot.state["current_trail_is_a"].as_bool() {
+                self.current_trail_is_a = current_trail_is_a;
+            }
+        }
+
+        if let Some(camera) = &snapshot.camera {
+            camera.apply_to(&mut self.camera);
+        }
+        self.state.current_lut_name = snapshot.lut_name.clone();
+        self.state.lut_reversed = snapshot.lut_reversed;
+        Ok(())
+    }
 }
 
 impl ParticleLifeModel {
@@ -4379,7 +4713,7 @@ impl ParticleLifeModel {
         device: &Arc<Device>,
         queue: &Arc<Queue>,
     ) -> SimulationResult<()> {
-        let new_count = new_count.clamp(1000, 100000);
+        let new_count = new_count.clamp(1000, 500_000);
         let old_count = self.state.particle_count as u32;
 
         if new_count == old_count {
@@ -4407,13 +4741,19 @@ impl ParticleLifeModel {
             size: new_particle_buffer_size,
             usage: wgpu::BufferUsages::STORAGE
                 | wgpu::BufferUsages::VERTEX
-                | wgpu::BufferUsages::COPY_DST,
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
             mapped_at_creation: false,
         });
 
         // Replace the buffer
         self.particle_buffer = new_particle_buffer;
 
+        let (particle_bins_buffer, sorted_indices_buffer) =
+            Self::create_particle_grid_buffers(device, new_count as usize);
+        self.particle_bins_buffer = particle_bins_buffer;
+        self.sorted_indices_buffer = sorted_indices_buffer;
+
         // Recreate bind groups with new buffer
         self.recreate_bind_groups(device)?;
 
@@ -4452,6 +4792,34 @@ impl ParticleLifeModel {
             ],
         });
 
+        // Recreate grid bind group with the resized per-particle grid buffers
+        self.grid_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Particle Life Grid Bind Group"),
+            layout: &self.grid_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: self.grid_params_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: self.cell_counts_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.cell_starts_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: self.particle_bins_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: self.sorted_indices_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
         tracing::info!("Recreating render bind group");
         // Recreate render bind group
         self.render_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
@@ -4491,10 +4859,16 @@ impl ParticleLifeModel {
         self.lut_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("Species Colors Bind Group"),
             layout: &self.render_bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: self.species_colors_buffer.as_entire_binding(),
-            }],
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: self.species_colors_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: self.color_mode_buffer.as_entire_binding(),
+                },
+            ],
         });
         Ok(())
     }
@@ -7,11 +7,12 @@
 
 use super::shaders::{
     BACKGROUND_RENDER_SHADER, COMPUTE_SHADER, FADE_FRAGMENT_SHADER, FADE_VERTEX_SHADER,
-    FORCE_RANDOMIZE_SHADER, FORCE_UPDATE_SHADER, FRAGMENT_SHADER, INIT_SHADER, VERTEX_SHADER,
+    FORCE_RANDOMIZE_SHADER, FORCE_UPDATE_SHADER, FRAGMENT_SHADER, GRID_CLEAR_SHADER,
+    GRID_COUNT_SHADER, GRID_PREFIX_SUM_SHADER, GRID_SCATTER_SHADER, INIT_SHADER, VERTEX_SHADER,
 };
 use super::simulation::{
-    BackgroundParams, FadeUniforms, ForceRandomizeParams, ForceUpdateParams, InitParams, Particle,
-    SimParams,
+    BackgroundParams, FadeUniforms, ForceRandomizeParams, ForceUpdateParams, GridParams,
+    InitParams, MAX_GRID_SIZE, Particle, SimParams,
 };
 use std::mem;
 use wgpu::util::DeviceExt;
@@ -157,6 +158,27 @@ impl ParticleLifeValidator {
         Ok(())
     }
 
+    /// Validates that the Particle Life spatial hash shaders compile without errors
+    fn validate_grid_shader_compilation(&self) -> Result<(), String> {
+        for (label, source) in [
+            ("Particle Life Grid Clear Shader", GRID_CLEAR_SHADER),
+            ("Particle Life Grid Count Shader", GRID_COUNT_SHADER),
+            (
+                "Particle Life Grid Prefix Sum Shader",
+                GRID_PREFIX_SUM_SHADER,
+            ),
+            ("Particle Life Grid Scatter Shader", GRID_SCATTER_SHADER),
+        ] {
+            let _ = self
+                .device
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: Some(label),
+                    source: wgpu::ShaderSource::Wgsl(source.into()),
+                });
+        }
+        Ok(())
+    }
+
     /// Validates that the compute shader can bind to the Rust structs
     fn validate_compute_shader_binding(&self) -> Result<(), String> {
         // Create dummy data
@@ -633,6 +655,9 @@ async fn test_particle_life_shader_compilation() {
     validator
         .validate_background_render_shader_compilation()
         .expect("Background render shader compilation failed");
+    validator
+        .validate_grid_shader_compilation()
+        .expect("Grid shader compilation failed");
 
     // Print struct sizes for debugging
     validator.print_struct_sizes();
@@ -938,3 +963,31 @@ fn test_sim_params_size_and_alignment() {
     assert_eq!(mem::size_of::<SimParams>(), 80);
     assert_eq!(sim_params_bytes.len(), 80);
 }
+
+#[test]
+fn test_grid_params() {
+    assert_eq!(mem::size_of::<GridParams>(), 16);
+
+    // Default interaction radius
+    let params = GridParams::new(0.01);
+    assert_eq!(params.grid_size, 200);
+    assert_eq!(params.cell_count, 200 * 200);
+
+    // Tiny radii are capped so the cell buffers stay bounded
+    assert_eq!(GridParams::new(0.0).grid_size, MAX_GRID_SIZE);
+    assert_eq!(GridParams::new(0.001).grid_size, MAX_GRID_SIZE);
+
+    // Radii wider than the world collapse to a single cell
+    assert_eq!(GridParams::new(1.5).grid_size, 1);
+
+    // Cells must never be narrower than the interaction radius
+    for max_distance in [0.001, 0.005, 0.01, 0.013, 0.05, 0.1, 0.3, 0.7, 1.0] {
+        let params = GridParams::new(max_distance);
+        assert!(
+            params.cell_size >= max_distance,
+            "cell size {} smaller than max distance {}",
+            params.cell_size,
+            max_distance
+        );
+    }
+}
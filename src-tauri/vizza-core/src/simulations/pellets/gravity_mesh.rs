@@ -0,0 +1,567 @@
+//! # Pellets Gravity Mesh
+//!
+//! Long-range gravity for Pellets, solved with a particle-mesh method. Summing
+//! every pair of particles is too slow for thousands of pellets, and the spatial
+//! grid only reaches the neighbouring cells, so distant clumps would never feel
+//! each other. Instead the particles' mass is spread onto a coarse periodic mesh,
+//! the gravitational potential is found by solving Poisson's equation on that
+//! mesh, and its gradient is interpolated back to every particle.
+//!
+//! The mesh wraps around like the world does, so gravity pulls across the edges
+//! of the toroidal domain. Structure smaller than a mesh cell is left to the
+//! direct short-range sum in the physics shader.
+//!
+//! Poisson's equation is solved with multigrid V-cycles over a pyramid of ever
+//! coarser meshes. Plain relaxation on the full mesh would need thousands of
+//! sweeps for the longest wavelengths to settle, leaving the large-scale field
+//! lagging behind the particles after every reset. Each V-cycle starts from the
+//! previous frame's potential and cuts the remaining error by an order of
+//! magnitude, so a couple per frame converge even from a flat start.
+
+use bytemuck::{Pod, Zeroable};
+use std::sync::Arc;
+use wgpu::util::DeviceExt;
+use wgpu::{Device, Queue};
+
+use super::shaders::{GRAVITY_MESH_SHADER, GRAVITY_MULTIGRID_SHADER};
+use super::simulation::Particle;
+
+/// Mesh cells along each side of the [-1,1] world
+pub const GRAVITY_MESH_SIZE: u32 = 64;
+
+/// Side of the coarsest multigrid level, which is solved outright
+const COARSEST_MESH_SIZE: u32 = 4;
+
+/// Multigrid V-cycles run every frame
+const V_CYCLES_PER_FRAME: u32 = 2;
+
+/// Red-black Gauss-Seidel sweeps on each level before and after the coarser
+/// level's correction
+const SMOOTHING_SWEEPS: u32 = 2;
+
+/// Mass represented by one step of the fixed-point accumulator
+const MASS_SCALE: f32 = 1.0 / 256.0;
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct GravityMeshParams {
+    pub particle_count: u32,
+    pub mesh_size: u32,
+    pub cell_size: f32,
+    pub coupling: f32, // 2 * pi * G for the 2D Poisson equation
+    pub mean_cell_mass: f32,
+    pub mass_scale: f32,
+    pub _pad0: u32,
+    pub _pad1: u32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct MultigridLevelParams {
+    pub size: u32,
+    pub _pad0: u32,
+    pub _pad1: u32,
+    pub _pad2: u32,
+}
+
+pub struct GravityMesh {
+    pub params_buffer: wgpu::Buffer,
+    pub field_buffer: wgpu::Buffer,
+    pub potential_buffer: wgpu::Buffer,
+    cell_mass_buffer: wgpu::Buffer,
+    source_buffer: wgpu::Buffer,
+
+    bind_group_layout: wgpu::BindGroupLayout,
+    bind_group: wgpu::BindGroup,
+
+    clear_pipeline: wgpu::ComputePipeline,
+    deposit_pipeline: wgpu::ComputePipeline,
+    source_pipeline: wgpu::ComputePipeline,
+    field_pipeline: wgpu::ComputePipeline,
+
+    // Side length and bind group of every multigrid level, finest first
+    level_sizes: Vec<u32>,
+    level_bind_groups: Vec<wgpu::BindGroup>,
+
+    smooth_red_pipeline: wgpu::ComputePipeline,
+    smooth_black_pipeline: wgpu::ComputePipeline,
+    residual_pipeline: wgpu::ComputePipeline,
+    restrict_pipeline: wgpu::ComputePipeline,
+    solve_coarsest_pipeline: wgpu::ComputePipeline,
+    prolong_pipeline: wgpu::ComputePipeline,
+}
+
+impl GravityMesh {
+    pub fn new(device: &Arc<Device>, particle_buffer: &wgpu::Buffer) -> Self {
+        let cell_count = (GRAVITY_MESH_SIZE * GRAVITY_MESH_SIZE) as u64;
+        let storage_buffer = |label: &str, element_size: u64| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(label),
+                size: cell_count * element_size,
+                usage: wgpu::BufferUsages::STORAGE
+                    | wgpu::BufferUsages::COPY_DST
+                    | wgpu::BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            })
+        };
+
+        let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Pellets Gravity Mesh Params Buffer"),
+            contents: bytemuck::cast_slice(&[GravityMeshParams {
+                particle_count: 0,
+                mesh_size: GRAVITY_MESH_SIZE,
+                cell_size: 2.0 / GRAVITY_MESH_SIZE as f32,
+                coupling: 0.0,
+                mean_cell_mass: 0.0,
+                mass_scale: MASS_SCALE,
+                _pad0: 0,
+                _pad1: 0,
+            }]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let cell_mass_buffer = storage_buffer("Pellets Gravity Mesh Mass Buffer", 4);
+        let source_buffer = storage_buffer("Pellets Gravity Mesh Source Buffer", 4);
+        let potential_buffer = storage_buffer("Pellets Gravity Mesh Potential Buffer", 4);
+        let field_buffer = storage_buffer("Pellets Gravity Mesh Field Buffer", 8);
+
+        let storage_entry = |binding: u32, read_only: bool| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Pellets Gravity Mesh Bind Group Layout"),
+            entries: &[
+                storage_entry(0, true),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                storage_entry(2, false),
+                storage_entry(3, false),
+                storage_entry(4, true),
+                storage_entry(5, false),
+            ],
+        });
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Pellets Gravity Mesh Shader"),
+            source: wgpu::ShaderSource::Wgsl(GRAVITY_MESH_SHADER.into()),
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Pellets Gravity Mesh Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = |label: &str, entry_point: &str| {
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                module: &shader,
+                entry_point: Some(entry_point),
+                cache: None,
+                compilation_options: Default::default(),
+            })
+        };
+        let clear_pipeline = pipeline("Pellets Gravity Mesh Clear Pipeline", "clear_mesh");
+        let deposit_pipeline = pipeline("Pellets Gravity Mesh Deposit Pipeline", "deposit");
+        let source_pipeline = pipeline("Pellets Gravity Mesh Source Pipeline", "compute_source");
+        let field_pipeline = pipeline("Pellets Gravity Mesh Field Pipeline", "compute_field");
+
+        let bind_group = Self::create_bind_group(
+            device,
+            &bind_group_layout,
+            particle_buffer,
+            &params_buffer,
+            &cell_mass_buffer,
+            &source_buffer,
+            &potential_buffer,
+            &field_buffer,
+        );
+
+        let level_sizes = std::iter::successors(Some(GRAVITY_MESH_SIZE), |size| {
+            (*size > COARSEST_MESH_SIZE).then_some(size / 2)
+        })
+        .collect::<Vec<_>>();
+        let (multigrid_layout, level_bind_groups) =
+            Self::create_level_bind_groups(device, &level_sizes, &potential_buffer, &source_buffer);
+
+        let multigrid_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Pellets Gravity Multigrid Shader"),
+            source: wgpu::ShaderSource::Wgsl(GRAVITY_MULTIGRID_SHADER.into()),
+        });
+        let multigrid_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Pellets Gravity Multigrid Pipeline Layout"),
+                bind_group_layouts: &[&multigrid_layout],
+                push_constant_ranges: &[],
+            });
+        let multigrid_pipeline = |label: &str, entry_point: &str| {
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(label),
+                layout: Some(&multigrid_pipeline_layout),
+                module: &multigrid_shader,
+                entry_point: Some(entry_point),
+                cache: None,
+                compilation_options: Default::default(),
+            })
+        };
+
+        Self {
+            params_buffer,
+            field_buffer,
+            potential_buffer,
+            cell_mass_buffer,
+            source_buffer,
+            bind_group_layout,
+            bind_group,
+            clear_pipeline,
+            deposit_pipeline,
+            source_pipeline,
+            field_pipeline,
+            level_sizes,
+            level_bind_groups,
+            smooth_red_pipeline: multigrid_pipeline(
+                "Pellets Gravity Multigrid Smooth Red Pipeline",
+                "smooth_red",
+            ),
+            smooth_black_pipeline: multigrid_pipeline(
+                "Pellets Gravity Multigrid Smooth Black Pipeline",
+                "smooth_black",
+            ),
+            residual_pipeline: multigrid_pipeline(
+                "Pellets Gravity Multigrid Residual Pipeline",
+                "compute_residual",
+            ),
+            restrict_pipeline: multigrid_pipeline(
+                "Pellets Gravity Multigrid Restrict Pipeline",
+                "restrict_residual",
+            ),
+            solve_coarsest_pipeline: multigrid_pipeline(
+                "Pellets Gravity Multigrid Coarsest Solve Pipeline",
+                "solve_coarsest",
+            ),
+            prolong_pipeline: multigrid_pipeline(
+                "Pellets Gravity Multigrid Prolong Pipeline",
+                "prolong_correction",
+            ),
+        }
+    }
+
+    /// Create the buffers of every coarse multigrid level and a bind group per
+    /// level. The finest level solves straight into the potential buffer, from
+    /// the source buffer; the coarse levels share one pyramid buffer each for
+    /// their potentials and right hand sides.
+    fn create_level_bind_groups(
+        device: &Arc<Device>,
+        level_sizes: &[u32],
+        potential_buffer: &wgpu::Buffer,
+        source_buffer: &wgpu::Buffer,
+    ) -> (wgpu::BindGroupLayout, Vec<wgpu::BindGroup>) {
+        let level_bytes = |size: u32| (size * size) as u64 * std::mem::size_of::<f32>() as u64;
+
+        // Coarse levels start at storage buffer offset boundaries
+        let alignment = device.limits().min_storage_buffer_offset_alignment as u64;
+        let mut coarse_offsets = Vec::new();
+        let mut coarse_bytes = 0;
+        for size in &level_sizes[1..] {
+            coarse_offsets.push(coarse_bytes);
+            coarse_bytes += level_bytes(*size).next_multiple_of(alignment);
+        }
+
+        let storage_buffer = |label: &str, size: u64| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(label),
+                size,
+                usage: wgpu::BufferUsages::STORAGE,
+                mapped_at_creation: false,
+            })
+        };
+        let residual_buffer = storage_buffer(
+            "Pellets Gravity Multigrid Residual Buffer",
+            level_bytes(level_sizes[0]),
+        );
+        let coarse_potential_buffer =
+            storage_buffer("Pellets Gravity Multigrid Potential Buffer", coarse_bytes);
+        let coarse_rhs_buffer =
+            storage_buffer("Pellets Gravity Multigrid Rhs Buffer", coarse_bytes);
+
+        let storage_entry = |binding: u32| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only: false },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Pellets Gravity Multigrid Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                storage_entry(1),
+                storage_entry(2),
+                storage_entry(3),
+                storage_entry(4),
+                storage_entry(5),
+            ],
+        });
+
+        fn buffer_range(
+            buffer: &wgpu::Buffer,
+            offset: u64,
+            size: u64,
+        ) -> wgpu::BindingResource<'_> {
+            wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                buffer,
+                offset,
+                size: wgpu::BufferSize::new(size),
+            })
+        }
+        let potential_binding = |level: usize| match level {
+            0 => buffer_range(potential_buffer, 0, level_bytes(level_sizes[0])),
+            _ => buffer_range(
+                &coarse_potential_buffer,
+                coarse_offsets[level - 1],
+                level_bytes(level_sizes[level]),
+            ),
+        };
+        let rhs_binding = |level: usize| match level {
+            0 => buffer_range(source_buffer, 0, level_bytes(level_sizes[0])),
+            _ => buffer_range(
+                &coarse_rhs_buffer,
+                coarse_offsets[level - 1],
+                level_bytes(level_sizes[level]),
+            ),
+        };
+
+        let bind_groups = (0..level_sizes.len())
+            .map(|level| {
+                let size = level_sizes[level];
+                let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("Pellets Gravity Multigrid Level Params Buffer"),
+                    contents: bytemuck::cast_slice(&[MultigridLevelParams {
+                        size,
+                        _pad0: 0,
+                        _pad1: 0,
+                        _pad2: 0,
+                    }]),
+                    usage: wgpu::BufferUsages::UNIFORM,
+                });
+                // The coarsest level has nothing below it and never reads these
+                let coarse_level = (level + 1).min(level_sizes.len() - 1);
+
+                device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("Pellets Gravity Multigrid Level Bind Group"),
+                    layout: &layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: params_buffer.as_entire_binding(),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: potential_binding(level),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 2,
+                            resource: rhs_binding(level),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 3,
+                            resource: buffer_range(&residual_buffer, 0, level_bytes(size)),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 4,
+                            resource: potential_binding(coarse_level),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 5,
+                            resource: rhs_binding(coarse_level),
+                        },
+                    ],
+                })
+            })
+            .collect();
+
+        (layout, bind_groups)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn create_bind_group(
+        device: &Arc<Device>,
+        layout: &wgpu::BindGroupLayout,
+        particle_buffer: &wgpu::Buffer,
+        params_buffer: &wgpu::Buffer,
+        cell_mass_buffer: &wgpu::Buffer,
+        source_buffer: &wgpu::Buffer,
+        potential_buffer: &wgpu::Buffer,
+        field_buffer: &wgpu::Buffer,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Pellets Gravity Mesh Bind Group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: particle_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: params_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: cell_mass_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: source_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: potential_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 5,
+                    resource: field_buffer.as_entire_binding(),
+                },
+            ],
+        })
+    }
+
+    /// Point the mesh at a new particle buffer after it has been recreated
+    pub fn rebind(&mut self, device: &Arc<Device>, particle_buffer: &wgpu::Buffer) {
+        self.bind_group = Self::create_bind_group(
+            device,
+            &self.bind_group_layout,
+            particle_buffer,
+            &self.params_buffer,
+            &self.cell_mass_buffer,
+            &self.source_buffer,
+            &self.potential_buffer,
+            &self.field_buffer,
+        );
+    }
+
+    pub fn update_params(&self, queue: &Arc<Queue>, particles: &[Particle], gravity: f32) {
+        let cell_count = (GRAVITY_MESH_SIZE * GRAVITY_MESH_SIZE) as f32;
+        // Masses are deposited in whole fixed-point steps, so the mean is taken
+        // over the same rounded values to keep the source free of drift
+        let total_mass = particles
+            .iter()
+            .map(|particle| (particle.mass / MASS_SCALE).round() * MASS_SCALE)
+            .sum::<f32>();
+
+        let params = GravityMeshParams {
+            particle_count: particles.len() as u32,
+            mesh_size: GRAVITY_MESH_SIZE,
+            cell_size: 2.0 / GRAVITY_MESH_SIZE as f32,
+            coupling: 2.0 * std::f32::consts::PI * gravity,
+            mean_cell_mass: total_mass / cell_count,
+            mass_scale: MASS_SCALE,
+            _pad0: 0,
+            _pad1: 0,
+        };
+        queue.write_buffer(&self.params_buffer, 0, bytemuck::cast_slice(&[params]));
+    }
+
+    /// Record the deposit, multigrid solve and field passes for `particle_count` particles
+    pub fn dispatch(&self, encoder: &mut wgpu::CommandEncoder, particle_count: u32) {
+        let cell_workgroups = (GRAVITY_MESH_SIZE * GRAVITY_MESH_SIZE).div_ceil(64);
+        let mesh_workgroups = GRAVITY_MESH_SIZE.div_ceil(8);
+
+        let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("Pellets Gravity Mesh Pass"),
+            timestamp_writes: None,
+        });
+
+        compute_pass.set_bind_group(0, &self.bind_group, &[]);
+        compute_pass.set_pipeline(&self.clear_pipeline);
+        compute_pass.dispatch_workgroups(cell_workgroups, 1, 1);
+        compute_pass.set_pipeline(&self.deposit_pipeline);
+        compute_pass.dispatch_workgroups(particle_count.div_ceil(64), 1, 1);
+        compute_pass.set_pipeline(&self.source_pipeline);
+        compute_pass.dispatch_workgroups(cell_workgroups, 1, 1);
+
+        for _ in 0..V_CYCLES_PER_FRAME {
+            self.dispatch_v_cycle(&mut compute_pass);
+        }
+
+        compute_pass.set_bind_group(0, &self.bind_group, &[]);
+        compute_pass.set_pipeline(&self.field_pipeline);
+        compute_pass.dispatch_workgroups(mesh_workgroups, mesh_workgroups, 1);
+    }
+
+    /// Smooth each level on the way down the pyramid, solve the coarsest level
+    /// and smooth again while adding the corrections back on the way up
+    fn dispatch_v_cycle(&self, compute_pass: &mut wgpu::ComputePass) {
+        let coarsest = self.level_sizes.len() - 1;
+        let workgroups = |size: u32| size.div_ceil(8);
+
+        for level in 0..coarsest {
+            let size = self.level_sizes[level];
+            compute_pass.set_bind_group(0, &self.level_bind_groups[level], &[]);
+            self.dispatch_smoothing(compute_pass, size);
+            compute_pass.set_pipeline(&self.residual_pipeline);
+            compute_pass.dispatch_workgroups(workgroups(size), workgroups(size), 1);
+            compute_pass.set_pipeline(&self.restrict_pipeline);
+            compute_pass.dispatch_workgroups(workgroups(size / 2), workgroups(size / 2), 1);
+        }
+
+        compute_pass.set_bind_group(0, &self.level_bind_groups[coarsest], &[]);
+        compute_pass.set_pipeline(&self.solve_coarsest_pipeline);
+        compute_pass.dispatch_workgroups(1, 1, 1);
+
+        for level in (0..coarsest).rev() {
+            let size = self.level_sizes[level];
+            compute_pass.set_bind_group(0, &self.level_bind_groups[level], &[]);
+            compute_pass.set_pipeline(&self.prolong_pipeline);
+            compute_pass.dispatch_workgroups(workgroups(size), workgroups(size), 1);
+            self.dispatch_smoothing(compute_pass, size);
+        }
+    }
+
+    /// Red-black sweeps over a level whose bind group is already set
+    fn dispatch_smoothing(&self, compute_pass: &mut wgpu::ComputePass, size: u32) {
+        for _ in 0..SMOOTHING_SWEEPS {
+            compute_pass.set_pipeline(&self.smooth_red_pipeline);
+            compute_pass.dispatch_workgroups(size.div_ceil(8), size.div_ceil(8), 1);
+            compute_pass.set_pipeline(&self.smooth_black_pipeline);
+            compute_pass.dispatch_workgroups(size.div_ceil(8), size.div_ceil(8), 1);
+        }
+    }
+
+    /// Forget the potential carried over between frames, e.g. after the
+    /// particles have been replaced
+    pub fn reset(&self, queue: &Arc<Queue>) {
+        let zeroes = vec![0u8; self.potential_buffer.size() as usize];
+        queue.write_buffer(&self.potential_buffer, 0, &zeroes);
+        queue.write_buffer(
+            &self.field_buffer,
+            0,
+            &vec![0u8; self.field_buffer.size() as usize],
+        );
+    }
+}
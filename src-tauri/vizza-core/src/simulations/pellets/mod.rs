@@ -25,6 +25,7 @@
 //! This separation allows for both responsive user controls and high-performance
 //! computation of particle interactions.
 
+pub mod gravity_mesh;
 pub mod settings;
 pub mod shaders;
 pub mod simulation;
@@ -36,11 +37,18 @@ mod tests;
 pub use settings::Settings;
 pub use simulation::PelletsModel;
 
+use crate::error::{SimulationError, SimulationResult};
+use crate::simulation::preset_manager::{AnyPresetManager, PelletsPresetManager};
+use crate::simulations::registry::{
+    SimulationCapabilities, SimulationContext, SimulationDescriptor,
+};
+use crate::simulations::traits::SimulationType;
+
 /// Initialize default presets for the Pellets simulation.
 ///
 /// Creates a set of predefined configurations that users can quickly
 /// load to explore different simulation behaviors.
-pub fn init_presets(preset_manager: &mut crate::simulation::preset_manager::PelletsPresetManager) {
+pub fn init_presets(preset_manager: &mut PelletsPresetManager) {
     // Initialize default presets for Pellets simulation
     preset_manager.add_preset(crate::simulation::preset_manager::Preset::new(
         "Default".to_string(),
@@ -58,3 +66,46 @@ pub fn init_presets(preset_manager: &mut crate::simulation::preset_manager::Pell
     let preset_count = preset_manager.get_preset_names().len();
     tracing::info!("Initialized {} pellets presets", preset_count);
 }
+
+/// Registry entry for the Pellets simulation
+pub const DESCRIPTOR: SimulationDescriptor = SimulationDescriptor {
+    id: "pellets",
+    display_name: "Pellets",
+    description: "2D particle physics with gravity and phase transitions",
+    hidden: false,
+    capabilities: SimulationCapabilities {
+        camera: true,
+        luts: true,
+        cursor: true,
+        deterministic: false,
+    },
+    create,
+    presets: Some(preset_manager),
+    initial_preset: None,
+    settings_schema: Some(settings::Settings::schema),
+    runtime_settings: &[
+        "currentLut",
+        "lut_reversed",
+        "cursor_size",
+        "cursor_strength",
+    ],
+};
+
+fn create(context: &SimulationContext) -> SimulationResult<SimulationType> {
+    let simulation = PelletsModel::new(
+        context.device,
+        context.queue,
+        context.surface_config,
+        Settings::default(),
+        context.render_settings,
+        context.lut_manager,
+    )
+    .map_err(|e| SimulationError::InitializationFailed(e.to_string()))?;
+    Ok(SimulationType::Pellets(Box::new(simulation)))
+}
+
+fn preset_manager() -> Box<dyn AnyPresetManager> {
+    let mut preset_manager = PelletsPresetManager::new(DESCRIPTOR.id.to_string());
+    init_presets(&mut preset_manager);
+    Box::new(preset_manager)
+}
@@ -17,6 +17,7 @@
 //! of the simulation, from basic particle properties to advanced physics
 //! behaviors and visual presentation.
 
+use crate::simulations::shared::SettingField;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -92,7 +93,7 @@ impl Settings {
     /// Randomize all settings within reasonable bounds
     pub fn randomize(&mut self) {
         use rand::Rng;
-        let mut rng = rand::rng();
+        let mut rng = crate::simulations::shared::random::rng();
 
         self.particle_size = rng.random_range(0.001..0.005);
         self.collision_damping = rng.random_range(0.5..0.95); // Similar range to energy_damping
@@ -108,4 +109,56 @@ impl Settings {
         self.density_damping_enabled = rng.random_bool(0.5); // 50% chance of being enabled
         self.overlap_resolution_strength = rng.random_range(0.01..0.2); // Conservative range, max 20%
     }
+    /// Fields of these settings with the ranges offered in the UI
+    pub fn schema() -> Vec<SettingField> {
+        vec![
+            SettingField::integer("particle_count", 1, 50000)
+                .step(1000)
+                .group("Particles")
+                .description("Number of particles")
+                .requires_reset(),
+            SettingField::float("particle_size", 0.0005, 1.0, 0.0005)
+                .group("Particles")
+                .description("Particle radius in world units"),
+            SettingField::float("collision_damping", 0.5, 1.0, 0.005)
+                .group("Physics")
+                .description("Fraction of energy kept when particles collide"),
+            SettingField::float("initial_velocity_max", 0.0, 1.0, 0.01)
+                .group("Particles")
+                .description("Fastest speed particles are spawned with")
+                .requires_reset(),
+            SettingField::float("initial_velocity_min", 0.0, 1.0, 0.01)
+                .group("Particles")
+                .description("Slowest speed particles are spawned with")
+                .requires_reset(),
+            SettingField::seed("random_seed")
+                .group("Particles")
+                .description("Seed for particle spawn positions and velocities")
+                .requires_reset(),
+            SettingField::enumeration("background_type", &["Black", "White"])
+                .group("Display")
+                .description("Background color"),
+            SettingField::float("gravitational_constant", 0.0, 0.02, 1e-6)
+                .group("Physics")
+                .description("Strength of gravity between particles"),
+            SettingField::float("energy_damping", 0.9, 1.0, 0.0005)
+                .group("Physics")
+                .description("Fraction of energy kept each step"),
+            SettingField::float("gravity_softening", 0.0, 0.02, 0.0005)
+                .group("Physics")
+                .description("Distance that keeps gravity finite when particles get close"),
+            SettingField::float("density_radius", 0.005, 0.1, 0.005)
+                .group("Display")
+                .description("Radius used to measure local density for coloring and damping"),
+            SettingField::enumeration("coloring_mode", &["Density", "Velocity", "Random"])
+                .group("Display")
+                .description("What particle colors represent"),
+            SettingField::bool("density_damping_enabled")
+                .group("Physics")
+                .description("Slow particles down in dense clumps"),
+            SettingField::float("overlap_resolution_strength", 0.0, 1.0, 0.005)
+                .group("Physics")
+                .description("How forcefully overlapping particles are pushed apart"),
+        ]
+    }
 }
@@ -0,0 +1,121 @@
+// Particle-mesh gravity for Pellets
+//
+// Long-range gravity is solved on a periodic mesh covering the toroidal world:
+// 1. clear_mesh: Zero the mass accumulator
+// 2. deposit: Spread each particle's mass over the four nearest mesh cells (cloud-in-cell)
+// 3. compute_source: Turn the mass per cell into the right hand side of Poisson's equation
+// 4. The potential is solved from the source by gravity_multigrid.wgsl
+// 5. compute_field: Central differences of the potential give the acceleration field
+//
+// The physics shader interpolates the field back to the particles with the same
+// cloud-in-cell weights, so a particle never pulls on itself.
+
+struct Particle {
+    position: vec2<f32>,
+    velocity: vec2<f32>,
+    mass: f32,
+    radius: f32,
+    clump_id: u32,
+    density: f32,
+    grabbed: u32,
+    _pad0: u32,
+    previous_position: vec2<f32>,
+}
+
+struct GravityMeshParams {
+    particle_count: u32,
+    mesh_size: u32,
+    cell_size: f32,
+    // 2 * pi * G, the coupling of the 2D Poisson equation
+    coupling: f32,
+    // Total particle mass spread evenly over all cells
+    mean_cell_mass: f32,
+    // Mass units per fixed-point step of the atomic accumulator
+    mass_scale: f32,
+    _pad0: u32,
+    _pad1: u32,
+}
+
+@group(0) @binding(0) var<storage, read> particles: array<Particle>;
+@group(0) @binding(1) var<uniform> params: GravityMeshParams;
+// Fixed-point mass per cell, since WGSL has no floating point atomics
+@group(0) @binding(2) var<storage, read_write> cell_mass: array<atomic<i32>>;
+@group(0) @binding(3) var<storage, read_write> source: array<f32>;
+@group(0) @binding(4) var<storage, read> potential: array<f32>;
+@group(0) @binding(5) var<storage, read_write> field: array<vec2<f32>>;
+
+fn cell_index(x: i32, y: i32) -> u32 {
+    let size = i32(params.mesh_size);
+    let wrapped_x = ((x % size) + size) % size;
+    let wrapped_y = ((y % size) + size) % size;
+    return u32(wrapped_y * size + wrapped_x);
+}
+
+@compute @workgroup_size(64)
+fn clear_mesh(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let index = global_id.x;
+    if (index >= params.mesh_size * params.mesh_size) {
+        return;
+    }
+    atomicStore(&cell_mass[index], 0);
+}
+
+@compute @workgroup_size(64)
+fn deposit(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let index = global_id.x;
+    if (index >= params.particle_count) {
+        return;
+    }
+
+    let particle = particles[index];
+
+    // Mesh coordinates relative to cell centres
+    let mesh_pos = (particle.position + vec2<f32>(1.0, 1.0)) / params.cell_size - vec2<f32>(0.5, 0.5);
+    let base = floor(mesh_pos);
+    let t = mesh_pos - base;
+    let x = i32(base.x);
+    let y = i32(base.y);
+
+    // Round three weights and give the remainder to the fourth, so the mesh
+    // holds exactly the particle's mass and the source has zero mean
+    let total = i32(round(particle.mass / params.mass_scale));
+    let w00 = i32(round(f32(total) * (1.0 - t.x) * (1.0 - t.y)));
+    let w10 = i32(round(f32(total) * t.x * (1.0 - t.y)));
+    let w01 = i32(round(f32(total) * (1.0 - t.x) * t.y));
+    let w11 = total - w00 - w10 - w01;
+
+    atomicAdd(&cell_mass[cell_index(x, y)], w00);
+    atomicAdd(&cell_mass[cell_index(x + 1, y)], w10);
+    atomicAdd(&cell_mass[cell_index(x, y + 1)], w01);
+    atomicAdd(&cell_mass[cell_index(x + 1, y + 1)], w11);
+}
+
+@compute @workgroup_size(64)
+fn compute_source(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let index = global_id.x;
+    if (index >= params.mesh_size * params.mesh_size) {
+        return;
+    }
+
+    // laplacian(phi) = 2 pi G (rho - mean rho). With rho = mass / h^2 the h^2 of
+    // the discrete Laplacian cancels, leaving the mass in the cell.
+    let mass = f32(atomicLoad(&cell_mass[index])) * params.mass_scale;
+    source[index] = params.coupling * (mass - params.mean_cell_mass);
+}
+
+@compute @workgroup_size(8, 8)
+fn compute_field(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    if (global_id.x >= params.mesh_size || global_id.y >= params.mesh_size) {
+        return;
+    }
+
+    let x = i32(global_id.x);
+    let y = i32(global_id.y);
+    let gradient = vec2<f32>(
+        potential[cell_index(x + 1, y)] - potential[cell_index(x - 1, y)],
+        potential[cell_index(x, y + 1)] - potential[cell_index(x, y - 1)]
+    ) / (2.0 * params.cell_size);
+
+    // Matter sits in potential wells, so it accelerates down the gradient
+    field[cell_index(x, y)] = -gradient;
+}
@@ -0,0 +1,157 @@
+// Multigrid Poisson solver for the Pellets gravity mesh
+//
+// Solves sum(neighbours) - 4 phi = rhs on one level of a periodic mesh
+// pyramid, where every level halves the resolution of the one above it. A
+// V-cycle smooths the finest level, hands the residual down to the next
+// coarser level, solves there recursively and adds the interpolated
+// correction back, so long wavelengths settle in a single cycle instead of
+// diffusing across the mesh one cell per sweep.
+//
+// 1. smooth_red / smooth_black: Red-black Gauss-Seidel sweeps, in place
+// 2. compute_residual: What the current potential leaves unsolved
+// 3. restrict_residual: Sum 2x2 residuals into the coarser level's right hand side
+// 4. solve_coarsest: Solve the smallest level outright on a single thread
+// 5. prolong_correction: Bilinearly interpolate the coarse correction back up
+//
+// The right hand side is scaled by the squared cell size of its level, which
+// is why restriction sums the four fine residuals instead of averaging them.
+
+struct LevelParams {
+    // Cells along each side of this level
+    size: u32,
+    _pad0: u32,
+    _pad1: u32,
+    _pad2: u32,
+}
+
+@group(0) @binding(0) var<uniform> level: LevelParams;
+@group(0) @binding(1) var<storage, read_write> potential: array<f32>;
+@group(0) @binding(2) var<storage, read_write> rhs: array<f32>;
+@group(0) @binding(3) var<storage, read_write> residual: array<f32>;
+// The next coarser level, half the size of this one
+@group(0) @binding(4) var<storage, read_write> coarse_potential: array<f32>;
+@group(0) @binding(5) var<storage, read_write> coarse_rhs: array<f32>;
+
+// Gauss-Seidel sweeps used to solve the coarsest level
+const COARSEST_SWEEPS: u32 = 32u;
+
+fn wrap_index(x: i32, y: i32, size: u32) -> u32 {
+    let n = i32(size);
+    let wrapped_x = ((x % n) + n) % n;
+    let wrapped_y = ((y % n) + n) % n;
+    return u32(wrapped_y * n + wrapped_x);
+}
+
+fn neighbor_sum(x: i32, y: i32) -> f32 {
+    return potential[wrap_index(x - 1, y, level.size)]
+        + potential[wrap_index(x + 1, y, level.size)]
+        + potential[wrap_index(x, y - 1, level.size)]
+        + potential[wrap_index(x, y + 1, level.size)];
+}
+
+// Cells of one colour only have neighbours of the other colour, so they can
+// all be updated at once without racing each other
+fn smooth_color(global_id: vec3<u32>, color: u32) {
+    if (global_id.x >= level.size || global_id.y >= level.size) {
+        return;
+    }
+    if ((global_id.x + global_id.y) % 2u != color) {
+        return;
+    }
+
+    let x = i32(global_id.x);
+    let y = i32(global_id.y);
+    let index = wrap_index(x, y, level.size);
+    potential[index] = (neighbor_sum(x, y) - rhs[index]) * 0.25;
+}
+
+@compute @workgroup_size(8, 8)
+fn smooth_red(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    smooth_color(global_id, 0u);
+}
+
+@compute @workgroup_size(8, 8)
+fn smooth_black(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    smooth_color(global_id, 1u);
+}
+
+@compute @workgroup_size(8, 8)
+fn compute_residual(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    if (global_id.x >= level.size || global_id.y >= level.size) {
+        return;
+    }
+
+    let x = i32(global_id.x);
+    let y = i32(global_id.y);
+    let index = wrap_index(x, y, level.size);
+    residual[index] = rhs[index] - (neighbor_sum(x, y) - 4.0 * potential[index]);
+}
+
+@compute @workgroup_size(8, 8)
+fn restrict_residual(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let coarse_size = level.size / 2u;
+    if (global_id.x >= coarse_size || global_id.y >= coarse_size) {
+        return;
+    }
+
+    // A coarse cell covers 2x2 fine cells and has four times their area
+    let x = i32(global_id.x) * 2;
+    let y = i32(global_id.y) * 2;
+    let coarse_index = global_id.y * coarse_size + global_id.x;
+    coarse_rhs[coarse_index] = residual[wrap_index(x, y, level.size)]
+        + residual[wrap_index(x + 1, y, level.size)]
+        + residual[wrap_index(x, y + 1, level.size)]
+        + residual[wrap_index(x + 1, y + 1, level.size)];
+    coarse_potential[coarse_index] = 0.0;
+}
+
+@compute @workgroup_size(1)
+fn solve_coarsest() {
+    let cell_count = level.size * level.size;
+
+    // The periodic problem only has a solution for a right hand side with
+    // zero mean, so remove any left over from rounding
+    var mean = 0.0;
+    for (var i = 0u; i < cell_count; i++) {
+        mean += rhs[i];
+    }
+    mean /= f32(cell_count);
+    for (var i = 0u; i < cell_count; i++) {
+        rhs[i] -= mean;
+    }
+
+    for (var sweep = 0u; sweep < COARSEST_SWEEPS; sweep++) {
+        for (var color = 0u; color < 2u; color++) {
+            for (var y = 0u; y < level.size; y++) {
+                for (var x = 0u; x < level.size; x++) {
+                    if ((x + y) % 2u == color) {
+                        let index = y * level.size + x;
+                        potential[index] = (neighbor_sum(i32(x), i32(y)) - rhs[index]) * 0.25;
+                    }
+                }
+            }
+        }
+    }
+}
+
+@compute @workgroup_size(8, 8)
+fn prolong_correction(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    if (global_id.x >= level.size || global_id.y >= level.size) {
+        return;
+    }
+
+    // Fine cell centre in coarse cell coordinates, relative to coarse centres
+    let coarse_size = level.size / 2u;
+    let coarse_pos = vec2<f32>(f32(global_id.x), f32(global_id.y)) * 0.5 - vec2<f32>(0.25, 0.25);
+    let base = floor(coarse_pos);
+    let t = coarse_pos - base;
+    let x = i32(base.x);
+    let y = i32(base.y);
+
+    let correction = coarse_potential[wrap_index(x, y, coarse_size)] * (1.0 - t.x) * (1.0 - t.y)
+        + coarse_potential[wrap_index(x + 1, y, coarse_size)] * t.x * (1.0 - t.y)
+        + coarse_potential[wrap_index(x, y + 1, coarse_size)] * (1.0 - t.x) * t.y
+        + coarse_potential[wrap_index(x + 1, y + 1, coarse_size)] * t.x * t.y;
+    let index = wrap_index(i32(global_id.x), i32(global_id.y), level.size);
+    potential[index] += correction;
+}
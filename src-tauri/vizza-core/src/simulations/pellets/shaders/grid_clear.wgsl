@@ -11,15 +11,9 @@ struct GridParams {
     _pad2: u32,
 }
 
-struct GridCell {
-    particle_count: u32,
-    particle_indices: array<u32, 64>,
-}
-
-@group(0) @binding(0) var<storage, read_write> grid: array<GridCell>;
-@group(0) @binding(1) var<uniform> params: GridParams;
+@group(0) @binding(0) var<uniform> params: GridParams;
 // Atomic per-cell particle counts used for concurrent population
-@group(0) @binding(2) var<storage, read_write> grid_counts: array<atomic<u32>>;
+@group(0) @binding(1) var<storage, read_write> grid_counts: array<atomic<u32>>;
 
 @compute @workgroup_size(64)
 fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
@@ -29,15 +23,7 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     if (index >= total_cells) {
         return;
     }
-    
-    // Clear the grid cell
-    grid[index].particle_count = 0u;
-    
-    // Clear particle indices array
-    for (var i = 0u; i < 64u; i++) {
-        grid[index].particle_indices[i] = 0u;
-    }
 
     // Reset atomic cell count
     atomicStore(&grid_counts[index], 0u);
-} 
\ No newline at end of file
+}
@@ -0,0 +1,158 @@
+// Spatial partitioning grid population shader
+// Creates a uniform grid for efficient neighbor lookups
+//
+// The grid is built with a counting sort, so a cell can hold any number of particles:
+// 1. count_particles: Count the particles in each cell, remembering each particle's slot
+// 2. prefix_sum: Turn the counts into the offset of each cell's first particle
+// 3. scatter_particles: Write every particle index into its cell's range of the sorted array
+//
+// The physics shader then visits cell c as sorted_indices[cell_starts[c]..cell_starts[c + 1]].
+
+struct Particle {
+    position: vec2<f32>,
+    velocity: vec2<f32>,
+    mass: f32,
+    radius: f32,
+    clump_id: u32,
+    density: f32,
+    grabbed: u32,
+    _pad0: u32,
+    previous_position: vec2<f32>,
+}
+
+struct GridParams {
+    particle_count: u32,
+    grid_width: u32,
+    grid_height: u32,
+    cell_size: f32,
+    world_width: f32,  // 2.0 for [-1,1] space
+    world_height: f32, // 2.0 for [-1,1] space
+    _pad1: u32,
+    _pad2: u32,
+}
+
+// Slot value for particles left out of the grid
+const NO_SLOT: u32 = 0xffffffffu;
+
+// Cell capacity of the old fixed-size grid, kept to report how many particles it would have dropped
+const LEGACY_CELL_CAPACITY: u32 = 64u;
+
+// Threads in the single workgroup that scans the cell counts
+const SCAN_THREADS: u32 = 256u;
+
+@group(0) @binding(0) var<storage, read> particles: array<Particle>;
+@group(0) @binding(1) var<uniform> params: GridParams;
+// Atomic per-cell particle counts for safe concurrent insertion
+@group(0) @binding(2) var<storage, read_write> grid_counts: array<atomic<u32>>;
+// Offset of each cell's first particle in sorted_indices, plus the total at the end
+@group(0) @binding(3) var<storage, read_write> cell_starts: array<u32>;
+// Position of each particle within its cell
+@group(0) @binding(4) var<storage, read_write> particle_slots: array<u32>;
+// Particle indices ordered by cell
+@group(0) @binding(5) var<storage, read_write> sorted_indices: array<u32>;
+// Debug counters read back by the CPU: [0] = particles beyond the legacy cell capacity
+@group(0) @binding(6) var<storage, read_write> grid_stats: array<u32>;
+
+var<workgroup> partial_sums: array<u32, SCAN_THREADS>;
+var<workgroup> overflow_total: atomic<u32>;
+
+// Convert world position to grid coordinates
+fn world_to_grid(pos: vec2<f32>) -> vec2<u32> {
+    // Convert from [-1,1] to [0,1] then to grid coordinates
+    let normalized_pos = (pos + vec2<f32>(1.0, 1.0)) * 0.5;
+    let grid_x = u32(normalized_pos.x * f32(params.grid_width));
+    let grid_y = u32(normalized_pos.y * f32(params.grid_height));
+    
+    // Clamp to grid bounds
+    return vec2<u32>(
+        min(grid_x, params.grid_width - 1u),
+        min(grid_y, params.grid_height - 1u)
+    );
+}
+
+// Get grid cell index from coordinates
+fn grid_coord_to_index(coord: vec2<u32>) -> u32 {
+    return coord.y * params.grid_width + coord.x;
+}
+
+@compute @workgroup_size(64)
+fn count_particles(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let index = global_id.x;
+    if (index >= params.particle_count) {
+        return;
+    }
+    
+    let particle = particles[index];
+    
+    // Skip grabbed particles (they don't need physics)
+    if (particle.grabbed != 0u) {
+        particle_slots[index] = NO_SLOT;
+        return;
+    }
+    
+    // Reserve a slot in the particle's cell
+    let cell_index = grid_coord_to_index(world_to_grid(particle.position));
+    particle_slots[index] = atomicAdd(&grid_counts[cell_index], 1u);
+}
+
+@compute @workgroup_size(256)
+fn prefix_sum(@builtin(local_invocation_id) local_id: vec3<u32>) {
+    let thread = local_id.x;
+    let total_cells = params.grid_width * params.grid_height;
+    
+    // Each thread sums a contiguous run of cells
+    let run_length = (total_cells + SCAN_THREADS - 1u) / SCAN_THREADS;
+    let begin = min(thread * run_length, total_cells);
+    let end = min(begin + run_length, total_cells);
+    var run_sum = 0u;
+    var overflow = 0u;
+    for (var cell = begin; cell < end; cell++) {
+        let count = atomicLoad(&grid_counts[cell]);
+        run_sum += count;
+        overflow += max(count, LEGACY_CELL_CAPACITY) - LEGACY_CELL_CAPACITY;
+    }
+    partial_sums[thread] = run_sum;
+    atomicAdd(&overflow_total, overflow);
+    workgroupBarrier();
+    
+    // Inclusive scan of the run sums across the workgroup
+    for (var offset = 1u; offset < SCAN_THREADS; offset *= 2u) {
+        var previous = 0u;
+        if (thread >= offset) {
+            previous = partial_sums[thread - offset];
+        }
+        workgroupBarrier();
+        partial_sums[thread] += previous;
+        workgroupBarrier();
+    }
+    
+    // Write the exclusive offsets for this thread's run
+    var offset = partial_sums[thread] - run_sum;
+    for (var cell = begin; cell < end; cell++) {
+        cell_starts[cell] = offset;
+        offset += atomicLoad(&grid_counts[cell]);
+    }
+    
+    if (thread == SCAN_THREADS - 1u) {
+        cell_starts[total_cells] = partial_sums[thread];
+    }
+    if (thread == 0u) {
+        grid_stats[0] = atomicLoad(&overflow_total);
+    }
+}
+
+@compute @workgroup_size(64)
+fn scatter_particles(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let index = global_id.x;
+    if (index >= params.particle_count) {
+        return;
+    }
+    
+    let slot = particle_slots[index];
+    if (slot == NO_SLOT) {
+        return;
+    }
+    
+    let cell_index = grid_coord_to_index(world_to_grid(particles[index].position));
+    sorted_indices[cell_starts[cell_index] + slot] = index;
+}
@@ -14,14 +14,17 @@
 //! ## Computational Approach
 //!
 //! The simulation uses a multi-stage approach: spatial partitioning for efficient
-//! neighbor lookups, compute shaders handle the physics calculations that determine
-//! particle behavior, while render shaders create the visual representation.
+//! neighbor lookups, a particle-mesh solver for long-range gravity, compute shaders
+//! handle the physics calculations that determine particle behavior, while render
+//! shaders create the visual representation.
 
 // Compute shaders
 pub const PHYSICS_COMPUTE_SHADER: &str = include_str!("physics_compute.wgsl");
 pub const DENSITY_COMPUTE_SHADER: &str = include_str!("density_compute.wgsl");
 pub const GRID_CLEAR_SHADER: &str = include_str!("grid_clear.wgsl");
 pub const GRID_POPULATE_SHADER: &str = include_str!("grid_populate.wgsl");
+pub const GRAVITY_MESH_SHADER: &str = include_str!("gravity_mesh.wgsl");
+pub const GRAVITY_MULTIGRID_SHADER: &str = include_str!("gravity_multigrid.wgsl");
 
 // Offscreen rendering shaders
 pub const BACKGROUND_RENDER_SHADER: &str = include_str!("background_render.wgsl");
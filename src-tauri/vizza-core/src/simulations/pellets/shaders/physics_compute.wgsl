@@ -7,6 +7,10 @@
 //
 // Spatial Partitioning: Uses a uniform grid to reduce O(n²) complexity to O(n)
 // for neighbor lookups, dramatically improving performance with large particle counts.
+//
+// Gravity: Pairs in neighbouring grid cells attract directly, which resolves close
+// encounters. Everything further away comes from the particle-mesh field solved in
+// gravity_mesh.wgsl, so clumps feel each other across the whole toroidal world.
 
 struct Particle {
     position: vec2<f32>,
@@ -52,17 +56,27 @@ struct GridParams {
     _pad2: u32,
 }
 
-struct GridCell {
+struct GravityMeshParams {
     particle_count: u32,
-    particle_indices: array<u32, 64>,
+    mesh_size: u32,
+    cell_size: f32,
+    coupling: f32,
+    mean_cell_mass: f32,
+    mass_scale: f32,
+    _pad0: u32,
+    _pad1: u32,
 }
 
 @group(0) @binding(0) var<storage, read_write> particles: array<Particle>;
 @group(0) @binding(1) var<uniform> params: PhysicsParams;
-@group(0) @binding(2) var<storage, read> grid: array<GridCell>;
+// Particle indices sorted by grid cell
+@group(0) @binding(2) var<storage, read> sorted_indices: array<u32>;
 @group(0) @binding(3) var<uniform> grid_params: GridParams;
-// Atomic per-cell particle counts for deterministic neighbor iteration
-@group(0) @binding(4) var<storage, read> grid_counts: array<atomic<u32>>;
+// Cell c holds sorted_indices[cell_starts[c]..cell_starts[c + 1]]
+@group(0) @binding(4) var<storage, read> cell_starts: array<u32>;
+// Long-range gravitational acceleration at the centre of each mesh cell
+@group(0) @binding(5) var<storage, read> gravity_field: array<vec2<f32>>;
+@group(0) @binding(6) var<uniform> mesh_params: GravityMeshParams;
 
 // Convert world position to grid coordinates
 fn world_to_grid(pos: vec2<f32>) -> vec2<u32> {
@@ -176,9 +190,9 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
                 let cx = (i32(center_cell.x) + dx + i32(grid_params.grid_width)) % i32(grid_params.grid_width);
                 let cy = (i32(center_cell.y) + dy + i32(grid_params.grid_height)) % i32(grid_params.grid_height);
                 let cell_index = grid_coord_to_index(vec2<u32>(u32(cx), u32(cy)));
-                let count = atomicLoad(&grid_counts[cell_index]);
-                for (var k = 0u; k < min(count, 64u); k++) {
-                    let j = grid[cell_index].particle_indices[k];
+                let cell_end = cell_starts[cell_index + 1u];
+                for (var k = cell_starts[cell_index]; k < cell_end; k++) {
+                    let j = sorted_indices[k];
                     if (j == index) { continue; }
                     let other = particles[j];
                     var delta = other.position - particle.position;
@@ -225,9 +239,10 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
 fn compute_acceleration(particle: Particle, particle_index: u32) -> vec2<f32> {
     var acceleration = vec2<f32>(0.0, 0.0);
     
-    // Gravitational forces using spatial grid
+    // Short-range gravity from the spatial grid, long-range gravity from the mesh
     if (params.gravitational_constant > 0.0) {
         acceleration += compute_gravity_grid(particle, particle_index);
+        acceleration += sample_gravity_mesh(particle.position);
     }
     
     // Mouse interaction
@@ -252,9 +267,9 @@ fn compute_gravity_grid(particle: Particle, particle_index: u32) -> vec2<f32> {
             let cx = (i32(center_cell.x) + dx + i32(grid_params.grid_width)) % i32(grid_params.grid_width);
             let cy = (i32(center_cell.y) + dy + i32(grid_params.grid_height)) % i32(grid_params.grid_height);
             let cell_index = grid_coord_to_index(vec2<u32>(u32(cx), u32(cy)));
-            let count = atomicLoad(&grid_counts[cell_index]);
-            for (var k = 0u; k < min(count, 64u); k++) {
-                let neighbor_index = grid[cell_index].particle_indices[k];
+            let cell_end = cell_starts[cell_index + 1u];
+            for (var k = cell_starts[cell_index]; k < cell_end; k++) {
+                let neighbor_index = sorted_indices[k];
                 if (neighbor_index == particle_index) { continue; }
                 let other = particles[neighbor_index];
                 var delta = other.position - particle.position;
@@ -283,6 +298,24 @@ fn compute_gravity_grid(particle: Particle, particle_index: u32) -> vec2<f32> {
     return total_force / particle.mass;
 }
 
+// Interpolate the mesh field with the cloud-in-cell weights used to deposit mass
+fn sample_gravity_mesh(position: vec2<f32>) -> vec2<f32> {
+    let size = i32(mesh_params.mesh_size);
+    let mesh_pos = (position + vec2<f32>(1.0, 1.0)) / mesh_params.cell_size - vec2<f32>(0.5, 0.5);
+    let base = floor(mesh_pos);
+    let t = mesh_pos - base;
+    let x0 = ((i32(base.x) % size) + size) % size;
+    let y0 = ((i32(base.y) % size) + size) % size;
+    let x1 = (x0 + 1) % size;
+    let y1 = (y0 + 1) % size;
+
+    let f00 = gravity_field[u32(y0 * size + x0)];
+    let f10 = gravity_field[u32(y0 * size + x1)];
+    let f01 = gravity_field[u32(y1 * size + x0)];
+    let f11 = gravity_field[u32(y1 * size + x1)];
+    return mix(mix(f00, f10, t.x), mix(f01, f11, t.x), t.y);
+}
+
 fn compute_mouse_force(particle: Particle) -> vec2<f32> {
     let delta = params.mouse_position - particle.position;
     let aspect_corrected_delta = vec2<f32>(delta.x * params.aspect_ratio, delta.y);
@@ -315,9 +348,9 @@ fn compute_collision_forces_grid(particle: Particle, particle_index: u32) -> vec
             let cx = (i32(center_cell.x) + dx + i32(grid_params.grid_width)) % i32(grid_params.grid_width);
             let cy = (i32(center_cell.y) + dy + i32(grid_params.grid_height)) % i32(grid_params.grid_height);
             let cell_index = grid_coord_to_index(vec2<u32>(u32(cx), u32(cy)));
-            let count = atomicLoad(&grid_counts[cell_index]);
-            for (var k = 0u; k < min(count, 64u); k++) {
-                let j = grid[cell_index].particle_indices[k];
+            let cell_end = cell_starts[cell_index + 1u];
+            for (var k = cell_starts[cell_index]; k < cell_end; k++) {
+                let j = sorted_indices[k];
                 if (j == particle_index) { continue; }
                 let other = particles[j];
                 var delta = other.position - particle.position;
@@ -368,9 +401,9 @@ fn resolve_collisions(particle: ptr<function, Particle>, particle_index: u32) {
                 let cx = (i32(center_cell.x) + dx + i32(grid_params.grid_width)) % i32(grid_params.grid_width);
                 let cy = (i32(center_cell.y) + dy + i32(grid_params.grid_height)) % i32(grid_params.grid_height);
                 let cell_index = grid_coord_to_index(vec2<u32>(u32(cx), u32(cy)));
-                let count = atomicLoad(&grid_counts[cell_index]);
-                for (var k = 0u; k < min(count, 64u); k++) {
-                    let i = grid[cell_index].particle_indices[k];
+                let cell_end = cell_starts[cell_index + 1u];
+                for (var k = cell_starts[cell_index]; k < cell_end; k++) {
+                    let i = sorted_indices[k];
                     if (i == particle_index) { continue; }
                     let other = particles[i];
                     var delta = (*particle).position - other.position;
@@ -18,11 +18,11 @@
 //! This design enables both high-performance physics simulation and
 //! intuitive user control over the system's behavior.
 
-use crate::commands::app_settings::{AppSettings, TextureFiltering};
 use crate::error::{SimulationError, SimulationResult};
+use crate::settings::{RenderSettings, TextureFiltering};
 use crate::simulations::shared::{
-    AverageColorResources, BindGroupBuilder, ComputePipelineBuilder, LutManager,
-    RenderPipelineBuilder, camera::Camera,
+    AverageColorResources, BindGroupBuilder, BufferReadback, ComputePipelineBuilder, LutManager,
+    RenderPipelineBuilder, SimulationSnapshot, camera::Camera,
 };
 use bytemuck::{Pod, Zeroable};
 use serde_json::Value;
@@ -30,6 +30,7 @@ use std::sync::Arc;
 use wgpu::util::DeviceExt;
 use wgpu::{Device, Queue, SurfaceConfiguration, TextureView};
 
+use super::gravity_mesh::GravityMesh;
 use super::shaders::{
     BACKGROUND_RENDER_SHADER, PARTICLE_FRAGMENT_RENDER_SHADER, PARTICLE_RENDER_SHADER,
     RENDER_INFINITE_SHADER,
@@ -121,12 +122,11 @@ pub struct GridParams {
     pub _pad2: u32,
 }
 
-#[repr(C)]
-#[derive(Copy, Clone, Pod, Zeroable)]
-pub struct GridCell {
-    pub particle_count: u32,
-    pub particle_indices: [u32; 64], // Max 64 particles per cell
-}
+/// Number of debug counters written by the grid prefix sum pass
+const GRID_STATS_LEN: usize = 1;
+
+/// Frames between reads of the grid debug counters
+const GRID_STATS_READBACK_INTERVAL: u64 = 30;
 
 // GPU-based physics implementation - no Rapier needed
 
@@ -141,16 +141,25 @@ pub struct PelletsModel {
     pub lut_buffer: wgpu::Buffer,
     pub background_color_buffer: wgpu::Buffer,
 
-    // Spatial partitioning resources
-    pub grid_buffer: wgpu::Buffer,
+    // Spatial partitioning resources, built each frame with a counting sort
     pub grid_params_buffer: wgpu::Buffer,
     pub grid_counts_buffer: wgpu::Buffer,
+    pub grid_starts_buffer: wgpu::Buffer,
+    pub grid_slots_buffer: wgpu::Buffer,
+    pub grid_indices_buffer: wgpu::Buffer,
+    pub grid_stats_buffer: wgpu::Buffer,
+    pub grid_stats_readback: BufferReadback,
+
+    // Long-range gravity solver
+    pub gravity_mesh: GravityMesh,
 
     // Compute pipelines
     pub physics_compute_pipeline: wgpu::ComputePipeline,
     pub density_compute_pipeline: wgpu::ComputePipeline,
     pub grid_clear_pipeline: wgpu::ComputePipeline,
-    pub grid_populate_pipeline: wgpu::ComputePipeline,
+    pub grid_count_pipeline: wgpu::ComputePipeline,
+    pub grid_prefix_sum_pipeline: wgpu::ComputePipeline,
+    pub grid_scatter_pipeline: wgpu::ComputePipeline,
 
     // Compute bind groups
     pub physics_bind_group: wgpu::BindGroup,
@@ -198,7 +207,7 @@ pub struct PelletsModel {
     pub state: State,
     pub camera: Camera,
     pub lut_manager: Arc<LutManager>,
-    pub app_settings: AppSettings,
+    pub render_settings: RenderSettings,
 
     // Surface configuration
     pub surface_config: SurfaceConfiguration,
@@ -222,7 +231,7 @@ impl PelletsModel {
         _queue: &Arc<Queue>,
         surface_config: &SurfaceConfiguration,
         settings: Settings,
-        app_settings: &AppSettings,
+        render_settings: &RenderSettings,
         lut_manager: &LutManager,
     ) -> Result<Self, Box<dyn std::error::Error>> {
         // Initialize particles
@@ -232,7 +241,9 @@ impl PelletsModel {
         let particle_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Pellets Particle Buffer"),
             contents: bytemuck::cast_slice(&particles),
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
         });
 
         let camera = Camera::new(
@@ -498,6 +509,26 @@ impl PelletsModel {
                         },
                         count: None,
                     },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 5,
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Storage { read_only: true },
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 6,
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
                 ],
             });
 
@@ -661,22 +692,6 @@ impl PelletsModel {
         let grid_height = (2.0 / cell_size) as u32; // 20 cells across [-1,1]
         let total_cells = grid_width * grid_height;
 
-        // Initialize grid with empty cells
-        let grid_cells = vec![
-            GridCell {
-                particle_count: 0,
-                particle_indices: [0; 64],
-            };
-            total_cells as usize
-        ];
-
-        // Create grid buffer
-        let grid_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Pellets Grid Buffer"),
-            contents: bytemuck::cast_slice(&grid_cells),
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-        });
-
         // Create atomic counts buffer (one u32 per cell)
         let grid_counts_zeroes: Vec<u32> = vec![0u32; total_cells as usize];
         let grid_counts_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -685,6 +700,30 @@ impl PelletsModel {
             usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
         });
 
+        // Create cell start offsets buffer (one u32 per cell plus the total)
+        let grid_starts_zeroes: Vec<u32> = vec![0u32; total_cells as usize + 1];
+        let grid_starts_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Pellets Grid Starts Buffer"),
+            contents: bytemuck::cast_slice(&grid_starts_zeroes),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+        });
+
+        // Create per-particle slot and sorted index buffers
+        let (grid_slots_buffer, grid_indices_buffer) =
+            Self::create_grid_particle_buffers(device, particle_buffer.size());
+
+        // Create debug counters and their readback buffer
+        let grid_stats_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Pellets Grid Stats Buffer"),
+            contents: bytemuck::cast_slice(&[0u32; GRID_STATS_LEN]),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        });
+        let grid_stats_readback = BufferReadback::new(
+            device,
+            "Pellets Grid Stats Staging Buffer",
+            grid_stats_buffer.size(),
+        );
+
         // Create grid parameters
         let grid_params = GridParams {
             particle_count: settings.particle_count,
@@ -722,16 +761,6 @@ impl PelletsModel {
                     wgpu::BindGroupLayoutEntry {
                         binding: 0,
                         visibility: wgpu::ShaderStages::COMPUTE,
-                        ty: wgpu::BindingType::Buffer {
-                            ty: wgpu::BufferBindingType::Storage { read_only: false },
-                            has_dynamic_offset: false,
-                            min_binding_size: None,
-                        },
-                        count: None,
-                    },
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 1,
-                        visibility: wgpu::ShaderStages::COMPUTE,
                         ty: wgpu::BindingType::Buffer {
                             ty: wgpu::BufferBindingType::Uniform,
                             has_dynamic_offset: false,
@@ -740,7 +769,7 @@ impl PelletsModel {
                         count: None,
                     },
                     wgpu::BindGroupLayoutEntry {
-                        binding: 2,
+                        binding: 1,
                         visibility: wgpu::ShaderStages::COMPUTE,
                         ty: wgpu::BindingType::Buffer {
                             ty: wgpu::BufferBindingType::Storage { read_only: false },
@@ -752,6 +781,17 @@ impl PelletsModel {
                 ],
             });
 
+        // Bindings 2-6 are the counts, starts, slots, sorted indices and stats
+        let grid_populate_storage_entry = |binding: u32| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only: false },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
         let grid_populate_bind_group_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                 label: Some("Pellets Grid Populate Bind Group Layout"),
@@ -769,16 +809,6 @@ impl PelletsModel {
                     wgpu::BindGroupLayoutEntry {
                         binding: 1,
                         visibility: wgpu::ShaderStages::COMPUTE,
-                        ty: wgpu::BindingType::Buffer {
-                            ty: wgpu::BufferBindingType::Storage { read_only: false },
-                            has_dynamic_offset: false,
-                            min_binding_size: None,
-                        },
-                        count: None,
-                    },
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 2,
-                        visibility: wgpu::ShaderStages::COMPUTE,
                         ty: wgpu::BindingType::Buffer {
                             ty: wgpu::BufferBindingType::Uniform,
                             has_dynamic_offset: false,
@@ -786,16 +816,11 @@ impl PelletsModel {
                         },
                         count: None,
                     },
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 3,
-                        visibility: wgpu::ShaderStages::COMPUTE,
-                        ty: wgpu::BindingType::Buffer {
-                            ty: wgpu::BufferBindingType::Storage { read_only: false },
-                            has_dynamic_offset: false,
-                            min_binding_size: None,
-                        },
-                        count: None,
-                    },
+                    grid_populate_storage_entry(2),
+                    grid_populate_storage_entry(3),
+                    grid_populate_storage_entry(4),
+                    grid_populate_storage_entry(5),
+                    grid_populate_storage_entry(6),
                 ],
             });
 
@@ -806,42 +831,27 @@ impl PelletsModel {
             entries: &[
                 wgpu::BindGroupEntry {
                     binding: 0,
-                    resource: grid_buffer.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
                     resource: grid_params_buffer.as_entire_binding(),
                 },
-                wgpu::BindGroupEntry {
-                    binding: 2,
-                    resource: grid_counts_buffer.as_entire_binding(),
-                },
-            ],
-        });
-
-        let grid_populate_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("Pellets Grid Populate Bind Group"),
-            layout: &grid_populate_bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: particle_buffer.as_entire_binding(),
-                },
                 wgpu::BindGroupEntry {
                     binding: 1,
-                    resource: grid_buffer.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 2,
-                    resource: grid_params_buffer.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 3,
                     resource: grid_counts_buffer.as_entire_binding(),
                 },
             ],
         });
 
+        let grid_populate_bind_group =
+            BindGroupBuilder::new(device, &grid_populate_bind_group_layout)
+                .add_buffer(0, &particle_buffer)
+                .add_buffer(1, &grid_params_buffer)
+                .add_buffer(2, &grid_counts_buffer)
+                .add_buffer(3, &grid_starts_buffer)
+                .add_buffer(4, &grid_slots_buffer)
+                .add_buffer(5, &grid_indices_buffer)
+                .add_buffer(6, &grid_stats_buffer)
+                .with_label("Pellets Grid Populate Bind Group".to_string())
+                .build();
+
         // Create grid compute pipelines
         let grid_clear_pipeline =
             device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
@@ -859,23 +869,33 @@ impl PelletsModel {
                 compilation_options: Default::default(),
             });
 
-        let grid_populate_pipeline =
+        // The three counting sort passes share one layout
+        let grid_populate_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Pellets Grid Populate Pipeline Layout"),
+                bind_group_layouts: &[&grid_populate_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+        let grid_populate_pipeline = |label: &str, entry_point: &str| {
             device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
-                label: Some("Pellets Grid Populate Pipeline"),
-                layout: Some(
-                    &device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                        label: Some("Pellets Grid Populate Pipeline Layout"),
-                        bind_group_layouts: &[&grid_populate_bind_group_layout],
-                        push_constant_ranges: &[],
-                    }),
-                ),
+                label: Some(label),
+                layout: Some(&grid_populate_pipeline_layout),
                 module: &grid_populate_shader,
-                entry_point: Some("main"),
+                entry_point: Some(entry_point),
                 cache: None,
                 compilation_options: Default::default(),
-            });
+            })
+        };
+        let grid_count_pipeline =
+            grid_populate_pipeline("Pellets Grid Count Pipeline", "count_particles");
+        let grid_prefix_sum_pipeline =
+            grid_populate_pipeline("Pellets Grid Prefix Sum Pipeline", "prefix_sum");
+        let grid_scatter_pipeline =
+            grid_populate_pipeline("Pellets Grid Scatter Pipeline", "scatter_particles");
+
+        let gravity_mesh = GravityMesh::new(device, &particle_buffer);
 
-        // Create physics bind group (after grid buffers are created)
+        // Create physics bind group (after grid and gravity mesh buffers are created)
         let physics_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("Pellets Physics Bind Group"),
             layout: &physics_bind_group_layout,
@@ -890,7 +910,7 @@ impl PelletsModel {
                 },
                 wgpu::BindGroupEntry {
                     binding: 2,
-                    resource: grid_buffer.as_entire_binding(),
+                    resource: grid_indices_buffer.as_entire_binding(),
                 },
                 wgpu::BindGroupEntry {
                     binding: 3,
@@ -898,7 +918,15 @@ impl PelletsModel {
                 },
                 wgpu::BindGroupEntry {
                     binding: 4,
-                    resource: grid_counts_buffer.as_entire_binding(),
+                    resource: grid_starts_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 5,
+                    resource: gravity_mesh.field_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 6,
+                    resource: gravity_mesh.params_buffer.as_entire_binding(),
                 },
             ],
         });
@@ -920,7 +948,7 @@ impl PelletsModel {
         });
 
         let display_view = display_texture.create_view(&wgpu::TextureViewDescriptor::default());
-        let filter_mode = if app_settings.texture_filtering == TextureFiltering::Linear {
+        let filter_mode = if render_settings.texture_filtering == TextureFiltering::Linear {
             wgpu::FilterMode::Linear
         } else {
             wgpu::FilterMode::Nearest
@@ -1354,7 +1382,7 @@ impl PelletsModel {
                     }),
                     entry_point: Some("fs_main_texture"),
                     targets: &[Some(wgpu::ColorTargetState {
-                        format: wgpu::TextureFormat::Bgra8UnormSrgb,
+                        format: surface_config.format,
                         blend: Some(wgpu::BlendState::REPLACE),
                         write_mask: wgpu::ColorWrites::ALL,
                     })],
@@ -1418,13 +1446,20 @@ impl PelletsModel {
             post_effect_params_buffer,
             lut_buffer,
             background_color_buffer,
-            grid_buffer,
             grid_params_buffer,
             grid_counts_buffer,
+            grid_starts_buffer,
+            grid_slots_buffer,
+            grid_indices_buffer,
+            grid_stats_buffer,
+            grid_stats_readback,
+            gravity_mesh,
             physics_compute_pipeline,
             density_compute_pipeline,
             grid_clear_pipeline,
-            grid_populate_pipeline,
+            grid_count_pipeline,
+            grid_prefix_sum_pipeline,
+            grid_scatter_pipeline,
             physics_bind_group,
             density_bind_group,
             grid_clear_bind_group,
@@ -1459,7 +1494,7 @@ impl PelletsModel {
             state,
             camera,
             lut_manager: Arc::new(lut_manager.clone()),
-            app_settings: app_settings.clone(),
+            render_settings: render_settings.clone(),
             surface_config: surface_config.clone(),
             frame_count: 0,
             density_update_frequency: 3, // Update density every 3 frames for performance
@@ -1473,7 +1508,7 @@ impl PelletsModel {
 
     fn initialize_particles(count: u32, settings: &Settings) -> Vec<Particle> {
         use rand::Rng;
-        let mut rng = rand::rng();
+        let mut rng = crate::simulations::shared::random::rng();
         let mut particles = Vec::with_capacity(count as usize);
 
         if count == 1 {
@@ -1589,6 +1624,7 @@ impl PelletsModel {
         queue: &Arc<Queue>,
     ) -> SimulationResult<()> {
         self.frame_count += 1;
+        self.collect_grid_stats(device);
 
         // Update physics parameters
         self.update_physics_params(queue);
@@ -1614,22 +1650,38 @@ impl PelletsModel {
             compute_pass.dispatch_workgroups(num_workgroups, 1, 1);
         }
 
-        // Step 2: Populate the spatial grid with particle positions
+        // Step 2: Populate the spatial grid by counting sort
         {
             let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
                 label: Some("Pellets Grid Populate Pass"),
                 timestamp_writes: None,
             });
 
-            compute_pass.set_pipeline(&self.grid_populate_pipeline);
             compute_pass.set_bind_group(0, &self.grid_populate_bind_group, &[]);
 
             let workgroup_size = 64;
             let num_workgroups = self.settings.particle_count.div_ceil(workgroup_size);
+
+            // Count particles per cell
+            compute_pass.set_pipeline(&self.grid_count_pipeline);
+            compute_pass.dispatch_workgroups(num_workgroups, 1, 1);
+
+            // Offsets of each cell in the sorted array, scanned by a single workgroup
+            compute_pass.set_pipeline(&self.grid_prefix_sum_pipeline);
+            compute_pass.dispatch_workgroups(1, 1, 1);
+
+            // Write particle indices into their cells
+            compute_pass.set_pipeline(&self.grid_scatter_pipeline);
             compute_pass.dispatch_workgroups(num_workgroups, 1, 1);
         }
 
-        // Step 3: Run physics simulation using spatial grid
+        // Step 3: Solve long-range gravity on the mesh
+        if self.settings.gravitational_constant > 0.0 {
+            self.gravity_mesh
+                .dispatch(&mut encoder, self.settings.particle_count);
+        }
+
+        // Step 4: Run physics simulation using spatial grid
         {
             let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
                 label: Some("Pellets Physics Compute Pass"),
@@ -1663,9 +1715,42 @@ impl PelletsModel {
         }
 
         queue.submit(std::iter::once(encoder.finish()));
+
+        if self.frame_count % GRID_STATS_READBACK_INTERVAL == 1 {
+            self.grid_stats_readback
+                .request(&self.grid_stats_buffer, device, queue);
+        }
         Ok(())
     }
 
+    /// Copy the grid debug counters into the state once a requested readback
+    /// has finished
+    fn collect_grid_stats(&mut self, device: &Arc<Device>) {
+        if let Some(stats) = self.grid_stats_readback.collect::<u32>(device) {
+            self.state.grid_overflow_count = stats[0];
+        }
+    }
+
+    /// Create the per-particle grid buffers for a particle buffer of `particle_buffer_size` bytes
+    fn create_grid_particle_buffers(
+        device: &Arc<Device>,
+        particle_buffer_size: u64,
+    ) -> (wgpu::Buffer, wgpu::Buffer) {
+        let capacity = (particle_buffer_size / std::mem::size_of::<Particle>() as u64).max(1);
+        let buffer = |label: &str| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(label),
+                size: capacity * std::mem::size_of::<u32>() as u64,
+                usage: wgpu::BufferUsages::STORAGE,
+                mapped_at_creation: false,
+            })
+        };
+        (
+            buffer("Pellets Grid Slots Buffer"),
+            buffer("Pellets Grid Indices Buffer"),
+        )
+    }
+
     fn update_physics_params(&mut self, queue: &Arc<Queue>) {
         // Apply velocity decay when mouse is not pressed (after throwing)
         if !self.state.mouse_pressed {
@@ -1725,6 +1810,13 @@ impl PelletsModel {
             0,
             bytemuck::cast_slice(&[grid_params]),
         );
+
+        let particle_count = (self.settings.particle_count as usize).min(self.particles.len());
+        self.gravity_mesh.update_params(
+            queue,
+            &self.particles[..particle_count],
+            self.settings.gravitational_constant,
+        );
     }
 
     fn update_density_params(&self, queue: &Arc<Queue>) {
@@ -1777,7 +1869,9 @@ impl PelletsModel {
             self.particle_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
                 label: Some("Pellets Particle Buffer"),
                 contents: bytemuck::cast_slice(&self.particles),
-                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+                usage: wgpu::BufferUsages::STORAGE
+                    | wgpu::BufferUsages::COPY_DST
+                    | wgpu::BufferUsages::COPY_SRC,
             });
 
             // Recreate the bind groups since the buffer changed
@@ -1795,6 +1889,10 @@ impl PelletsModel {
     }
 
     fn recreate_bind_groups(&mut self, device: &Arc<Device>) -> SimulationResult<()> {
+        // The per-particle grid buffers follow the particle buffer's capacity
+        (self.grid_slots_buffer, self.grid_indices_buffer) =
+            Self::create_grid_particle_buffers(device, self.particle_buffer.size());
+
         // Recreate physics bind group layout (with grid buffers)
         let physics_bind_group_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -1850,26 +1948,18 @@ impl PelletsModel {
                         },
                         count: None,
                     },
-                ],
-            });
-
-        // Create density bind group layout (separate from physics layout)
-        let density_bind_group_layout =
-            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                label: Some("Pellets Density Bind Group Layout"),
-                entries: &[
                     wgpu::BindGroupLayoutEntry {
-                        binding: 0,
+                        binding: 5,
                         visibility: wgpu::ShaderStages::COMPUTE,
                         ty: wgpu::BindingType::Buffer {
-                            ty: wgpu::BufferBindingType::Storage { read_only: false },
+                            ty: wgpu::BufferBindingType::Storage { read_only: true },
                             has_dynamic_offset: false,
                             min_binding_size: None,
                         },
                         count: None,
                     },
                     wgpu::BindGroupLayoutEntry {
-                        binding: 1,
+                        binding: 6,
                         visibility: wgpu::ShaderStages::COMPUTE,
                         ty: wgpu::BindingType::Buffer {
                             ty: wgpu::BufferBindingType::Uniform,
@@ -1881,24 +1971,14 @@ impl PelletsModel {
                 ],
             });
 
-        // Create grid populate bind group layout
-        let grid_populate_bind_group_layout =
+        // Create density bind group layout (separate from physics layout)
+        let density_bind_group_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                label: Some("Pellets Grid Populate Bind Group Layout"),
+                label: Some("Pellets Density Bind Group Layout"),
                 entries: &[
                     wgpu::BindGroupLayoutEntry {
                         binding: 0,
                         visibility: wgpu::ShaderStages::COMPUTE,
-                        ty: wgpu::BindingType::Buffer {
-                            ty: wgpu::BufferBindingType::Storage { read_only: true },
-                            has_dynamic_offset: false,
-                            min_binding_size: None,
-                        },
-                        count: None,
-                    },
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 1,
-                        visibility: wgpu::ShaderStages::COMPUTE,
                         ty: wgpu::BindingType::Buffer {
                             ty: wgpu::BufferBindingType::Storage { read_only: false },
                             has_dynamic_offset: false,
@@ -1907,7 +1987,7 @@ impl PelletsModel {
                         count: None,
                     },
                     wgpu::BindGroupLayoutEntry {
-                        binding: 2,
+                        binding: 1,
                         visibility: wgpu::ShaderStages::COMPUTE,
                         ty: wgpu::BindingType::Buffer {
                             ty: wgpu::BufferBindingType::Uniform,
@@ -1916,16 +1996,6 @@ impl PelletsModel {
                         },
                         count: None,
                     },
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 3,
-                        visibility: wgpu::ShaderStages::COMPUTE,
-                        ty: wgpu::BindingType::Buffer {
-                            ty: wgpu::BufferBindingType::Storage { read_only: false },
-                            has_dynamic_offset: false,
-                            min_binding_size: None,
-                        },
-                        count: None,
-                    },
                 ],
             });
 
@@ -1944,7 +2014,7 @@ impl PelletsModel {
                 },
                 wgpu::BindGroupEntry {
                     binding: 2,
-                    resource: self.grid_buffer.as_entire_binding(),
+                    resource: self.grid_indices_buffer.as_entire_binding(),
                 },
                 wgpu::BindGroupEntry {
                     binding: 3,
@@ -1952,11 +2022,21 @@ impl PelletsModel {
                 },
                 wgpu::BindGroupEntry {
                     binding: 4,
-                    resource: self.grid_counts_buffer.as_entire_binding(),
+                    resource: self.grid_starts_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 5,
+                    resource: self.gravity_mesh.field_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 6,
+                    resource: self.gravity_mesh.params_buffer.as_entire_binding(),
                 },
             ],
         });
 
+        self.gravity_mesh.rebind(device, &self.particle_buffer);
+
         // Recreate density compute bind group
         self.density_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("Pellets Density Bind Group"),
@@ -1974,28 +2054,17 @@ impl PelletsModel {
         });
 
         // Recreate grid populate bind group
-        self.grid_populate_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("Pellets Grid Populate Bind Group"),
-            layout: &grid_populate_bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: self.particle_buffer.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: self.grid_buffer.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 2,
-                    resource: self.grid_params_buffer.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 3,
-                    resource: self.grid_counts_buffer.as_entire_binding(),
-                },
-            ],
-        });
+        self.grid_populate_bind_group =
+            BindGroupBuilder::new(device, &self.grid_count_pipeline.get_bind_group_layout(0))
+                .add_buffer(0, &self.particle_buffer)
+                .add_buffer(1, &self.grid_params_buffer)
+                .add_buffer(2, &self.grid_counts_buffer)
+                .add_buffer(3, &self.grid_starts_buffer)
+                .add_buffer(4, &self.grid_slots_buffer)
+                .add_buffer(5, &self.grid_indices_buffer)
+                .add_buffer(6, &self.grid_stats_buffer)
+                .with_label("Pellets Grid Populate Bind Group".to_string())
+                .build();
 
         // Recreate render bind group
         let render_bind_group_layout =
@@ -2325,6 +2394,7 @@ impl crate::simulations::traits::Simulation for PelletsModel {
     ) -> SimulationResult<()> {
         // Step GPU physics simulation
         self.step_physics(device, queue)?;
+        self.state.simulation_time += delta_time;
 
         // Update camera with smoothing
         self.camera.update(delta_time);
@@ -2637,7 +2707,7 @@ impl crate::simulations::traits::Simulation for PelletsModel {
 
         let display_view = display_texture.create_view(&wgpu::TextureViewDescriptor::default());
 
-        let filter_mode = if self.app_settings.texture_filtering == TextureFiltering::Linear {
+        let filter_mode = if self.render_settings.texture_filtering == TextureFiltering::Linear {
             wgpu::FilterMode::Linear
         } else {
             wgpu::FilterMode::Nearest
@@ -2948,11 +3018,9 @@ impl crate::simulations::traits::Simulation for PelletsModel {
         let clamped_x = world_x.clamp(-1.0, 1.0);
         let clamped_y = (-world_y).clamp(-1.0, 1.0); // Fix Y-axis inversion
 
-        // Calculate mouse velocity based on time difference
-        let current_time = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs_f64();
+        // Calculate mouse velocity based on simulated time, so that replayed
+        // interactions throw particles exactly as they did when recorded
+        let current_time = self.state.simulation_time as f64;
 
         let time_delta = current_time - self.state.last_mouse_time;
 
@@ -3081,7 +3149,9 @@ impl crate::simulations::traits::Simulation for PelletsModel {
             self.particle_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
                 label: Some("Pellets Particle Buffer"),
                 contents: bytemuck::cast_slice(&self.particles),
-                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+                usage: wgpu::BufferUsages::STORAGE
+                    | wgpu::BufferUsages::COPY_DST
+                    | wgpu::BufferUsages::COPY_SRC,
             });
 
             // Recreate the bind groups since the buffer changed
@@ -3095,6 +3165,9 @@ impl crate::simulations::traits::Simulation for PelletsModel {
             );
         }
 
+        // The carried over potential belongs to the old particles
+        self.gravity_mesh.reset(queue);
+
         // Reset camera
         self.camera.reset();
 
@@ -3124,4 +3197,99 @@ impl crate::simulations::traits::Simulation for PelletsModel {
         self.settings.randomize();
         Ok(())
     }
+
+    fn save_snapshot(
+        &self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<SimulationSnapshot> {
+        let mut snapshot = SimulationSnapshot::new("pellets", self.get_settings())
+            .with_lut(&self.state.current_lut_name, self.state.lut_reversed)
+            .with_camera(&self.camera)
+            .with_state(serde_json::json!({
+                "particle_count": self.particles.len(),
+                "frame_count": self.frame_count,
+                "simulation_time": self.state.simulation_time,
+            }));
+        snapshot.capture_buffer("particles", &self.particle_buffer, device, queue)?;
+        snapshot.capture_buffer(
+            "gravity_potential",
+            &self.gravity_mesh.potential_buffer,
+            device,
+            queue,
+        )?;
+        Ok(snapshot)
+    }
+
+    fn load_snapshot(
+        &mut self,
+        snapshot: &SimulationSnapshot,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()> {
+        snapshot.expect_type("pellets")?;
+        self.apply_settings(snapshot.settings.clone(), device, queue)?;
+
+        // The GPU buffer may be larger than the particle count, since it is
+        // only recreated when it grows
+        let particle_count = snapshot.state["particle_count"]
+            .as_u64()
+            .unwrap_or(self.settings.particle_count as u64) as usize;
+        let data = snapshot.buffer("particles")?;
+        let particle_bytes = particle_count * std::mem::size_of::<Particle>();
+        if data.len() < particle_bytes {
+            return Err(SimulationError::InvalidParameter(format!(
+                "Snapshot holds {} bytes of particles but {} particles need {}",
+                data.len(),
+                particle_count,
+                particle_bytes
+            )));
+        }
+        self.particles = data[..particle_bytes]
+            .chunks_exact(std::mem::size_of::<Particle>())
+            .map(bytemuck::pod_read_unaligned)
+            .collect();
+        self.settings.particle_count = particle_count as u32;
+
+        if self.particle_buffer.size() < particle_bytes as u64 {
+            self.particle_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Pellets Particle Buffer"),
+                contents: bytemuck::cast_slice(&self.particles),
+                usage: wgpu::BufferUsages::STORAGE
+                    | wgpu::BufferUsages::COPY_DST
+                    | wgpu::BufferUsages::COPY_SRC,
+            });
+            self.recreate_bind_groups(device)?;
+        } else {
+            queue.write_buffer(
+                &self.particle_buffer,
+                0,
+                bytemuck::cast_slice(&self.particles),
+            );
+        }
+
+        // Snapshots taken before the gravity mesh existed start from a flat potential
+        if snapshot.buffer("gravity_potential").is_ok() {
+            snapshot.restore_buffer(
+                "gravity_potential",
+                &self.gravity_mesh.potential_buffer,
+                queue,
+            )?;
+        } else {
+            self.gravity_mesh.reset(queue);
+        }
+
+        if let Some(frame_count) = snapshot.state["frame_count"].as_u64() {
+            self.frame_count = frame_count;
+        }
+        if let Some(simulation_time) = snapshot.state["simulation_time"].as_f64() {
+            self.state.simulation_time = simulation_time as f32;
+        }
+        if let Some(camera) = &snapshot.camera {
+            camera.apply_to(&mut self.camera);
+        }
+        self.state.current_lut_name = snapshot.lut_name.clone();
+        self.state.lut_reversed = snapshot.lut_reversed;
+        Ok(())
+    }
 }
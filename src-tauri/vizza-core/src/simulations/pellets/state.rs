@@ -51,6 +51,10 @@ pub struct State {
     /// Simulation runtime state
     pub simulation_time: f32,
     pub is_running: bool,
+
+    /// Debug counter: particles that sit beyond the 64 per cell the spatial grid
+    /// used to hold, and so would have been left out of collision checks
+    pub grid_overflow_count: u32,
 }
 
 impl Default for State {
@@ -72,6 +76,7 @@ impl Default for State {
             camera_zoom: 1.0,
             simulation_time: 0.0,
             is_running: true,
+            grid_overflow_count: 0,
         }
     }
 }
@@ -91,6 +96,7 @@ impl State {
         self.camera_zoom = 1.0;
         self.simulation_time = 0.0;
         self.is_running = true;
+        self.grid_overflow_count = 0;
     }
 
     /// Reset only the camera state
@@ -20,12 +20,15 @@
 //! This layered approach ensures that both isolated components and their
 //! interactions work correctly across different hardware configurations.
 
+use super::gravity_mesh::{GRAVITY_MESH_SIZE, GravityMesh};
 use super::shaders::{
-    BACKGROUND_RENDER_SHADER, DENSITY_COMPUTE_SHADER, GRID_CLEAR_SHADER, GRID_POPULATE_SHADER,
+    BACKGROUND_RENDER_SHADER, DENSITY_COMPUTE_SHADER, GRAVITY_MESH_SHADER,
+    GRAVITY_MULTIGRID_SHADER, GRID_CLEAR_SHADER, GRID_POPULATE_SHADER,
     PARTICLE_FRAGMENT_RENDER_SHADER, PARTICLE_RENDER_SHADER, PHYSICS_COMPUTE_SHADER,
 };
 use super::simulation::{BackgroundParams, DensityParams, Particle, PhysicsParams, RenderParams};
 use std::mem;
+use std::sync::Arc;
 use wgpu::util::DeviceExt;
 
 /// Test framework for validating Pellets shader compilation and buffer binding
@@ -114,6 +117,28 @@ impl PelletsValidator {
         Ok(())
     }
 
+    /// Validates that the Pellets gravity mesh shader compiles without errors
+    fn validate_gravity_mesh_shader_compilation(&self) -> Result<(), String> {
+        let _ = self
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("Pellets Gravity Mesh Shader"),
+                source: wgpu::ShaderSource::Wgsl(GRAVITY_MESH_SHADER.into()),
+            });
+        Ok(())
+    }
+
+    /// Validates that the Pellets gravity multigrid shader compiles without errors
+    fn validate_gravity_multigrid_shader_compilation(&self) -> Result<(), String> {
+        let _ = self
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("Pellets Gravity Multigrid Shader"),
+                source: wgpu::ShaderSource::Wgsl(GRAVITY_MULTIGRID_SHADER.into()),
+            });
+        Ok(())
+    }
+
     /// Validates that the Pellets background render shader compiles without errors
     fn validate_background_render_shader_compilation(&self) -> Result<(), String> {
         let _ = self
@@ -638,6 +663,27 @@ impl PelletsValidator {
                             },
                             count: None,
                         },
+                        // Long-range gravity field and mesh parameters (bindings 5 and 6)
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 5,
+                            visibility: wgpu::ShaderStages::COMPUTE,
+                            ty: wgpu::BindingType::Buffer {
+                                ty: wgpu::BufferBindingType::Storage { read_only: true },
+                                has_dynamic_offset: false,
+                                min_binding_size: None,
+                            },
+                            count: None,
+                        },
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 6,
+                            visibility: wgpu::ShaderStages::COMPUTE,
+                            ty: wgpu::BindingType::Buffer {
+                                ty: wgpu::BufferBindingType::Uniform,
+                                has_dynamic_offset: false,
+                                min_binding_size: None,
+                            },
+                            count: None,
+                        },
                     ],
                 });
 
@@ -702,6 +748,12 @@ async fn test_pellets_shader_compilation() {
     validator
         .validate_grid_populate_shader_compilation()
         .expect("Grid populate shader compilation failed");
+    validator
+        .validate_gravity_mesh_shader_compilation()
+        .expect("Gravity mesh shader compilation failed");
+    validator
+        .validate_gravity_multigrid_shader_compilation()
+        .expect("Gravity multigrid shader compilation failed");
     validator
         .validate_background_render_shader_compilation()
         .expect("Background render shader compilation failed");
@@ -881,6 +933,179 @@ fn test_struct_layout_consistency() {
     });
 }
 
+#[tokio::test]
+async fn test_gravity_mesh_pulls_across_wrap() {
+    // Two clumps either side of the x = +-1 seam are 0.2 apart through the wrap
+    // but 1.8 apart directly, so each should be pulled towards the seam
+    let PelletsValidator { device, _queue } = PelletsValidator::new().await;
+    let device = Arc::new(device);
+    let queue = Arc::new(_queue);
+
+    let particle_at = |x: f32, y: f32| Particle {
+        position: [x, y],
+        velocity: [0.0, 0.0],
+        mass: 1.0,
+        radius: 0.01,
+        clump_id: 0,
+        density: 0.0,
+        grabbed: 0,
+        _pad0: 0,
+        previous_position: [x, y],
+    };
+    let mut particles = Vec::new();
+    for i in 0..50 {
+        let offset = (i % 5) as f32 * 0.01;
+        let y = (i / 5) as f32 * 0.01 - 0.05;
+        particles.push(particle_at(0.85 + offset, y));
+        particles.push(particle_at(-0.9 + offset, y));
+    }
+    let particle_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Pellets Particle Buffer"),
+        contents: bytemuck::cast_slice(&particles),
+        usage: wgpu::BufferUsages::STORAGE,
+    });
+
+    let gravity_mesh = GravityMesh::new(&device, &particle_buffer);
+    gravity_mesh.update_params(&queue, &particles, 0.01);
+    // A single frame from a flat potential is enough for the multigrid solve
+    let mut encoder = device.create_command_encoder(&Default::default());
+    gravity_mesh.dispatch(&mut encoder, particles.len() as u32);
+    queue.submit(std::iter::once(encoder.finish()));
+
+    let data = crate::simulations::shared::snapshot::read_buffer(
+        &device,
+        &queue,
+        &gravity_mesh.field_buffer,
+    )
+    .unwrap();
+    let field: &[[f32; 2]] = bytemuck::cast_slice(&data);
+
+    // Total pull on each clump, interpolated the way the physics shader does.
+    // Each clump's pull on itself cancels out, leaving the other clump's.
+    let size = GRAVITY_MESH_SIZE as i32;
+    let cell_size = 2.0 / GRAVITY_MESH_SIZE as f32;
+    let sample = |position: [f32; 2]| {
+        let mesh_x = (position[0] + 1.0) / cell_size - 0.5;
+        let mesh_y = (position[1] + 1.0) / cell_size - 0.5;
+        let (tx, ty) = (mesh_x - mesh_x.floor(), mesh_y - mesh_y.floor());
+        let mut acceleration = [0.0f32; 2];
+        for (dx, dy, weight) in [
+            (0, 0, (1.0 - tx) * (1.0 - ty)),
+            (1, 0, tx * (1.0 - ty)),
+            (0, 1, (1.0 - tx) * ty),
+            (1, 1, tx * ty),
+        ] {
+            let x = (mesh_x.floor() as i32 + dx).rem_euclid(size);
+            let y = (mesh_y.floor() as i32 + dy).rem_euclid(size);
+            let value = field[(y * size + x) as usize];
+            acceleration[0] += value[0] * weight;
+            acceleration[1] += value[1] * weight;
+        }
+        acceleration
+    };
+    let total_pull = |clump: usize| {
+        particles
+            .iter()
+            .skip(clump)
+            .step_by(2)
+            .map(|particle| sample(particle.position))
+            .fold([0.0f32; 2], |total, a| [total[0] + a[0], total[1] + a[1]])
+    };
+
+    let right_clump = total_pull(0);
+    let left_clump = total_pull(1);
+    assert!(
+        right_clump[0] > 0.0 && left_clump[0] < 0.0,
+        "Clumps should attract through the wrap: right {:?}, left {:?}",
+        right_clump,
+        left_clump
+    );
+    // The clumps sit level with each other, so the pull is mostly horizontal
+    assert!(right_clump[1].abs() < right_clump[0].abs() * 0.1);
+    assert!(left_clump[1].abs() < left_clump[0].abs() * 0.1);
+
+    // Further frames barely change the field, since the first one converged
+    let first_field = field.to_vec();
+    for _ in 0..20 {
+        let mut encoder = device.create_command_encoder(&Default::default());
+        gravity_mesh.dispatch(&mut encoder, particles.len() as u32);
+        queue.submit(std::iter::once(encoder.finish()));
+    }
+    let data = crate::simulations::shared::snapshot::read_buffer(
+        &device,
+        &queue,
+        &gravity_mesh.field_buffer,
+    )
+    .unwrap();
+    let settled_field: &[[f32; 2]] = bytemuck::cast_slice(&data);
+    let largest = settled_field
+        .iter()
+        .map(|value| value[0].hypot(value[1]))
+        .fold(0.0f32, f32::max);
+    let largest_change = first_field
+        .iter()
+        .zip(settled_field)
+        .map(|(first, settled)| (first[0] - settled[0]).hypot(first[1] - settled[1]))
+        .fold(0.0f32, f32::max);
+    assert!(
+        largest_change < largest * 0.01,
+        "Field still moving after the first frame: {} of {}",
+        largest_change,
+        largest
+    );
+}
+
+#[tokio::test]
+async fn test_grid_holds_dense_cells() {
+    use crate::settings::RenderSettings;
+    use crate::simulation::headless::HeadlessRenderer;
+    use crate::simulations::traits::SimulationType;
+
+    let renderer = HeadlessRenderer::new(
+        64,
+        64,
+        wgpu::TextureFormat::Rgba8UnormSrgb,
+        RenderSettings::default(),
+    )
+    .await
+    .unwrap();
+    let SimulationType::Pellets(mut model) = renderer.create_simulation("pellets").await.unwrap()
+    else {
+        panic!("Expected a pellets simulation");
+    };
+
+    // Pack 200 particles into the single grid cell just above the origin
+    let cell_size = 2.0 / model.grid_width as f32;
+    for (i, particle) in model.particles.iter_mut().take(200).enumerate() {
+        let x = (i % 20) as f32 / 20.0;
+        let y = (i / 20) as f32 / 10.0;
+        particle.position = [cell_size * (0.05 + 0.9 * x), cell_size * (0.05 + 0.9 * y)];
+    }
+    // Spread the rest thinly enough that no other cell comes close to 64
+    let spread = model.particles.len() - 200;
+    let side = (spread as f32).sqrt().ceil() as usize;
+    for (i, particle) in model.particles.iter_mut().skip(200).enumerate() {
+        let x = (i % side) as f32 / side as f32;
+        let y = (i / side) as f32 / side as f32;
+        particle.position = [-1.0 + x, -1.0 + y];
+    }
+    renderer.queue.write_buffer(
+        &model.particle_buffer,
+        0,
+        bytemuck::cast_slice(&model.particles),
+    );
+
+    // The grid counters are requested on the first frame and collected on the next
+    model
+        .step_physics(&renderer.device, &renderer.queue)
+        .unwrap();
+    renderer.device.poll(wgpu::Maintain::Wait);
+    model
+        .step_physics(&renderer.device, &renderer.queue)
+        .unwrap();
+    assert_eq!(model.state.grid_overflow_count, 200 - 64);
+}
+
 #[cfg(test)]
 mod tests {
     use crate::simulation::preset_manager::PelletsPresetManager;
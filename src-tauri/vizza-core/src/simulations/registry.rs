@@ -0,0 +1,198 @@
+//! # Simulation Registry
+//!
+//! Every simulation module describes itself with a [`SimulationDescriptor`]:
+//! its identifier, how it is constructed, the presets it ships with and the
+//! optional features it supports. The manager, the preset manager, the headless
+//! renderer and the command-line interface look simulations up here rather than
+//! matching on type names, so a new simulation plugs in by exporting a
+//! descriptor from its module and adding it to [`SIMULATIONS`].
+
+use serde::Serialize;
+use std::sync::Arc;
+use wgpu::{Device, Queue, SurfaceConfiguration};
+
+use crate::error::{SimulationError, SimulationResult};
+use crate::settings::RenderSettings;
+use crate::simulation::preset_manager::AnyPresetManager;
+use crate::simulations::shared::{LutManager, SettingField};
+use crate::simulations::traits::SimulationType;
+
+/// All simulations known to Vizza, in the order they are listed to users
+pub const SIMULATIONS: &[&SimulationDescriptor] = &[
+    &crate::simulations::slime_mold::DESCRIPTOR,
+    &crate::simulations::gray_scott::DESCRIPTOR,
+    &crate::simulations::particle_life::DESCRIPTOR,
+    &crate::simulations::flow::DESCRIPTOR,
+    &crate::simulations::pellets::DESCRIPTOR,
+    &crate::simulations::ecosystem::DESCRIPTOR,
+    &crate::simulations::gradient::DESCRIPTOR,
+    &crate::simulations::main_menu::DESCRIPTOR,
+];
+
+/// GPU handles and shared resources a simulation is constructed with
+pub struct SimulationContext<'a> {
+    pub device: &'a Arc<Device>,
+    pub queue: &'a Arc<Queue>,
+    pub surface_config: &'a SurfaceConfiguration,
+    pub adapter_info: &'a wgpu::AdapterInfo,
+    pub lut_manager: &'a LutManager,
+    pub render_settings: &'a RenderSettings,
+}
+
+/// Optional features a simulation supports beyond rendering
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct SimulationCapabilities {
+    /// The view can be panned and zoomed
+    pub camera: bool,
+    /// Colors come from a LUT that can be swapped and reversed
+    pub luts: bool,
+    /// Mouse interaction has an adjustable cursor size and strength
+    pub cursor: bool,
+    /// Frames only depend on the seed, settings and input, so deterministic
+    /// runs can be recorded and replayed
+    pub deterministic: bool,
+}
+
+/// Describes a simulation to the rest of the application
+pub struct SimulationDescriptor {
+    /// Identifier used by commands, preset directories and the CLI
+    pub id: &'static str,
+    pub display_name: &'static str,
+    pub description: &'static str,
+    /// Background simulations, such as the one behind the main menu, are not
+    /// offered to users
+    pub hidden: bool,
+    pub capabilities: SimulationCapabilities,
+    /// Construct the simulation with its default settings
+    pub create: fn(&SimulationContext) -> SimulationResult<SimulationType>,
+    /// Build a preset manager over the simulation's settings type, holding the
+    /// built-in presets and any saved user presets. `None` when the simulation
+    /// has no presets.
+    pub presets: Option<fn() -> Box<dyn AnyPresetManager>>,
+    /// Preset the app applies right after creating the simulation
+    pub initial_preset: Option<&'static str>,
+    /// Describe the fields of the simulation's settings. `None` when the
+    /// simulation has no user-facing settings.
+    pub settings_schema: Option<fn() -> Vec<SettingField>>,
+    /// Names `update_setting` accepts besides the schema fields, for values
+    /// kept outside the settings such as the cursor size or the current LUT
+    pub runtime_settings: &'static [&'static str],
+}
+
+impl SimulationDescriptor {
+    pub fn supports_presets(&self) -> bool {
+        self.presets.is_some()
+    }
+
+    /// Summary of this descriptor for the frontend
+    pub fn info(&self) -> SimulationInfo {
+        SimulationInfo {
+            id: self.id,
+            display_name: self.display_name,
+            description: self.description,
+            supports_presets: self.supports_presets(),
+            capabilities: self.capabilities,
+        }
+    }
+}
+
+/// Serializable summary of a [`SimulationDescriptor`]
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulationInfo {
+    pub id: &'static str,
+    pub display_name: &'static str,
+    pub description: &'static str,
+    pub supports_presets: bool,
+    pub capabilities: SimulationCapabilities,
+}
+
+/// Look up a simulation by identifier
+pub fn get(id: &str) -> SimulationResult<&'static SimulationDescriptor> {
+    SIMULATIONS
+        .iter()
+        .copied()
+        .find(|descriptor| descriptor.id == id)
+        .ok_or_else(|| SimulationError::UnknownType(id.to_string()))
+}
+
+/// Settings schema of a simulation, or an error if it has no settings
+pub fn settings_schema(id: &str) -> SimulationResult<Vec<SettingField>> {
+    let descriptor = get(id)?;
+    let schema = descriptor
+        .settings_schema
+        .ok_or(SimulationError::UnsupportedOperation)?;
+    Ok(schema())
+}
+
+/// Simulations that can be offered to users
+pub fn list() -> Vec<SimulationInfo> {
+    SIMULATIONS
+        .iter()
+        .filter(|descriptor| !descriptor.hidden)
+        .map(|descriptor| descriptor.info())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::headless::HeadlessRenderer;
+    use crate::simulations::traits::Simulation;
+
+    #[test]
+    fn test_simulation_ids_are_unique() {
+        for (index, descriptor) in SIMULATIONS.iter().enumerate() {
+            assert!(
+                SIMULATIONS[index + 1..]
+                    .iter()
+                    .all(|other| other.id != descriptor.id),
+                "{} is registered twice",
+                descriptor.id
+            );
+            assert_eq!(get(descriptor.id).unwrap().id, descriptor.id);
+        }
+        assert!(matches!(
+            get("not_a_simulation"),
+            Err(SimulationError::UnknownType(_))
+        ));
+    }
+
+    #[test]
+    fn test_list_skips_hidden_simulations() {
+        let ids = list().iter().map(|info| info.id).collect::<Vec<_>>();
+        assert!(ids.contains(&"slime_mold"));
+        assert!(!ids.contains(&"main_menu"));
+
+        let presets = SIMULATIONS
+            .iter()
+            .filter(|descriptor| descriptor.initial_preset.is_some())
+            .all(|descriptor| descriptor.supports_presets());
+        assert!(presets, "Initial presets need a preset manager");
+    }
+
+    #[tokio::test]
+    async fn test_descriptors_match_created_simulations() {
+        let renderer = HeadlessRenderer::new(
+            64,
+            48,
+            wgpu::TextureFormat::Rgba8UnormSrgb,
+            RenderSettings::default(),
+        )
+        .await
+        .expect("Failed to create headless renderer");
+
+        for descriptor in SIMULATIONS {
+            let simulation = renderer.create_simulation(descriptor.id).await.unwrap();
+            assert_eq!(simulation.type_name(), descriptor.id);
+            assert_eq!(
+                simulation.camera().is_some(),
+                descriptor.capabilities.camera,
+                "{} camera capability",
+                descriptor.id
+            );
+            if descriptor.supports_presets() {
+                assert!(simulation.get_settings().is_object());
+            }
+        }
+    }
+}
@@ -238,6 +238,26 @@ impl Camera {
         self.update_uniform();
     }
 
+    /// Jump to a position and zoom without smoothing
+    pub fn set_view(&mut self, position: [f32; 2], zoom: f32) {
+        self.position = position;
+        self.target_position = position;
+        self.zoom = zoom;
+        self.target_zoom = zoom;
+        self.update_uniform();
+    }
+
+    /// Position and zoom the camera is smoothly moving towards
+    pub fn target(&self) -> ([f32; 2], f32) {
+        (self.target_position, self.target_zoom)
+    }
+
+    /// Move smoothly towards a position and zoom from wherever the camera is
+    pub fn set_target(&mut self, position: [f32; 2], zoom: f32) {
+        self.target_position = position;
+        self.target_zoom = zoom;
+    }
+
     /// Update viewport dimensions (call when window is resized)
     pub fn resize(&mut self, width: f32, height: f32) {
         self.viewport_width = width;
@@ -1,11 +1,11 @@
-use crate::commands::get_settings_dir;
 use crate::error::{LutError, LutResult};
+use crate::settings::data_dir;
 use include_dir::{Dir, include_dir};
 use rand::Rng;
 use std::collections::HashMap;
 use std::io;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct LutData {
     pub name: String,
     pub red: [u8; 256],
@@ -191,7 +191,7 @@ impl LutManager {
     }
 
     fn lut_dir() -> LutResult<std::path::PathBuf> {
-        let lut_dir = get_settings_dir().join("LUTs");
+        let lut_dir = data_dir().join("LUTs");
         Ok(lut_dir)
     }
 
@@ -244,7 +244,7 @@ impl LutManager {
 
     pub(crate) fn get_random_lut(&self) -> LutResult<LutData> {
         let lut_names: Vec<&str> = EMBEDDED_LUTS.keys().copied().collect();
-        let random_index = rand::rng().random_range(0..lut_names.len());
+        let random_index = super::random::rng().random_range(0..lut_names.len());
         let lut_name = lut_names[random_index];
         self.get(lut_name)
     }
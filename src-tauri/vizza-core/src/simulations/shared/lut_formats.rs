@@ -0,0 +1,1183 @@
+//! # LUT Import and Export
+//!
+//! Moves palettes between `LutData` and the formats of other tools. On import
+//! each format is reduced either to evenly spaced color samples or to a
+//! function over the length of the gradient, which is then resampled to the
+//! 256 entries of a LUT. Gradient formats are exported with only as many stops
+//! as it takes to reproduce the LUT to within one step per channel.
+
+use super::lut::{LutData, LutManager};
+use crate::error::{LutError, LutResult};
+use std::f32::consts::PI;
+use std::path::Path;
+
+/// Entries per LUT channel
+const LUT_SIZE: usize = 256;
+
+/// Height of exported PNG strips
+const PNG_STRIP_HEIGHT: u32 = 32;
+
+/// Color with channels in 0..=1
+type Rgb = [f32; 3];
+
+/// Palette formats LUTs can be imported from and exported to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LutFormat {
+    /// Adobe/Resolve 1D `.cube`
+    Cube,
+    /// GIMP gradient, `.ggr`
+    GimpGradient,
+    /// GIMP palette, `.gpl`
+    GimpPalette,
+    /// Fractint `.map`
+    FractintMap,
+    /// CSS `linear-gradient()`, stored in a `.css` or `.txt` file
+    CssGradient,
+    /// Palette image, sampled along its longer side
+    Png,
+    /// JSON array of `#rrggbb` colors
+    Json,
+}
+
+impl LutFormat {
+    /// Pick the format from a file's extension
+    pub fn from_path(path: &Path) -> LutResult<Self> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("cube") => Ok(Self::Cube),
+            Some("ggr") => Ok(Self::GimpGradient),
+            Some("gpl") => Ok(Self::GimpPalette),
+            Some("map") => Ok(Self::FractintMap),
+            Some("css" | "txt") => Ok(Self::CssGradient),
+            Some("png") => Ok(Self::Png),
+            Some("json") => Ok(Self::Json),
+            _ => Err(LutError::FormatError(format!(
+                "Unsupported palette file {}",
+                path.display()
+            ))),
+        }
+    }
+}
+
+fn format_error(message: impl Into<String>) -> LutError {
+    LutError::FormatError(message.into())
+}
+
+impl LutData {
+    /// Parse `data` in `format` as a LUT named `name`
+    pub fn import(name: String, format: LutFormat, data: &[u8]) -> LutResult<Self> {
+        let text = || {
+            std::str::from_utf8(data)
+                .map_err(|e| format_error(format!("Palette file is not text: {}", e)))
+        };
+        match format {
+            LutFormat::Cube => Self::from_cube(name, text()?),
+            LutFormat::GimpGradient => Self::from_ggr(name, text()?),
+            LutFormat::GimpPalette => Self::from_gpl(name, text()?),
+            LutFormat::FractintMap => Self::from_fractint_map(name, text()?),
+            LutFormat::CssGradient => Self::from_css_gradient(name, text()?),
+            LutFormat::Png => Self::from_png(name, data),
+            LutFormat::Json => Self::from_hex_json(name, text()?),
+        }
+    }
+
+    /// Build a LUT from evenly spaced colors, interpolating between them
+    pub fn from_samples(name: String, samples: &[Rgb]) -> LutResult<Self> {
+        let last = samples
+            .len()
+            .checked_sub(1)
+            .ok_or_else(|| format_error("Palette has no colors"))?;
+        Ok(Self::from_fn(name, |t| {
+            let position = t * last as f32;
+            let index = (position.floor() as usize).min(last);
+            let next = (index + 1).min(last);
+            lerp(samples[index], samples[next], position - index as f32)
+        }))
+    }
+
+    /// Build a LUT by evaluating `color_at` from 0 to 1
+    fn from_fn(name: String, color_at: impl Fn(f32) -> Rgb) -> Self {
+        let colors: Vec<[u8; 3]> = (0..LUT_SIZE)
+            .map(|i| color_at(i as f32 / (LUT_SIZE - 1) as f32).map(to_byte))
+            .collect();
+        let channel = |c: usize| std::array::from_fn(|i| colors[i][c]);
+        Self {
+            name,
+            red: channel(0),
+            green: channel(1),
+            blue: channel(2),
+        }
+    }
+
+    /// Parse a 1D `.cube` LUT. 3D LUTs describe a color transform rather than
+    /// a palette and are rejected.
+    pub fn from_cube(name: String, text: &str) -> LutResult<Self> {
+        let mut size = None;
+        let mut samples = Vec::new();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("LUT_1D_SIZE") => {
+                    size = Some(
+                        words
+                            .next()
+                            .and_then(|size| size.parse::<usize>().ok())
+                            .ok_or_else(|| format_error(format!("Invalid size: {}", line)))?,
+                    )
+                }
+                Some("LUT_3D_SIZE") => {
+                    return Err(format_error("Only 1D .cube LUTs can be imported"));
+                }
+                Some("TITLE" | "DOMAIN_MIN" | "DOMAIN_MAX" | "LUT_1D_INPUT_RANGE") => {}
+                _ => samples.push(parse_rgb(line, 1.0)?),
+            }
+        }
+
+        let size = size.ok_or_else(|| format_error("Missing LUT_1D_SIZE"))?;
+        if samples.len() != size {
+            return Err(format_error(format!(
+                "Expected {} entries but found {}",
+                size,
+                samples.len()
+            )));
+        }
+        Self::from_samples(name, &samples)
+    }
+
+    /// Parse a GIMP gradient, following GIMP's blending and HSV coloring
+    pub fn from_ggr(name: String, text: &str) -> LutResult<Self> {
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+        if lines.next() != Some("GIMP Gradient") {
+            return Err(format_error("Not a GIMP gradient"));
+        }
+        let mut count = lines.next();
+        if count.is_some_and(|line| line.starts_with("Name:")) {
+            count = lines.next();
+        }
+        let count = count
+            .and_then(|count| count.parse::<usize>().ok())
+            .filter(|&count| count > 0)
+            .ok_or_else(|| format_error("Missing gradient segment count"))?;
+
+        let segments = lines
+            .take(count)
+            .map(GradientSegment::parse)
+            .collect::<LutResult<Vec<_>>>()?;
+        if segments.len() != count {
+            return Err(format_error(format!(
+                "Expected {} gradient segments but found {}",
+                count,
+                segments.len()
+            )));
+        }
+
+        Ok(Self::from_fn(name, |t| {
+            segments
+                .iter()
+                .find(|segment| t <= segment.right)
+                .unwrap_or(&segments[count - 1])
+                .color_at(t)
+        }))
+    }
+
+    /// Parse a GIMP palette, blending from one swatch to the next
+    pub fn from_gpl(name: String, text: &str) -> LutResult<Self> {
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+        if lines.next() != Some("GIMP Palette") {
+            return Err(format_error("Not a GIMP palette"));
+        }
+        let samples = lines
+            .filter(|line| {
+                !line.starts_with('#')
+                    && !line.starts_with("Name:")
+                    && !line.starts_with("Columns:")
+            })
+            .map(|line| parse_rgb(line, 255.0))
+            .collect::<LutResult<Vec<_>>>()?;
+        Self::from_samples(name, &samples)
+    }
+
+    /// Parse a Fractint `.map`, one `r g b` line per entry with an optional
+    /// comment after the color
+    pub fn from_fractint_map(name: String, text: &str) -> LutResult<Self> {
+        let samples = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| parse_rgb(line, 255.0))
+            .collect::<LutResult<Vec<_>>>()?;
+        Self::from_samples(name, &samples)
+    }
+
+    /// Parse a CSS `linear-gradient()`. The direction is ignored, color stops
+    /// are placed as CSS places them, and alpha is dropped.
+    pub fn from_css_gradient(name: String, css: &str) -> LutResult<Self> {
+        let css = css.to_ascii_lowercase();
+        let start = css
+            .find("linear-gradient(")
+            .ok_or_else(|| format_error("Expected a CSS linear-gradient()"))?
+            + "linear-gradient(".len();
+        let arguments = split_arguments(&css[start..])?;
+
+        let mut stops = Vec::new();
+        for (index, argument) in arguments.iter().enumerate() {
+            if index == 0 && is_direction(argument) {
+                continue;
+            }
+            stops.extend(parse_color_stop(argument)?);
+        }
+        if stops.is_empty() {
+            return Err(format_error("Gradient has no color stops"));
+        }
+        let stops = place_stops(stops);
+
+        Ok(Self::from_fn(name, |t| {
+            let Some(next) = stops.iter().position(|&(position, _)| position >= t) else {
+                return stops[stops.len() - 1].1;
+            };
+            if next == 0 {
+                return stops[0].1;
+            }
+            let (start, from) = stops[next - 1];
+            let (end, to) = stops[next];
+            if end <= start {
+                to
+            } else {
+                lerp(from, to, (t - start) / (end - start))
+            }
+        }))
+    }
+
+    /// Parse a JSON array of colors such as `["#000000", "#ff8000"]`
+    pub fn from_hex_json(name: String, json: &str) -> LutResult<Self> {
+        let colors: Vec<String> = serde_json::from_str(json)
+            .map_err(|e| format_error(format!("Expected a JSON array of colors: {}", e)))?;
+        let samples = colors
+            .iter()
+            .map(|color| parse_css_color(&color.trim().to_ascii_lowercase()))
+            .collect::<LutResult<Vec<_>>>()?;
+        Self::from_samples(name, &samples)
+    }
+
+    /// Decode a PNG palette strip. Colors are taken along the image's longer
+    /// side and averaged across the shorter one.
+    pub fn from_png(name: String, data: &[u8]) -> LutResult<Self> {
+        let mut decoder = png::Decoder::new(data);
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+        let mut reader = decoder
+            .read_info()
+            .map_err(|e| format_error(format!("Invalid PNG: {}", e)))?;
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let frame = reader
+            .next_frame(&mut pixels)
+            .map_err(|e| format_error(format!("Invalid PNG: {}", e)))?;
+
+        let channels = frame.color_type.samples();
+        let grayscale = matches!(
+            frame.color_type,
+            png::ColorType::Grayscale | png::ColorType::GrayscaleAlpha
+        );
+        let pixel = |x: usize, y: usize| -> Rgb {
+            let offset = y * frame.line_size + x * channels;
+            let channel = |c: usize| pixels[offset + c] as f32 / 255.0;
+            if grayscale {
+                [channel(0); 3]
+            } else {
+                [channel(0), channel(1), channel(2)]
+            }
+        };
+
+        let (width, height) = (frame.width as usize, frame.height as usize);
+        let horizontal = width >= height;
+        let (length, across) = if horizontal {
+            (width, height)
+        } else {
+            (height, width)
+        };
+        let samples: Vec<Rgb> = (0..length)
+            .map(|i| {
+                let mut sum = [0.0; 3];
+                for j in 0..across {
+                    let color = if horizontal { pixel(i, j) } else { pixel(j, i) };
+                    for (total, value) in sum.iter_mut().zip(color) {
+                        *total += value;
+                    }
+                }
+                sum.map(|total| total / across as f32)
+            })
+            .collect();
+        Self::from_samples(name, &samples)
+    }
+}
+
+impl LutData {
+    /// Write the LUT in `format`
+    pub fn export(&self, format: LutFormat) -> LutResult<Vec<u8>> {
+        Ok(match format {
+            LutFormat::Cube => self.to_cube().into_bytes(),
+            LutFormat::GimpGradient => self.to_ggr().into_bytes(),
+            LutFormat::GimpPalette => self.to_gpl().into_bytes(),
+            LutFormat::FractintMap => self.to_fractint_map().into_bytes(),
+            LutFormat::CssGradient => self.to_css_gradient().into_bytes(),
+            LutFormat::Png => self.to_png(PNG_STRIP_HEIGHT)?,
+            LutFormat::Json => self.to_hex_json().into_bytes(),
+        })
+    }
+
+    fn color(&self, index: usize) -> [u8; 3] {
+        [self.red[index], self.green[index], self.blue[index]]
+    }
+
+    fn colors(&self) -> impl Iterator<Item = [u8; 3]> + '_ {
+        (0..LUT_SIZE).map(|index| self.color(index))
+    }
+
+    /// Every entry as `#rrggbb`
+    pub fn hex_colors(&self) -> Vec<String> {
+        self.colors().map(hex).collect()
+    }
+
+    /// Every entry as a JSON array of `#rrggbb` strings
+    pub fn to_hex_json(&self) -> String {
+        serde_json::Value::from(self.hex_colors()).to_string()
+    }
+
+    /// A 1D `.cube` with one line per entry
+    pub fn to_cube(&self) -> String {
+        let entries: String = self
+            .colors()
+            .map(|color| format!("{}\n", unit_channels(color)))
+            .collect();
+        format!(
+            "TITLE \"{}\"\nLUT_1D_SIZE {}\n{}",
+            self.name, LUT_SIZE, entries
+        )
+    }
+
+    /// A GIMP gradient of linear RGB segments
+    pub fn to_ggr(&self) -> String {
+        let stops = self.key_stops();
+        let position = |index: usize| index as f32 / (LUT_SIZE - 1) as f32;
+        let segments: String = stops
+            .windows(2)
+            .map(|pair| {
+                let (left, right) = (position(pair[0]), position(pair[1]));
+                format!(
+                    "{:.6} {:.6} {:.6} {} 1.000000 {} 1.000000 0 0\n",
+                    left,
+                    (left + right) / 2.0,
+                    right,
+                    unit_channels(self.color(pair[0])),
+                    unit_channels(self.color(pair[1]))
+                )
+            })
+            .collect();
+        format!(
+            "GIMP Gradient\nName: {}\n{}\n{}",
+            self.name,
+            stops.len() - 1,
+            segments
+        )
+    }
+
+    /// A GIMP palette with a swatch per entry
+    pub fn to_gpl(&self) -> String {
+        let swatches: String = self
+            .colors()
+            .map(|[r, g, b]| format!("{:3} {:3} {:3}\t{}\n", r, g, b, hex([r, g, b])))
+            .collect();
+        format!(
+            "GIMP Palette\nName: {}\nColumns: 16\n#\n{}",
+            self.name, swatches
+        )
+    }
+
+    /// A Fractint `.map`
+    pub fn to_fractint_map(&self) -> String {
+        self.colors()
+            .map(|[r, g, b]| format!("{} {} {}\n", r, g, b))
+            .collect()
+    }
+
+    /// A left to right CSS `linear-gradient()`
+    pub fn to_css_gradient(&self) -> String {
+        let stops: Vec<String> = self
+            .key_stops()
+            .into_iter()
+            .map(|index| {
+                let percent = format!("{:.4}", index as f32 * 100.0 / (LUT_SIZE - 1) as f32);
+                let percent = percent.trim_end_matches('0').trim_end_matches('.');
+                format!("{} {}%", hex(self.color(index)), percent)
+            })
+            .collect();
+        format!("linear-gradient(90deg, {})", stops.join(", "))
+    }
+
+    /// A PNG strip `height` pixels tall with a column per entry
+    pub fn to_png(&self, height: u32) -> LutResult<Vec<u8>> {
+        let row: Vec<u8> = self.colors().flatten().collect();
+        let pixels = row.repeat(height as usize);
+
+        let mut data = Vec::new();
+        let mut encoder = png::Encoder::new(&mut data, LUT_SIZE as u32, height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let saving_failed = |e: png::EncodingError| LutError::SavingFailed(e.to_string());
+        let mut writer = encoder.write_header().map_err(saving_failed)?;
+        writer.write_image_data(&pixels).map_err(saving_failed)?;
+        writer.finish().map_err(saving_failed)?;
+        Ok(data)
+    }
+
+    /// Entries a piecewise linear gradient needs to reproduce the LUT to
+    /// within one step per channel, always including both ends
+    fn key_stops(&self) -> Vec<usize> {
+        let level = |index: usize| self.color(index).map(f32::from);
+        let mut stops = vec![0];
+        let mut start = 0;
+        for end in 2..LUT_SIZE {
+            let fits = (start + 1..end).all(|index| {
+                let t = (index - start) as f32 / (end - start) as f32;
+                lerp(level(start), level(end), t)
+                    .iter()
+                    .zip(level(index))
+                    .all(|(expected, actual)| (expected - actual).abs() <= 1.0)
+            });
+            if !fits {
+                start = end - 1;
+                stops.push(start);
+            }
+        }
+        stops.push(LUT_SIZE - 1);
+        stops
+    }
+}
+
+impl LutManager {
+    /// Import the palette file at `path` as a custom LUT, named after the file
+    /// unless `name` is given. Returns the name it was saved under.
+    pub fn import(&self, path: &Path, name: Option<&str>) -> LutResult<String> {
+        let format = LutFormat::from_path(path)?;
+        let name = match name {
+            Some(name) => name.to_string(),
+            None => path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .ok_or_else(|| format_error(format!("Invalid file name {}", path.display())))?
+                .to_string(),
+        };
+        let data = std::fs::read(path).map_err(|e| LutError::FileError {
+            path: path.to_path_buf(),
+            error: e.to_string(),
+        })?;
+
+        let lut_data = LutData::import(name.clone(), format, &data)?;
+        self.save_custom(&name, &lut_data)?;
+        Ok(name)
+    }
+
+    /// Write the built-in or custom LUT `name` to `path`, in the format given
+    /// by the file's extension
+    pub fn export(&self, name: &str, path: &Path) -> LutResult<()> {
+        let format = LutFormat::from_path(path)?;
+        let data = self.get(name)?.export(format)?;
+        std::fs::write(path, data).map_err(|e| LutError::FileError {
+            path: path.to_path_buf(),
+            error: e.to_string(),
+        })
+    }
+}
+
+fn to_byte(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn hex([r, g, b]: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Channels as `r g b` in 0..=1
+fn unit_channels(color: [u8; 3]) -> String {
+    color
+        .map(|channel| format!("{:.6}", channel as f32 / 255.0))
+        .join(" ")
+}
+
+fn lerp(from: Rgb, to: Rgb, t: f32) -> Rgb {
+    [0, 1, 2].map(|c| from[c] + (to[c] - from[c]) * t)
+}
+
+/// Read the first three numbers of `line` as a color, dividing by `scale`
+fn parse_rgb(line: &str, scale: f32) -> LutResult<Rgb> {
+    let mut values = line.split_whitespace().map(|value| value.parse::<f32>());
+    let mut channel = || match values.next() {
+        Some(Ok(value)) => Ok(value / scale),
+        _ => Err(format_error(format!("Invalid color: {}", line))),
+    };
+    Ok([channel()?, channel()?, channel()?])
+}
+
+/// One segment of a GIMP gradient
+#[derive(Debug, Clone)]
+struct GradientSegment {
+    left: f32,
+    middle: f32,
+    right: f32,
+    left_color: Rgb,
+    right_color: Rgb,
+    blending: u32,
+    coloring: u32,
+}
+
+impl GradientSegment {
+    /// Parse `left middle right r g b a r g b a blending coloring`, ignoring
+    /// the endpoint color sources newer GIMP versions append
+    fn parse(line: &str) -> LutResult<Self> {
+        let fields = line
+            .split_whitespace()
+            .map(|field| field.parse::<f32>())
+            .collect::<Result<Vec<_>, _>>()
+            .ok()
+            .filter(|fields| fields.len() >= 13)
+            .ok_or_else(|| format_error(format!("Invalid gradient segment: {}", line)))?;
+        Ok(Self {
+            left: fields[0],
+            middle: fields[1],
+            right: fields[2],
+            left_color: [fields[3], fields[4], fields[5]],
+            right_color: [fields[7], fields[8], fields[9]],
+            blending: fields[11] as u32,
+            coloring: fields[12] as u32,
+        })
+    }
+
+    fn color_at(&self, t: f32) -> Rgb {
+        let length = self.right - self.left;
+        let (middle, position) = if length < f32::EPSILON {
+            (0.5, 0.5)
+        } else {
+            ((self.middle - self.left) / length, (t - self.left) / length)
+        };
+        let linear = linear_factor(middle, position);
+        let factor = match self.blending {
+            // Curved
+            1 => position.powf(0.5f32.ln() / middle.max(f32::EPSILON).ln()),
+            // Sine
+            2 => ((-PI / 2.0 + PI * linear).sin() + 1.0) / 2.0,
+            // Sphere increasing
+            3 => (1.0 - (linear - 1.0).powi(2)).sqrt(),
+            // Sphere decreasing
+            4 => 1.0 - (1.0 - linear.powi(2)).sqrt(),
+            // Step
+            5 => {
+                if position >= middle {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            _ => linear,
+        };
+
+        match self.coloring {
+            1 | 2 => {
+                let [left_hue, s0, v0] = rgb_to_hsv(self.left_color);
+                let [right_hue, s1, v1] = rgb_to_hsv(self.right_color);
+                // 1 turns counter-clockwise (increasing hue), 2 clockwise
+                let hue = if self.coloring == 1 {
+                    let span = (right_hue - left_hue).rem_euclid(1.0);
+                    left_hue + span * factor
+                } else {
+                    let span = (left_hue - right_hue).rem_euclid(1.0);
+                    left_hue - span * factor
+                };
+                hsv_to_rgb([
+                    hue.rem_euclid(1.0),
+                    s0 + (s1 - s0) * factor,
+                    v0 + (v1 - v0) * factor,
+                ])
+            }
+            _ => lerp(self.left_color, self.right_color, factor),
+        }
+    }
+}
+
+/// GIMP's linear blend, bending at `middle`
+fn linear_factor(middle: f32, position: f32) -> f32 {
+    if position <= middle {
+        if middle < f32::EPSILON {
+            0.0
+        } else {
+            0.5 * position / middle
+        }
+    } else {
+        let rest = 1.0 - middle;
+        if rest < f32::EPSILON {
+            1.0
+        } else {
+            0.5 + 0.5 * (position - middle) / rest
+        }
+    }
+}
+
+/// Hue, saturation and value, all in 0..=1
+fn rgb_to_hsv([r, g, b]: Rgb) -> [f32; 3] {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    let hue = if delta <= 0.0 {
+        0.0
+    } else if max == r {
+        ((g - b) / delta).rem_euclid(6.0) / 6.0
+    } else if max == g {
+        ((b - r) / delta + 2.0) / 6.0
+    } else {
+        ((r - g) / delta + 4.0) / 6.0
+    };
+    let saturation = if max <= 0.0 { 0.0 } else { delta / max };
+    [hue, saturation, max]
+}
+
+fn hsv_to_rgb([hue, saturation, value]: [f32; 3]) -> Rgb {
+    let sector = hue * 6.0;
+    let chroma = value * saturation;
+    let x = chroma * (1.0 - (sector.rem_euclid(2.0) - 1.0).abs());
+    let [r, g, b] = match sector as u32 {
+        0 => [chroma, x, 0.0],
+        1 => [x, chroma, 0.0],
+        2 => [0.0, chroma, x],
+        3 => [0.0, x, chroma],
+        4 => [x, 0.0, chroma],
+        _ => [chroma, 0.0, x],
+    };
+    let m = value - chroma;
+    [r + m, g + m, b + m]
+}
+
+fn hsl_to_rgb(hue: f32, saturation: f32, lightness: f32) -> Rgb {
+    let value = lightness + saturation * lightness.min(1.0 - lightness);
+    let saturation = if value <= 0.0 {
+        0.0
+    } else {
+        2.0 * (1.0 - lightness / value)
+    };
+    hsv_to_rgb([hue.rem_euclid(1.0), saturation, value])
+}
+
+/// Split the arguments of a CSS function at top-level commas, stopping at
+/// the closing parenthesis
+fn split_arguments(css: &str) -> LutResult<Vec<&str>> {
+    let mut arguments = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, c) in css.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => {
+                arguments.push(css[start..index].trim());
+                return Ok(arguments);
+            }
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                arguments.push(css[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    Err(format_error("Unclosed linear-gradient()"))
+}
+
+fn is_direction(argument: &str) -> bool {
+    argument.starts_with("to ")
+        || ["deg", "grad", "rad", "turn"].iter().any(|unit| {
+            argument
+                .strip_suffix(unit)
+                .is_some_and(|angle| angle.parse::<f32>().is_ok())
+        })
+}
+
+/// Parse `color [position [position]]`. A stop with two positions becomes
+/// two stops of the same color.
+fn parse_color_stop(argument: &str) -> LutResult<Vec<(Rgb, Option<f32>)>> {
+    let split = match argument.find('(') {
+        Some(open) => argument[open..]
+            .find(')')
+            .map(|close| open + close + 1)
+            .ok_or_else(|| format_error(format!("Invalid color stop: {}", argument)))?,
+        None => argument.find(char::is_whitespace).unwrap_or(argument.len()),
+    };
+    let color = parse_css_color(&argument[..split])?;
+    let positions = argument[split..]
+        .split_whitespace()
+        .map(parse_percentage)
+        .collect::<LutResult<Vec<_>>>()?;
+    Ok(match positions.as_slice() {
+        [] => vec![(color, None)],
+        [position] => vec![(color, Some(*position))],
+        [start, end] => vec![(color, Some(*start)), (color, Some(*end))],
+        _ => return Err(format_error(format!("Invalid color stop: {}", argument))),
+    })
+}
+
+/// A stop position, which must be a percentage or zero
+fn parse_percentage(value: &str) -> LutResult<f32> {
+    let position = match value.strip_suffix('%') {
+        Some(percent) => percent.parse::<f32>().ok().map(|percent| percent / 100.0),
+        // Lengths other than zero depend on the element's size
+        None => value.parse::<f32>().ok().filter(|&zero| zero == 0.0),
+    };
+    position.ok_or_else(|| format_error(format!("Stop positions must be percentages: {}", value)))
+}
+
+/// Give every stop a position as CSS does: the ends default to 0% and 100%,
+/// positions never go backwards, and unplaced stops are spread evenly
+/// between their placed neighbours
+fn place_stops(stops: Vec<(Rgb, Option<f32>)>) -> Vec<(f32, Rgb)> {
+    let last = stops.len() - 1;
+    let mut positions: Vec<Option<f32>> = stops.iter().map(|&(_, position)| position).collect();
+    positions[0].get_or_insert(0.0);
+    positions[last].get_or_insert(1.0);
+
+    let mut furthest = f32::MIN;
+    for position in positions.iter_mut().flatten() {
+        *position = position.max(furthest);
+        furthest = *position;
+    }
+
+    let mut placed = vec![positions[0].unwrap_or_default(); stops.len()];
+    let mut previous = 0;
+    for (index, &position) in positions.iter().enumerate().skip(1) {
+        let Some(position) = position else {
+            continue;
+        };
+        let start = placed[previous];
+        let gap = index - previous;
+        for (offset, slot) in placed[previous + 1..index].iter_mut().enumerate() {
+            *slot = start + (position - start) * (offset + 1) as f32 / gap as f32;
+        }
+        placed[index] = position;
+        previous = index;
+    }
+
+    placed
+        .into_iter()
+        .zip(stops)
+        .map(|(position, (color, _))| (position, color))
+        .collect()
+}
+
+fn parse_css_color(color: &str) -> LutResult<Rgb> {
+    let invalid = || format_error(format!("Unsupported color: {}", color));
+
+    if let Some(hex) = color.strip_prefix('#') {
+        let digits = hex
+            .chars()
+            .map(|digit| digit.to_digit(16).map(|digit| digit as f32))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(invalid)?;
+        return match digits.len() {
+            3 | 4 => Ok([digits[0] / 15.0, digits[1] / 15.0, digits[2] / 15.0]),
+            6 | 8 => Ok([0, 2, 4].map(|i| (digits[i] * 16.0 + digits[i + 1]) / 255.0)),
+            _ => Err(invalid()),
+        };
+    }
+
+    if let Some((function, rest)) = color.split_once('(') {
+        let values: Vec<&str> = rest
+            .trim_end_matches(')')
+            .split([',', ' ', '/'])
+            .filter(|value| !value.is_empty())
+            .collect();
+        if values.len() < 3 {
+            return Err(invalid());
+        }
+        let number = |value: &str, scale: f32| -> LutResult<f32> {
+            match value.strip_suffix('%') {
+                Some(percent) => percent.parse::<f32>().map(|percent| percent / 100.0),
+                None => value.parse::<f32>().map(|value| value / scale),
+            }
+            .map_err(|_| invalid())
+        };
+        return match function.trim() {
+            "rgb" | "rgba" => Ok([
+                number(values[0], 255.0)?,
+                number(values[1], 255.0)?,
+                number(values[2], 255.0)?,
+            ]),
+            "hsl" | "hsla" => {
+                let hue = match values[0].strip_suffix("turn") {
+                    Some(turns) => turns.parse::<f32>().map_err(|_| invalid())?,
+                    None => number(values[0].trim_end_matches("deg"), 360.0)?,
+                };
+                Ok(hsl_to_rgb(
+                    hue,
+                    number(values[1], 100.0)?,
+                    number(values[2], 100.0)?,
+                ))
+            }
+            _ => Err(invalid()),
+        };
+    }
+
+    let hex = match color {
+        "black" => 0x000000,
+        "white" => 0xffffff,
+        "red" => 0xff0000,
+        "lime" => 0x00ff00,
+        "green" => 0x008000,
+        "blue" => 0x0000ff,
+        "yellow" => 0xffff00,
+        "cyan" | "aqua" => 0x00ffff,
+        "magenta" | "fuchsia" => 0xff00ff,
+        "gray" | "grey" => 0x808080,
+        "silver" => 0xc0c0c0,
+        "maroon" => 0x800000,
+        "olive" => 0x808000,
+        "navy" => 0x000080,
+        "purple" => 0x800080,
+        "teal" => 0x008080,
+        "orange" => 0xffa500,
+        _ => return Err(invalid()),
+    };
+    Ok([16, 8, 0].map(|shift| ((hex >> shift) & 0xff) as f32 / 255.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgb(lut: &LutData, index: usize) -> [u8; 3] {
+        [lut.red[index], lut.green[index], lut.blue[index]]
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            LutFormat::from_path(Path::new("warm.CUBE")).unwrap(),
+            LutFormat::Cube
+        );
+        assert_eq!(
+            LutFormat::from_path(Path::new("sunset.ggr")).unwrap(),
+            LutFormat::GimpGradient
+        );
+        assert_eq!(
+            LutFormat::from_path(Path::new("strip.png")).unwrap(),
+            LutFormat::Png
+        );
+        assert!(LutFormat::from_path(Path::new("palette.ase")).is_err());
+        assert!(LutFormat::from_path(Path::new("palette")).is_err());
+    }
+
+    #[test]
+    fn test_samples_are_resampled_to_lut_size() {
+        let lut = LutData::from_samples("ramp".to_string(), &[[0.0; 3], [1.0; 3]]).unwrap();
+        for i in 0..LUT_SIZE {
+            assert_eq!(rgb(&lut, i), [i as u8; 3]);
+        }
+
+        let lut = LutData::from_samples("flat".to_string(), &[[1.0, 0.0, 0.0]]).unwrap();
+        assert_eq!(rgb(&lut, 0), [255, 0, 0]);
+        assert_eq!(rgb(&lut, 255), [255, 0, 0]);
+
+        assert!(LutData::from_samples("empty".to_string(), &[]).is_err());
+    }
+
+    #[test]
+    fn test_cube() {
+        let cube = "TITLE \"Warm\"\n# comment\nLUT_1D_SIZE 3\nDOMAIN_MIN 0 0 0\nDOMAIN_MAX 1 1 1\n\
+                    0 0 0\n1 0 0\n1 1 0\n";
+        let lut = LutData::from_cube("warm".to_string(), cube).unwrap();
+        assert_eq!(lut.name, "warm");
+        assert_eq!(rgb(&lut, 0), [0, 0, 0]);
+        assert_eq!(rgb(&lut, 255), [255, 255, 0]);
+        assert_eq!(rgb(&lut, 128)[0], 255);
+
+        assert!(LutData::from_cube("short".to_string(), "LUT_1D_SIZE 4\n0 0 0\n1 1 1\n").is_err());
+        assert!(LutData::from_cube("3d".to_string(), "LUT_3D_SIZE 2\n0 0 0\n").is_err());
+        assert!(LutData::from_cube("bad".to_string(), "LUT_1D_SIZE 1\n0 zero 0\n").is_err());
+    }
+
+    #[test]
+    fn test_ggr() {
+        let ggr = "GIMP Gradient\nName: Split\n2\n\
+                   0 0.25 0.5 0 0 0 1 1 0 0 1 0 0\n\
+                   0.5 0.75 1 0 0 1 1 0 1 0 1 5 0 0 0\n";
+        let lut = LutData::from_ggr("split".to_string(), ggr).unwrap();
+        assert_eq!(rgb(&lut, 0), [0, 0, 0]);
+        // Linear up to red at the middle of the gradient
+        assert_eq!(rgb(&lut, 127), [254, 0, 0]);
+        // Step blending jumps halfway through the second segment
+        assert_eq!(rgb(&lut, 160), [0, 0, 255]);
+        assert_eq!(rgb(&lut, 255), [0, 255, 0]);
+
+        assert!(LutData::from_ggr("bad".to_string(), "GIMP Gradient\n2\n0 0.5 1\n").is_err());
+        assert!(LutData::from_ggr("bad".to_string(), "GIMP Palette\n1\n").is_err());
+    }
+
+    #[test]
+    fn test_ggr_hsv_coloring() {
+        // Red to blue counter-clockwise passes through green, clockwise
+        // through magenta
+        let segment =
+            |coloring: u32| format!("GIMP Gradient\n1\n0 0.5 1 1 0 0 1 0 0 1 1 0 {}\n", coloring);
+        let ccw = LutData::from_ggr("ccw".to_string(), &segment(1)).unwrap();
+        let cw = LutData::from_ggr("cw".to_string(), &segment(2)).unwrap();
+        let rgb_blend = LutData::from_ggr("rgb".to_string(), &segment(0)).unwrap();
+
+        assert_eq!(rgb(&ccw, 128)[1], 255);
+        let [red, green, blue] = rgb(&cw, 128);
+        assert!(red > 250 && green == 0 && blue > 250);
+        assert_eq!(rgb(&rgb_blend, 128)[1], 0);
+        assert_eq!(rgb(&ccw, 255), [0, 0, 255]);
+    }
+
+    #[test]
+    fn test_gpl_and_fractint_map() {
+        let gpl =
+            "GIMP Palette\nName: Duo\nColumns: 2\n#\n  0   0   0\tBlack\n255 255 255\tWhite\n";
+        let lut = LutData::from_gpl("duo".to_string(), gpl).unwrap();
+        assert_eq!(rgb(&lut, 0), [0, 0, 0]);
+        assert_eq!(rgb(&lut, 100), [100, 100, 100]);
+        assert!(LutData::from_gpl("bad".to_string(), "0 0 0\n").is_err());
+
+        let map = "0 0 0 black\n255 0 0 red, the rest is a comment\n";
+        let lut = LutData::from_fractint_map("map".to_string(), map).unwrap();
+        assert_eq!(rgb(&lut, 255), [255, 0, 0]);
+        assert!(LutData::from_fractint_map("bad".to_string(), "0 0\n").is_err());
+    }
+
+    #[test]
+    fn test_css_gradient() {
+        let lut = LutData::from_css_gradient(
+            "css".to_string(),
+            "background: linear-gradient(90deg, #000 0%, rgb(255, 0, 0) 50%, Blue);",
+        )
+        .unwrap();
+        assert_eq!(rgb(&lut, 0), [0, 0, 0]);
+        assert_eq!(rgb(&lut, 255), [0, 0, 255]);
+        assert_eq!(rgb(&lut, 64)[0], 128);
+
+        // Hard stops, a stop with two positions, and an unplaced stop spread
+        // between its neighbours
+        let lut = LutData::from_css_gradient(
+            "stripes".to_string(),
+            "linear-gradient(to right, red 0% 25%, lime 25% 75%, hsl(240deg 100% 50%))",
+        )
+        .unwrap();
+        assert_eq!(rgb(&lut, 60), [255, 0, 0]);
+        assert_eq!(rgb(&lut, 70), [0, 255, 0]);
+        assert_eq!(rgb(&lut, 190), [0, 255, 0]);
+        assert_eq!(rgb(&lut, 255), [0, 0, 255]);
+
+        assert!(
+            LutData::from_css_gradient("bad".to_string(), "radial-gradient(red, blue)").is_err()
+        );
+        assert!(
+            LutData::from_css_gradient("bad".to_string(), "linear-gradient(red, blue").is_err()
+        );
+        assert!(
+            LutData::from_css_gradient("bad".to_string(), "linear-gradient(red 10px, blue)")
+                .is_err()
+        );
+        assert!(
+            LutData::from_css_gradient("bad".to_string(), "linear-gradient(tomato, blue)").is_err()
+        );
+    }
+
+    #[test]
+    fn test_stop_placement() {
+        let stops = vec![
+            ([0.0; 3], None),
+            ([0.0; 3], Some(0.6)),
+            ([0.0; 3], Some(0.4)),
+            ([0.0; 3], None),
+            ([0.0; 3], None),
+        ];
+        let positions: Vec<f32> = place_stops(stops).into_iter().map(|(p, _)| p).collect();
+        assert_eq!(positions[..3], [0.0, 0.6, 0.6]);
+        assert!((positions[3] - 0.8).abs() < 1e-6);
+        assert_eq!(positions[4], 1.0);
+    }
+
+    #[test]
+    fn test_css_colors() {
+        assert_eq!(
+            parse_css_color("#ff8000").unwrap(),
+            [1.0, 128.0 / 255.0, 0.0]
+        );
+        assert_eq!(parse_css_color("#f00f").unwrap(), [1.0, 0.0, 0.0]);
+        assert_eq!(
+            parse_css_color("rgba(0 100% 0 / 50%)").unwrap(),
+            [0.0, 1.0, 0.0]
+        );
+        assert_eq!(
+            parse_css_color("teal").unwrap(),
+            [0.0, 128.0 / 255.0, 128.0 / 255.0]
+        );
+        let cyan = parse_css_color("hsl(0.5turn, 100%, 50%)").unwrap();
+        assert_eq!(cyan.map(to_byte), [0, 255, 255]);
+        assert!(parse_css_color("#12345").is_err());
+        assert!(parse_css_color("cmyk(0, 0, 0, 1)").is_err());
+    }
+
+    fn encode_png(width: u32, height: u32, color: png::ColorType, pixels: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut encoder = png::Encoder::new(&mut data, width, height);
+        encoder.set_color(color);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(pixels).unwrap();
+        writer.finish().unwrap();
+        data
+    }
+
+    #[test]
+    fn test_png_strip() {
+        // Two rows averaged into a red to blue strip
+        let pixels = [
+            255, 0, 0, 0, 0, 255, //
+            255, 0, 0, 0, 0, 255,
+        ];
+        let data = encode_png(2, 2, png::ColorType::Rgb, &pixels);
+        let lut = LutData::from_png("strip".to_string(), &data).unwrap();
+        assert_eq!(rgb(&lut, 0), [255, 0, 0]);
+        assert_eq!(rgb(&lut, 255), [0, 0, 255]);
+
+        // Tall grayscale images are read from top to bottom, averaging columns
+        let data = encode_png(2, 3, png::ColorType::Grayscale, &[0, 0, 100, 200, 255, 255]);
+        let lut = LutData::from_png("tall".to_string(), &data).unwrap();
+        assert_eq!(rgb(&lut, 0), [0, 0, 0]);
+        assert_eq!(rgb(&lut, 128), [150, 150, 150]);
+        assert_eq!(rgb(&lut, 255), [255, 255, 255]);
+
+        assert!(LutData::from_png("bad".to_string(), b"not a png").is_err());
+    }
+
+    #[test]
+    fn test_import_dispatches_on_format() {
+        let lut = LutData::import(
+            "css".to_string(),
+            LutFormat::CssGradient,
+            b"linear-gradient(black, white)",
+        )
+        .unwrap();
+        assert_eq!(rgb(&lut, 255), [255, 255, 255]);
+        assert!(LutData::import("bad".to_string(), LutFormat::Cube, &[0xff, 0xfe]).is_err());
+    }
+
+    #[test]
+    fn test_hex_json_import() {
+        let lut =
+            LutData::from_hex_json("json".to_string(), r##"["#000000", "#FF0000"]"##).unwrap();
+        assert_eq!(rgb(&lut, 255), [255, 0, 0]);
+        assert!(LutData::from_hex_json("bad".to_string(), "[1, 2]").is_err());
+        assert!(LutData::from_hex_json("bad".to_string(), "[]").is_err());
+    }
+
+    fn curve_lut() -> LutData {
+        LutData {
+            name: "curve".to_string(),
+            red: std::array::from_fn(|i| i as u8),
+            green: std::array::from_fn(|i| (i * i / 255) as u8),
+            blue: std::array::from_fn(|i| if i < 128 { 255 } else { 40 }),
+        }
+    }
+
+    fn ramp_lut() -> LutData {
+        LutData {
+            name: "ramp".to_string(),
+            red: std::array::from_fn(|i| i as u8),
+            green: std::array::from_fn(|i| i as u8),
+            blue: std::array::from_fn(|i| i as u8),
+        }
+    }
+
+    #[test]
+    fn test_exact_round_trips() {
+        let lut = curve_lut();
+        for format in [
+            LutFormat::Cube,
+            LutFormat::GimpPalette,
+            LutFormat::FractintMap,
+            LutFormat::Png,
+            LutFormat::Json,
+        ] {
+            let data = lut.export(format).unwrap();
+            let imported = LutData::import("curve".to_string(), format, &data).unwrap();
+            assert_eq!(imported, lut, "{:?} did not round trip", format);
+        }
+    }
+
+    #[test]
+    fn test_gradient_round_trips_within_one_step() {
+        let lut = curve_lut();
+        for format in [LutFormat::GimpGradient, LutFormat::CssGradient] {
+            let data = lut.export(format).unwrap();
+            let imported = LutData::import("curve".to_string(), format, &data).unwrap();
+            for i in 0..LUT_SIZE {
+                for (expected, actual) in rgb(&lut, i).into_iter().zip(rgb(&imported, i)) {
+                    assert!(
+                        expected.abs_diff(actual) <= 1,
+                        "{:?} entry {} was {} instead of {}",
+                        format,
+                        i,
+                        actual,
+                        expected
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_gradient_exports_keep_only_needed_stops() {
+        let lut = ramp_lut();
+        assert_eq!(
+            lut.to_css_gradient(),
+            "linear-gradient(90deg, #000000 0%, #ffffff 100%)"
+        );
+        assert_eq!(
+            lut.to_ggr(),
+            "GIMP Gradient\nName: ramp\n1\n\
+             0.000000 0.500000 1.000000 0.000000 0.000000 0.000000 1.000000 \
+             1.000000 1.000000 1.000000 1.000000 0 0\n"
+        );
+
+        // The hard edge in the blue channel needs a stop on each side
+        let stops = curve_lut().key_stops();
+        assert!(stops.contains(&127) && stops.contains(&128));
+        assert!(stops.len() < 64);
+    }
+
+    #[test]
+    fn test_text_exports() {
+        let lut = ramp_lut();
+        assert!(
+            lut.to_cube()
+                .starts_with("TITLE \"ramp\"\nLUT_1D_SIZE 256\n0.000000 0.000000 0.000000\n")
+        );
+        assert!(lut.to_gpl().starts_with("GIMP Palette\nName: ramp\n"));
+        assert!(lut.to_gpl().contains("\n255 255 255\t#ffffff\n"));
+        assert_eq!(lut.to_fractint_map().lines().nth(16), Some("16 16 16"));
+
+        let colors = lut.hex_colors();
+        assert_eq!(colors.len(), LUT_SIZE);
+        assert_eq!(colors[171], "#ababab");
+        assert!(lut.to_hex_json().starts_with(r##"["#000000","#010101""##));
+    }
+
+    #[test]
+    fn test_png_export_is_a_strip() {
+        let data = ramp_lut().to_png(PNG_STRIP_HEIGHT).unwrap();
+        let reader = png::Decoder::new(data.as_slice()).read_info().unwrap();
+        assert_eq!(reader.info().width, LUT_SIZE as u32);
+        assert_eq!(reader.info().height, PNG_STRIP_HEIGHT);
+    }
+}
@@ -23,8 +23,14 @@ pub mod camera;
 pub mod coordinates;
 pub mod gpu_utils;
 pub mod lut;
+pub mod lut_formats;
+pub mod offscreen;
 pub mod position_generators;
 pub mod post_processing;
+pub mod random;
+pub mod readback;
+pub mod settings_schema;
+pub mod snapshot;
 
 pub use average_color::AverageColorResources;
 pub use gpu_utils::{
@@ -32,8 +38,13 @@ pub use gpu_utils::{
     ShaderManager,
 };
 pub use lut::{LutData, LutManager, SimulationLutManager};
+pub use lut_formats::LutFormat;
+pub use offscreen::{OffscreenTarget, save_png};
 pub use position_generators::{PositionGenerator, SlimeMoldPositionGenerator};
 pub use post_processing::{PostProcessingResources, PostProcessingState};
+pub use readback::BufferReadback;
+pub use settings_schema::SettingField;
+pub use snapshot::SimulationSnapshot;
 
 pub const INFINITE_RENDER_SHADER: &str = include_str!("infinite_render.wgsl");
 pub const AVERAGE_COLOR_SHADER: &str = include_str!("average_color.wgsl");
@@ -0,0 +1,127 @@
+//! # Buffer Readback
+//!
+//! Reads small GPU buffers, such as statistics counters, back to the CPU
+//! without stalling the frame. A request copies the buffer into a staging
+//! buffer and maps it asynchronously; the contents are collected on a later
+//! frame once the GPU has caught up, so the render loop never waits on it.
+
+use bytemuck::Pod;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, Ordering};
+use wgpu::{Buffer, Device, Queue};
+
+const IDLE: u8 = 0;
+const PENDING: u8 = 1;
+const READY: u8 = 2;
+const FAILED: u8 = 3;
+
+/// A staging buffer that copies a GPU buffer and maps it without waiting
+#[derive(Debug)]
+pub struct BufferReadback {
+    staging: Buffer,
+    state: Arc<AtomicU8>,
+}
+
+impl BufferReadback {
+    pub fn new(device: &Device, label: &str, size: u64) -> Self {
+        Self {
+            staging: device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(label),
+                size,
+                usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }),
+            state: Arc::new(AtomicU8::new(IDLE)),
+        }
+    }
+
+    /// Copy `source` into the staging buffer and start mapping it. Call after
+    /// the commands that write `source` have been submitted. Returns false
+    /// while the previous copy has not been collected yet.
+    pub fn request(&self, source: &Buffer, device: &Device, queue: &Queue) -> bool {
+        if self.state.load(Ordering::Acquire) != IDLE {
+            return false;
+        }
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Buffer Readback Encoder"),
+        });
+        encoder.copy_buffer_to_buffer(source, 0, &self.staging, 0, self.staging.size());
+        queue.submit(std::iter::once(encoder.finish()));
+
+        self.state.store(PENDING, Ordering::Release);
+        let state = self.state.clone();
+        self.staging
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                let next = if result.is_ok() { READY } else { FAILED };
+                state.store(next, Ordering::Release);
+            });
+        true
+    }
+
+    /// The copied contents once mapping has finished, or `None` while the GPU
+    /// is still busy or no copy was requested. Never blocks.
+    pub fn collect<T: Pod>(&self, device: &Device) -> Option<Vec<T>> {
+        if self.state.load(Ordering::Acquire) == IDLE {
+            return None;
+        }
+        device.poll(wgpu::Maintain::Poll);
+
+        let contents = match self.state.load(Ordering::Acquire) {
+            READY => {
+                let contents = {
+                    let mapped = self.staging.slice(..).get_mapped_range();
+                    bytemuck::cast_slice(&mapped).to_vec()
+                };
+                self.staging.unmap();
+                Some(contents)
+            }
+            FAILED => {
+                tracing::warn!("Failed to read back a GPU buffer");
+                None
+            }
+            _ => return None,
+        };
+        self.state.store(IDLE, Ordering::Release);
+        contents
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::RenderSettings;
+    use crate::simulation::headless::HeadlessRenderer;
+    use wgpu::util::DeviceExt;
+
+    #[tokio::test]
+    async fn test_readback_collects_on_a_later_poll() {
+        let renderer = HeadlessRenderer::new(
+            16,
+            16,
+            wgpu::TextureFormat::Rgba8UnormSrgb,
+            RenderSettings::default(),
+        )
+        .await
+        .unwrap();
+        let (device, queue) = (&renderer.device, &renderer.queue);
+
+        let source = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: bytemuck::cast_slice(&[1u32, 2, 3, 4]),
+            usage: wgpu::BufferUsages::COPY_SRC,
+        });
+        let readback = BufferReadback::new(device, "Test Readback", source.size());
+
+        assert!(readback.collect::<u32>(device).is_none());
+        assert!(readback.request(&source, device, queue));
+        // Only one copy is in flight at a time
+        assert!(!readback.request(&source, device, queue));
+
+        device.poll(wgpu::Maintain::Wait);
+        assert_eq!(readback.collect::<u32>(device), Some(vec![1, 2, 3, 4]));
+        assert!(readback.collect::<u32>(device).is_none());
+        assert!(readback.request(&source, device, queue));
+    }
+}
@@ -0,0 +1,434 @@
+//! # Settings Schema
+//!
+//! Machine-readable description of a simulation's `Settings` struct. Each
+//! field lists its type, the range or variants it accepts, the group it is
+//! shown under and whether changing it restarts the simulation. Frontends,
+//! the randomizer and external controllers build their controls from this
+//! instead of hard-coding ranges per simulation.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::error::{SimulationError, SimulationResult};
+
+/// Type of a setting and the values it accepts
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SettingKind {
+    Float {
+        min: f64,
+        max: f64,
+        step: f64,
+    },
+    Integer {
+        min: i64,
+        max: i64,
+        step: i64,
+    },
+    Bool,
+    /// One of a fixed set of strings, spelled as they appear in presets.
+    /// Values are matched ignoring case, spaces and underscores, so display
+    /// names and snake_case spellings are accepted too.
+    Enum {
+        variants: &'static [&'static str],
+    },
+    /// A `[start, end]` pair with both ends inside `min..=max`
+    FloatRange {
+        min: f64,
+        max: f64,
+        step: f64,
+    },
+    /// Square matrix of floats, sized by the field named in `size_field`
+    Matrix {
+        min: f64,
+        max: f64,
+        step: f64,
+        size_field: &'static str,
+    },
+}
+
+/// Schema entry for a single field of a simulation's settings
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SettingField {
+    /// Key of the field in serialized settings and preset files
+    pub name: &'static str,
+    /// Name accepted by `update_setting`, which differs from `name` for
+    /// simulations whose frontend uses camelCase names
+    pub setting_name: &'static str,
+    #[serde(flatten)]
+    pub kind: SettingKind,
+    pub group: &'static str,
+    pub description: &'static str,
+    /// Changing the field respawns the simulation, or only takes effect after
+    /// a reset
+    pub requires_reset: bool,
+}
+
+impl SettingField {
+    fn new(name: &'static str, kind: SettingKind) -> Self {
+        Self {
+            name,
+            setting_name: name,
+            kind,
+            group: "",
+            description: "",
+            requires_reset: false,
+        }
+    }
+
+    pub fn float(name: &'static str, min: f64, max: f64, step: f64) -> Self {
+        Self::new(name, SettingKind::Float { min, max, step })
+    }
+
+    pub fn integer(name: &'static str, min: i64, max: i64) -> Self {
+        Self::new(name, SettingKind::Integer { min, max, step: 1 })
+    }
+
+    /// A random seed, accepting any `u32`
+    pub fn seed(name: &'static str) -> Self {
+        Self::integer(name, 0, u32::MAX as i64)
+    }
+
+    pub fn bool(name: &'static str) -> Self {
+        Self::new(name, SettingKind::Bool)
+    }
+
+    pub fn enumeration(name: &'static str, variants: &'static [&'static str]) -> Self {
+        Self::new(name, SettingKind::Enum { variants })
+    }
+
+    pub fn float_range(name: &'static str, min: f64, max: f64, step: f64) -> Self {
+        Self::new(name, SettingKind::FloatRange { min, max, step })
+    }
+
+    pub fn matrix(
+        name: &'static str,
+        min: f64,
+        max: f64,
+        step: f64,
+        size_field: &'static str,
+    ) -> Self {
+        Self::new(
+            name,
+            SettingKind::Matrix {
+                min,
+                max,
+                step,
+                size_field,
+            },
+        )
+    }
+
+    pub fn step(mut self, value: i64) -> Self {
+        if let SettingKind::Integer { step, .. } = &mut self.kind {
+            *step = value;
+        }
+        self
+    }
+
+    pub fn group(mut self, group: &'static str) -> Self {
+        self.group = group;
+        self
+    }
+
+    pub fn description(mut self, description: &'static str) -> Self {
+        self.description = description;
+        self
+    }
+
+    pub fn setting_name(mut self, setting_name: &'static str) -> Self {
+        self.setting_name = setting_name;
+        self
+    }
+
+    pub fn requires_reset(mut self) -> Self {
+        self.requires_reset = true;
+        self
+    }
+    /// Check that `value` has the field's type and lies within its range.
+    /// `settings` holds the sibling fields, used to size matrices.
+    pub fn validate(
+        &self,
+        value: &Value,
+        settings: Option<&Map<String, Value>>,
+    ) -> SimulationResult<()> {
+        let valid = match &self.kind {
+            SettingKind::Float { min, max, .. } => in_range(value, *min, *max),
+            SettingKind::Integer { min, max, .. } => value
+                .as_i64()
+                .is_some_and(|value| value >= *min && value <= *max),
+            SettingKind::Bool => value.is_boolean(),
+            SettingKind::Enum { variants } => value.as_str().is_some_and(|value| {
+                variants
+                    .iter()
+                    .any(|variant| normalize(variant) == normalize(value))
+            }),
+            SettingKind::FloatRange { min, max, .. } => match value {
+                Value::Array(pair) if pair.len() == 2 => {
+                    in_range(&pair[0], *min, *max) && in_range(&pair[1], *min, *max)
+                }
+                Value::Object(range) => {
+                    range
+                        .get("start")
+                        .zip(range.get("end"))
+                        .is_some_and(|(start, end)| {
+                            in_range(start, *min, *max) && in_range(end, *min, *max)
+                        })
+                }
+                _ => false,
+            },
+            SettingKind::Matrix {
+                min,
+                max,
+                size_field,
+                ..
+            } => {
+                let size = settings
+                    .and_then(|settings| settings.get(*size_field))
+                    .and_then(Value::as_u64);
+                value.as_array().is_some_and(|rows| {
+                    size.is_none_or(|size| rows.len() as u64 == size)
+                        && rows.iter().all(|row| {
+                            row.as_array().is_some_and(|row| {
+                                row.len() == rows.len()
+                                    && row.iter().all(|value| in_range(value, *min, *max))
+                            })
+                        })
+                })
+            }
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(SimulationError::InvalidParameter(format!(
+                "{} must be {}, got {}",
+                self.setting_name,
+                self.allowed(),
+                value
+            )))
+        }
+    }
+
+    /// Human-readable description of the values the field accepts
+    fn allowed(&self) -> String {
+        match &self.kind {
+            SettingKind::Float { min, max, .. } => format!("a number from {min} to {max}"),
+            SettingKind::Integer { min, max, .. } => format!("an integer from {min} to {max}"),
+            SettingKind::Bool => "true or false".to_string(),
+            SettingKind::Enum { variants } => format!("one of {}", variants.join(", ")),
+            SettingKind::FloatRange { min, max, .. } => {
+                format!("a pair of numbers from {min} to {max}")
+            }
+            SettingKind::Matrix {
+                min,
+                max,
+                size_field,
+                ..
+            } => {
+                format!("a square matrix sized by {size_field} with values from {min} to {max}")
+            }
+        }
+    }
+}
+
+/// Validate a single `update_setting` call. Names in `runtime_settings`, such
+/// as the cursor size, are not part of the schema and are left to the
+/// simulation; any other name missing from the schema is rejected.
+pub fn validate_setting(
+    schema: &[SettingField],
+    runtime_settings: &[&str],
+    setting_name: &str,
+    value: &Value,
+) -> SimulationResult<()> {
+    match schema
+        .iter()
+        .find(|field| field.setting_name == setting_name || field.name == setting_name)
+    {
+        Some(field) => field.validate(value, None),
+        None if runtime_settings.contains(&setting_name) => Ok(()),
+        None => {
+            let names = schema
+                .iter()
+                .map(|field| field.setting_name)
+                .chain(runtime_settings.iter().copied())
+                .collect::<Vec<_>>();
+            Err(SimulationError::InvalidParameter(format!(
+                "Unknown setting {}, expected one of: {}",
+                setting_name,
+                names.join(", ")
+            )))
+        }
+    }
+}
+
+/// Validate a complete or partial settings object, as applied from presets
+/// and snapshots. Fields the schema does not describe are rejected.
+pub fn validate_settings(schema: &[SettingField], settings: &Value) -> SimulationResult<()> {
+    let settings = settings.as_object().ok_or_else(|| {
+        SimulationError::InvalidParameter(format!("Settings must be an object, got {}", settings))
+    })?;
+
+    for (name, value) in settings {
+        let field = schema
+            .iter()
+            .find(|field| field.name == name)
+            .ok_or_else(|| {
+                SimulationError::InvalidParameter(format!("Unknown setting {}", name))
+            })?;
+        field.validate(value, Some(settings))?;
+    }
+    Ok(())
+}
+
+/// Compare in single precision, since settings are stored as `f32` and the
+/// bounds are written as `f64`
+fn in_range(value: &Value, min: f64, max: f64) -> bool {
+    value
+        .as_f64()
+        .is_some_and(|value| value as f32 >= min as f32 && value as f32 <= max as f32)
+}
+
+fn normalize(variant: &str) -> String {
+    variant
+        .chars()
+        .filter(|c| *c != ' ' && *c != '_')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulations::{ecosystem, flow, gray_scott, particle_life, pellets, slime_mold};
+    use serde::Serialize;
+    use serde_json::json;
+
+    /// Check that every serialized field is described and holds a value the
+    /// schema accepts
+    fn check(simulation: &str, schema: Vec<SettingField>, settings: impl Serialize) {
+        let settings = serde_json::to_value(settings).unwrap();
+        let fields = settings.as_object().unwrap();
+        assert_eq!(
+            schema.len(),
+            fields.len(),
+            "{simulation} schema and settings have different fields"
+        );
+        assert!(
+            schema
+                .iter()
+                .all(|field| !field.group.is_empty() && !field.description.is_empty())
+        );
+        if let Err(e) = validate_settings(&schema, &settings) {
+            panic!("{simulation}: {e}");
+        }
+    }
+
+    #[test]
+    fn test_schemas_describe_default_settings() {
+        check(
+            "slime_mold",
+            slime_mold::settings::Settings::schema(),
+            slime_mold::settings::Settings::default(),
+        );
+        check(
+            "gray_scott",
+            gray_scott::settings::Settings::schema(),
+            gray_scott::settings::Settings::default(),
+        );
+        check(
+            "particle_life",
+            particle_life::settings::Settings::schema(),
+            particle_life::settings::Settings::default(),
+        );
+        check(
+            "flow",
+            flow::settings::Settings::schema(),
+            flow::settings::Settings::default(),
+        );
+        check(
+            "pellets",
+            pellets::settings::Settings::schema(),
+            pellets::settings::Settings::default(),
+        );
+        check(
+            "ecosystem",
+            ecosystem::settings::Settings::schema(),
+            ecosystem::settings::Settings::default(),
+        );
+    }
+
+    #[test]
+    fn test_schemas_describe_built_in_presets() {
+        for descriptor in crate::simulations::registry::SIMULATIONS {
+            let (Some(schema), Some(presets)) = (descriptor.settings_schema, descriptor.presets)
+            else {
+                continue;
+            };
+            let presets = presets();
+            for name in presets.get_preset_names() {
+                if !presets.is_built_in_preset(&name) {
+                    continue;
+                }
+                let settings = presets.get_preset_settings_json(&name).unwrap().unwrap();
+                check(
+                    &format!("{} preset {name:?}", descriptor.id),
+                    schema(),
+                    settings,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_validation_rejects_bad_values() {
+        let schema = particle_life::settings::Settings::schema();
+        let runtime_settings = particle_life::DESCRIPTOR.runtime_settings;
+        let validate =
+            |name: &str, value: &Value| validate_setting(&schema, runtime_settings, name, value);
+
+        assert!(validate("max_distance", &json!(0.05)).is_ok());
+        let error = validate("max_distance", &json!(0.0)).unwrap_err();
+        assert!(matches!(error, SimulationError::InvalidParameter(_)));
+        assert_eq!(
+            error.to_string(),
+            "Invalid parameter: max_distance must be a number from 0.001 to 1, got 0.0"
+        );
+        assert!(validate("wrap_edges", &json!(1)).is_err());
+        assert!(validate("species_count", &json!(9)).is_err());
+        assert!(validate("force_matrix", &json!([[0.5, 2.0], [0.0, 0.0]])).is_err());
+        // Runtime settings are checked by the simulation itself
+        assert!(validate("cursor_size", &json!(0.2)).is_ok());
+        let error = validate("max_distanse", &json!(0.05)).unwrap_err();
+        assert!(matches!(error, SimulationError::InvalidParameter(_)));
+        assert!(error.to_string().contains("max_distance"));
+
+        let mut settings =
+            serde_json::to_value(particle_life::settings::Settings::default()).unwrap();
+        settings["species_count"] = json!(3);
+        assert!(
+            validate_settings(&schema, &settings).is_err(),
+            "matrix is 4x4"
+        );
+        assert!(validate_settings(&schema, &json!({ "typo": 1 })).is_err());
+    }
+
+    #[test]
+    fn test_enum_spellings() {
+        let schema = gray_scott::settings::Settings::schema();
+        for spelling in ["Diagonal Gradient", "diagonal_gradient", "DiagonalGradient"] {
+            assert!(validate_setting(&schema, &[], "nutrient_pattern", &json!(spelling)).is_ok());
+        }
+        assert!(validate_setting(&schema, &[], "nutrient_pattern", &json!("Plaid")).is_err());
+
+        let schema = flow::settings::Settings::schema();
+        assert!(validate_setting(&schema, &[], "noiseType", &json!("FBM")).is_ok());
+        assert!(validate_setting(&schema, &[], "particleShape", &json!("Flower")).is_ok());
+        assert!(validate_setting(&schema, &[], "particleShape", &json!("Star")).is_ok());
+        // Presets keep storing the shape as "Star"
+        assert_eq!(
+            serde_json::to_value(flow::settings::ParticleShape::Star).unwrap(),
+            json!("Star")
+        );
+    }
+}
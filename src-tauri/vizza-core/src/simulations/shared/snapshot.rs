@@ -0,0 +1,530 @@
+//! # Simulation Snapshots
+//!
+//! Full captures of a running simulation: its settings, camera and LUT together
+//! with the raw contents of the GPU buffers and textures that hold its state
+//! (agents, particles, trail maps, chemical fields). Loading a snapshot into a
+//! simulation of the same type and size continues the run from that moment.
+//!
+//! Snapshots are stored in a compact binary file: a magic tag and version, a
+//! JSON header describing the contents, then the raw LUT and buffer bytes.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use wgpu::{Device, Queue};
+
+use super::camera::Camera;
+use super::lut::LutData;
+use crate::error::{AppResult, SimulationError, SimulationResult};
+
+const SNAPSHOT_MAGIC: &[u8; 8] = b"VIZZASNP";
+const SNAPSHOT_VERSION: u32 = 1;
+const LUT_BYTES: usize = 256 * 3;
+
+/// Camera pose at the time of the snapshot, including where an in-progress
+/// pan or zoom was heading
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CameraSnapshot {
+    pub position: [f32; 2],
+    pub zoom: f32,
+    pub target_position: [f32; 2],
+    pub target_zoom: f32,
+}
+
+impl CameraSnapshot {
+    pub fn of(camera: &Camera) -> Self {
+        let (target_position, target_zoom) = camera.target();
+        Self {
+            position: camera.position,
+            zoom: camera.zoom,
+            target_position,
+            target_zoom,
+        }
+    }
+
+    pub fn apply_to(&self, camera: &mut Camera) {
+        camera.set_view(self.position, self.zoom);
+        camera.set_target(self.target_position, self.target_zoom);
+    }
+}
+
+/// Raw contents of one GPU buffer or texture
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotBuffer {
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationSnapshot {
+    pub simulation_type: String,
+    /// Settings as returned by `Simulation::get_settings`
+    pub settings: Value,
+    /// Runtime values that are not part of the settings, such as frame counters
+    pub state: Value,
+    pub lut_name: String,
+    pub lut_reversed: bool,
+    /// Colors of the LUT named `lut_name`, before reversal, so custom and
+    /// temporary LUTs survive even if they no longer exist by name
+    pub lut: Option<LutData>,
+    pub camera: Option<CameraSnapshot>,
+    pub buffers: Vec<SnapshotBuffer>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SnapshotHeader {
+    simulation_type: String,
+    settings: Value,
+    state: Value,
+    lut_name: String,
+    lut_reversed: bool,
+    has_lut: bool,
+    camera: Option<CameraSnapshot>,
+    buffers: Vec<BufferHeader>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BufferHeader {
+    name: String,
+    size: u64,
+}
+
+impl SimulationSnapshot {
+    pub fn new(simulation_type: &str, settings: Value) -> Self {
+        Self {
+            simulation_type: simulation_type.to_string(),
+            settings,
+            state: Value::Null,
+            lut_name: String::new(),
+            lut_reversed: false,
+            lut: None,
+            camera: None,
+            buffers: Vec::new(),
+        }
+    }
+
+    pub fn with_lut(mut self, lut_name: &str, lut_reversed: bool) -> Self {
+        self.lut_name = lut_name.to_string();
+        self.lut_reversed = lut_reversed;
+        self
+    }
+
+    pub fn with_camera(mut self, camera: &Camera) -> Self {
+        self.camera = Some(CameraSnapshot::of(camera));
+        self
+    }
+
+    pub fn with_state(mut self, state: Value) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// Read `buffer` back from the GPU and store it under `name`
+    pub fn capture_buffer(
+        &mut self,
+        name: &str,
+        buffer: &wgpu::Buffer,
+        device: &Device,
+        queue: &Queue,
+    ) -> SimulationResult<()> {
+        let data = read_buffer(device, queue, buffer)?;
+        self.buffers.push(SnapshotBuffer {
+            name: name.to_string(),
+            data,
+        });
+        Ok(())
+    }
+
+    /// Read `texture` back from the GPU and store it under `name`
+    pub fn capture_texture(
+        &mut self,
+        name: &str,
+        texture: &wgpu::Texture,
+        device: &Device,
+        queue: &Queue,
+    ) -> SimulationResult<()> {
+        let data = read_texture(device, queue, texture)?;
+        self.buffers.push(SnapshotBuffer {
+            name: name.to_string(),
+            data,
+        });
+        Ok(())
+    }
+
+    /// Stored contents of the buffer called `name`
+    pub fn buffer(&self, name: &str) -> SimulationResult<&[u8]> {
+        self.buffers
+            .iter()
+            .find(|buffer| buffer.name == name)
+            .map(|buffer| buffer.data.as_slice())
+            .ok_or_else(|| {
+                SimulationError::InvalidParameter(format!("Snapshot has no '{}' buffer", name))
+            })
+    }
+
+    /// Upload the stored buffer `name` into `buffer`, which must be the same size
+    pub fn restore_buffer(
+        &self,
+        name: &str,
+        buffer: &wgpu::Buffer,
+        queue: &Queue,
+    ) -> SimulationResult<()> {
+        let data = self.buffer(name)?;
+        if data.len() as u64 != buffer.size() {
+            return Err(SimulationError::InvalidParameter(format!(
+                "Snapshot buffer '{}' holds {} bytes but the simulation expects {}",
+                name,
+                data.len(),
+                buffer.size()
+            )));
+        }
+        queue.write_buffer(buffer, 0, data);
+        Ok(())
+    }
+
+    /// Upload the stored texture `name` into `texture`, which must have the
+    /// same size and format
+    pub fn restore_texture(
+        &self,
+        name: &str,
+        texture: &wgpu::Texture,
+        queue: &Queue,
+    ) -> SimulationResult<()> {
+        let data = self.buffer(name)?;
+        let bytes_per_row = texture_bytes_per_row(texture)?;
+        let expected = bytes_per_row as usize * texture.height() as usize;
+        if data.len() != expected {
+            return Err(SimulationError::InvalidParameter(format!(
+                "Snapshot texture '{}' holds {} bytes but the simulation expects {}",
+                name,
+                data.len(),
+                expected
+            )));
+        }
+
+        queue.write_texture(
+            texture.as_image_copy(),
+            data,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(bytes_per_row),
+                rows_per_image: Some(texture.height()),
+            },
+            texture.size(),
+        );
+        Ok(())
+    }
+
+    /// Fail unless the snapshot was taken from a simulation of `simulation_type`
+    pub fn expect_type(&self, simulation_type: &str) -> SimulationResult<()> {
+        if self.simulation_type != simulation_type {
+            return Err(SimulationError::InvalidParameter(format!(
+                "Snapshot is of a {} simulation, not {}",
+                self.simulation_type, simulation_type
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> AppResult<Vec<u8>> {
+        let header = SnapshotHeader {
+            simulation_type: self.simulation_type.clone(),
+            settings: self.settings.clone(),
+            state: self.state.clone(),
+            lut_name: self.lut_name.clone(),
+            lut_reversed: self.lut_reversed,
+            has_lut: self.lut.is_some(),
+            camera: self.camera,
+            buffers: self
+                .buffers
+                .iter()
+                .map(|buffer| BufferHeader {
+                    name: buffer.name.clone(),
+                    size: buffer.data.len() as u64,
+                })
+                .collect(),
+        };
+        let header = serde_json::to_vec(&header)?;
+
+        let payload_size: usize = self.buffers.iter().map(|buffer| buffer.data.len()).sum();
+        let mut bytes = Vec::with_capacity(16 + header.len() + LUT_BYTES + payload_size);
+        bytes.extend_from_slice(SNAPSHOT_MAGIC);
+        bytes.extend_from_slice(&SNAPSHOT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(header.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&header);
+        if let Some(lut) = &self.lut {
+            bytes.extend_from_slice(&lut.red);
+            bytes.extend_from_slice(&lut.green);
+            bytes.extend_from_slice(&lut.blue);
+        }
+        for buffer in &self.buffers {
+            bytes.extend_from_slice(&buffer.data);
+        }
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> AppResult<Self> {
+        let mut reader = ByteReader { bytes, offset: 0 };
+
+        if reader.take(SNAPSHOT_MAGIC.len())? != SNAPSHOT_MAGIC {
+            return Err(invalid_file("not a Vizza snapshot"));
+        }
+        let version = reader.read_u32()?;
+        if version != SNAPSHOT_VERSION {
+            return Err(invalid_file(&format!(
+                "unsupported snapshot version {}",
+                version
+            )));
+        }
+        let header_len = reader.read_u32()? as usize;
+        let header: SnapshotHeader = serde_json::from_slice(reader.take(header_len)?)?;
+
+        let lut = if header.has_lut {
+            let data = reader.take(LUT_BYTES)?;
+            let mut lut = LutData {
+                name: header.lut_name.clone(),
+                red: [0; 256],
+                green: [0; 256],
+                blue: [0; 256],
+            };
+            lut.red.copy_from_slice(&data[..256]);
+            lut.green.copy_from_slice(&data[256..512]);
+            lut.blue.copy_from_slice(&data[512..]);
+            Some(lut)
+        } else {
+            None
+        };
+
+        let buffers = header
+            .buffers
+            .into_iter()
+            .map(|buffer| {
+                Ok(SnapshotBuffer {
+                    data: reader.take(buffer.size as usize)?.to_vec(),
+                    name: buffer.name,
+                })
+            })
+            .collect::<AppResult<Vec<_>>>()?;
+
+        if reader.offset != bytes.len() {
+            return Err(invalid_file("unexpected data after the last buffer"));
+        }
+
+        Ok(Self {
+            simulation_type: header.simulation_type,
+            settings: header.settings,
+            state: header.state,
+            lut_name: header.lut_name,
+            lut_reversed: header.lut_reversed,
+            lut,
+            camera: header.camera,
+            buffers,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> AppResult<()> {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, self.to_bytes()?)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> AppResult<Self> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+}
+
+fn invalid_file(reason: &str) -> crate::error::AppError {
+    SimulationError::InvalidParameter(format!("Invalid snapshot file: {}", reason)).into()
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, len: usize) -> AppResult<&'a [u8]> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| invalid_file("file is truncated"))?;
+        let slice = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(slice)
+    }
+
+    fn read_u32(&mut self) -> AppResult<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
+
+/// Copy the whole of `buffer` back to the CPU. The buffer needs `COPY_SRC` usage.
+pub fn read_buffer(
+    device: &Device,
+    queue: &Queue,
+    buffer: &wgpu::Buffer,
+) -> SimulationResult<Vec<u8>> {
+    let staging_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Snapshot Readback Buffer"),
+        size: buffer.size(),
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Snapshot Readback Encoder"),
+    });
+    encoder.copy_buffer_to_buffer(buffer, 0, &staging_buffer, 0, buffer.size());
+    queue.submit(std::iter::once(encoder.finish()));
+
+    map_staging_buffer(device, &staging_buffer)?;
+    let data = staging_buffer.slice(..).get_mapped_range().to_vec();
+    staging_buffer.unmap();
+    Ok(data)
+}
+
+/// Copy a 2D texture back to the CPU as tightly packed rows. The texture needs
+/// `COPY_SRC` usage.
+pub fn read_texture(
+    device: &Device,
+    queue: &Queue,
+    texture: &wgpu::Texture,
+) -> SimulationResult<Vec<u8>> {
+    let unpadded_bytes_per_row = texture_bytes_per_row(texture)?;
+    let padded_bytes_per_row = unpadded_bytes_per_row.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+        * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+
+    let staging_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Snapshot Texture Readback Buffer"),
+        size: padded_bytes_per_row as u64 * texture.height() as u64,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Snapshot Texture Readback Encoder"),
+    });
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::TexelCopyBufferInfo {
+            buffer: &staging_buffer,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_bytes_per_row),
+                rows_per_image: Some(texture.height()),
+            },
+        },
+        texture.size(),
+    );
+    queue.submit(std::iter::once(encoder.finish()));
+
+    map_staging_buffer(device, &staging_buffer)?;
+    let mut data = Vec::with_capacity(unpadded_bytes_per_row as usize * texture.height() as usize);
+    {
+        let mapped = staging_buffer.slice(..).get_mapped_range();
+        for row in mapped.chunks(padded_bytes_per_row as usize) {
+            data.extend_from_slice(&row[..unpadded_bytes_per_row as usize]);
+        }
+    }
+    staging_buffer.unmap();
+    Ok(data)
+}
+
+fn texture_bytes_per_row(texture: &wgpu::Texture) -> SimulationResult<u32> {
+    texture
+        .format()
+        .block_copy_size(None)
+        .map(|block_size| block_size * texture.width())
+        .ok_or_else(|| {
+            SimulationError::InvalidParameter(format!(
+                "Cannot snapshot textures of format {:?}",
+                texture.format()
+            ))
+        })
+}
+
+fn map_staging_buffer(device: &Device, staging_buffer: &wgpu::Buffer) -> SimulationResult<()> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    staging_buffer
+        .slice(..)
+        .map_async(wgpu::MapMode::Read, move |v| {
+            let _ = sender.send(v);
+        });
+    device.poll(wgpu::Maintain::Wait);
+    receiver
+        .recv()
+        .map_err(|e| SimulationError::Gpu(Box::new(e)))?
+        .map_err(|e| SimulationError::Gpu(Box::new(e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> SimulationSnapshot {
+        let mut snapshot =
+            SimulationSnapshot::new("gray_scott", serde_json::json!({ "feed_rate": 0.055 }))
+                .with_lut("MATPLOTLIB_viridis", true)
+                .with_state(serde_json::json!({ "current_buffer": 1 }));
+        snapshot.camera = Some(CameraSnapshot {
+            position: [0.5, -0.25],
+            zoom: 2.0,
+            target_position: [0.75, -0.25],
+            target_zoom: 2.5,
+        });
+        snapshot.lut = Some(LutData {
+            name: "MATPLOTLIB_viridis".to_string(),
+            red: [1; 256],
+            green: [2; 256],
+            blue: [3; 256],
+        });
+        snapshot.buffers.push(SnapshotBuffer {
+            name: "uvs".to_string(),
+            data: (0..=255).collect(),
+        });
+        snapshot.buffers.push(SnapshotBuffer {
+            name: "empty".to_string(),
+            data: Vec::new(),
+        });
+        snapshot
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let snapshot = snapshot();
+        let bytes = snapshot.to_bytes().unwrap();
+        let restored = SimulationSnapshot::from_bytes(&bytes).unwrap();
+
+        assert_eq!(restored.simulation_type, snapshot.simulation_type);
+        assert_eq!(restored.settings, snapshot.settings);
+        assert_eq!(restored.state, snapshot.state);
+        assert_eq!(restored.camera, snapshot.camera);
+        assert!(restored.lut_reversed);
+        assert_eq!(restored.lut.as_ref().unwrap().blue, [3; 256]);
+        assert_eq!(restored.buffers, snapshot.buffers);
+        assert_eq!(restored.buffer("uvs").unwrap().len(), 256);
+        assert!(restored.buffer("missing").is_err());
+    }
+
+    #[test]
+    fn test_snapshot_rejects_damaged_files() {
+        let bytes = snapshot().to_bytes().unwrap();
+
+        assert!(SimulationSnapshot::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(SimulationSnapshot::from_bytes(b"not a snapshot").is_err());
+
+        let mut wrong_version = bytes.clone();
+        wrong_version[8] = 99;
+        assert!(SimulationSnapshot::from_bytes(&wrong_version).is_err());
+
+        let mut trailing = bytes;
+        trailing.push(0);
+        assert!(SimulationSnapshot::from_bytes(&trailing).is_err());
+    }
+}
@@ -1,5 +1,4 @@
 pub mod buffer_pool;
-pub mod commands;
 pub mod render;
 pub mod settings;
 pub mod shaders;
@@ -11,7 +10,12 @@ mod tests;
 
 pub use simulation::SlimeMoldModel;
 
-use crate::simulation::preset_manager::{Preset, SlimeMoldPresetManager};
+use crate::error::SimulationResult;
+use crate::simulation::preset_manager::{AnyPresetManager, Preset, SlimeMoldPresetManager};
+use crate::simulations::registry::{
+    SimulationCapabilities, SimulationContext, SimulationDescriptor,
+};
+use crate::simulations::traits::SimulationType;
 
 /// Initialize slime mold presets with built-in configurations
 pub fn init_presets(preset_manager: &mut SlimeMoldPresetManager) {
@@ -194,3 +198,47 @@ pub fn init_presets(preset_manager: &mut SlimeMoldPresetManager) {
     let preset_count = preset_manager.get_preset_names().len();
     tracing::info!("Initialized {} slime mold presets", preset_count);
 }
+
+/// Registry entry for the slime mold simulation
+pub const DESCRIPTOR: SimulationDescriptor = SimulationDescriptor {
+    id: "slime_mold",
+    display_name: "Slime Mold",
+    description: "Agent collaboration simulation",
+    hidden: false,
+    capabilities: SimulationCapabilities {
+        camera: true,
+        luts: true,
+        cursor: true,
+        deterministic: false,
+    },
+    create,
+    presets: Some(preset_manager),
+    initial_preset: None,
+    settings_schema: Some(settings::Settings::schema),
+    runtime_settings: &[
+        "position_generator",
+        "trailMapFiltering",
+        "cursor_size",
+        "cursor_strength",
+    ],
+};
+
+fn create(context: &SimulationContext) -> SimulationResult<SimulationType> {
+    let simulation = SlimeMoldModel::new(
+        context.device,
+        context.queue,
+        context.surface_config,
+        context.adapter_info,
+        10_000_000,
+        settings::Settings::default(),
+        context.render_settings,
+        context.lut_manager,
+    )?;
+    Ok(SimulationType::SlimeMold(Box::new(simulation)))
+}
+
+fn preset_manager() -> Box<dyn AnyPresetManager> {
+    let mut preset_manager = SlimeMoldPresetManager::new(DESCRIPTOR.id.to_string());
+    init_presets(&mut preset_manager);
+    Box::new(preset_manager)
+}
@@ -1,3 +1,4 @@
+use crate::simulations::shared::SettingField;
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 use std::ops::Range;
@@ -203,15 +204,15 @@ impl Settings {
     /// Randomize all settings within reasonable bounds
     pub fn randomize(&mut self) {
         use rand::Rng;
-        let mut rng = rand::rng();
+        let mut rng = crate::simulations::shared::random::rng();
 
-        self.agent_speed_min = rand::random::<f32>() * 500.0;
+        self.agent_speed_min = rng.random::<f32>() * 500.0;
         self.agent_speed_max =
-            self.agent_speed_min + rand::random::<f32>() * (500.0 - self.agent_speed_min);
-        self.agent_turn_rate = (rand::random::<f32>() * 360.0) * std::f32::consts::PI / 180.0; // Convert degrees to radians
-        self.agent_jitter = rand::random::<f32>();
-        self.agent_sensor_angle = (rand::random::<f32>() * 180.0) * std::f32::consts::PI / 180.0; // Convert degrees to radians
-        self.agent_sensor_distance = rand::random::<f32>() * 500.0;
+            self.agent_speed_min + rng.random::<f32>() * (500.0 - self.agent_speed_min);
+        self.agent_turn_rate = (rng.random::<f32>() * 360.0) * std::f32::consts::PI / 180.0; // Convert degrees to radians
+        self.agent_jitter = rng.random::<f32>();
+        self.agent_sensor_angle = (rng.random::<f32>() * 180.0) * std::f32::consts::PI / 180.0; // Convert degrees to radians
+        self.agent_sensor_distance = rng.random::<f32>() * 500.0;
         self.pheromone_decay_rate = 100.0;
         self.pheromone_deposition_rate = 100.0;
         self.pheromone_diffusion_rate = 100.0;
@@ -225,12 +226,88 @@ impl Settings {
         self.gradient_angle = 0.0;
 
         // Randomize starting direction range
-        let start = rand::random::<f32>() * 360.0;
-        let end = start + rand::random::<f32>() * (360.0 - start);
+        let start = rng.random::<f32>() * 360.0;
+        let end = start + rng.random::<f32>() * (360.0 - start);
         self.agent_possible_starting_headings = start..end;
 
         self.diffusion_frequency = 1;
         self.decay_frequency = 1;
         self.random_seed = rng.random();
     }
+    /// Fields of these settings with the ranges offered in the UI
+    pub fn schema() -> Vec<SettingField> {
+        use std::f64::consts::{PI, TAU};
+
+        vec![
+            SettingField::float("agent_jitter", 0.0, 5.0, 0.01)
+                .group("Agents")
+                .description("Random wobble added to each agent's heading"),
+            SettingField::float_range("agent_possible_starting_headings", 0.0, 360.0, 1.0)
+                .group("Agents")
+                .description("Headings in degrees that respawned agents start with")
+                .requires_reset(),
+            SettingField::float("agent_sensor_angle", 0.0, PI, 0.01)
+                .group("Agents")
+                .description("Angle in radians between the forward and side sensors"),
+            SettingField::float("agent_sensor_distance", 0.0, 500.0, 1.0)
+                .group("Agents")
+                .description("How far ahead agents sense pheromones, in pixels"),
+            SettingField::float("agent_speed_max", 0.0, 500.0, 10.0)
+                .group("Agents")
+                .description("Fastest speed an agent can be given"),
+            SettingField::float("agent_speed_min", 0.0, 500.0, 10.0)
+                .group("Agents")
+                .description("Slowest speed an agent can be given"),
+            SettingField::float("agent_turn_rate", 0.0, TAU, 0.01)
+                .group("Agents")
+                .description("Turning speed in radians per second"),
+            SettingField::float("pheromone_decay_rate", 0.0, 10000.0, 1.0)
+                .group("Pheromones")
+                .description("How quickly trails fade"),
+            SettingField::float("pheromone_deposition_rate", 0.0, 100.0, 1.0)
+                .group("Pheromones")
+                .description("How much pheromone agents leave behind"),
+            SettingField::float("pheromone_diffusion_rate", 0.0, 100.0, 1.0)
+                .group("Pheromones")
+                .description("How quickly trails spread out"),
+            SettingField::enumeration(
+                "gradient_type",
+                &[
+                    "disabled",
+                    "linear",
+                    "radial",
+                    "ellipse",
+                    "spiral",
+                    "checkerboard",
+                ],
+            )
+            .group("Gradient")
+            .description("Shape of the pheromone gradient painted under the trails"),
+            SettingField::float("gradient_strength", 0.0, 2.0, 0.01)
+                .group("Gradient")
+                .description("Intensity of the gradient"),
+            SettingField::float("gradient_center_x", 0.0, 1.0, 0.01)
+                .group("Gradient")
+                .description("Horizontal center of the gradient as a fraction of the width"),
+            SettingField::float("gradient_center_y", 0.0, 1.0, 0.01)
+                .group("Gradient")
+                .description("Vertical center of the gradient as a fraction of the height"),
+            SettingField::float("gradient_size", 0.1, 2.0, 0.01)
+                .group("Gradient")
+                .description("Extent of the gradient relative to the screen"),
+            SettingField::float("gradient_angle", 0.0, 360.0, 1.0)
+                .group("Gradient")
+                .description("Rotation of the gradient in degrees"),
+            SettingField::integer("diffusion_frequency", 1, 60)
+                .group("Pheromones")
+                .description("Diffuse the trail map every this many frames"),
+            SettingField::integer("decay_frequency", 1, 60)
+                .group("Pheromones")
+                .description("Decay the trail map every this many frames"),
+            SettingField::seed("random_seed")
+                .group("Agents")
+                .description("Seed for agent spawn positions and headings")
+                .requires_reset(),
+        ]
+    }
 }
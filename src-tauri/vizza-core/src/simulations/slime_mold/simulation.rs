@@ -1,6 +1,7 @@
-use crate::commands::app_settings::AppSettings;
 use crate::error::{SimulationError, SimulationResult};
+use crate::settings::RenderSettings;
 use bytemuck::{Pod, Zeroable};
+use rand::Rng;
 use serde_json::Value;
 use std::sync::Arc;
 use wgpu::util::DeviceExt;
@@ -11,7 +12,7 @@ use super::render::{bind_group_manager::BindGroupManager, pipeline_manager::Pipe
 use super::settings::Settings;
 use super::workgroup_optimizer::WorkgroupConfig;
 use crate::simulations::shared::post_processing::{PostProcessingResources, PostProcessingState};
-use crate::simulations::shared::{LutData, LutManager, camera::Camera};
+use crate::simulations::shared::{LutData, LutManager, SimulationSnapshot, camera::Camera, random};
 
 #[repr(C)]
 #[derive(Debug, Copy, Clone, Pod, Zeroable)]
@@ -173,7 +174,7 @@ pub struct SlimeMoldModel {
     pub lut_manager: Arc<LutManager>,
     pub post_processing_state: PostProcessingState,
     pub post_processing_resources: PostProcessingResources,
-    pub app_settings: AppSettings,
+    pub render_settings: RenderSettings,
 }
 
 impl SlimeMoldModel {
@@ -198,7 +199,7 @@ impl SlimeMoldModel {
         adapter_info: &wgpu::AdapterInfo,
         agent_count: usize,
         settings: Settings,
-        app_settings: &AppSettings,
+        render_settings: &RenderSettings,
         lut_manager: &LutManager,
     ) -> SimulationResult<Self> {
         let physical_width = surface_config.width;
@@ -230,6 +231,22 @@ impl SlimeMoldModel {
             (physical_width, physical_height)
         };
 
+        // Software adapters have much smaller binding limits, so cap the agent
+        // count to what fits in a single storage binding
+        let agent_size_bytes = (4 * std::mem::size_of::<f32>()) as u64;
+        let max_agent_count = (max_storage_buffer_size / agent_size_bytes) as usize;
+        let agent_count = if agent_count > max_agent_count {
+            tracing::warn!(
+                "Agent buffer for {} agents exceeds GPU limit {} bytes. Reducing to {} agents",
+                agent_count,
+                max_storage_buffer_size,
+                max_agent_count
+            );
+            max_agent_count
+        } else {
+            agent_count
+        };
+
         // Create simulation-specific buffers
         let agent_buffer = create_agent_buffer(
             device,
@@ -254,8 +271,9 @@ impl SlimeMoldModel {
         {
             let mut view = trail_map_buffer.slice(..).get_mapped_range_mut();
             let view_slice = bytemuck::cast_slice_mut::<u8, f32>(&mut view);
+            let mut rng = random::rng();
             for cell in view_slice.iter_mut() {
-                *cell = rand::random::<f32>() * 0.1; // Small initial values
+                *cell = rng.random::<f32>() * 0.1; // Small initial values
             }
         }
         trail_map_buffer.unmap();
@@ -323,9 +341,9 @@ impl SlimeMoldModel {
             address_mode_u: wgpu::AddressMode::ClampToEdge,
             address_mode_v: wgpu::AddressMode::ClampToEdge,
             address_mode_w: wgpu::AddressMode::ClampToEdge,
-            mag_filter: app_settings.texture_filtering.into(),
-            min_filter: app_settings.texture_filtering.into(),
-            mipmap_filter: app_settings.texture_filtering.into(),
+            mag_filter: render_settings.texture_filtering.into(),
+            min_filter: render_settings.texture_filtering.into(),
+            mipmap_filter: render_settings.texture_filtering.into(),
             ..Default::default()
         });
 
@@ -514,7 +532,7 @@ impl SlimeMoldModel {
             lut_manager: Arc::new(lut_manager.clone()),
             post_processing_state,
             post_processing_resources,
-            app_settings: app_settings.clone(),
+            render_settings: render_settings.clone(),
         };
 
         if let Ok(mut lut_data) = lut_manager.get(&simulation.current_lut_name) {
@@ -1038,7 +1056,7 @@ impl SlimeMoldModel {
         queue: &Arc<Queue>,
     ) -> SimulationResult<()> {
         // Update the random seed to ensure different randomization
-        self.settings.random_seed = rand::random::<u32>();
+        self.settings.random_seed = random::rng().random::<u32>();
 
         // Update the sim size buffer with the new random seed
         let sim_size = SimSizeUniform::new(
@@ -1804,6 +1822,39 @@ impl crate::simulations::traits::Simulation for SlimeMoldModel {
         self.update_settings(new_settings, queue);
         Ok(())
     }
+
+    fn save_snapshot(
+        &self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<SimulationSnapshot> {
+        let mut snapshot = SimulationSnapshot::new("slime_mold", self.get_settings())
+            .with_lut(&self.current_lut_name, self.lut_reversed)
+            .with_camera(&self.camera)
+            .with_state(serde_json::json!({ "agent_count": self.agent_count }));
+        snapshot.capture_buffer("agents", &self.agent_buffer, device, queue)?;
+        snapshot.capture_buffer("trail_map", &self.trail_map_buffer, device, queue)?;
+        Ok(snapshot)
+    }
+
+    fn load_snapshot(
+        &mut self,
+        snapshot: &SimulationSnapshot,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()> {
+        snapshot.expect_type("slime_mold")?;
+        self.apply_settings(snapshot.settings.clone(), device, queue)?;
+        snapshot.restore_buffer("agents", &self.agent_buffer, queue)?;
+        snapshot.restore_buffer("trail_map", &self.trail_map_buffer, queue)?;
+
+        if let Some(camera) = &snapshot.camera {
+            camera.apply_to(&mut self.camera);
+        }
+        self.current_lut_name = snapshot.lut_name.clone();
+        self.lut_reversed = snapshot.lut_reversed;
+        Ok(())
+    }
 }
 
 // Helper functions (moved from gpu_state.rs)
@@ -2151,9 +2202,9 @@ impl SlimeMoldModel {
             address_mode_u: wgpu::AddressMode::ClampToEdge,
             address_mode_v: wgpu::AddressMode::ClampToEdge,
             address_mode_w: wgpu::AddressMode::ClampToEdge,
-            mag_filter: self.app_settings.texture_filtering.into(),
-            min_filter: self.app_settings.texture_filtering.into(),
-            mipmap_filter: self.app_settings.texture_filtering.into(),
+            mag_filter: self.render_settings.texture_filtering.into(),
+            min_filter: self.render_settings.texture_filtering.into(),
+            mipmap_filter: self.render_settings.texture_filtering.into(),
             ..Default::default()
         });
     }
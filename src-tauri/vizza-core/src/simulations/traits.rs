@@ -0,0 +1,1221 @@
+//! # Simulation Traits Module
+//!
+//! Defines the core interfaces that unify all simulations in the Vizza application.
+//! This module establishes the contract that ensures consistent behavior across
+//! different simulation types while preserving their unique characteristics.
+//!
+//! ## Design Philosophy
+//!
+//! The trait system is designed to provide a unified interface for simulation
+//! management while maintaining the flexibility needed for diverse simulation
+//! types. This approach enables polymorphic handling of simulations while
+//! ensuring each can implement its unique behavior and requirements.
+//!
+//! ## Core Concepts
+//!
+//! The trait system emphasizes the distinction between configuration and
+//! runtime state, enabling proper preset management and state restoration.
+//! It also provides comprehensive user interaction capabilities that work
+//! consistently across all simulation types.
+
+use crate::error::{SimulationError, SimulationResult};
+use crate::simulations::registry::{self, SimulationContext, SimulationDescriptor};
+use crate::simulations::shared::camera::Camera;
+use crate::simulations::shared::{LutData, LutManager, SimulationSnapshot, settings_schema};
+use serde_json::Value;
+use std::sync::Arc;
+use wgpu::{Device, Queue, SurfaceConfiguration, TextureView};
+
+/// Common interface for all simulation types
+///
+/// This trait defines the contract that all simulations must implement.
+/// It provides a unified way to interact with different simulation types
+/// while maintaining clear separation between settings (presettable) and state (runtime).
+pub trait Simulation {
+    /// Render a single frame of the simulation
+    fn render_frame(
+        &mut self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+        surface_view: &TextureView,
+        delta_time: f32,
+    ) -> SimulationResult<()>;
+
+    /// Render a static frame without updating simulation state (for paused mode)
+    fn render_frame_static(
+        &mut self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+        surface_view: &TextureView,
+    ) -> SimulationResult<()>;
+
+    /// Handle window resize events
+    fn resize(
+        &mut self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+        new_config: &SurfaceConfiguration,
+    ) -> SimulationResult<()>;
+
+    /// Update a specific setting by name
+    ///
+    /// This method should only modify user-configurable settings that can be saved in presets.
+    /// Runtime state should not be modified through this method.
+    fn update_setting(
+        &mut self,
+        setting_name: &str,
+        value: Value,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()>;
+
+    /// Get the current settings as a serializable value
+    ///
+    /// This should return only user-configurable settings that can be saved in presets.
+    /// Runtime state should not be included in the returned value.
+    fn get_settings(&self) -> Value;
+
+    /// Get the current runtime state as a serializable value
+    ///
+    /// This should return runtime state that is not saved in presets.
+    /// Examples: current agent positions, simulation time, etc.
+    fn get_state(&self) -> Value;
+
+    /// Handle mouse interaction for cursor-based particle attraction/repulsion
+    fn handle_mouse_interaction(
+        &mut self,
+        world_x: f32,
+        world_y: f32,
+        mouse_button: u32, // 0 = left, 1 = middle, 2 = right
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()>;
+
+    /// Handle mouse release events
+    fn handle_mouse_release(
+        &mut self,
+        mouse_button: u32, // 0 = left, 1 = middle, 2 = right
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()>;
+
+    /// Pan the camera by the given delta
+    fn pan_camera(&mut self, delta_x: f32, delta_y: f32);
+
+    /// Zoom the camera by the given delta
+    fn zoom_camera(&mut self, delta: f32);
+
+    /// Zoom the camera to a specific cursor position
+    fn zoom_camera_to_cursor(&mut self, delta: f32, cursor_x: f32, cursor_y: f32);
+
+    /// Reset the camera to default position and zoom
+    fn reset_camera(&mut self);
+
+    /// Get the current camera state as a serializable value
+    fn get_camera_state(&self) -> Value;
+
+    /// Save the current settings as a preset
+    ///
+    /// This should only save settings, not runtime state.
+    fn save_preset(&self, _preset_name: &str) -> SimulationResult<()>;
+
+    /// Load settings from a preset and reset runtime state
+    ///
+    /// This should load settings and reset any runtime state to default values.
+    fn load_preset(&mut self, _preset_name: &str, _queue: &Arc<Queue>) -> SimulationResult<()>;
+
+    /// Update the simulation settings directly
+    ///
+    /// This should apply new settings to the simulation without resetting runtime state.
+    fn apply_settings(
+        &mut self,
+        settings: serde_json::Value,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()>;
+
+    /// Reset the simulation's runtime state
+    ///
+    /// This should reset runtime state (like agent positions, trail maps) but preserve settings.
+    fn reset_runtime_state(
+        &mut self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()>;
+
+    /// Toggle GUI visibility
+    fn toggle_gui(&mut self) -> bool;
+
+    /// Check if GUI is visible
+    fn is_gui_visible(&self) -> bool;
+
+    /// Randomize the current settings
+    fn randomize_settings(
+        &mut self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()>;
+
+    /// Capture the full simulation state
+    ///
+    /// Unlike presets, which only hold settings, a snapshot includes the GPU
+    /// buffers holding agents, particles and fields, along with the camera and LUT.
+    fn save_snapshot(
+        &self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<SimulationSnapshot>;
+
+    /// Restore a snapshot taken from a simulation of the same type and size
+    fn load_snapshot(
+        &mut self,
+        snapshot: &SimulationSnapshot,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()>;
+}
+
+/// Enum wrapper for all simulation types
+///
+/// This provides a type-safe way to handle different simulation types
+/// without using trait objects (Box<dyn Simulation>).
+#[derive(Debug)]
+pub enum SimulationType {
+    SlimeMold(Box<crate::simulations::slime_mold::SlimeMoldModel>),
+    GrayScott(Box<crate::simulations::gray_scott::GrayScottModel>),
+    ParticleLife(Box<crate::simulations::particle_life::ParticleLifeModel>),
+    Flow(Box<crate::simulations::flow::simulation::FlowModel>),
+    Pellets(Box<crate::simulations::pellets::PelletsModel>),
+    Ecosystem(Box<crate::simulations::ecosystem::EcosystemModel>),
+    MainMenu(Box<crate::simulations::main_menu::MainMenuModel>),
+    Gradient(Box<crate::simulations::gradient::GradientSimulation>),
+}
+
+impl SimulationType {
+    /// Create a new simulation of the specified type
+    pub async fn new(
+        simulation_type: &str,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+        surface_config: &SurfaceConfiguration,
+        adapter_info: &wgpu::AdapterInfo,
+        lut_manager: &crate::simulations::shared::LutManager,
+        render_settings: &crate::settings::RenderSettings,
+    ) -> SimulationResult<Self> {
+        let descriptor = registry::get(simulation_type)?;
+        (descriptor.create)(&SimulationContext {
+            device,
+            queue,
+            surface_config,
+            adapter_info,
+            lut_manager,
+            render_settings,
+        })
+    }
+
+    /// Identifier of the simulation type, as accepted by [`SimulationType::new`]
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            SimulationType::SlimeMold(_) => "slime_mold",
+            SimulationType::GrayScott(_) => "gray_scott",
+            SimulationType::ParticleLife(_) => "particle_life",
+            SimulationType::Pellets(_) => "pellets",
+            SimulationType::Flow(_) => "flow",
+            SimulationType::Ecosystem(_) => "ecosystem",
+            SimulationType::MainMenu(_) => "main_menu",
+            SimulationType::Gradient(_) => "gradient",
+        }
+    }
+
+    /// Registry entry describing this simulation's type
+    pub fn descriptor(&self) -> &'static SimulationDescriptor {
+        registry::get(self.type_name()).expect("Every simulation type is registered")
+    }
+
+    /// Name of the active LUT and whether it is shown reversed
+    pub fn current_lut(&self) -> Option<(&str, bool)> {
+        match self {
+            SimulationType::SlimeMold(simulation) => {
+                Some((&simulation.current_lut_name, simulation.lut_reversed))
+            }
+            SimulationType::GrayScott(simulation) => {
+                Some((&simulation.current_lut_name, simulation.lut_reversed))
+            }
+            SimulationType::ParticleLife(simulation) => Some((
+                &simulation.state.current_lut_name,
+                simulation.state.lut_reversed,
+            )),
+            SimulationType::Pellets(simulation) => Some((
+                &simulation.state.current_lut_name,
+                simulation.state.lut_reversed,
+            )),
+            SimulationType::Flow(simulation) => {
+                Some((&simulation.current_lut, simulation.lut_reversed))
+            }
+            SimulationType::Ecosystem(simulation) => Some((
+                &simulation.state.current_lut_name,
+                simulation.state.lut_reversed,
+            )),
+            SimulationType::MainMenu(_) | SimulationType::Gradient(_) => None,
+        }
+    }
+
+    /// Camera of the simulations that can be panned and zoomed
+    pub fn camera(&self) -> Option<&Camera> {
+        match self {
+            SimulationType::SlimeMold(simulation) => Some(&simulation.camera),
+            SimulationType::GrayScott(simulation) => Some(&simulation.renderer.camera),
+            SimulationType::ParticleLife(simulation) => Some(&simulation.camera),
+            SimulationType::Pellets(simulation) => Some(&simulation.camera),
+            SimulationType::Flow(simulation) => Some(&simulation.camera),
+            SimulationType::Ecosystem(simulation) => Some(&simulation.camera),
+            SimulationType::MainMenu(_) | SimulationType::Gradient(_) => None,
+        }
+    }
+
+    pub fn camera_mut(&mut self) -> Option<&mut Camera> {
+        match self {
+            SimulationType::SlimeMold(simulation) => Some(&mut simulation.camera),
+            SimulationType::GrayScott(simulation) => Some(&mut simulation.renderer.camera),
+            SimulationType::ParticleLife(simulation) => Some(&mut simulation.camera),
+            SimulationType::Pellets(simulation) => Some(&mut simulation.camera),
+            SimulationType::Flow(simulation) => Some(&mut simulation.camera),
+            SimulationType::Ecosystem(simulation) => Some(&mut simulation.camera),
+            SimulationType::MainMenu(_) | SimulationType::Gradient(_) => None,
+        }
+    }
+
+    /// Load the named LUT and apply it, honouring the simulation's reversed flag
+    pub fn apply_lut(
+        &mut self,
+        lut_manager: &LutManager,
+        lut_name: &str,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()> {
+        match self {
+            SimulationType::SlimeMold(simulation) => {
+                // For slime mold, load the LUT data and apply it directly
+                let mut lut_data = lut_manager.get(lut_name).map_err(|e| {
+                    SimulationError::from(format!("Failed to load LUT '{}': {}", lut_name, e))
+                })?;
+
+                if simulation.lut_reversed {
+                    lut_data.reverse();
+                }
+
+                simulation.update_lut(&lut_data, queue);
+                simulation.current_lut_name = lut_name.to_string();
+
+                tracing::info!("LUT '{}' applied to slime mold simulation", lut_name);
+            }
+            SimulationType::GrayScott(simulation) => {
+                // For Gray-Scott, load the LUT data and apply it to the renderer
+                let mut lut_data = lut_manager.get(lut_name).map_err(|e| {
+                    SimulationError::from(format!("Failed to load LUT '{}': {}", lut_name, e))
+                })?;
+
+                if simulation.lut_reversed {
+                    lut_data.reverse();
+                }
+
+                simulation.renderer.update_lut(&lut_data, queue);
+                simulation.current_lut_name = lut_name.to_string();
+
+                tracing::info!("LUT '{}' applied to Gray-Scott simulation", lut_name);
+            }
+            SimulationType::ParticleLife(simulation) => {
+                // For particle life, use the existing update_setting method
+                simulation.update_setting(
+                    "lut_name",
+                    serde_json::json!(lut_name),
+                    device,
+                    queue,
+                )?;
+            }
+            SimulationType::Flow(simulation) => {
+                // For Flow, use the existing update_setting method
+                simulation.update_setting(
+                    "currentLut",
+                    serde_json::json!(lut_name),
+                    device,
+                    queue,
+                )?;
+                tracing::info!("LUT '{}' applied to Flow simulation", lut_name);
+            }
+            SimulationType::Pellets(simulation) => {
+                // For Pellets, use the existing update_setting method
+                simulation.update_setting(
+                    "currentLut",
+                    serde_json::json!(lut_name),
+                    device,
+                    queue,
+                )?;
+                tracing::info!("LUT '{}' applied to Pellets simulation", lut_name);
+            }
+            SimulationType::Ecosystem(simulation) => {
+                simulation.update_setting(
+                    "currentLut",
+                    serde_json::json!(lut_name),
+                    device,
+                    queue,
+                )?;
+                tracing::info!("LUT '{}' applied to Ecosystem simulation", lut_name);
+            }
+            SimulationType::MainMenu(_) => {
+                // Main menu doesn't support LUT changes
+                tracing::warn!("LUT changes not supported for main menu simulation");
+            }
+            SimulationType::Gradient(simulation) => {
+                // For gradient simulation, load the LUT data and apply it directly
+                let lut_data = lut_manager.get(lut_name).map_err(|e| {
+                    SimulationError::from(format!("Failed to load LUT '{}': {}", lut_name, e))
+                })?;
+
+                simulation.update_lut(device, queue, &lut_data);
+
+                tracing::info!("LUT '{}' applied to gradient simulation", lut_name);
+            }
+        }
+        Ok(())
+    }
+
+    /// Toggle whether the active LUT is shown reversed
+    pub fn reverse_lut(
+        &mut self,
+        lut_manager: &LutManager,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()> {
+        match self {
+            SimulationType::SlimeMold(simulation) => {
+                // Toggle the reversed flag and reload the LUT
+                simulation.lut_reversed = !simulation.lut_reversed;
+                let mut lut_data = lut_manager.get(&simulation.current_lut_name).map_err(|e| {
+                    SimulationError::from(format!(
+                        "Failed to load LUT '{}': {}",
+                        simulation.current_lut_name, e
+                    ))
+                })?;
+
+                if simulation.lut_reversed {
+                    lut_data.reverse();
+                }
+
+                simulation.update_lut(&lut_data, queue);
+                tracing::info!("LUT reversed for slime mold simulation");
+            }
+            SimulationType::GrayScott(simulation) => {
+                // Toggle the reversed flag and reload the LUT
+                simulation.lut_reversed = !simulation.lut_reversed;
+                let mut lut_data = lut_manager.get(&simulation.current_lut_name).map_err(|e| {
+                    SimulationError::from(format!(
+                        "Failed to load LUT '{}': {}",
+                        simulation.current_lut_name, e
+                    ))
+                })?;
+
+                if simulation.lut_reversed {
+                    lut_data.reverse();
+                }
+
+                simulation.renderer.update_lut(&lut_data, queue);
+                tracing::info!("LUT reversed for Gray-Scott simulation");
+            }
+            SimulationType::ParticleLife(simulation) => {
+                // For particle life, we need to update the LUT with reversed flag
+                let current_reversed = simulation.state.lut_reversed;
+                let color_mode = simulation.state.color_mode;
+                let current_lut_name = simulation.state.current_lut_name.clone();
+                simulation.update_lut(
+                    device,
+                    queue,
+                    lut_manager,
+                    color_mode,
+                    Some(&current_lut_name),
+                    !current_reversed,
+                )?;
+            }
+            SimulationType::Flow(simulation) => {
+                // For Flow, use the built-in LUT reversal mechanism
+                let current_reversed = simulation.lut_reversed;
+                simulation.update_setting(
+                    "lutReversed",
+                    serde_json::json!(!current_reversed),
+                    device,
+                    queue,
+                )?;
+                tracing::info!("LUT reversed for Flow simulation");
+            }
+            SimulationType::Pellets(simulation) => {
+                // For Pellets, use the built-in LUT reversal mechanism
+                let current_reversed = simulation.state.lut_reversed;
+                simulation.update_setting(
+                    "lut_reversed",
+                    serde_json::json!(!current_reversed),
+                    device,
+                    queue,
+                )?;
+                tracing::info!("LUT reversed for Pellets simulation");
+            }
+            SimulationType::Ecosystem(simulation) => {
+                let current_reversed = simulation.state.lut_reversed;
+                simulation.update_setting(
+                    "lut_reversed",
+                    serde_json::json!(!current_reversed),
+                    device,
+                    queue,
+                )?;
+                tracing::info!("LUT reversed for Ecosystem simulation");
+            }
+            SimulationType::MainMenu(_) | SimulationType::Gradient(_) => {
+                // These simulations don't support LUT reversal
+                tracing::warn!("LUT reversal not supported for this simulation type");
+            }
+        }
+        Ok(())
+    }
+
+    /// Show a LUT that only exists in `lut_manager`'s temporary slot
+    pub fn apply_custom_lut(
+        &mut self,
+        lut_manager: &LutManager,
+        lut_data: &LutData,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()> {
+        match self {
+            SimulationType::SlimeMold(simulation) => {
+                simulation.update_lut(lut_data, queue);
+                tracing::info!("Custom LUT applied to slime mold simulation");
+            }
+            SimulationType::GrayScott(simulation) => {
+                simulation.renderer.update_lut(lut_data, queue);
+                tracing::info!("Custom LUT applied to Gray-Scott simulation");
+            }
+            SimulationType::ParticleLife(simulation) => {
+                let color_mode = simulation.state.color_mode;
+                let lut_reversed = simulation.state.lut_reversed;
+
+                simulation.update_lut(
+                    device,
+                    queue,
+                    lut_manager,
+                    color_mode,
+                    Some("temp_lut"),
+                    lut_reversed,
+                )?;
+                tracing::info!("Custom LUT applied to particle life simulation");
+            }
+            SimulationType::Flow(simulation) => {
+                // Direct-write the temporary LUT to the GPU buffer for immediate preview
+                if let Ok(lut) = lut_manager.get("temp_lut") {
+                    let data_u32 = lut.to_u32_buffer();
+                    queue.write_buffer(&simulation.lut_buffer, 0, bytemuck::cast_slice(&data_u32));
+                    tracing::info!("Custom LUT directly written to Flow LUT buffer");
+                } else {
+                    // Fallback to settings path if temp LUT missing
+                    simulation.update_setting(
+                        "currentLut",
+                        serde_json::json!("temp_lut"),
+                        device,
+                        queue,
+                    )?;
+                }
+            }
+            SimulationType::Pellets(simulation) => {
+                // Direct-write to the Pellets LUT buffer to avoid relying on the sim's internal LUT manager clone
+                if let Ok(lut) = lut_manager.get("temp_lut") {
+                    let data_u32 = lut.to_u32_buffer();
+                    queue.write_buffer(&simulation.lut_buffer, 0, bytemuck::cast_slice(&data_u32));
+                    tracing::info!("Custom LUT directly written to Pellets LUT buffer");
+                } else {
+                    // Fallback to sim path
+                    simulation.update_setting(
+                        "currentLut",
+                        serde_json::json!("temp_lut"),
+                        device,
+                        queue,
+                    )?;
+                }
+            }
+            SimulationType::Ecosystem(simulation) => {
+                if let Ok(lut) = lut_manager.get("temp_lut") {
+                    let data_u32 = lut.to_u32_buffer();
+                    queue.write_buffer(&simulation.lut_buffer, 0, bytemuck::cast_slice(&data_u32));
+                    tracing::info!("Custom LUT directly written to Ecosystem LUT buffer");
+                } else {
+                    // Fallback to sim path
+                    simulation.update_setting(
+                        "currentLut",
+                        serde_json::json!("temp_lut"),
+                        device,
+                        queue,
+                    )?;
+                }
+            }
+            SimulationType::MainMenu(_) => {
+                // Main menu doesn't support custom LUTs
+                tracing::warn!("Custom LUT not supported for main menu simulation");
+            }
+            SimulationType::Gradient(simulation) => {
+                simulation.update_lut(device, queue, lut_data);
+                tracing::info!("Custom LUT applied to gradient simulation");
+            }
+        }
+        Ok(())
+    }
+
+    pub fn reset_runtime_state(
+        &mut self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()> {
+        match self {
+            SimulationType::SlimeMold(simulation) => simulation.reset_runtime_state(device, queue),
+            SimulationType::GrayScott(simulation) => simulation.reset_runtime_state(device, queue),
+            SimulationType::ParticleLife(simulation) => {
+                simulation.reset_runtime_state(device, queue)
+            }
+            SimulationType::Flow(simulation) => simulation.reset_runtime_state(device, queue),
+            SimulationType::Pellets(simulation) => simulation.reset_runtime_state(device, queue),
+            SimulationType::Ecosystem(simulation) => simulation.reset_runtime_state(device, queue),
+            SimulationType::MainMenu(simulation) => simulation.reset_runtime_state(device, queue),
+            SimulationType::Gradient(simulation) => simulation.reset_runtime_state(device, queue),
+        }
+    }
+
+    /// Whether rendering into a target larger than the surface adds detail.
+    /// Slime mold, Gray-Scott and flow draw from a grid or trail texture sized
+    /// to the surface, as do Particle Life's traces, so a larger render of them
+    /// is only an upscale.
+    pub fn gains_resolution_when_scaled(&self) -> bool {
+        match self {
+            SimulationType::ParticleLife(simulation) => !simulation.state.traces_enabled,
+            SimulationType::Pellets(_)
+            | SimulationType::Ecosystem(_)
+            | SimulationType::MainMenu(_)
+            | SimulationType::Gradient(_) => true,
+            SimulationType::SlimeMold(_)
+            | SimulationType::GrayScott(_)
+            | SimulationType::Flow(_) => false,
+        }
+    }
+
+    /// Render the current frame without stepping into `view`, which is sized
+    /// by `target_config`. Simulations that gain resolution have their display
+    /// textures rebuilt at the target size for the render and restored to
+    /// `surface_config` afterwards; the rest are upscaled. Returns whether the
+    /// frame was rendered at the target resolution.
+    pub fn render_frame_static_scaled(
+        &mut self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+        view: &TextureView,
+        target_config: &SurfaceConfiguration,
+        surface_config: &SurfaceConfiguration,
+    ) -> SimulationResult<bool> {
+        if !self.gains_resolution_when_scaled() {
+            self.render_frame_static(device, queue, view)?;
+            return Ok(false);
+        }
+
+        let max_dimension = device.limits().max_texture_dimension_2d;
+        let (width, height) = (target_config.width, target_config.height);
+        let fits = match self {
+            // Procedural backgrounds draw straight into the view at any size
+            SimulationType::MainMenu(_) | SimulationType::Gradient(_) => {
+                self.render_frame_static(device, queue, view)?;
+                return Ok(true);
+            }
+            // Pellets draws at twice the surface size
+            SimulationType::Pellets(_) => width * 2 <= max_dimension && height * 2 <= max_dimension,
+            // Particle Life caps its display texture at 8192 pixels
+            SimulationType::ParticleLife(_) => width <= 8192 && height <= 8192,
+            _ => true,
+        };
+        if !fits {
+            self.render_frame_static(device, queue, view)?;
+            return Ok(false);
+        }
+
+        self.resize(device, queue, target_config)?;
+        let result = self.render_frame_static(device, queue, view);
+        self.resize(device, queue, surface_config)?;
+        result.map(|_| true)
+    }
+}
+
+impl Simulation for SimulationType {
+    fn render_frame(
+        &mut self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+        surface_view: &TextureView,
+        delta_time: f32,
+    ) -> SimulationResult<()> {
+        match self {
+            SimulationType::SlimeMold(simulation) => {
+                simulation.render_frame(device, queue, surface_view, delta_time)
+            }
+            SimulationType::GrayScott(simulation) => {
+                simulation.render_frame(device, queue, surface_view, delta_time)
+            }
+            SimulationType::ParticleLife(simulation) => {
+                simulation.render_frame(device, queue, surface_view, delta_time)
+            }
+            SimulationType::Flow(sim) => sim.render_frame(device, queue, surface_view, delta_time),
+            SimulationType::Pellets(simulation) => {
+                simulation.render_frame(device, queue, surface_view, delta_time)
+            }
+            SimulationType::Ecosystem(simulation) => {
+                simulation.render_frame(device, queue, surface_view, delta_time)
+            }
+            SimulationType::MainMenu(simulation) => {
+                simulation.render_frame(device, queue, surface_view, delta_time)
+            }
+            SimulationType::Gradient(simulation) => {
+                simulation.render_frame(device, queue, surface_view, delta_time)
+            }
+        }
+    }
+
+    fn render_frame_static(
+        &mut self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+        surface_view: &TextureView,
+    ) -> SimulationResult<()> {
+        match self {
+            SimulationType::SlimeMold(simulation) => {
+                simulation.render_frame_static(device, queue, surface_view)
+            }
+            SimulationType::GrayScott(simulation) => {
+                simulation.render_frame_static(device, queue, surface_view)
+            }
+            SimulationType::ParticleLife(simulation) => {
+                simulation.render_frame_static(device, queue, surface_view)
+            }
+            SimulationType::Flow(sim) => sim.render_frame_static(device, queue, surface_view),
+            SimulationType::Pellets(simulation) => {
+                simulation.render_frame_static(device, queue, surface_view)
+            }
+            SimulationType::Ecosystem(simulation) => {
+                simulation.render_frame_static(device, queue, surface_view)
+            }
+            SimulationType::MainMenu(simulation) => {
+                simulation.render_frame_static(device, queue, surface_view)
+            }
+            SimulationType::Gradient(simulation) => {
+                simulation.render_frame_static(device, queue, surface_view)
+            }
+        }
+    }
+
+    fn resize(
+        &mut self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+        new_config: &SurfaceConfiguration,
+    ) -> SimulationResult<()> {
+        match self {
+            SimulationType::SlimeMold(simulation) => simulation.resize(device, queue, new_config),
+            SimulationType::GrayScott(simulation) => simulation.resize(new_config),
+            SimulationType::ParticleLife(simulation) => {
+                simulation.resize(device, queue, new_config)
+            }
+            SimulationType::Flow(sim) => sim.resize(device, queue, new_config),
+            SimulationType::Pellets(simulation) => simulation.resize(device, queue, new_config),
+            SimulationType::Ecosystem(simulation) => simulation.resize(device, queue, new_config),
+            SimulationType::MainMenu(simulation) => simulation.resize(device, queue, new_config),
+            SimulationType::Gradient(simulation) => simulation.resize(device, queue, new_config),
+        }
+    }
+
+    fn update_setting(
+        &mut self,
+        setting_name: &str,
+        value: Value,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()> {
+        let descriptor = self.descriptor();
+        if let Some(schema) = descriptor.settings_schema {
+            settings_schema::validate_setting(
+                &schema(),
+                descriptor.runtime_settings,
+                setting_name,
+                &value,
+            )?;
+        }
+
+        match self {
+            SimulationType::SlimeMold(simulation) => {
+                simulation.update_setting(setting_name, value, device, queue)
+            }
+            SimulationType::GrayScott(simulation) => {
+                simulation.update_setting(setting_name, value, device, queue)
+            }
+            SimulationType::ParticleLife(simulation) => {
+                simulation.update_setting(setting_name, value, device, queue)
+            }
+            SimulationType::Flow(sim) => sim.update_setting(setting_name, value, device, queue),
+            SimulationType::Pellets(simulation) => {
+                simulation.update_setting(setting_name, value, device, queue)
+            }
+            SimulationType::Ecosystem(simulation) => {
+                simulation.update_setting(setting_name, value, device, queue)
+            }
+            SimulationType::MainMenu(simulation) => {
+                simulation.update_setting(setting_name, value, device, queue)
+            }
+            SimulationType::Gradient(simulation) => {
+                simulation.update_setting(setting_name, value, device, queue)
+            }
+        }
+    }
+
+    fn get_settings(&self) -> Value {
+        match self {
+            SimulationType::SlimeMold(simulation) => simulation.get_settings(),
+            SimulationType::GrayScott(simulation) => simulation.get_settings(),
+            SimulationType::ParticleLife(simulation) => simulation.get_settings(),
+            SimulationType::Flow(sim) => sim.get_settings(),
+            SimulationType::Pellets(simulation) => simulation.get_settings(),
+            SimulationType::Ecosystem(simulation) => simulation.get_settings(),
+            SimulationType::MainMenu(simulation) => simulation.get_settings(),
+            SimulationType::Gradient(simulation) => simulation.get_settings(),
+        }
+    }
+
+    fn get_state(&self) -> Value {
+        match self {
+            SimulationType::SlimeMold(simulation) => simulation.get_state(),
+            SimulationType::GrayScott(simulation) => simulation.get_state(),
+            SimulationType::ParticleLife(simulation) => simulation.get_state(),
+            SimulationType::Flow(sim) => sim.get_state(),
+            SimulationType::Pellets(simulation) => simulation.get_state(),
+            SimulationType::Ecosystem(simulation) => simulation.get_state(),
+            SimulationType::MainMenu(simulation) => simulation.get_state(),
+            SimulationType::Gradient(simulation) => simulation.get_state(),
+        }
+    }
+
+    fn handle_mouse_interaction(
+        &mut self,
+        world_x: f32,
+        world_y: f32,
+        mouse_button: u32, // 0 = left, 1 = middle, 2 = right
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()> {
+        match self {
+            SimulationType::SlimeMold(simulation) => {
+                simulation.handle_mouse_interaction(world_x, world_y, mouse_button, device, queue)
+            }
+            SimulationType::GrayScott(simulation) => {
+                simulation.handle_mouse_interaction(world_x, world_y, mouse_button, device, queue)
+            }
+            SimulationType::ParticleLife(simulation) => {
+                simulation.handle_mouse_interaction(world_x, world_y, mouse_button, device, queue)
+            }
+            SimulationType::Flow(sim) => {
+                sim.handle_mouse_interaction(world_x, world_y, mouse_button, device, queue)
+            }
+            SimulationType::Pellets(simulation) => {
+                simulation.handle_mouse_interaction(world_x, world_y, mouse_button, device, queue)
+            }
+            SimulationType::Ecosystem(simulation) => {
+                simulation.handle_mouse_interaction(world_x, world_y, mouse_button, device, queue)
+            }
+            SimulationType::MainMenu(simulation) => {
+                simulation.handle_mouse_interaction(world_x, world_y, mouse_button, device, queue)
+            }
+            SimulationType::Gradient(simulation) => {
+                simulation.handle_mouse_interaction(world_x, world_y, mouse_button, device, queue)
+            }
+        }
+    }
+
+    fn handle_mouse_release(
+        &mut self,
+        mouse_button: u32, // 0 = left, 1 = middle, 2 = right
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()> {
+        match self {
+            SimulationType::SlimeMold(simulation) => {
+                simulation.handle_mouse_release(mouse_button, queue)
+            }
+            SimulationType::GrayScott(simulation) => {
+                simulation.handle_mouse_release(mouse_button, queue)
+            }
+            SimulationType::ParticleLife(simulation) => {
+                simulation.handle_mouse_release(mouse_button, queue)
+            }
+            SimulationType::Flow(sim) => sim.handle_mouse_release(mouse_button, queue),
+            SimulationType::Pellets(simulation) => {
+                simulation.handle_mouse_release(mouse_button, queue)
+            }
+            SimulationType::Ecosystem(simulation) => {
+                simulation.handle_mouse_release(mouse_button, queue)
+            }
+            SimulationType::MainMenu(simulation) => {
+                simulation.handle_mouse_release(mouse_button, queue)
+            }
+            SimulationType::Gradient(simulation) => {
+                simulation.handle_mouse_release(mouse_button, queue)
+            }
+        }
+    }
+
+    fn pan_camera(&mut self, delta_x: f32, delta_y: f32) {
+        match self {
+            SimulationType::SlimeMold(simulation) => simulation.pan_camera(delta_x, delta_y),
+            SimulationType::GrayScott(simulation) => simulation.pan_camera(delta_x, delta_y),
+            SimulationType::ParticleLife(simulation) => simulation.pan_camera(delta_x, delta_y),
+            SimulationType::Flow(sim) => sim.pan_camera(delta_x, delta_y),
+            SimulationType::Pellets(simulation) => simulation.pan_camera(delta_x, delta_y),
+            SimulationType::Ecosystem(simulation) => simulation.pan_camera(delta_x, delta_y),
+            SimulationType::MainMenu(simulation) => simulation.pan_camera(delta_x, delta_y),
+            SimulationType::Gradient(simulation) => simulation.pan_camera(delta_x, delta_y),
+        }
+    }
+
+    fn zoom_camera(&mut self, delta: f32) {
+        match self {
+            SimulationType::SlimeMold(simulation) => simulation.zoom_camera(delta),
+            SimulationType::GrayScott(simulation) => simulation.zoom_camera(delta),
+            SimulationType::ParticleLife(simulation) => simulation.zoom_camera(delta),
+            SimulationType::Flow(sim) => sim.zoom_camera(delta),
+            SimulationType::Pellets(simulation) => simulation.zoom_camera(delta),
+            SimulationType::Ecosystem(simulation) => simulation.zoom_camera(delta),
+            SimulationType::MainMenu(simulation) => simulation.zoom_camera(delta),
+            SimulationType::Gradient(simulation) => simulation.zoom_camera(delta),
+        }
+    }
+
+    fn zoom_camera_to_cursor(&mut self, delta: f32, cursor_x: f32, cursor_y: f32) {
+        match self {
+            SimulationType::SlimeMold(simulation) => {
+                simulation.zoom_camera_to_cursor(delta, cursor_x, cursor_y)
+            }
+            SimulationType::GrayScott(simulation) => {
+                simulation.zoom_camera_to_cursor(delta, cursor_x, cursor_y)
+            }
+            SimulationType::ParticleLife(simulation) => {
+                simulation.zoom_camera_to_cursor(delta, cursor_x, cursor_y)
+            }
+            SimulationType::Flow(sim) => sim.zoom_camera_to_cursor(delta, cursor_x, cursor_y),
+            SimulationType::Pellets(simulation) => {
+                simulation.zoom_camera_to_cursor(delta, cursor_x, cursor_y)
+            }
+            SimulationType::Ecosystem(simulation) => {
+                simulation.zoom_camera_to_cursor(delta, cursor_x, cursor_y)
+            }
+            SimulationType::MainMenu(simulation) => {
+                simulation.zoom_camera_to_cursor(delta, cursor_x, cursor_y)
+            }
+            SimulationType::Gradient(simulation) => {
+                simulation.zoom_camera_to_cursor(delta, cursor_x, cursor_y)
+            }
+        }
+    }
+
+    fn reset_camera(&mut self) {
+        match self {
+            SimulationType::SlimeMold(simulation) => simulation.reset_camera(),
+            SimulationType::GrayScott(simulation) => simulation.reset_camera(),
+            SimulationType::ParticleLife(simulation) => simulation.reset_camera(),
+            SimulationType::Flow(sim) => sim.reset_camera(),
+            SimulationType::Pellets(simulation) => simulation.reset_camera(),
+            SimulationType::Ecosystem(simulation) => simulation.reset_camera(),
+            SimulationType::MainMenu(simulation) => simulation.reset_camera(),
+            SimulationType::Gradient(simulation) => simulation.reset_camera(),
+        }
+    }
+
+    fn get_camera_state(&self) -> Value {
+        match self {
+            SimulationType::SlimeMold(simulation) => simulation.get_camera_state(),
+            SimulationType::GrayScott(simulation) => simulation.get_camera_state(),
+            SimulationType::ParticleLife(simulation) => simulation.get_camera_state(),
+            SimulationType::Flow(sim) => sim.get_camera_state(),
+            SimulationType::Pellets(simulation) => simulation.get_camera_state(),
+            SimulationType::Ecosystem(simulation) => simulation.get_camera_state(),
+            SimulationType::MainMenu(simulation) => simulation.get_camera_state(),
+            SimulationType::Gradient(simulation) => simulation.get_camera_state(),
+        }
+    }
+
+    fn save_preset(&self, preset_name: &str) -> SimulationResult<()> {
+        match self {
+            SimulationType::SlimeMold(simulation) => simulation.save_preset(preset_name),
+            SimulationType::GrayScott(simulation) => simulation.save_preset(preset_name),
+            SimulationType::ParticleLife(simulation) => simulation.save_preset(preset_name),
+            SimulationType::Flow(sim) => sim.save_preset(preset_name),
+            SimulationType::Pellets(simulation) => simulation.save_preset(preset_name),
+            SimulationType::Ecosystem(simulation) => simulation.save_preset(preset_name),
+            SimulationType::MainMenu(simulation) => simulation.save_preset(preset_name),
+            SimulationType::Gradient(simulation) => simulation.save_preset(preset_name),
+        }
+    }
+
+    fn load_preset(&mut self, preset_name: &str, queue: &Arc<Queue>) -> SimulationResult<()> {
+        match self {
+            SimulationType::SlimeMold(simulation) => simulation.load_preset(preset_name, queue),
+            SimulationType::GrayScott(simulation) => simulation.load_preset(preset_name, queue),
+            SimulationType::ParticleLife(simulation) => simulation.load_preset(preset_name, queue),
+            SimulationType::Flow(sim) => sim.load_preset(preset_name, queue),
+            SimulationType::Pellets(simulation) => simulation.load_preset(preset_name, queue),
+            SimulationType::Ecosystem(simulation) => simulation.load_preset(preset_name, queue),
+            SimulationType::MainMenu(simulation) => simulation.load_preset(preset_name, queue),
+            SimulationType::Gradient(simulation) => simulation.load_preset(preset_name, queue),
+        }
+    }
+
+    fn apply_settings(
+        &mut self,
+        settings: serde_json::Value,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()> {
+        if let Some(schema) = self.descriptor().settings_schema {
+            settings_schema::validate_settings(&schema(), &settings)?;
+        }
+
+        match self {
+            SimulationType::SlimeMold(simulation) => {
+                simulation.apply_settings(settings, device, queue)
+            }
+            SimulationType::GrayScott(simulation) => {
+                simulation.apply_settings(settings, device, queue)
+            }
+            SimulationType::ParticleLife(simulation) => {
+                simulation.apply_settings(settings, device, queue)
+            }
+            SimulationType::Flow(sim) => sim.apply_settings(settings, device, queue),
+            SimulationType::Pellets(simulation) => {
+                simulation.apply_settings(settings, device, queue)
+            }
+            SimulationType::Ecosystem(simulation) => {
+                simulation.apply_settings(settings, device, queue)
+            }
+            SimulationType::MainMenu(simulation) => {
+                simulation.apply_settings(settings, device, queue)
+            }
+            SimulationType::Gradient(simulation) => {
+                simulation.apply_settings(settings, device, queue)
+            }
+        }
+    }
+
+    fn reset_runtime_state(
+        &mut self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()> {
+        match self {
+            SimulationType::SlimeMold(simulation) => simulation.reset_runtime_state(device, queue),
+            SimulationType::GrayScott(simulation) => simulation.reset_runtime_state(device, queue),
+            SimulationType::ParticleLife(simulation) => {
+                simulation.reset_runtime_state(device, queue)
+            }
+            SimulationType::Flow(sim) => sim.reset_runtime_state(device, queue),
+            SimulationType::Pellets(simulation) => simulation.reset_runtime_state(device, queue),
+            SimulationType::Ecosystem(simulation) => simulation.reset_runtime_state(device, queue),
+            SimulationType::MainMenu(simulation) => simulation.reset_runtime_state(device, queue),
+            SimulationType::Gradient(simulation) => simulation.reset_runtime_state(device, queue),
+        }
+    }
+
+    fn toggle_gui(&mut self) -> bool {
+        match self {
+            SimulationType::SlimeMold(simulation) => simulation.toggle_gui(),
+            SimulationType::GrayScott(simulation) => simulation.toggle_gui(),
+            SimulationType::ParticleLife(simulation) => simulation.toggle_gui(),
+            SimulationType::Flow(sim) => sim.toggle_gui(),
+            SimulationType::Pellets(simulation) => simulation.toggle_gui(),
+            SimulationType::Ecosystem(simulation) => simulation.toggle_gui(),
+            SimulationType::MainMenu(simulation) => simulation.toggle_gui(),
+            SimulationType::Gradient(simulation) => simulation.toggle_gui(),
+        }
+    }
+
+    fn is_gui_visible(&self) -> bool {
+        match self {
+            SimulationType::SlimeMold(simulation) => simulation.is_gui_visible(),
+            SimulationType::GrayScott(simulation) => simulation.is_gui_visible(),
+            SimulationType::ParticleLife(simulation) => simulation.is_gui_visible(),
+            SimulationType::Flow(sim) => sim.is_gui_visible(),
+            SimulationType::Pellets(simulation) => simulation.is_gui_visible(),
+            SimulationType::Ecosystem(simulation) => simulation.is_gui_visible(),
+            SimulationType::MainMenu(simulation) => simulation.is_gui_visible(),
+            SimulationType::Gradient(simulation) => simulation.is_gui_visible(),
+        }
+    }
+
+    fn randomize_settings(
+        &mut self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()> {
+        match self {
+            SimulationType::SlimeMold(simulation) => simulation.randomize_settings(device, queue),
+            SimulationType::GrayScott(simulation) => simulation.randomize_settings(device, queue),
+            SimulationType::ParticleLife(simulation) => {
+                simulation.randomize_settings(device, queue)
+            }
+            SimulationType::Flow(sim) => sim.randomize_settings(device, queue),
+            SimulationType::Pellets(simulation) => simulation.randomize_settings(device, queue),
+            SimulationType::Ecosystem(simulation) => simulation.randomize_settings(device, queue),
+            SimulationType::MainMenu(simulation) => simulation.randomize_settings(device, queue),
+            SimulationType::Gradient(simulation) => simulation.randomize_settings(device, queue),
+        }
+    }
+
+    fn save_snapshot(
+        &self,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<SimulationSnapshot> {
+        match self {
+            SimulationType::SlimeMold(simulation) => simulation.save_snapshot(device, queue),
+            SimulationType::GrayScott(simulation) => simulation.save_snapshot(device, queue),
+            SimulationType::ParticleLife(simulation) => simulation.save_snapshot(device, queue),
+            SimulationType::Flow(sim) => sim.save_snapshot(device, queue),
+            SimulationType::Pellets(simulation) => simulation.save_snapshot(device, queue),
+            SimulationType::Ecosystem(simulation) => simulation.save_snapshot(device, queue),
+            SimulationType::MainMenu(simulation) => simulation.save_snapshot(device, queue),
+            SimulationType::Gradient(simulation) => simulation.save_snapshot(device, queue),
+        }
+    }
+
+    fn load_snapshot(
+        &mut self,
+        snapshot: &SimulationSnapshot,
+        device: &Arc<Device>,
+        queue: &Arc<Queue>,
+    ) -> SimulationResult<()> {
+        match self {
+            SimulationType::SlimeMold(simulation) => {
+                simulation.load_snapshot(snapshot, device, queue)
+            }
+            SimulationType::GrayScott(simulation) => {
+                simulation.load_snapshot(snapshot, device, queue)
+            }
+            SimulationType::ParticleLife(simulation) => {
+                simulation.load_snapshot(snapshot, device, queue)
+            }
+            SimulationType::Flow(sim) => sim.load_snapshot(snapshot, device, queue),
+            SimulationType::Pellets(simulation) => {
+                simulation.load_snapshot(snapshot, device, queue)
+            }
+            SimulationType::Ecosystem(simulation) => {
+                simulation.load_snapshot(snapshot, device, queue)
+            }
+            SimulationType::MainMenu(simulation) => {
+                simulation.load_snapshot(snapshot, device, queue)
+            }
+            SimulationType::Gradient(simulation) => {
+                simulation.load_snapshot(snapshot, device, queue)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::RenderSettings;
+    use crate::simulation::headless::{DEFAULT_DELTA_TIME, HeadlessRenderer};
+    use crate::simulations::shared::OffscreenTarget;
+    use wgpu::TextureFormat;
+
+    /// Mean difference between horizontally adjacent pixels
+    fn contrast(pixels: &[u8], width: usize) -> f64 {
+        let total = pixels
+            .chunks_exact(width * 4)
+            .flat_map(|row| row.windows(8).step_by(4))
+            .map(|pair| {
+                (0..3)
+                    .map(|c| pair[c].abs_diff(pair[c + 4]) as f64)
+                    .sum::<f64>()
+            })
+            .sum::<f64>();
+        total / (pixels.len() / 4) as f64
+    }
+
+    #[tokio::test]
+    async fn test_scaled_render_gains_resolution() {
+        let renderer = HeadlessRenderer::new(
+            128,
+            96,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderSettings::default(),
+        )
+        .await
+        .expect("Failed to create headless renderer");
+        let large = OffscreenTarget::new(&renderer.device, 512, 384, renderer.target.format);
+
+        for (simulation_type, gains_resolution) in [
+            ("particle_life", true),
+            ("pellets", true),
+            ("ecosystem", true),
+            ("slime_mold", false),
+            ("gray_scott", false),
+            ("flow", false),
+        ] {
+            let mut simulation = renderer.create_simulation(simulation_type).await.unwrap();
+            assert_eq!(
+                simulation.gains_resolution_when_scaled(),
+                gains_resolution,
+                "{}",
+                simulation_type
+            );
+            if !gains_resolution {
+                continue;
+            }
+
+            renderer
+                .render_frames(&mut simulation, 30, DEFAULT_DELTA_TIME, |_, _| Ok(()))
+                .unwrap();
+            simulation
+                .render_frame_static(&renderer.device, &renderer.queue, &renderer.target.view)
+                .unwrap();
+            let before = renderer.read_pixels().unwrap();
+
+            let scaled = simulation
+                .render_frame_static_scaled(
+                    &renderer.device,
+                    &renderer.queue,
+                    &large.view,
+                    &large.surface_config(),
+                    &renderer.surface_config(),
+                )
+                .unwrap();
+            assert!(scaled, "{} was upscaled", simulation_type);
+            let pixels = large
+                .read_pixels(&renderer.device, &renderer.queue)
+                .unwrap();
+
+            // An upscale by four spreads each edge over four pixels and
+            // divides the contrast between neighbours by about as much
+            let ratio = contrast(&pixels, 512) / contrast(&before, 128);
+            assert!(
+                ratio > 0.4,
+                "{} lost detail when scaled: {:.2}",
+                simulation_type,
+                ratio
+            );
+
+            // The window-sized display resources are restored
+            simulation
+                .render_frame_static(&renderer.device, &renderer.queue, &renderer.target.view)
+                .unwrap();
+            assert!(
+                renderer.read_pixels().unwrap() == before,
+                "{} changed after a scaled render",
+                simulation_type
+            );
+        }
+    }
+}